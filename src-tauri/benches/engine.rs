@@ -0,0 +1,84 @@
+//! Compara os caminhos de cifragem da máquina: o didático, que monta o caminho do
+//! sinal em cada tecla, e o rápido, usado pelos ataques e por textos longos. Compara
+//! também as duas formas de decifrar um bloco da busca exaustiva: uma máquina por
+//! chave ou o kernel em lote.
+//!
+//! Rodar com `cargo bench --bench engine`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use enigma_lib::cryptanalysis::kernel::ScramblerTable;
+use enigma_lib::cryptanalysis::{decrypt, key_config, machine_at};
+use enigma_lib::enigma::{EnigmaConfig, EnigmaMachine, RotorConfig};
+
+const LETTERS: usize = 200_000;
+
+/// Tamanho do texto decifrado em cada chave do bloco.
+const BLOCK_TEXT: usize = 100;
+
+fn config() -> EnigmaConfig {
+    let rotor = |name: &str, position, ring| RotorConfig { name: name.to_string(), position, ring };
+    EnigmaConfig {
+        rotors: (rotor("III", 'V', 'C'), rotor("I", 'K', 'B'), rotor("II", 'X', 'A')),
+        reflector: "B".to_string(),
+        plugboard_pairs: "AV BS CG DL FU HZ IN KM OW RX".to_string(),
+    }
+}
+
+/// Roda `f` sobre o texto de exemplo e imprime o tempo por letra.
+fn bench(name: &str, f: impl Fn(&mut EnigmaMachine, &[u8])) -> Duration {
+    let text: Vec<u8> = (0..LETTERS).map(|i| (i * 7 % 26) as u8).collect();
+    let started = Instant::now();
+    f(&mut EnigmaMachine::new(config()), &text);
+    let elapsed = started.elapsed();
+    println!("{:<24} {:>10.1} ns/letra", name, elapsed.as_nanos() as f64 / LETTERS as f64);
+    elapsed
+}
+
+fn main() {
+    let detailed = bench("process_char_detailed", |machine, text| {
+        for &c in text {
+            black_box(machine.process_char_detailed((b'A' + c) as char));
+        }
+    });
+    bench("process_char", |machine, text| {
+        for &c in text {
+            black_box(machine.process_char((b'A' + c) as char));
+        }
+    });
+    let fast = bench("process_bytes", |machine, text| {
+        let mut buffer = text.to_vec();
+        machine.process_bytes(&mut buffer);
+        black_box(buffer);
+    });
+    let speedup = detailed.as_secs_f64() / fast.as_secs_f64();
+    println!("process_bytes é {:.0}x mais rápido que process_char_detailed", speedup);
+
+    let text: Vec<u8> = (0..BLOCK_TEXT).map(|i| (i * 7 % 26) as u8).collect();
+    let rotors = ("III", "I", "II");
+    let keys = || (b'A'..=b'Z').flat_map(|m| (b'A'..=b'Z').map(move |r| (m, r)));
+    let blocks = 20;
+
+    let started = Instant::now();
+    for _ in 0..blocks {
+        for (m, r) in keys() {
+            black_box(decrypt(&mut machine_at(rotors, "B", ('K', m as char, r as char)), &text));
+        }
+    }
+    let per_machine = started.elapsed() / blocks;
+    println!("{:<24} {:>10.1} µs/bloco", "máquina por chave", per_machine.as_nanos() as f64 / 1000.0);
+
+    let started = Instant::now();
+    for _ in 0..blocks {
+        let table = ScramblerTable::new(&key_config(rotors, "B", ('K', 'A', 'A'), ('A', 'A', 'A'), ""), text.len());
+        let starts: Vec<(u8, u8)> = keys().map(|(m, r)| (m - b'A', r - b'A')).collect();
+        let mut out = vec![0u8; starts.len() * text.len()];
+        table.decrypt(&starts, &text, &mut out);
+        black_box(out);
+    }
+    let per_kernel = started.elapsed() / blocks;
+    println!("{:<24} {:>10.1} µs/bloco", "kernel em lote", per_kernel.as_nanos() as f64 / 1000.0);
+    let speedup = per_machine.as_secs_f64() / per_kernel.as_secs_f64();
+    println!("o kernel é {:.1}x mais rápido num bloco de 676 chaves e {} letras", speedup, BLOCK_TEXT);
+}
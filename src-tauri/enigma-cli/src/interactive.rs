@@ -0,0 +1,149 @@
+//! Modo interativo: a máquina no terminal, tecla a tecla, também por SSH.
+//!
+//! Cada letra teclada gira os rotores e acende uma lâmpada, e a linha de estado é
+//! redesenhada na hora com as janelas dos rotores, a última tecla e o que já foi
+//! cifrado. Ctrl+R volta às posições iniciais; Esc, Ctrl+C ou Ctrl+D encerram.
+//! Sem terminal (entrada redirecionada), cada linha lida é cifrada de uma vez.
+
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{self, ClearType};
+use crossterm::{cursor, queue};
+use enigma_core::{EnigmaConfig, EnigmaMachine};
+
+/// Letras de entrada e de saída mostradas na linha de estado.
+const VISIBLE: usize = 30;
+
+/// Sessão de digitação: a máquina e o que já passou por ela desde o início.
+pub struct Session {
+    start: EnigmaConfig,
+    machine: EnigmaMachine,
+    typed: String,
+    lamps: String,
+}
+
+impl Session {
+    pub fn new(config: EnigmaConfig) -> Self {
+        Self { machine: EnigmaMachine::new(config.clone()), start: config, typed: String::new(), lamps: String::new() }
+    }
+
+    /// Tecla `c` e retorna a lâmpada que acende; `None` se não for uma letra.
+    pub fn key(&mut self, c: char) -> Option<char> {
+        if !c.is_ascii_alphabetic() {
+            return None;
+        }
+        let c = c.to_ascii_uppercase();
+        let lamp = self.machine.process_char(c);
+        self.typed.push(c);
+        self.lamps.push(lamp);
+        Some(lamp)
+    }
+
+    /// Volta às posições iniciais e esquece o que foi teclado.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.start.clone());
+    }
+
+    /// Texto cifrado até agora.
+    pub fn lamps(&self) -> &str {
+        &self.lamps
+    }
+
+    /// "[X K F]  E → Q  ...WETTERB → ...QBLTWLD".
+    pub fn status(&self) -> String {
+        let (l, m, r) = self.machine.get_positions();
+        let last = match (self.typed.chars().last(), self.lamps.chars().last()) {
+            (Some(key), Some(lamp)) => format!("{} → {}", key, lamp),
+            _ => "- → -".to_string(),
+        };
+        format!("[{} {} {}]  {}  {} → {}", l, m, r, last, tail(&self.typed), tail(&self.lamps))
+    }
+}
+
+/// Últimas `VISIBLE` letras, com reticências se houver mais.
+fn tail(text: &str) -> String {
+    if text.len() > VISIBLE {
+        format!("...{}", &text[text.len() - VISIBLE..])
+    } else {
+        text.to_string()
+    }
+}
+
+/// Abre o modo interativo com a máquina em `config`.
+pub fn run(config: EnigmaConfig) -> Result<(), String> {
+    let mut session = Session::new(config);
+    if io::stdin().is_terminal() {
+        terminal::enable_raw_mode().map_err(|e| format!("Erro ao preparar o terminal: {}", e))?;
+        // O modo normal do terminal volta mesmo se a leitura falhar
+        let result = type_keys(&mut session);
+        let restored = terminal::disable_raw_mode().map_err(|e| format!("Erro ao restaurar o terminal: {}", e));
+        result.and(restored)?;
+        println!("\n{}", session.lamps());
+        Ok(())
+    } else {
+        type_lines(&mut session)
+    }
+}
+
+fn type_keys(session: &mut Session) -> Result<(), String> {
+    let mut stdout = io::stdout();
+    let error = |e: io::Error| format!("Erro no terminal: {}", e);
+    loop {
+        queue!(stdout, cursor::MoveToColumn(0), terminal::Clear(ClearType::CurrentLine)).map_err(error)?;
+        write!(stdout, "{}", session.status()).map_err(error)?;
+        stdout.flush().map_err(error)?;
+
+        let Event::Key(KeyEvent { code, modifiers, kind, .. }) = event::read().map_err(error)? else {
+            continue;
+        };
+        if kind == KeyEventKind::Release {
+            continue;
+        }
+        let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+        match code {
+            KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c' | 'd') if ctrl => return Ok(()),
+            KeyCode::Char('r') if ctrl => session.reset(),
+            KeyCode::Char(c) => {
+                session.key(c);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn type_lines(session: &mut Session) -> Result<(), String> {
+    for line in io::stdin().lock().lines() {
+        let line = line.map_err(|e| format!("Erro ao ler a entrada: {}", e))?;
+        for c in line.chars() {
+            session.key(c);
+        }
+        println!("{}", session.status());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::default_config;
+
+    #[test]
+    fn test_session_keys_and_reset() {
+        let mut session = Session::new(default_config());
+        assert_eq!(session.key('1'), None);
+        let lamp = session.key('h').unwrap();
+        assert_eq!(lamp, EnigmaMachine::new(default_config()).process_char('H'));
+        assert_eq!(session.status(), format!("[A A B]  H → {}  H → {}", lamp, lamp));
+
+        for _ in 0..40 {
+            session.key('a');
+        }
+        assert!(session.status().contains("  ...AAA"));
+        assert_eq!(session.lamps().len(), 41);
+
+        session.reset();
+        assert_eq!(session.status(), "[A A A]  - → -   → ");
+    }
+}
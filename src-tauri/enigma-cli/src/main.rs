@@ -0,0 +1,458 @@
+//! Linha de comando da Enigma: cifra, mostra o caminho do sinal e ataca textos
+//! sem a interface gráfica, para scripts e máquinas sem tela.
+//!
+//! ```text
+//! enigma-cli encrypt --rotors "III II I" --positions XKE --plugboard "AV BS" "Wetter bericht"
+//! echo QBLTW... | enigma-cli decrypt --config chave.json
+//! enigma-cli attack --scorer chi2-de < interceptado.txt
+//! cat mensagem.txt | enigma-cli trace --jsonl | jq -r .output
+//! enigma-cli interactive --positions XKE
+//! enigma-cli serve --addr 0.0.0.0:8080 --allow-origin https://turma.escola.br
+//! ```
+
+mod interactive;
+mod server;
+
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::atomic::AtomicBool;
+
+use clap::{Args, Parser, Subcommand};
+use enigma_core::{Component, EncryptionStep, EnigmaConfig, EnigmaMachine, EnigmaReader, OutputFormat, RotorConfig};
+use enigma_cryptanalysis::fitness::ScorerRegistry;
+use enigma_cryptanalysis::pipeline::{self, PipelineOptions};
+use serde::Serialize;
+
+#[derive(Parser)]
+#[command(name = "enigma-cli", version, about = "Simulador da máquina Enigma M3")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Cifra um texto (do argumento ou da entrada padrão)
+    Encrypt {
+        #[command(flatten)]
+        key: KeyArgs,
+        #[command(flatten)]
+        format: FormatArgs,
+        text: Option<String>,
+    },
+    /// Decifra um texto. A Enigma é recíproca: é a mesma operação de `encrypt`
+    Decrypt {
+        #[command(flatten)]
+        key: KeyArgs,
+        #[command(flatten)]
+        format: FormatArgs,
+        text: Option<String>,
+    },
+    /// Mostra o caminho do sinal de cada letra
+    Trace {
+        #[command(flatten)]
+        key: KeyArgs,
+        /// Saída em JSON, no formato que a UI recebe
+        #[arg(long)]
+        json: bool,
+        /// Uma linha JSON por letra (índice, entrada, saída e posições), à medida que
+        /// a entrada chega; bom para `jq` e planilhas
+        #[arg(long, conflicts_with = "json")]
+        jsonl: bool,
+        text: Option<String>,
+    },
+    /// Ataca um texto cifrado sem conhecer a chave (rotores, posições, anéis e plugboard)
+    Attack {
+        /// Pontuador da etapa do plugboard (ex.: "ic", "chi2-de", "chi2-auto")
+        #[arg(long)]
+        scorer: Option<String>,
+        /// Trecho conhecido do texto claro; substitui o pontuador
+        #[arg(long)]
+        crib: Option<String>,
+        /// Quantos candidatos da busca de rotores seguem para as outras etapas
+        #[arg(long, default_value_t = 3)]
+        candidates: usize,
+        /// Saída em JSON
+        #[arg(long)]
+        json: bool,
+        text: Option<String>,
+    },
+    /// Tecla a tecla no terminal, com as janelas dos rotores e as lâmpadas
+    Interactive {
+        #[command(flatten)]
+        key: KeyArgs,
+    },
+    /// Servidor HTTP com os comandos de cifragem e de análise em endpoints REST
+    Serve {
+        /// Endereço de escuta; use 0.0.0.0:8080 para aceitar os outros computadores da sala
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        addr: SocketAddr,
+        /// Origem de navegador autorizada a chamar a API (ex.: https://turma.escola.br),
+        /// pode repetir; sem nenhuma, só a UI local do app
+        #[arg(long = "allow-origin")]
+        allow_origins: Vec<String>,
+    },
+}
+
+/// Chave da máquina: um arquivo de configuração e/ou opções, que têm precedência
+/// sobre o arquivo. Sem nada, a máquina começa em I II III, B, AAA, AAA.
+#[derive(Args, Debug, Default)]
+struct KeyArgs {
+    /// Arquivo JSON com a configuração, no formato que a UI usa
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+    /// Rotores da esquerda para a direita (ex.: "III II I")
+    #[arg(long)]
+    rotors: Option<String>,
+    /// Refletor ("B" ou "C")
+    #[arg(long)]
+    reflector: Option<String>,
+    /// Posições iniciais da esquerda para a direita (ex.: "XKE")
+    #[arg(long)]
+    positions: Option<String>,
+    /// Anéis da esquerda para a direita (ex.: "AAA")
+    #[arg(long)]
+    rings: Option<String>,
+    /// Pares do plugboard separados por espaços (ex.: "AV BS")
+    #[arg(long)]
+    plugboard: Option<String>,
+}
+
+impl KeyArgs {
+    fn config(&self) -> Result<EnigmaConfig, String> {
+        let mut config = match &self.config {
+            Some(path) => {
+                let json = std::fs::read_to_string(path)
+                    .map_err(|e| format!("Erro ao abrir a configuração {}: {}", path.display(), e))?;
+                serde_json::from_str(&json).map_err(|e| format!("Configuração inválida: {}", e))?
+            }
+            None => default_config(),
+        };
+        // As opções vêm da esquerda para a direita; `EnigmaConfig` guarda os
+        // rotores da direita para a esquerda
+        let mut wheels = [&mut config.rotors.2, &mut config.rotors.1, &mut config.rotors.0];
+        if let Some(rotors) = &self.rotors {
+            let names: Vec<&str> = rotors.split_whitespace().collect();
+            if names.len() != 3 {
+                return Err(format!("Informe três rotores, da esquerda para a direita: {}", rotors));
+            }
+            for (wheel, name) in wheels.iter_mut().zip(names) {
+                wheel.name = name.to_string();
+            }
+        }
+        if let Some(positions) = &self.positions {
+            for (wheel, position) in wheels.iter_mut().zip(three_letters(positions, "posições")?) {
+                wheel.position = position;
+            }
+        }
+        if let Some(rings) = &self.rings {
+            for (wheel, ring) in wheels.iter_mut().zip(three_letters(rings, "anéis")?) {
+                wheel.ring = ring;
+            }
+        }
+        if let Some(reflector) = &self.reflector {
+            config.reflector = reflector.clone();
+        }
+        if let Some(plugboard) = &self.plugboard {
+            config.plugboard_pairs = plugboard.clone();
+        }
+        config.normalized()
+    }
+}
+
+/// Formato da saída, o mesmo da cópia na UI. Sem nenhuma destas opções as letras
+/// saem juntas, à medida que a entrada chega.
+#[derive(Args, Debug, Default)]
+struct FormatArgs {
+    /// Letras por grupo (ex.: 5)
+    #[arg(long)]
+    group: Option<usize>,
+    /// Grupos por linha
+    #[arg(long, requires = "group")]
+    line_groups: Option<usize>,
+    /// Linha antes do texto; "{letters}" vira o número de letras
+    #[arg(long)]
+    preamble: Option<String>,
+}
+
+impl FormatArgs {
+    fn format(&self) -> Option<OutputFormat> {
+        if self.group.is_none() && self.preamble.is_none() {
+            return None;
+        }
+        Some(OutputFormat {
+            group_size: self.group.unwrap_or(0),
+            groups_per_line: self.line_groups.unwrap_or(0),
+            preamble: self.preamble.clone(),
+        })
+    }
+}
+
+fn default_config() -> EnigmaConfig {
+    let rotor = |name: &str| RotorConfig { name: name.to_string(), position: 'A', ring: 'A' };
+    EnigmaConfig {
+        rotors: (rotor("III"), rotor("II"), rotor("I")),
+        reflector: "B".to_string(),
+        plugboard_pairs: String::new(),
+    }
+}
+
+fn three_letters(text: &str, what: &str) -> Result<[char; 3], String> {
+    let letters: Vec<char> = text.chars().collect();
+    <[char; 3]>::try_from(letters).map_err(|_| format!("Informe três letras de {}: {}", what, text))
+}
+
+/// Texto do argumento ou, sem ele, toda a entrada padrão.
+fn input(text: Option<String>) -> Result<String, String> {
+    match text {
+        Some(text) => Ok(text),
+        None => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text).map_err(|e| format!("Erro ao ler a entrada: {}", e))?;
+            Ok(text)
+        }
+    }
+}
+
+/// Cifra o argumento, ou a entrada padrão aos poucos, sem carregá-la toda. Com um
+/// formato, o texto todo é lido antes, para montar os grupos e o preâmbulo.
+fn encrypt(key: &KeyArgs, format: &FormatArgs, text: Option<String>) -> Result<(), String> {
+    let mut machine = EnigmaMachine::new(key.config()?);
+    let mut stdout = io::stdout().lock();
+    let written = match (format.format(), text) {
+        (Some(format), text) => stdout.write_all(format.apply(&machine.process_string(&input(text)?)).as_bytes()),
+        (None, Some(text)) => stdout.write_all(machine.process_string(&text).as_bytes()),
+        (None, None) => io::copy(&mut EnigmaReader::new(io::stdin().lock(), machine), &mut stdout).map(|_| ()),
+    };
+    written.and_then(|_| writeln!(stdout)).map_err(|e| format!("Erro ao escrever a saída: {}", e))
+}
+
+/// Uma linha por letra: "H → Q  AAA→AAB  P H>H · III H>Z · ...".
+fn trace_line(step: &EncryptionStep) -> String {
+    let (before, after) = (step.positions_before_step, step.positions_after_step);
+    let path: Vec<String> = step
+        .path
+        .iter()
+        .map(|entry| {
+            let component = match &entry.component {
+                Component::Plugboard => "P".to_string(),
+                Component::EntryWheel => "ETW".to_string(),
+                Component::Rotor { name, .. } => name.to_string(),
+                Component::Reflector { name } => format!("UKW-{}", name),
+            };
+            format!("{} {}>{}", component, entry.input_char, entry.output_char)
+        })
+        .collect();
+    format!(
+        "{} → {}  {}{}{}→{}{}{}  {}",
+        step.input_char,
+        step.output_char,
+        before.0,
+        before.1,
+        before.2,
+        after.0,
+        after.1,
+        after.2,
+        path.join(" · ")
+    )
+}
+
+/// Uma letra na saída `--jsonl`.
+#[derive(Debug, Serialize, PartialEq)]
+struct TraceRecord {
+    /// Posição da letra na mensagem, a partir de 0.
+    index: usize,
+    input: char,
+    output: char,
+    /// Janelas dos rotores (Esquerda, Meio, Direita) ao acender a lâmpada, ex.: "AAB".
+    positions: String,
+}
+
+impl TraceRecord {
+    fn new(index: usize, step: &EncryptionStep) -> Self {
+        let (l, m, r) = step.positions_after_step;
+        Self { index, input: step.input_char, output: step.output_char, positions: [l, m, r].iter().collect() }
+    }
+}
+
+/// Escreve um `TraceRecord` por letra, linha a linha da entrada padrão (ou do
+/// argumento), sem esperar o fim do texto.
+fn trace_jsonl(machine: &mut EnigmaMachine, text: Option<String>) -> Result<(), String> {
+    let mut stdout = io::stdout().lock();
+    let mut index = 0;
+    let mut emit = |machine: &mut EnigmaMachine, line: &str| -> Result<(), String> {
+        for step in machine.encrypt_iter_detailed(line.chars()) {
+            serde_json::to_writer(&mut stdout, &TraceRecord::new(index, &step))
+                .map_err(|e| format!("Erro ao gerar o JSON: {}", e))?;
+            writeln!(stdout).map_err(|e| format!("Erro ao escrever a saída: {}", e))?;
+            index += 1;
+        }
+        Ok(())
+    };
+    match text {
+        Some(text) => emit(machine, &text),
+        None => {
+            for line in io::stdin().lock().lines() {
+                emit(machine, &line.map_err(|e| format!("Erro ao ler a entrada: {}", e))?)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn trace(key: &KeyArgs, json: bool, jsonl: bool, text: Option<String>) -> Result<(), String> {
+    let mut machine = EnigmaMachine::new(key.config()?);
+    if jsonl {
+        return trace_jsonl(&mut machine, text);
+    }
+    let text = input(text)?;
+    if json {
+        let steps = machine.process_string_detailed(&text);
+        let json = serde_json::to_string_pretty(&steps).map_err(|e| format!("Erro ao gerar o JSON: {}", e))?;
+        println!("{}", json);
+    } else {
+        for step in machine.encrypt_iter_detailed(text.chars()) {
+            println!("{}", trace_line(&step));
+        }
+    }
+    Ok(())
+}
+
+fn attack(
+    scorer: Option<String>,
+    crib: Option<String>,
+    candidates: usize,
+    json: bool,
+    text: Option<String>,
+) -> Result<(), String> {
+    let ciphertext = input(text)?;
+    let scorer = ScorerRegistry::default().resolve(crib.as_deref(), scorer.as_deref())?;
+    let options = PipelineOptions { candidates, ..PipelineOptions::default() };
+    // O andamento vai para stderr, só num terminal, para não sujar a saída
+    let show_progress = io::stderr().is_terminal();
+    let result = pipeline::break_ciphertext(
+        &ciphertext,
+        scorer.as_ref(),
+        &options,
+        |done, total| {
+            if show_progress {
+                eprint!("\rBusca de rotores: {}/{}", done, total);
+            }
+        },
+        |update| {
+            if show_progress {
+                eprintln!("\r{:?}: {:.4} {}", update.stage, update.score, update.preview);
+            }
+        },
+        &AtomicBool::new(false),
+    )?;
+
+    if json {
+        let json =
+            serde_json::to_string_pretty(&result).map_err(|e| format!("Erro ao gerar o JSON: {}", e))?;
+        println!("{}", json);
+        return Ok(());
+    }
+    for (i, solution) in result.solutions.iter().enumerate() {
+        let config = &solution.config;
+        let (right, middle, left) = &config.rotors;
+        println!(
+            "{}. {:.4}  {} {} {}  posições {}{}{}  anéis {}{}{}  refletor {}  plugboard \"{}\"",
+            i + 1,
+            solution.score,
+            left.name,
+            middle.name,
+            right.name,
+            left.position,
+            middle.position,
+            right.position,
+            left.ring,
+            middle.ring,
+            right.ring,
+            config.reflector,
+            config.plugboard_pairs
+        );
+        println!("   {}", solution.preview);
+    }
+    println!("{} chaves testadas", result.keys_tried);
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let result = match Cli::parse().command {
+        Command::Encrypt { key, format, text } | Command::Decrypt { key, format, text } => encrypt(&key, &format, text),
+        Command::Trace { key, json, jsonl, text } => trace(&key, json, jsonl, text),
+        Command::Attack { scorer, crib, candidates, json, text } => attack(scorer, crib, candidates, json, text),
+        Command::Interactive { key } => key.config().and_then(interactive::run),
+        Command::Serve { addr, allow_origins } => server::serve(addr, allow_origins),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("erro: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_flags_left_to_right() {
+        let key = KeyArgs {
+            rotors: Some("iii ii i".into()),
+            positions: Some("XKE".into()),
+            plugboard: Some("av bs".into()),
+            ..KeyArgs::default()
+        };
+        let config = key.config().unwrap();
+        assert_eq!(config.rotors.0.name, "I");
+        assert_eq!(config.rotors.2.name, "III");
+        assert_eq!((config.rotors.2.position, config.rotors.1.position, config.rotors.0.position), ('X', 'K', 'E'));
+        assert_eq!(config.plugboard_pairs, "AV BS");
+
+        assert!(KeyArgs { rotors: Some("I II".into()), ..KeyArgs::default() }.config().is_err());
+        assert!(KeyArgs { rings: Some("AAAA".into()), ..KeyArgs::default() }.config().is_err());
+        assert!(KeyArgs { reflector: Some("Z".into()), ..KeyArgs::default() }.config().is_err());
+    }
+
+    #[test]
+    fn test_format_flags() {
+        assert_eq!(FormatArgs::default().format(), None);
+        let format = FormatArgs { group: Some(5), line_groups: Some(10), preamble: None }.format().unwrap();
+        assert_eq!(format, OutputFormat { group_size: 5, groups_per_line: 10, preamble: None });
+        let preamble = FormatArgs { preamble: Some("{letters} =".into()), ..FormatArgs::default() }.format().unwrap();
+        assert_eq!(preamble.apply("ab cd"), "4 =\nABCD");
+    }
+
+    #[test]
+    fn test_trace_line() {
+        let step = EnigmaMachine::new(default_config()).process_char_detailed('A').1;
+        let line = trace_line(&step);
+        assert!(line.starts_with(&format!("A → {}  AAA→AAB  P A>A · III A>", step.output_char)));
+        assert!(line.contains("UKW-B"));
+    }
+
+    #[test]
+    fn test_trace_record() {
+        let mut machine = EnigmaMachine::new(default_config());
+        let steps: Vec<EncryptionStep> = machine.encrypt_iter_detailed("ab".chars()).collect();
+        let record = TraceRecord::new(1, &steps[1]);
+        assert_eq!(
+            record,
+            TraceRecord { index: 1, input: 'B', output: steps[1].output_char, positions: "AAC".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_cli_parses_subcommands() {
+        let cli = Cli::try_parse_from(["enigma-cli", "trace", "--json", "--positions", "ABC", "HELLO"]).unwrap();
+        assert!(matches!(cli.command, Command::Trace { json: true, text: Some(_), .. }));
+        assert!(Cli::try_parse_from(["enigma-cli", "attack", "--candidates", "x"]).is_err());
+        assert!(Cli::try_parse_from(["enigma-cli", "trace", "--json", "--jsonl"]).is_err());
+    }
+}
@@ -0,0 +1,348 @@
+//! Servidor HTTP: os comandos de cifragem e de análise do app como endpoints REST,
+//! para uma turma usar um único backend a partir do navegador, sem instalar o app.
+//!
+//! Todos os endpoints recebem e devolvem JSON, com os mesmos campos (em snake_case)
+//! dos argumentos dos comandos do Tauri. Uma entrada inválida responde 400 com a
+//! mensagem de erro no corpo. Os ataques rodam fora das threads do servidor, e um
+//! pânico num handler responde 500 sem derrubar o servidor.
+//!
+//! Só as origens autorizadas (por padrão, o app local) podem chamar a API de um
+//! navegador.
+
+use std::net::SocketAddr;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::extract::State;
+use axum::http::{header, HeaderValue, Method, StatusCode};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use enigma_core::{EncryptionStep, EnigmaConfig, EnigmaMachine};
+use enigma_cryptanalysis::avalanche::{self, SettingsComparison};
+use enigma_cryptanalysis::banburismus::{self, Alignment};
+use enigma_cryptanalysis::cillies::{self, CillieReport, IndicatorMessage};
+use enigma_cryptanalysis::cribs::{self, CribContext, CribSuggestion};
+use enigma_cryptanalysis::depth::{self, KappaOffset};
+use enigma_cryptanalysis::fitness::ScorerRegistry;
+use enigma_cryptanalysis::flattening::{self, FlatteningDemo};
+use enigma_cryptanalysis::herivel::{self, RingGuess};
+use enigma_cryptanalysis::keyspace::{self, Keyspace, KeyspaceOptions, Language, MachineModel, UnicityReport};
+use enigma_cryptanalysis::pipeline::{self, PipelineOptions};
+use enigma_cryptanalysis::rejewski::{self, RejewskiReport};
+use enigma_cryptanalysis::report::{AttackReport, SearchStats};
+use enigma_cryptanalysis::self_encryption::{self, SelfEncryptionDemo};
+use enigma_cryptanalysis::validate_config;
+use serde::Deserialize;
+use tower_http::catch_panic::CatchPanicLayer;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+type ApiResult<T> = Result<Json<T>, (StatusCode, String)>;
+
+fn respond<T>(result: Result<T, String>) -> ApiResult<T> {
+    result.map(Json).map_err(|e| (StatusCode::BAD_REQUEST, e))
+}
+
+/// Roda `job` numa thread de trabalho, para não travar as outras requisições.
+async fn blocking<T: Send + 'static>(job: impl FnOnce() -> Result<T, String> + Send + 'static) -> ApiResult<T> {
+    match tokio::task::spawn_blocking(job).await {
+        Ok(result) => respond(result),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, format!("Erro na tarefa: {}", e))),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ProcessRequest {
+    config: EnigmaConfig,
+    text: String,
+}
+
+/// Valida antes de montar a máquina, que entra em pânico com nomes desconhecidos.
+fn machine(config: EnigmaConfig) -> Result<EnigmaMachine, String> {
+    Ok(EnigmaMachine::new(validate_config(&config)?))
+}
+
+async fn process(Json(request): Json<ProcessRequest>) -> ApiResult<String> {
+    respond(machine(request.config).map(|mut machine| machine.process_string(&request.text)))
+}
+
+async fn process_detailed(Json(request): Json<ProcessRequest>) -> ApiResult<Vec<EncryptionStep>> {
+    respond(machine(request.config).map(|mut machine| machine.process_string_detailed(&request.text)))
+}
+
+async fn process_batch(Json(messages): Json<Vec<ProcessRequest>>) -> ApiResult<Vec<String>> {
+    blocking(move || {
+        let machines = messages
+            .iter()
+            .enumerate()
+            .map(|(i, message)| machine(message.config.clone()).map_err(|e| format!("Mensagem {}: {}", i + 1, e)))
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(machines.into_iter().zip(&messages).map(|(mut machine, m)| machine.process_string(&m.text)).collect())
+    })
+    .await
+}
+
+#[derive(Debug, Deserialize)]
+struct CompareRequest {
+    config_a: EnigmaConfig,
+    config_b: EnigmaConfig,
+    text: String,
+}
+
+async fn compare_settings(Json(request): Json<CompareRequest>) -> ApiResult<SettingsComparison> {
+    respond(avalanche::compare_settings(&request.config_a, &request.config_b, &request.text))
+}
+
+#[derive(Debug, Deserialize)]
+struct FrequencyRequest {
+    config: EnigmaConfig,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+async fn frequency_demo(Json(request): Json<FrequencyRequest>) -> ApiResult<FlatteningDemo> {
+    respond(flattening::flattening_demo(&request.config, request.text.as_deref()))
+}
+
+#[derive(Debug, Deserialize)]
+struct SelfEncryptionRequest {
+    config: EnigmaConfig,
+    steps: usize,
+}
+
+async fn self_encryption_demo(Json(request): Json<SelfEncryptionRequest>) -> ApiResult<SelfEncryptionDemo> {
+    respond(self_encryption::self_encryption_demo(&request.config, request.steps))
+}
+
+#[derive(Debug, Deserialize)]
+struct PairRequest {
+    first: String,
+    second: String,
+    max_offset: usize,
+}
+
+async fn banburismus(Json(request): Json<PairRequest>) -> Json<Vec<Alignment>> {
+    Json(banburismus::rank_distances(
+        &request.first,
+        &request.second,
+        request.max_offset,
+        banburismus::NAVAL_GERMAN_REPEAT_RATE,
+    ))
+}
+
+async fn depth_test(Json(request): Json<PairRequest>) -> Json<Vec<KappaOffset>> {
+    Json(depth::kappa_test(&request.first, &request.second, request.max_offset))
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyspaceRequest {
+    model: MachineModel,
+    #[serde(default)]
+    options: Option<KeyspaceOptions>,
+    #[serde(default)]
+    language: Option<Language>,
+}
+
+async fn keyspace(Json(request): Json<KeyspaceRequest>) -> Json<Keyspace> {
+    Json(keyspace::keyspace(request.model, &request.options.unwrap_or_default()))
+}
+
+async fn unicity(Json(request): Json<KeyspaceRequest>) -> ApiResult<UnicityReport> {
+    let language = request.language.ok_or((StatusCode::BAD_REQUEST, "Informe a língua.".to_string()))?;
+    let space = keyspace::keyspace(request.model, &request.options.unwrap_or_default());
+    respond(keyspace::unicity(space.bits, language))
+}
+
+#[derive(Debug, Deserialize)]
+struct CribsRequest {
+    ciphertext_len: usize,
+    #[serde(default)]
+    context: Option<CribContext>,
+}
+
+async fn suggest_cribs(Json(request): Json<CribsRequest>) -> Json<Vec<CribSuggestion>> {
+    Json(cribs::suggest_cribs(request.ciphertext_len, request.context))
+}
+
+#[derive(Debug, Deserialize)]
+struct HerivelRequest {
+    grundstellungen: Vec<String>,
+    radius: u8,
+    top: usize,
+}
+
+async fn herivel_tip(Json(request): Json<HerivelRequest>) -> Json<Vec<RingGuess>> {
+    Json(herivel::rank_ring_settings(&request.grundstellungen, request.radius, request.top))
+}
+
+#[derive(Debug, Deserialize)]
+struct CilliesRequest {
+    config: EnigmaConfig,
+    messages: Vec<IndicatorMessage>,
+}
+
+async fn detect_cillies(Json(request): Json<CilliesRequest>) -> ApiResult<Vec<CillieReport>> {
+    blocking(move || cillies::detect(&request.config, &request.messages)).await
+}
+
+#[derive(Debug, Deserialize)]
+struct IndicatorsRequest {
+    config: EnigmaConfig,
+    message_keys: Vec<String>,
+}
+
+async fn rejewski_indicators(Json(request): Json<IndicatorsRequest>) -> ApiResult<Vec<String>> {
+    blocking(move || rejewski::encipher_indicators(&request.config, &request.message_keys)).await
+}
+
+#[derive(Debug, Deserialize)]
+struct RejewskiRequest {
+    indicators: Vec<String>,
+    reflector: String,
+}
+
+/// Sem o catálogo do ciclômetro, percorre todas as posições.
+async fn rejewski_attack(Json(request): Json<RejewskiRequest>) -> ApiResult<RejewskiReport> {
+    blocking(move || rejewski::attack(&request.indicators, &request.reflector)).await
+}
+
+async fn scorers(State(scorers): State<Arc<ScorerRegistry>>) -> Json<Vec<String>> {
+    Json(scorers.names())
+}
+
+#[derive(Debug, Deserialize)]
+struct PipelineRequest {
+    ciphertext: String,
+    #[serde(default)]
+    scorer: Option<String>,
+    #[serde(default)]
+    options: Option<PipelineOptions>,
+}
+
+/// Ataque completo só com o texto cifrado. Sem eventos de andamento: a resposta
+/// chega quando o ataque termina.
+async fn attack_pipeline(
+    State(scorers): State<Arc<ScorerRegistry>>,
+    Json(request): Json<PipelineRequest>,
+) -> ApiResult<AttackReport> {
+    blocking(move || {
+        let scorer = scorers.resolve(None, request.scorer.as_deref())?;
+        let started = Instant::now();
+        let result = pipeline::break_ciphertext(
+            &request.ciphertext,
+            scorer.as_ref(),
+            &request.options.unwrap_or_default(),
+            |_, _| {},
+            |_| {},
+            &AtomicBool::new(false),
+        )?;
+        let stats = SearchStats::since(started, result.keys_tried, result.cancelled);
+        Ok(AttackReport::new(result.solutions.into_iter().map(Into::into), stats))
+    })
+    .await
+}
+
+/// Origens autorizadas quando nenhuma é informada: a UI do app em desenvolvimento.
+pub const DEFAULT_ORIGINS: [&str; 2] = ["http://localhost:1420", "http://127.0.0.1:1420"];
+
+/// CORS só para `origins` (ex.: `https://turma.escola.br`), com os métodos e o
+/// cabeçalho que a API usa.
+fn cors(origins: &[String]) -> Result<CorsLayer, String> {
+    let origins = origins
+        .iter()
+        .map(|origin| origin.parse::<HeaderValue>().map_err(|_| format!("Origem inválida: {}", origin)))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(CorsLayer::new()
+        .allow_origin(AllowOrigin::list(origins))
+        .allow_methods([Method::GET, Method::POST])
+        .allow_headers([header::CONTENT_TYPE]))
+}
+
+/// Rotas da API, com o CORS de `cors`.
+fn router(cors: CorsLayer) -> Router {
+    Router::new()
+        .route("/api/process", post(process))
+        .route("/api/process/detailed", post(process_detailed))
+        .route("/api/process/batch", post(process_batch))
+        .route("/api/compare-settings", post(compare_settings))
+        .route("/api/frequency-demo", post(frequency_demo))
+        .route("/api/self-encryption-demo", post(self_encryption_demo))
+        .route("/api/banburismus", post(banburismus))
+        .route("/api/depth-test", post(depth_test))
+        .route("/api/keyspace", post(keyspace))
+        .route("/api/unicity", post(unicity))
+        .route("/api/suggest-cribs", post(suggest_cribs))
+        .route("/api/herivel-tip", post(herivel_tip))
+        .route("/api/cillies", post(detect_cillies))
+        .route("/api/rejewski/indicators", post(rejewski_indicators))
+        .route("/api/rejewski/attack", post(rejewski_attack))
+        .route("/api/scorers", get(scorers))
+        .route("/api/attack/pipeline", post(attack_pipeline))
+        .with_state(Arc::new(ScorerRegistry::default()))
+        .layer(CatchPanicLayer::new())
+        .layer(cors)
+}
+
+/// Atende em `addr` até o processo ser encerrado, aceitando chamadas de navegador
+/// só de `origins` (ou de `DEFAULT_ORIGINS`, se vazio).
+pub fn serve(addr: SocketAddr, origins: Vec<String>) -> Result<(), String> {
+    let origins = if origins.is_empty() { DEFAULT_ORIGINS.map(String::from).to_vec() } else { origins };
+    let cors = cors(&origins)?;
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| format!("Erro ao iniciar o servidor: {}", e))?;
+    runtime.block_on(async {
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .map_err(|e| format!("Erro ao abrir {}: {}", addr, e))?;
+        eprintln!("Servidor da Enigma em http://{} (origens: {})", addr, origins.join(", "));
+        axum::serve(listener, router(cors)).await.map_err(|e| format!("Erro no servidor: {}", e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::default_config;
+
+    #[tokio::test]
+    async fn test_process_rejects_invalid_config() {
+        let mut config = default_config();
+        let request = ProcessRequest { config: config.clone(), text: "Hello".into() };
+        let Json(output) = process(Json(request)).await.unwrap();
+        assert_eq!(output, EnigmaMachine::new(config.clone()).process_string("HELLO"));
+
+        config.reflector = "Z".into();
+        let (status, message) = process(Json(ProcessRequest { config, text: "Hello".into() })).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(message.contains('Z'));
+    }
+
+    #[tokio::test]
+    async fn test_batch_names_bad_message() {
+        let mut bad = default_config();
+        bad.rotors.1.name = "IX".into();
+        let messages = vec![
+            ProcessRequest { config: default_config(), text: "A".into() },
+            ProcessRequest { config: bad, text: "B".into() },
+        ];
+        let (_, message) = process_batch(Json(messages)).await.unwrap_err();
+        assert!(message.starts_with("Mensagem 2"));
+    }
+
+    #[tokio::test]
+    async fn test_cillies_and_indicators_reject_invalid_config() {
+        let mut config = default_config();
+        config.rotors.0.position = '1';
+        let request = CilliesRequest { config: config.clone(), messages: Vec::new() };
+        let (status, _) = detect_cillies(Json(request)).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let request = IndicatorsRequest { config, message_keys: vec!["ABC".into()] };
+        let (status, _) = rejewski_indicators(Json(request)).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_cors_rejects_malformed_origins() {
+        assert!(cors(&DEFAULT_ORIGINS.map(String::from)).is_ok());
+        assert!(cors(&["http://sala\n12".to_string()]).is_err());
+    }
+}
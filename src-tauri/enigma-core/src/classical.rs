@@ -0,0 +1,355 @@
+//! Cifras clássicas para comparar com a Enigma nas aulas: César, Vigenère e
+//! Playfair, atrás do mesmo `CipherMachine` e com o próprio passo detalhado.
+//!
+//! Ao contrário da Enigma, elas não são recíprocas: a configuração diz se a
+//! máquina cifra ou decifra.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{char_to_u8, u8_to_char, CipherMachine};
+
+/// Uma das cifras clássicas, como vem da UI, com o nome em `cipher` (ex.:
+/// `{"cipher": "vigenere", "key": "LEMON"}`).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "cipher", rename_all = "snake_case"))]
+pub enum ClassicalConfig {
+    Caesar(CaesarConfig),
+    Vigenere(VigenereConfig),
+    Playfair(PlayfairConfig),
+}
+
+/// Passos detalhados de uma cifra clássica, com o nome em `cipher` e os passos
+/// em `steps`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "cipher", content = "steps", rename_all = "snake_case"))]
+pub enum ClassicalSteps {
+    Caesar(Vec<CaesarStep>),
+    Vigenere(Vec<VigenereStep>),
+    Playfair(Vec<PlayfairStep>),
+}
+
+/// Desloca a letra `c` em `shift` posições, para a frente ou para trás.
+fn shift_letter(c: char, shift: u8, decrypt: bool) -> char {
+    let shift = if decrypt { 26 - shift % 26 } else { shift % 26 };
+    u8_to_char((char_to_u8(c) + shift) % 26)
+}
+
+/// Configuração da cifra de César.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CaesarConfig {
+    /// Deslocamento (3 na cifra do próprio César).
+    pub shift: u8,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub decrypt: bool,
+}
+
+/// Uma tecla na cifra de César.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct CaesarStep {
+    pub input_char: char,
+    pub output_char: char,
+    pub shift: u8,
+}
+
+/// Cifra de César: todas as letras andam o mesmo tanto no alfabeto.
+#[derive(Debug, Clone)]
+pub struct Caesar {
+    config: CaesarConfig,
+}
+
+impl Caesar {
+    pub fn new(config: CaesarConfig) -> Self {
+        Self { config: CaesarConfig { shift: config.shift % 26, ..config } }
+    }
+}
+
+impl CipherMachine for Caesar {
+    type Step = CaesarStep;
+    /// O deslocamento, que não muda.
+    type State = u8;
+
+    fn name(&self) -> &'static str {
+        "César"
+    }
+
+    fn process_char(&mut self, c: char) -> char {
+        shift_letter(c, self.config.shift, self.config.decrypt)
+    }
+
+    fn process_detailed(&mut self, c: char) -> (char, CaesarStep) {
+        let output_char = self.process_char(c);
+        (output_char, CaesarStep { input_char: c, output_char, shift: self.config.shift })
+    }
+
+    fn snapshot(&self) -> u8 {
+        self.config.shift
+    }
+}
+
+/// Configuração da cifra de Vigenère.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VigenereConfig {
+    /// Palavra-chave; só as letras contam.
+    pub key: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub decrypt: bool,
+}
+
+/// Uma tecla na cifra de Vigenère.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct VigenereStep {
+    pub input_char: char,
+    pub output_char: char,
+    /// Letra da chave usada nesta tecla e o deslocamento que ela dá.
+    pub key_char: char,
+    pub shift: u8,
+}
+
+/// Estado visível da Vigenère: a letra da chave da próxima tecla.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VigenereState {
+    pub key_char: char,
+    /// Posição dessa letra na chave (a partir de 0).
+    pub index: usize,
+}
+
+/// Cifra de Vigenère: uma César diferente para cada letra, seguindo a chave.
+#[derive(Debug, Clone)]
+pub struct Vigenere {
+    key: Vec<char>,
+    decrypt: bool,
+    index: usize,
+}
+
+impl Vigenere {
+    pub fn new(config: VigenereConfig) -> Result<Self, String> {
+        let key: Vec<char> =
+            config.key.chars().filter(char::is_ascii_alphabetic).map(|c| c.to_ascii_uppercase()).collect();
+        if key.is_empty() {
+            return Err(format!("Chave de Vigenère sem letras: {}", config.key));
+        }
+        Ok(Self { key, decrypt: config.decrypt, index: 0 })
+    }
+}
+
+impl CipherMachine for Vigenere {
+    type Step = VigenereStep;
+    type State = VigenereState;
+
+    fn name(&self) -> &'static str {
+        "Vigenère"
+    }
+
+    fn process_char(&mut self, c: char) -> char {
+        self.process_detailed(c).0
+    }
+
+    fn process_detailed(&mut self, c: char) -> (char, VigenereStep) {
+        let key_char = self.key[self.index];
+        let shift = char_to_u8(key_char);
+        let output_char = shift_letter(c, shift, self.decrypt);
+        self.index = (self.index + 1) % self.key.len();
+        (output_char, VigenereStep { input_char: c, output_char, key_char, shift })
+    }
+
+    fn snapshot(&self) -> VigenereState {
+        VigenereState { key_char: self.key[self.index], index: self.index }
+    }
+}
+
+/// Configuração da cifra de Playfair.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PlayfairConfig {
+    /// Palavra-chave que abre o quadrado; o J conta como I.
+    pub key: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub decrypt: bool,
+}
+
+/// Regra aplicada a um par de letras.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum PlayfairRule {
+    /// Mesma linha: cada letra troca pela da direita (esquerda ao decifrar).
+    SameRow,
+    /// Mesma coluna: cada letra troca pela de baixo (de cima ao decifrar).
+    SameColumn,
+    /// Cantos de um retângulo: cada letra troca pela da própria linha na coluna
+    /// da outra.
+    Rectangle,
+}
+
+/// Um par de letras na cifra de Playfair.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct PlayfairStep {
+    pub input: [char; 2],
+    pub output: [char; 2],
+    pub rule: PlayfairRule,
+    /// Linha e coluna (0..5) das letras de entrada no quadrado.
+    pub cells: [(u8, u8); 2],
+}
+
+/// Cifra de Playfair: troca pares de letras usando um quadrado 5×5 montado a
+/// partir da chave.
+///
+/// Num texto, as letras são agrupadas em pares; um X separa letras iguais num par
+/// (Q, se a letra for o X) e completa a última. Uma tecla sozinha vira o par com
+/// o X de enchimento, e só a primeira letra do resultado volta.
+#[derive(Debug, Clone)]
+pub struct Playfair {
+    square: [char; 25],
+    decrypt: bool,
+}
+
+impl Playfair {
+    pub fn new(config: PlayfairConfig) -> Self {
+        let mut square = ['A'; 25];
+        let mut len = 0;
+        let key = config.key.chars().filter(char::is_ascii_alphabetic).map(|c| c.to_ascii_uppercase());
+        for c in key.chain('A'..='Z') {
+            let c = if c == 'J' { 'I' } else { c };
+            if !square[..len].contains(&c) {
+                square[len] = c;
+                len += 1;
+            }
+        }
+        Self { square, decrypt: config.decrypt }
+    }
+
+    /// Linha e coluna de `c` no quadrado.
+    fn cell(&self, c: char) -> (u8, u8) {
+        let c = if c == 'J' { 'I' } else { c };
+        let i = self.square.iter().position(|&s| s == c).unwrap_or(0) as u8;
+        (i / 5, i % 5)
+    }
+
+    fn at(&self, (row, column): (u8, u8)) -> char {
+        self.square[(row % 5 * 5 + column % 5) as usize]
+    }
+
+    /// Cifra (ou decifra) o par `a`, `b`.
+    fn pair(&self, a: char, b: char) -> PlayfairStep {
+        let cells = [self.cell(a), self.cell(b)];
+        let [(r1, c1), (r2, c2)] = cells;
+        // Andar uma casa para trás é andar quatro para a frente
+        let step = if self.decrypt { 4 } else { 1 };
+        let (rule, out) = if r1 == r2 {
+            (PlayfairRule::SameRow, [(r1, c1 + step), (r2, c2 + step)])
+        } else if c1 == c2 {
+            (PlayfairRule::SameColumn, [(r1 + step, c1), (r2 + step, c2)])
+        } else {
+            (PlayfairRule::Rectangle, [(r1, c2), (r2, c1)])
+        };
+        PlayfairStep { input: [a, b], output: [self.at(out[0]), self.at(out[1])], rule, cells }
+    }
+
+    /// Pares de letras de `text`. Ao cifrar, um X separa letras iguais num par;
+    /// ao decifrar o texto já vem em pares.
+    fn digraphs(&self, text: &str) -> Vec<[char; 2]> {
+        let filler = |c: char| if c == 'X' { 'Q' } else { 'X' };
+        let mut letters = text
+            .chars()
+            .filter(|&c| self.accepts(c))
+            .map(|c| match c.to_ascii_uppercase() {
+                'J' => 'I',
+                c => c,
+            })
+            .peekable();
+        let mut pairs = Vec::new();
+        while let Some(a) = letters.next() {
+            let b = match letters.peek() {
+                Some(&b) if b != a || self.decrypt => {
+                    letters.next();
+                    b
+                }
+                _ => filler(a),
+            };
+            pairs.push([a, b]);
+        }
+        pairs
+    }
+}
+
+impl CipherMachine for Playfair {
+    type Step = PlayfairStep;
+    /// O quadrado, linha por linha.
+    type State = String;
+
+    fn name(&self) -> &'static str {
+        "Playfair"
+    }
+
+    fn process_char(&mut self, c: char) -> char {
+        self.process_detailed(c).0
+    }
+
+    fn process_detailed(&mut self, c: char) -> (char, PlayfairStep) {
+        let c = if c == 'J' { 'I' } else { c };
+        let step = self.pair(c, if c == 'X' { 'Q' } else { 'X' });
+        (step.output[0], step)
+    }
+
+    fn snapshot(&self) -> String {
+        self.square.iter().collect()
+    }
+
+    fn process_string(&mut self, text: &str) -> String {
+        self.process_string_detailed(text).iter().flat_map(|step| step.output).collect()
+    }
+
+    fn process_string_detailed(&mut self, text: &str) -> Vec<PlayfairStep> {
+        self.digraphs(text).into_iter().map(|[a, b]| self.pair(a, b)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_caesar_and_vigenere() {
+        let mut caesar = Caesar::new(CaesarConfig { shift: 29, decrypt: false });
+        assert_eq!(caesar.process_string("Hello, world"), "KHOORZRUOG");
+        let mut back = Caesar::new(CaesarConfig { shift: 3, decrypt: true });
+        assert_eq!(back.process_string("KHOOR"), "HELLO");
+
+        let config = VigenereConfig { key: "lemon".into(), decrypt: false };
+        let mut vigenere = Vigenere::new(config.clone()).unwrap();
+        assert_eq!(vigenere.process_string("attack at dawn"), "LXFOPVEFRNHR");
+        assert_eq!(vigenere.snapshot(), VigenereState { key_char: 'M', index: 2 });
+        let mut back = Vigenere::new(VigenereConfig { decrypt: true, ..config }).unwrap();
+        let steps = back.process_string_detailed("LXF");
+        assert_eq!(steps[1], VigenereStep { input_char: 'X', output_char: 'T', key_char: 'E', shift: 4 });
+        assert!(Vigenere::new(VigenereConfig { key: "123".into(), decrypt: false }).is_err());
+    }
+
+    #[test]
+    fn test_playfair_classic_example() {
+        let config = PlayfairConfig { key: "playfair example".into(), decrypt: false };
+        let mut playfair = Playfair::new(config.clone());
+        assert_eq!(playfair.snapshot(), "PLAYFIREXMBCDGHKNOQSTUVWZ");
+        let ciphertext = playfair.process_string("Hide the gold in the tree stump");
+        assert_eq!(ciphertext, "BMODZBXDNABEKUDMUIXMMOUVIF");
+
+        let steps = playfair.process_string_detailed("HIEE");
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[1].input, ['E', 'X']);
+        assert_eq!(steps[1].rule, PlayfairRule::SameRow);
+
+        let mut back = Playfair::new(PlayfairConfig { decrypt: true, ..config });
+        assert_eq!(back.process_string(&ciphertext), "HIDETHEGOLDINTHETREXESTUMP");
+    }
+}
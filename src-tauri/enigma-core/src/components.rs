@@ -0,0 +1,461 @@
+//! Registro das peças que a máquina sabe montar: os rotores e refletores embutidos
+//! e os que vêm de pacotes de peças (`ComponentPack`) carregados em tempo de
+//! execução, para quem quer experimentar variantes sem recompilar.
+//!
+//! As peças embutidas não podem ser substituídas: o autoteste e os ataques contam
+//! com as fiações históricas. Sem a feature `std` só existem as embutidas.
+//!
+//! Cada peça e modelo tem também dados históricos (`HistoricalInfo`) para os
+//! painéis de informação da UI: os das embutidas ficam aqui; os dos pacotes vêm no
+//! próprio JSON.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "std")]
+use crate::{char_to_u8, engine::LATIN};
+use crate::{
+    KNOWN_REFLECTOR_NAMES, KNOWN_ROTOR_NAMES, REFLECTOR_A_WIRING, REFLECTOR_B_WIRING, REFLECTOR_C_WIRING,
+    ROTOR_III_NOTCH, ROTOR_III_WIRING, ROTOR_II_NOTCH, ROTOR_II_WIRING, ROTOR_IV_NOTCH, ROTOR_IV_WIRING,
+    ROTOR_I_NOTCH, ROTOR_I_WIRING, ROTOR_V_NOTCH, ROTOR_V_WIRING,
+};
+
+/// Dados históricos de uma peça ou de um modelo. Tudo é opcional: as peças de
+/// pacotes só trazem o que o autor preencheu.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct HistoricalInfo {
+    /// Ano em que entrou em uso.
+    pub introduced: Option<u16>,
+    /// Forças que o usavam (ex.: "Heer, Luftwaffe e Kriegsmarine").
+    pub service: Option<String>,
+    /// Curiosidades, uma por item.
+    pub trivia: Vec<String>,
+}
+
+/// Uma peça registrada com os dados históricos.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ComponentDetails {
+    pub name: String,
+    /// Letras das ranhuras, na janela (vazio nos refletores).
+    pub notches: String,
+    pub info: HistoricalInfo,
+}
+
+/// Ano, forças e curiosidade dos rotores embutidos.
+const ROTOR_HISTORY: [(&str, u16, &str, &str); 5] = [
+    ("I", 1930, "Heer, Luftwaffe e Kriegsmarine", "Com o II e o III, formava o jogo original de rotores da Enigma I."),
+    (
+        "II",
+        1930,
+        "Heer, Luftwaffe e Kriegsmarine",
+        "Em Bletchley Park, as ranhuras dos rotores I a V (Q, E, V, J, Z) eram decoradas com a frase \
+         \"Royal Flags Wave Kings Above\".",
+    ),
+    (
+        "III",
+        1930,
+        "Heer, Luftwaffe e Kriegsmarine",
+        "Na direita, faz o rotor do meio girar quando a janela passa de V para W.",
+    ),
+    (
+        "IV",
+        1938,
+        "Heer, Luftwaffe e Kriegsmarine",
+        "Chegou em dezembro de 1938 com o V: as ordens possíveis dos rotores passaram de 6 para 60.",
+    ),
+    ("V", 1938, "Heer, Luftwaffe e Kriegsmarine", "Com ele, a caixa passou a ter cinco rotores para escolher três."),
+];
+
+/// Ano, forças e curiosidade dos refletores embutidos.
+const REFLECTOR_HISTORY: [(&str, u16, &str, &str); 3] = [
+    ("A", 1930, "Heer e Luftwaffe", "Foi trocado pelo refletor B em novembro de 1937."),
+    ("B", 1937, "Heer, Luftwaffe e Kriegsmarine", "O refletor mais usado durante a guerra."),
+    ("C", 1940, "Heer e Luftwaffe", "Usado por pouco tempo, entre 1940 e 1941."),
+];
+
+fn builtin_info(history: &[(&str, u16, &str, &str)], name: &str) -> Option<HistoricalInfo> {
+    let &(_, year, service, trivia) = history.iter().find(|(known, ..)| *known == name)?;
+    Some(HistoricalInfo {
+        introduced: Some(year),
+        service: Some(service.to_string()),
+        trivia: alloc::vec![trivia.to_string()],
+    })
+}
+
+/// Um rotor de um pacote.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RotorSpec {
+    /// Nome usado em `RotorConfig::name` (ex.: "VI").
+    pub name: String,
+    /// Letra de saída de cada contato, de A a Z (ex.: "JPGVOUMFYQBENHZRDKASXLICTW").
+    pub wiring: String,
+    /// Letra da janela em que o rotor aciona o vizinho. Um só entalhe por rotor:
+    /// o passo é o do M3.
+    pub notch: char,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub info: HistoricalInfo,
+}
+
+/// Um refletor de um pacote.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReflectorSpec {
+    pub name: String,
+    /// Fiação em pares: cada letra vai para outra, que volta para ela.
+    pub wiring: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub info: HistoricalInfo,
+}
+
+/// Um modelo de máquina: a caixa de rotores e os refletores que vinham com ele.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ModelDefinition {
+    pub name: String,
+    pub rotors: Vec<String>,
+    pub reflectors: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub info: HistoricalInfo,
+}
+
+/// Pacote de peças, lido de um JSON. Listas ausentes contam como vazias.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct ComponentPack {
+    pub name: String,
+    pub rotors: Vec<RotorSpec>,
+    pub reflectors: Vec<ReflectorSpec>,
+    pub models: Vec<ModelDefinition>,
+}
+
+/// Fiação e entalhe de um rotor, com o nome canônico.
+pub(crate) type RotorParts = ([u8; 26], u8, &'static str);
+
+#[cfg(feature = "std")]
+struct Extras {
+    rotors: alloc::collections::BTreeMap<&'static str, ([u8; 26], u8)>,
+    reflectors: alloc::collections::BTreeMap<&'static str, [u8; 26]>,
+    models: alloc::collections::BTreeMap<String, ModelDefinition>,
+    rotor_info: alloc::collections::BTreeMap<&'static str, HistoricalInfo>,
+    reflector_info: alloc::collections::BTreeMap<&'static str, HistoricalInfo>,
+}
+
+#[cfg(feature = "std")]
+static EXTRAS: std::sync::RwLock<Extras> = std::sync::RwLock::new(Extras {
+    rotors: alloc::collections::BTreeMap::new(),
+    reflectors: alloc::collections::BTreeMap::new(),
+    models: alloc::collections::BTreeMap::new(),
+    rotor_info: alloc::collections::BTreeMap::new(),
+    reflector_info: alloc::collections::BTreeMap::new(),
+});
+
+#[cfg(feature = "std")]
+fn extras() -> std::sync::RwLockReadGuard<'static, Extras> {
+    EXTRAS.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Fiação, entalhe e nome canônico do rotor `name` (sem diferenciar maiúsculas).
+pub(crate) fn rotor_parts(name: &str) -> Option<RotorParts> {
+    let builtin = match name.to_ascii_uppercase().as_str() {
+        "I" => Some((ROTOR_I_WIRING, ROTOR_I_NOTCH, "I")),
+        "II" => Some((ROTOR_II_WIRING, ROTOR_II_NOTCH, "II")),
+        "III" => Some((ROTOR_III_WIRING, ROTOR_III_NOTCH, "III")),
+        "IV" => Some((ROTOR_IV_WIRING, ROTOR_IV_NOTCH, "IV")),
+        "V" => Some((ROTOR_V_WIRING, ROTOR_V_NOTCH, "V")),
+        _ => None,
+    };
+    #[cfg(feature = "std")]
+    let builtin = builtin.or_else(|| {
+        let extras = extras();
+        let (name, (wiring, notch)) = extras.rotors.iter().find(|(known, _)| known.eq_ignore_ascii_case(name))?;
+        Some((*wiring, *notch, *name))
+    });
+    builtin
+}
+
+/// Fiação e nome canônico do refletor `name` (sem diferenciar maiúsculas).
+pub(crate) fn reflector_parts(name: &str) -> Option<([u8; 26], &'static str)> {
+    let builtin = match name.to_ascii_uppercase().as_str() {
+        "A" => Some((REFLECTOR_A_WIRING, "A")),
+        "B" => Some((REFLECTOR_B_WIRING, "B")),
+        "C" => Some((REFLECTOR_C_WIRING, "C")),
+        _ => None,
+    };
+    #[cfg(feature = "std")]
+    let builtin = builtin.or_else(|| {
+        let extras = extras();
+        let (name, wiring) = extras.reflectors.iter().find(|(known, _)| known.eq_ignore_ascii_case(name))?;
+        Some((*wiring, *name))
+    });
+    builtin
+}
+
+/// Nome canônico do rotor `name`, se a máquina sabe montá-lo.
+pub fn rotor_name(name: &str) -> Option<&'static str> {
+    rotor_parts(name).map(|(_, _, name)| name)
+}
+
+/// Nome canônico do refletor `name`, se a máquina sabe montá-lo.
+pub fn reflector_name(name: &str) -> Option<&'static str> {
+    reflector_parts(name).map(|(_, name)| name)
+}
+
+/// Todos os rotores: os embutidos e depois os dos pacotes, em ordem alfabética.
+pub fn rotor_names() -> Vec<&'static str> {
+    let names = KNOWN_ROTOR_NAMES.to_vec();
+    #[cfg(feature = "std")]
+    let names = [names, extras().rotors.keys().copied().collect()].concat();
+    names
+}
+
+/// Todos os refletores: os embutidos e depois os dos pacotes.
+pub fn reflector_names() -> Vec<&'static str> {
+    let names = KNOWN_REFLECTOR_NAMES.to_vec();
+    #[cfg(feature = "std")]
+    let names = [names, extras().reflectors.keys().copied().collect()].concat();
+    names
+}
+
+/// Modelos registrados pelos pacotes, em ordem alfabética.
+pub fn models() -> Vec<ModelDefinition> {
+    #[cfg(feature = "std")]
+    {
+        extras().models.values().cloned().collect()
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        Vec::new()
+    }
+}
+
+/// Rotor `name` com a ranhura e os dados históricos.
+pub fn rotor_details(name: &str) -> Option<ComponentDetails> {
+    let (_, notch, name) = rotor_parts(name)?;
+    let info = builtin_info(&ROTOR_HISTORY, name);
+    #[cfg(feature = "std")]
+    let info = info.or_else(|| extras().rotor_info.get(name).cloned());
+    Some(ComponentDetails {
+        name: name.to_string(),
+        notches: crate::u8_to_char(notch).to_string(),
+        info: info.unwrap_or_default(),
+    })
+}
+
+/// Refletor `name` com os dados históricos.
+pub fn reflector_details(name: &str) -> Option<ComponentDetails> {
+    let (_, name) = reflector_parts(name)?;
+    let info = builtin_info(&REFLECTOR_HISTORY, name);
+    #[cfg(feature = "std")]
+    let info = info.or_else(|| extras().reflector_info.get(name).cloned());
+    Some(ComponentDetails { name: name.to_string(), notches: String::new(), info: info.unwrap_or_default() })
+}
+
+/// Confere um nome de peça. O ponto e os dois-pontos separam os campos do código
+/// curto (`EnigmaConfig::to_share_code`), e as barras não entram porque o nome vai
+/// para nomes de arquivo (como o catálogo do ciclômetro de um refletor).
+#[cfg(feature = "std")]
+fn check_name(kind: &str, name: &str) -> Result<(), String> {
+    if name.is_empty() || name.chars().any(|c| c.is_whitespace() || matches!(c, '.' | ':' | '/' | '\\')) {
+        return Err(format!("Nome de {} inválido: \"{}\"", kind, name));
+    }
+    Ok(())
+}
+
+/// Lê a fiação de um refletor, que deve trocar letras aos pares sem deixar
+/// nenhuma no lugar.
+#[cfg(feature = "std")]
+fn reflector_wiring(spec: &ReflectorSpec) -> Result<[u8; 26], String> {
+    let wiring = LATIN.wiring(&spec.wiring.to_ascii_uppercase())?;
+    if wiring.iter().enumerate().any(|(i, &j)| j as usize == i || wiring[j as usize] as usize != i) {
+        return Err(format!("O refletor {} deve trocar as letras aos pares, sem deixar nenhuma no lugar.", spec.name));
+    }
+    Ok(wiring)
+}
+
+/// Registra as peças e os modelos de `pack`. O pacote entra inteiro ou não entra:
+/// qualquer peça inválida recusa todas. Uma peça com o nome de outra de um pacote
+/// anterior a substitui; com o nome de uma embutida, é recusada.
+///
+/// Os nomes ficam na memória até o app fechar (`Rotor::name` é `&'static str`):
+/// os pacotes são carregados uma vez, ao abrir.
+#[cfg(feature = "std")]
+pub fn register_pack(pack: &ComponentPack) -> Result<(), String> {
+    let mut rotors = Vec::new();
+    for spec in &pack.rotors {
+        let name = spec.name.to_ascii_uppercase();
+        check_name("rotor", &name)?;
+        if KNOWN_ROTOR_NAMES.contains(&name.as_str()) {
+            return Err(format!("O rotor {} é embutido e não pode ser substituído.", name));
+        }
+        let notch = spec.notch.to_ascii_uppercase();
+        if !notch.is_ascii_uppercase() {
+            return Err(format!("O entalhe do rotor {} deve ser uma letra de A a Z.", name));
+        }
+        let wiring = LATIN.wiring(&spec.wiring.to_ascii_uppercase())?;
+        rotors.push((name, wiring, char_to_u8(notch), spec.info.clone()));
+    }
+    let mut reflectors = Vec::new();
+    for spec in &pack.reflectors {
+        let name = spec.name.to_ascii_uppercase();
+        check_name("refletor", &name)?;
+        if KNOWN_REFLECTOR_NAMES.contains(&name.as_str()) {
+            return Err(format!("O refletor {} é embutido e não pode ser substituído.", name));
+        }
+        reflectors.push((name, reflector_wiring(spec)?, spec.info.clone()));
+    }
+
+    let mut extras = EXTRAS.write().unwrap_or_else(|e| e.into_inner());
+    let mut models = Vec::new();
+    for model in &pack.models {
+        let rotor_known = |name: &String| {
+            rotors.iter().any(|(known, ..)| known.eq_ignore_ascii_case(name))
+                || KNOWN_ROTOR_NAMES.iter().any(|known| known.eq_ignore_ascii_case(name))
+                || extras.rotors.keys().any(|known| known.eq_ignore_ascii_case(name))
+        };
+        let reflector_known = |name: &String| {
+            reflectors.iter().any(|(known, ..)| known.eq_ignore_ascii_case(name))
+                || KNOWN_REFLECTOR_NAMES.iter().any(|known| known.eq_ignore_ascii_case(name))
+                || extras.reflectors.keys().any(|known| known.eq_ignore_ascii_case(name))
+        };
+        if model.rotors.len() < 3 || model.reflectors.is_empty() {
+            return Err(format!("O modelo {} precisa de três rotores e um refletor.", model.name));
+        }
+        if let Some(name) = model.rotors.iter().find(|name| !rotor_known(name)) {
+            return Err(format!("Rotor desconhecido no modelo {}: {}", model.name, name));
+        }
+        if let Some(name) = model.reflectors.iter().find(|name| !reflector_known(name)) {
+            return Err(format!("Refletor desconhecido no modelo {}: {}", model.name, name));
+        }
+        let upper = |names: &Vec<String>| names.iter().map(|name| name.to_ascii_uppercase()).collect();
+        models.push(ModelDefinition {
+            name: model.name.clone(),
+            rotors: upper(&model.rotors),
+            reflectors: upper(&model.reflectors),
+            info: model.info.clone(),
+        });
+    }
+
+    for (name, wiring, notch, info) in rotors {
+        let key = extras.rotors.keys().copied().find(|known| *known == name).unwrap_or_else(|| leak(name));
+        extras.rotors.insert(key, (wiring, notch));
+        extras.rotor_info.insert(key, info);
+    }
+    for (name, wiring, info) in reflectors {
+        let key = extras.reflectors.keys().copied().find(|known| *known == name).unwrap_or_else(|| leak(name));
+        extras.reflectors.insert(key, wiring);
+        extras.reflector_info.insert(key, info);
+    }
+    for model in models {
+        extras.models.insert(model.name.clone(), model);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+fn leak(name: String) -> &'static str {
+    alloc::boxed::Box::leak(name.into_boxed_str())
+}
+
+impl ComponentPack {
+    /// Descrição curta do conteúdo ("2 rotores, 1 refletor, 1 modelo").
+    pub fn summary(&self) -> String {
+        let count = |n: usize, one: &str, many: &str| format!("{} {}", n, if n == 1 { one } else { many });
+        [
+            count(self.rotors.len(), "rotor", "rotores"),
+            count(self.reflectors.len(), "refletor", "refletores"),
+            count(self.models.len(), "modelo", "modelos"),
+        ]
+        .join(", ")
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use crate::{EnigmaConfig, EnigmaMachine};
+
+    fn pack() -> ComponentPack {
+        ComponentPack {
+            name: "Kriegsmarine".to_string(),
+            rotors: alloc::vec![RotorSpec {
+                name: "vi".to_string(),
+                wiring: "JPGVOUMFYQBENHZRDKASXLICTW".to_string(),
+                notch: 'z',
+                info: HistoricalInfo { introduced: Some(1939), ..Default::default() },
+            }],
+            reflectors: alloc::vec![ReflectorSpec {
+                name: "B-fino".to_string(),
+                wiring: "ENKQAUYWJICOPBLMDXZVFTHRGS".to_string(),
+                info: HistoricalInfo::default(),
+            }],
+            models: alloc::vec![ModelDefinition {
+                name: "M4 (Kriegsmarine)".to_string(),
+                rotors: ["I", "II", "III", "IV", "V", "VI"].iter().map(|name| name.to_string()).collect(),
+                reflectors: alloc::vec!["b-fino".to_string()],
+                info: HistoricalInfo { service: Some("Kriegsmarine".to_string()), ..Default::default() },
+            }],
+        }
+    }
+
+    #[test]
+    fn test_registered_pack_builds_machines() {
+        register_pack(&pack()).unwrap();
+        assert_eq!(rotor_name("Vi"), Some("VI"));
+        assert!(rotor_names().contains(&"VI"));
+        assert_eq!(reflector_name("b-fino"), Some("B-FINO"));
+        let model = models().into_iter().find(|model| model.name == "M4 (Kriegsmarine)").unwrap();
+        assert_eq!(model.reflectors, ["B-FINO"]);
+        assert_eq!(model.info.service.as_deref(), Some("Kriegsmarine"));
+        let vi = rotor_details("vi").unwrap();
+        assert_eq!((vi.notches.as_str(), vi.info.introduced), ("Z", Some(1939)));
+        assert_eq!(reflector_details("B-FINO").unwrap().info, HistoricalInfo::default());
+
+        let config = EnigmaConfig::from_share_code("ENIGMA:VI.II.I:B-FINO:AAA:AAA:").unwrap().normalized().unwrap();
+        let ciphertext = EnigmaMachine::new(config.clone()).process_string("HELLO");
+        assert_eq!(EnigmaMachine::new(config).process_string(&ciphertext), "HELLO");
+        assert_eq!(pack().summary(), "1 rotor, 1 refletor, 1 modelo");
+    }
+
+    #[test]
+    fn test_builtin_details_carry_history() {
+        let iv = rotor_details("iv").unwrap();
+        assert_eq!((iv.name.as_str(), iv.notches.as_str(), iv.info.introduced), ("IV", "J", Some(1938)));
+        assert!(iv.info.trivia[0].contains("60"));
+        let b = reflector_details("B").unwrap();
+        assert_eq!((b.notches.as_str(), b.info.introduced), ("", Some(1937)));
+        assert!(rotor_details("IX").is_none());
+    }
+
+    #[test]
+    fn test_invalid_packs_are_rejected_whole() {
+        let mut builtin = pack();
+        builtin.rotors[0].name = "III".to_string();
+        assert!(register_pack(&builtin).is_err());
+
+        let mut bad_reflector = pack();
+        bad_reflector.rotors[0].name = "VII".to_string();
+        bad_reflector.reflectors[0].wiring = "EKMFLGDQVZNTOWYHXUSPAIBRCJ".to_string();
+        assert!(register_pack(&bad_reflector).is_err());
+        assert_eq!(rotor_name("VII"), None);
+
+        let mut bad_model = pack();
+        bad_model.rotors[0].name = "VIII".to_string();
+        bad_model.models[0].rotors[5] = "IX".to_string();
+        assert!(register_pack(&bad_model).unwrap_err().contains("IX"));
+        assert_eq!(rotor_name("VIII"), None);
+
+        let mut bad_name = pack();
+        bad_name.rotors[0].name = "V.I".to_string();
+        assert!(register_pack(&bad_name).is_err());
+        bad_name.rotors[0].name = "VI".to_string();
+        bad_name.reflectors[0].name = "../UKW".to_string();
+        assert!(register_pack(&bad_name).unwrap_err().contains("../UKW"));
+    }
+}
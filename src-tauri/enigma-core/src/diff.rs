@@ -0,0 +1,103 @@
+//! Duas máquinas teclando o mesmo texto lado a lado, para mostrar onde uma chave
+//! quase certa deixa de decifrar (um anel errado, por exemplo, só aparece quando
+//! o rotor do meio gira fora de hora).
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::{EnigmaConfig, EnigmaMachine};
+
+/// Uma tecla nas duas máquinas.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct DiffStep {
+    pub input_char: char,
+    pub output_a: char,
+    pub output_b: char,
+    /// Posições (Esquerda, Meio, Direita) de cada máquina *depois* da tecla.
+    pub positions_a: (char, char, char),
+    pub positions_b: (char, char, char),
+    /// As duas máquinas acenderam a mesma lâmpada.
+    pub same: bool,
+}
+
+/// Resultado de `diff_traces`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct TraceDiff {
+    pub output_a: String,
+    pub output_b: String,
+    pub steps: Vec<DiffStep>,
+    /// Primeira tecla (a partir de 0) em que as saídas diferem, se houver.
+    pub first_divergence: Option<usize>,
+    /// Quantas teclas deram a mesma saída nas duas máquinas.
+    pub matching: usize,
+}
+
+/// Tecla `text` nas máquinas `config_a` e `config_b` ao mesmo tempo e alinha as
+/// saídas letra a letra. Ignora caracteres não alfabéticos, como `encrypt_iter`.
+pub fn diff_traces(config_a: EnigmaConfig, config_b: EnigmaConfig, text: &str) -> Result<TraceDiff, String> {
+    let config_a = config_a.normalized().map_err(|e| format!("Máquina A: {}", e))?;
+    let config_b = config_b.normalized().map_err(|e| format!("Máquina B: {}", e))?;
+    let mut machine_a = EnigmaMachine::new(config_a);
+    let mut machine_b = EnigmaMachine::new(config_b);
+
+    let steps: Vec<DiffStep> = machine_a
+        .encrypt_iter_detailed(text.chars())
+        .zip(machine_b.encrypt_iter_detailed(text.chars()))
+        .map(|(a, b)| DiffStep {
+            input_char: a.input_char,
+            output_a: a.output_char,
+            output_b: b.output_char,
+            positions_a: a.positions_after_step,
+            positions_b: b.positions_after_step,
+            same: a.output_char == b.output_char,
+        })
+        .collect();
+    Ok(TraceDiff {
+        output_a: steps.iter().map(|step| step.output_a).collect(),
+        output_b: steps.iter().map(|step| step.output_b).collect(),
+        first_divergence: steps.iter().position(|step| !step.same),
+        matching: steps.iter().filter(|step| step.same).count(),
+        steps,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(code: &str) -> EnigmaConfig {
+        EnigmaConfig::from_share_code(code).unwrap()
+    }
+
+    #[test]
+    fn test_wrong_ring_diverges_at_the_turnover() {
+        // Anel e posição do rotor da direita adiantados juntos: a fiação fica no
+        // mesmo lugar, mas o entalhe chega uma tecla antes.
+        let text = "A".repeat(30);
+        let diff = diff_traces(config("ENIGMA:I.II.III:B:AAA:AAA:"), config("ENIGMA:I.II.III:B:AAB:AAB:"), &text)
+            .unwrap();
+        assert_eq!(diff.steps.len(), 30);
+        assert_eq!(diff.first_divergence, Some(20));
+        assert!(diff.steps[..20].iter().all(|step| step.same));
+        assert_eq!(diff.steps[20].positions_a, ('A', 'A', 'V'));
+        assert_eq!(diff.steps[20].positions_b, ('A', 'B', 'W'));
+        assert_eq!(&diff.output_a[..20], &diff.output_b[..20]);
+        assert_eq!(diff.matching, diff.steps.iter().filter(|step| step.same).count());
+    }
+
+    #[test]
+    fn test_same_key_never_diverges() {
+        let a = config("ENIGMA:II.IV.V:B:BLA:BUL:AVBSCGDLFUHZINKMOWRX");
+        let diff = diff_traces(a.clone(), a, "Feind in Sicht!").unwrap();
+        assert_eq!(diff.first_divergence, None);
+        assert_eq!(diff.matching, 12);
+        let mut bad = config("ENIGMA:I.II.III:B:AAA:AAA:");
+        bad.reflector = "Z".into();
+        assert!(diff_traces(config("ENIGMA:I.II.III:B:AAA:AAA:"), bad, "A").unwrap_err().starts_with("Máquina B"));
+    }
+}
@@ -0,0 +1,272 @@
+//! Peças de uma máquina de rotores com `N` símbolos.
+//!
+//! Rotores, refletor e plugboard trabalham com índices 0..N; o `Alphabet` converte
+//! entre índices e os símbolos do teclado. A Enigma M3 é a instância com as 26
+//! letras latinas (`LATIN`), e o mesmo motor serve para máquinas com outros
+//! alfabetos (cirílico da Fialka, alfanuméricos didáticos).
+
+use alloc::format;
+use alloc::string::String;
+
+/// Símbolos de uma máquina, na ordem dos contatos.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Alphabet<const N: usize> {
+    symbols: [char; N],
+}
+
+/// As 26 letras da Enigma.
+pub const LATIN: Alphabet<26> = Alphabet::new([
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V',
+    'W', 'X', 'Y', 'Z',
+]);
+
+impl<const N: usize> Alphabet<N> {
+    /// Os índices cabem em `u8`, então o alfabeto tem no máximo 256 símbolos.
+    pub const fn new(symbols: [char; N]) -> Self {
+        assert!(N > 0 && N <= 256, "O alfabeto deve ter de 1 a 256 símbolos.");
+        Self { symbols }
+    }
+
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Índice de `c`, ou `None` se a máquina não tem essa tecla.
+    pub fn index(&self, c: char) -> Option<u8> {
+        self.symbols.iter().position(|&s| s == c).map(|i| i as u8)
+    }
+
+    /// Símbolo do índice `i` (0..N).
+    pub fn symbol(&self, i: u8) -> char {
+        self.symbols[i as usize]
+    }
+
+    /// Lê uma fiação escrita como os símbolos de saída de cada contato (ex.:
+    /// "EKMFLGDQVZNTOWYHXUSPAIBRCJ"), conferindo que é uma permutação.
+    pub fn wiring(&self, spec: &str) -> Result<[u8; N], String> {
+        let mut wiring = [0u8; N];
+        let mut seen = [false; N];
+        let mut count = 0;
+        for c in spec.chars() {
+            let i = self.index(c).ok_or_else(|| format!("Símbolo fora do alfabeto na fiação: {}", c))?;
+            if count == N || seen[i as usize] {
+                return Err(format!("A fiação deve usar cada um dos {} símbolos uma vez: {}", N, spec));
+            }
+            seen[i as usize] = true;
+            wiring[count] = i;
+            count += 1;
+        }
+        if count != N {
+            return Err(format!("A fiação deve usar cada um dos {} símbolos uma vez: {}", N, spec));
+        }
+        Ok(wiring)
+    }
+}
+
+/// Soma módulo `N` de índices 0..N.
+#[inline]
+fn add<const N: usize>(a: u8, b: u8) -> u8 {
+    ((a as usize + b as usize) % N) as u8
+}
+
+/// Subtração módulo `N` de índices 0..N.
+#[inline]
+fn sub<const N: usize>(a: u8, b: u8) -> u8 {
+    ((a as usize + N - b as usize) % N) as u8
+}
+
+/// Fiação de um rotor posto ao contrário na máquina (Typex, SIGABA): o contato `x`
+/// de um lado fica onde estava o `-x` do outro, então `w'(x) = -w⁻¹(-x)`.
+pub fn reversed_wiring<const N: usize>(wiring: &[u8; N]) -> [u8; N] {
+    let mut inverse = [0u8; N];
+    for (i, &output) in wiring.iter().enumerate() {
+        inverse[output as usize] = i as u8;
+    }
+    core::array::from_fn(|x| sub::<N>(0, inverse[sub::<N>(0, x as u8) as usize]))
+}
+
+/// Plugboard (Steckerbrett): troca pares de símbolos, na ida e na volta.
+#[derive(Debug, Clone)]
+pub struct Plugboard<const N: usize> {
+    /// Mapeia um índice para outro; quem não está num par mapeia para si mesmo.
+    map: [u8; N],
+}
+
+impl<const N: usize> Plugboard<N> {
+    /// Cria o plugboard a partir de pares de símbolos (ex: "AB CD"). Símbolos fora
+    /// do alfabeto são ignorados.
+    pub fn new(alphabet: &Alphabet<N>, pairs: &str) -> Self {
+        let mut map: [u8; N] = core::array::from_fn(|i| i as u8);
+        let mut pending = None;
+        for i in pairs.chars().filter_map(|c| alphabet.index(c)) {
+            match pending.take() {
+                None => pending = Some(i),
+                Some(j) => {
+                    map[i as usize] = j;
+                    map[j as usize] = i;
+                }
+            }
+        }
+        Self { map }
+    }
+
+    /// Processa um índice. Como é recíproco, serve para a entrada e para a saída.
+    #[inline]
+    pub fn process(&self, c: u8) -> u8 {
+        self.map[c as usize]
+    }
+}
+
+/// Refletor (Umkehrwalze).
+#[derive(Debug, Clone)]
+pub struct Reflector<const N: usize> {
+    wiring: [u8; N],
+    /// Nome para fins didáticos (ex: "B").
+    pub(crate) name: &'static str,
+}
+
+impl<const N: usize> Reflector<N> {
+    pub fn new(wiring: [u8; N], name: &'static str) -> Self {
+        Self { wiring, name }
+    }
+
+    /// Reflete o sinal.
+    #[inline]
+    pub fn reflect(&self, c: u8) -> u8 {
+        self.wiring[c as usize]
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// Rotor (Walze).
+#[derive(Debug, Clone)]
+pub struct Rotor<const N: usize> {
+    /// Mapeamento de "ida" (direita para esquerda).
+    wiring: [u8; N],
+    /// Mapeamento de "volta" (esquerda para direita).
+    inverse_wiring: [u8; N],
+    /// Posição atual do rotor (0..N).
+    pub(crate) position: u8,
+    /// Configuração do anel (0..N).
+    ring_setting: u8,
+    /// Posição da ranhura (notch) que aciona o próximo rotor.
+    pub(crate) notch: u8,
+    /// Nome para fins didáticos (ex: "I").
+    pub(crate) name: &'static str,
+}
+
+impl<const N: usize> Rotor<N> {
+    /// Cria um rotor com a fiação, a ranhura, a posição inicial e o anel dados
+    /// (índices 0..N).
+    pub fn new(wiring: [u8; N], notch: u8, position: u8, ring_setting: u8, name: &'static str) -> Self {
+        // Calcula o mapeamento inverso (essencial para o caminho de volta)
+        let mut inverse_wiring = [0u8; N];
+        for (i, &output) in wiring.iter().enumerate() {
+            inverse_wiring[output as usize] = i as u8;
+        }
+        Self { wiring, inverse_wiring, position, ring_setting, notch, name }
+    }
+
+    pub fn position(&self) -> u8 {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: u8) {
+        self.position = position;
+    }
+
+    pub fn notch(&self) -> u8 {
+        self.notch
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Retorna se o rotor está atualmente na posição da ranhura.
+    #[inline]
+    pub fn at_notch(&self) -> bool {
+        self.position == self.notch
+    }
+
+    /// Gira o rotor uma posição (módulo N).
+    #[inline]
+    pub fn step(&mut self) {
+        self.position = add::<N>(self.position, 1);
+    }
+
+    /// Mapeia um sinal da direita para a esquerda (ida).
+    #[inline]
+    pub fn forward(&self, c: u8) -> u8 {
+        self.through(&self.wiring, c)
+    }
+
+    /// Mapeia um sinal da esquerda para a direita (volta).
+    #[inline]
+    pub fn backward(&self, c: u8) -> u8 {
+        self.through(&self.inverse_wiring, c)
+    }
+
+    /// Quanto a fiação está girada: a posição menos o anel (0..N).
+    #[inline]
+    pub fn offset(&self) -> u8 {
+        sub::<N>(self.position, self.ring_setting)
+    }
+
+    /// Ajusta a entrada pela posição e pelo anel, passa pelo mapeamento e desfaz o
+    /// ajuste na saída.
+    #[inline]
+    fn through(&self, wiring: &[u8; N], c: u8) -> u8 {
+        let offset = self.offset();
+        sub::<N>(wiring[add::<N>(c, offset) as usize], offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Máquina de brinquedo com 6 símbolos: rotor, refletor e volta.
+    fn scramble(rotor: &Rotor<6>, reflector: &Reflector<6>, c: u8) -> u8 {
+        rotor.backward(reflector.reflect(rotor.forward(c)))
+    }
+
+    #[test]
+    fn test_alphabet_wiring() {
+        assert_eq!(LATIN.index('Q'), Some(16));
+        assert_eq!(LATIN.index('q'), None);
+        assert_eq!(LATIN.symbol(25), 'Z');
+        assert_eq!(LATIN.wiring("EKMFLGDQVZNTOWYHXUSPAIBRCJ").unwrap()[..3], [4, 10, 12]);
+        assert!(LATIN.wiring("EKMF").is_err());
+        assert!(LATIN.wiring("EEMFLGDQVZNTOWYHXUSPAIBRCJ").is_err());
+
+        let digits = Alphabet::new(['0', '1', '2', '3', '4', '5']);
+        assert_eq!(digits.len(), 6);
+        assert_eq!(digits.wiring("310524").unwrap(), [3, 1, 0, 5, 2, 4]);
+        assert!(digits.wiring("31052A").is_err());
+    }
+
+    #[test]
+    fn test_small_alphabet_is_reciprocal() {
+        let digits = Alphabet::new(['0', '1', '2', '3', '4', '5']);
+        let mut rotor = Rotor::new(digits.wiring("310524").unwrap(), 5, 0, 2, "T");
+        let reflector = Reflector::new(digits.wiring("345012").unwrap(), "R");
+        let plugboard = Plugboard::new(&digits, "05");
+        for _ in 0..12 {
+            for c in 0..6 {
+                let out = plugboard.process(scramble(&rotor, &reflector, plugboard.process(c)));
+                assert_ne!(out, c);
+                assert_eq!(plugboard.process(scramble(&rotor, &reflector, plugboard.process(out))), c);
+            }
+            rotor.step();
+        }
+        assert_eq!(rotor.position(), 0);
+    }
+}
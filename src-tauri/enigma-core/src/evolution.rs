@@ -0,0 +1,58 @@
+//! Como o alfabeto de substituição muda de tecla em tecla: uma linha por tecla,
+//! com a lâmpada de cada uma das 26 letras, para a UI desenhar um mapa de calor.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::{EnigmaConfig, EnigmaMachine};
+
+/// Resultado de `mapping_evolution`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct MappingEvolution {
+    /// Posições (Esquerda, Meio, Direita) em cada tecla, depois do passo.
+    pub positions: Vec<(char, char, char)>,
+    /// `rows[k][x]` é a lâmpada (0 a 25) que acende ao teclar a letra `x` na tecla
+    /// `k` (a partir de 0).
+    pub rows: Vec<[u8; 26]>,
+}
+
+/// Alfabetos de substituição das próximas `n` teclas da máquina de `config`, sem
+/// cifrar nada.
+pub fn mapping_evolution(config: EnigmaConfig, n: usize) -> Result<MappingEvolution, String> {
+    let mut machine = EnigmaMachine::new(config.normalized()?);
+    let (positions, rows) = (0..n)
+        .map(|_| {
+            machine.step_rotors();
+            (machine.get_positions(), machine.permutation())
+        })
+        .unzip();
+    Ok(MappingEvolution { positions, rows })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::char_to_u8;
+
+    #[test]
+    fn test_rows_match_the_keypresses() {
+        let config = EnigmaConfig::from_share_code("ENIGMA:I.II.III:B:ADU:AAA:AB").unwrap();
+        let evolution = mapping_evolution(config.clone(), 5).unwrap();
+        assert_eq!(evolution.rows.len(), 5);
+        assert_eq!(evolution.positions[..3], [('A', 'D', 'V'), ('A', 'E', 'W'), ('B', 'F', 'X')]);
+
+        // Coluna da letra K: a mesma sequência de lâmpadas que teclar K cinco vezes
+        let lamps = EnigmaMachine::new(config.clone()).process_string("KKKKK");
+        assert!(lamps.chars().map(char_to_u8).eq(evolution.rows.iter().map(|row| row[10])));
+        assert!(evolution.rows.iter().all(|row| (0..26).all(|x| row[x] != x as u8)));
+        assert_ne!(evolution.rows[0], evolution.rows[1]);
+
+        assert!(mapping_evolution(config.clone(), 0).unwrap().rows.is_empty());
+        let mut bad = config;
+        bad.reflector = "Z".into();
+        assert!(mapping_evolution(bad, 1).is_err());
+    }
+}
@@ -0,0 +1,89 @@
+//! Texto cifrado no formato de transmissão: grupos de letras, linhas e o preâmbulo
+//! com o número de letras.
+//!
+//! A UI (ao copiar o texto) e o `enigma-cli` passam pelo mesmo formatador, para os
+//! dois gerarem exatamente o mesmo texto.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Marcador do preâmbulo trocado pelo número de letras do texto.
+pub const LETTER_COUNT: &str = "{letters}";
+
+/// Como apresentar um texto cifrado. Campos ausentes no JSON ficam no padrão:
+/// grupos de cinco letras numa linha só, sem preâmbulo.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct OutputFormat {
+    /// Letras por grupo (0: sem grupos).
+    pub group_size: usize,
+    /// Grupos por linha (0: tudo numa linha).
+    pub groups_per_line: usize,
+    /// Linha antes do texto (ex.: "1230 = 2tl = 1tl = {letters} ="), com
+    /// `LETTER_COUNT` trocado pelo número de letras.
+    pub preamble: Option<String>,
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        Self { group_size: 5, groups_per_line: 0, preamble: None }
+    }
+}
+
+impl OutputFormat {
+    /// Formata as letras de `text` (em maiúsculas), descartando o resto.
+    pub fn apply(&self, text: &str) -> String {
+        let letters: Vec<char> =
+            text.chars().filter(|c| c.is_ascii_alphabetic()).map(|c| c.to_ascii_uppercase()).collect();
+        let mut output = String::with_capacity(letters.len() * 2);
+        if let Some(preamble) = self.preamble_line(letters.len()) {
+            output.push_str(&preamble);
+        }
+        for (i, letter) in letters.into_iter().enumerate() {
+            output.extend(self.separator(i));
+            output.push(letter);
+        }
+        output
+    }
+
+    /// Linha do preâmbulo (com a quebra de linha) de um texto com `letters` letras.
+    pub fn preamble_line(&self, letters: usize) -> Option<String> {
+        let preamble = self.preamble.as_ref()?;
+        Some(preamble.replace(LETTER_COUNT, &letters.to_string()) + "\n")
+    }
+
+    /// Separador que vai antes da letra de número `index` (a partir de 0): espaço
+    /// entre grupos, quebra de linha entre linhas. Permite formatar aos poucos, sem
+    /// ter o texto inteiro.
+    pub fn separator(&self, index: usize) -> Option<char> {
+        if self.group_size == 0 || index == 0 || !index.is_multiple_of(self.group_size) {
+            return None;
+        }
+        let group = index / self.group_size;
+        Some(if self.groups_per_line > 0 && group.is_multiple_of(self.groups_per_line) { '\n' } else { ' ' })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_groups_and_lines() {
+        assert_eq!(OutputFormat::default().apply("qblt wldp-fsx"), "QBLTW LDPFS X");
+        let format = OutputFormat { group_size: 2, groups_per_line: 3, preamble: None };
+        assert_eq!(format.apply("ABCDEFGHIJKLM"), "AB CD EF\nGH IJ KL\nM");
+        let joined = OutputFormat { group_size: 0, ..OutputFormat::default() };
+        assert_eq!(joined.apply("ab cd"), "ABCD");
+        assert_eq!(OutputFormat::default().apply(""), "");
+    }
+
+    #[test]
+    fn test_preamble_counts_letters() {
+        let preamble = Some("1230 = 2tl = 1tl = {letters} =".to_string());
+        let format = OutputFormat { preamble, ..OutputFormat::default() };
+        assert_eq!(format.apply("QBLTW LDP"), "1230 = 2tl = 1tl = 8 =\nQBLTW LDP");
+    }
+}
@@ -0,0 +1,150 @@
+//! Adaptadores de `std::io` que cifram fluxos de bytes na passagem.
+//!
+//! Permitem montar pipelines de arquivos e sockets sem carregar o texto todo na
+//! memória. Como em `EnigmaMachine::process_string`, só as letras passam (em
+//! maiúscula); espaços, quebras de linha e outros bytes são descartados.
+
+use std::io::{self, Read, Write};
+
+use crate::EnigmaMachine;
+
+/// Troca as letras ASCII de `buf` por 0-25, juntando-as no início, e retorna quantas são.
+fn compact_letters(buf: &mut [u8]) -> usize {
+    let mut letters = 0;
+    for i in 0..buf.len() {
+        if buf[i].is_ascii_alphabetic() {
+            buf[letters] = buf[i].to_ascii_uppercase() - b'A';
+            letters += 1;
+        }
+    }
+    letters
+}
+
+/// Cifra letras 0-25 no lugar e as devolve como texto A-Z.
+fn encrypt_letters(machine: &mut EnigmaMachine, letters: &mut [u8]) {
+    machine.process_bytes(letters);
+    for c in letters.iter_mut() {
+        *c += b'A';
+    }
+}
+
+/// Cifra tudo o que é escrito e repassa o resultado para `W`.
+pub struct EnigmaWriter<W: Write> {
+    inner: W,
+    machine: EnigmaMachine,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> EnigmaWriter<W> {
+    pub fn new(inner: W, machine: EnigmaMachine) -> Self {
+        Self { inner, machine, buffer: Vec::new() }
+    }
+
+    /// Máquina no estado atual (depois das letras já escritas).
+    pub fn machine(&self) -> &EnigmaMachine {
+        &self.machine
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for EnigmaWriter<W> {
+    /// Consome `buf` inteiro. Se `W` falhar, os rotores já terão girado pelas
+    /// letras de `buf`.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.clear();
+        self.buffer.extend_from_slice(buf);
+        let letters = compact_letters(&mut self.buffer);
+        encrypt_letters(&mut self.machine, &mut self.buffer[..letters]);
+        self.inner.write_all(&self.buffer[..letters])?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Lê de `R` e entrega o texto já cifrado.
+pub struct EnigmaReader<R: Read> {
+    inner: R,
+    machine: EnigmaMachine,
+}
+
+impl<R: Read> EnigmaReader<R> {
+    pub fn new(inner: R, machine: EnigmaMachine) -> Self {
+        Self { inner, machine }
+    }
+
+    /// Máquina no estado atual (depois das letras já lidas).
+    pub fn machine(&self) -> &EnigmaMachine {
+        &self.machine
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for EnigmaReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let read = self.inner.read(buf)?;
+            if read == 0 {
+                return Ok(0);
+            }
+            // Um trecho sem letras não pode virar um 0, que indicaria o fim do fluxo
+            let letters = compact_letters(&mut buf[..read]);
+            if letters > 0 {
+                encrypt_letters(&mut self.machine, &mut buf[..letters]);
+                return Ok(letters);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EnigmaConfig, RotorConfig};
+
+    fn machine() -> EnigmaMachine {
+        let rotor = |name: &str, position| RotorConfig { name: name.into(), position, ring: 'A' };
+        EnigmaMachine::new(EnigmaConfig {
+            rotors: (rotor("I", 'Q'), rotor("II", 'E'), rotor("III", 'A')),
+            reflector: "B".into(),
+            plugboard_pairs: "AT BS DE".into(),
+        })
+    }
+
+    #[test]
+    fn test_writer_then_reader_round_trip() {
+        let plain = "Ataque ao amanhecer,\nflanco norte.";
+        let mut writer = EnigmaWriter::new(Vec::new(), machine());
+        for chunk in plain.as_bytes().chunks(5) {
+            writer.write_all(chunk).unwrap();
+        }
+        let cipher = writer.into_inner();
+        assert_eq!(cipher, machine().process_string(plain).into_bytes());
+
+        // Leitor com trechos só de pontuação no meio
+        let spaced: Vec<u8> = cipher.iter().flat_map(|&c| [c, b' ', b'.', b'\n']).collect();
+        let mut reader = EnigmaReader::new(spaced.as_slice(), machine());
+        let mut decrypted = String::new();
+        reader.read_to_string(&mut decrypted).unwrap();
+        assert_eq!(decrypted, "ATAQUEAOAMANHECERFLANCONORTE");
+        let mut typed = machine();
+        typed.process_string(plain);
+        assert_eq!(reader.machine().get_positions(), typed.get_positions());
+    }
+}
@@ -0,0 +1,63 @@
+//! Disposição histórica do teclado e do painel de lâmpadas (QWERTZ, sem a
+//! fileira de números), para todas as interfaces desenharem a mesma máquina.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Fileiras do teclado da Enigma, de cima para baixo. O painel de lâmpadas repete
+/// a mesma disposição.
+pub const KEYBOARD_ROWS: [&str; 3] = ["QWERTZUIO", "ASDFGHJK", "PYXCVBNML"];
+
+/// Uma fileira de teclas ou de lâmpadas.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct KeyRow {
+    /// Letras da esquerda para a direita.
+    pub keys: String,
+    /// Recuo da fileira em larguras de tecla: a do meio, com uma tecla a menos,
+    /// fica centralizada entre as outras.
+    pub offset: f32,
+}
+
+/// Teclado e painel de lâmpadas, de cima para baixo.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct KeyboardLayout {
+    pub keyboard: Vec<KeyRow>,
+    pub lampboard: Vec<KeyRow>,
+}
+
+/// Disposição do teclado e das lâmpadas do M3.
+pub fn keyboard_layout() -> KeyboardLayout {
+    let widest = KEYBOARD_ROWS.iter().map(|row| row.len()).max().unwrap_or_default();
+    let rows: Vec<KeyRow> = KEYBOARD_ROWS
+        .iter()
+        .map(|row| KeyRow { keys: row.to_string(), offset: (widest - row.len()) as f32 / 2.0 })
+        .collect();
+    KeyboardLayout { keyboard: rows.clone(), lampboard: rows }
+}
+
+/// Fileira e coluna (a partir de 0) da tecla `c` no teclado.
+pub fn key_position(c: char) -> Option<(usize, usize)> {
+    let c = c.to_ascii_uppercase();
+    KEYBOARD_ROWS.iter().enumerate().find_map(|(row, keys)| keys.find(c).map(|column| (row, column)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_has_every_letter_once() {
+        let layout = keyboard_layout();
+        let mut letters: Vec<char> = layout.keyboard.iter().flat_map(|row| row.keys.chars()).collect();
+        letters.sort_unstable();
+        assert!(letters.iter().copied().eq('A'..='Z'));
+        assert_eq!(layout.lampboard, layout.keyboard);
+        assert_eq!(layout.keyboard[1].offset, 0.5);
+        assert_eq!(key_position('p'), Some((2, 0)));
+        assert_eq!(key_position('1'), None);
+    }
+}
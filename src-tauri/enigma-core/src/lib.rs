@@ -0,0 +1,1012 @@
+//! Núcleo da simulação da máquina Enigma M3 (usada pelo exército alemão), sem
+//! dependência do Tauri, para ser reaproveitado em scripts e outras interfaces.
+//!
+//! Features:
+//! - `std` (padrão): sem ela o crate é `no_std` e só precisa de `alloc`, para rodar
+//!   em microcontroladores (réplicas físicas da máquina).
+//! - `serde` (padrão): `Serialize`/`Deserialize` nas estruturas trocadas com a UI.
+//! - `parallel` (padrão, requer `std`): `process_bytes_parallel` usa o rayon para
+//!   dividir o texto entre os núcleos; sem ela, os pedaços são cifrados em sequência.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub mod classical;
+pub mod components;
+mod diff;
+pub mod engine;
+mod evolution;
+mod format;
+#[cfg(feature = "std")]
+mod io;
+mod keyboard;
+pub mod lorenz;
+mod machine;
+mod narration;
+mod period;
+mod share;
+pub mod sigaba;
+mod trace;
+pub mod typex;
+mod vectors;
+
+pub use diff::{diff_traces, DiffStep, TraceDiff};
+pub use evolution::{mapping_evolution, MappingEvolution};
+pub use format::{OutputFormat, LETTER_COUNT};
+#[cfg(feature = "std")]
+pub use io::{EnigmaReader, EnigmaWriter};
+pub use keyboard::{key_position, keyboard_layout, KeyRow, KeyboardLayout, KEYBOARD_ROWS};
+use engine::{Plugboard, Reflector, Rotor, LATIN};
+pub use machine::{CipherMachine, EnigmaState};
+pub use narration::Narration;
+pub use period::{repeated_letter, RepeatedLetter};
+pub use trace::{CompactComponent, CompactEntry, CompactStep, Trace};
+pub use vectors::{self_test, VectorResult};
+
+// --- Constantes (Definições de Rotores e Refletores Reais) ---
+
+/// Mapeamento do Rotor I (EKMFLGDQVZNTOWYHXUSPAIBRCJ)
+const ROTOR_I_WIRING: [u8; 26] = [
+    4, 10, 12, 5, 11, 6, 3, 16, 21, 25, 13, 19, 14, 22, 24, 7, 23, 20, 18, 15, 0, 8, 1, 17, 2, 9,
+];
+/// Posição da ranhura (notch) do Rotor I ('Q' -> 16)
+const ROTOR_I_NOTCH: u8 = 16;
+
+/// Mapeamento do Rotor II (AJDKSIRUXBLHWTMCQGZNPYFVOE)
+const ROTOR_II_WIRING: [u8; 26] = [
+    0, 9, 3, 10, 18, 8, 17, 20, 23, 1, 11, 7, 22, 19, 12, 2, 16, 6, 25, 13, 15, 24, 5, 21, 14, 4,
+];
+/// Posição da ranhura (notch) do Rotor II ('E' -> 4)
+const ROTOR_II_NOTCH: u8 = 4;
+
+/// Mapeamento do Rotor III (BDFHJLCPRTXVZNYEIWGAKMUSQO)
+const ROTOR_III_WIRING: [u8; 26] = [
+    1, 3, 5, 7, 9, 11, 2, 15, 17, 19, 23, 21, 25, 13, 24, 4, 8, 22, 6, 0, 10, 12, 20, 18, 16, 14,
+];
+/// Posição da ranhura (notch) do Rotor III ('V' -> 21)
+const ROTOR_III_NOTCH: u8 = 21;
+
+/// Mapeamento do Rotor IV (ESOVPZJAYQUIRHXLNFTGKDCMWB)
+const ROTOR_IV_WIRING: [u8; 26] = [
+    4, 18, 14, 21, 15, 25, 9, 0, 24, 16, 20, 8, 17, 7, 23, 11, 13, 5, 19, 6, 10, 3, 2, 12, 22, 1,
+];
+/// Posição da ranhura (notch) do Rotor IV ('J' -> 9)
+const ROTOR_IV_NOTCH: u8 = 9;
+
+/// Mapeamento do Rotor V (VZBRGITYUPSDNHLXAWMJQOFECK)
+const ROTOR_V_WIRING: [u8; 26] = [
+    21, 25, 1, 17, 6, 8, 19, 24, 20, 15, 18, 3, 13, 7, 11, 23, 0, 22, 12, 9, 16, 14, 5, 4, 2, 10,
+];
+/// Posição da ranhura (notch) do Rotor V ('Z' -> 25)
+const ROTOR_V_NOTCH: u8 = 25;
+
+/// Mapeamento do Refletor A (EJMZALYXVBWFCRQUONTSPIKHGD), usado até 1937
+const REFLECTOR_A_WIRING: [u8; 26] = [
+    4, 9, 12, 25, 0, 11, 24, 23, 21, 1, 22, 5, 2, 17, 16, 20, 14, 13, 19, 18, 15, 8, 10, 7, 6, 3,
+];
+
+/// Mapeamento do Refletor B (YRUHQSLDPXNGOKMIEBFZCWVJAT)
+const REFLECTOR_B_WIRING: [u8; 26] = [
+    24, 17, 20, 7, 16, 18, 11, 3, 15, 23, 13, 6, 14, 10, 12, 8, 4, 1, 5, 25, 2, 22, 21, 9, 0, 19,
+];
+
+/// Mapeamento do Refletor C (FVPJIAOYEDRZXWGCTKUQSBNMHL)
+const REFLECTOR_C_WIRING: [u8; 26] = [
+    5, 21, 15, 9, 8, 0, 14, 24, 4, 3, 17, 25, 23, 22, 6, 2, 19, 10, 20, 16, 18, 1, 13, 12, 7, 11,
+];
+
+/// Tamanho dos pedaços cifrados em paralelo por `process_bytes_parallel`. Textos
+/// menores que isso são cifrados direto.
+const PARALLEL_CHUNK: usize = 1 << 16;
+
+/// Rotores da caixa do simulador. Os ataques percorrem as ordens destes.
+pub const ROTOR_NAMES: [&str; 3] = ["I", "II", "III"];
+
+/// Refletores do simulador. Os ataques testam estes.
+pub const REFLECTOR_NAMES: [&str; 2] = ["B", "C"];
+
+/// Rotores embutidos (nomes aceitos por `RotorConfig::name`): os do simulador e os
+/// IV e V do exército, de 1938, para conferir mensagens históricas. Os pacotes de
+/// peças acrescentam outros (`components`).
+pub const KNOWN_ROTOR_NAMES: [&str; 5] = ["I", "II", "III", "IV", "V"];
+
+/// Refletores embutidos (nomes aceitos por `EnigmaConfig::reflector`), incluindo o
+/// A, anterior a 1937.
+pub const KNOWN_REFLECTOR_NAMES: [&str; 3] = ["A", "B", "C"];
+
+// --- Estruturas de Dados para a UI (Tauri) ---
+
+/// Define a direção do sinal através do componente.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum PathDirection {
+    Forward,
+    Reflect,
+    Backward,
+}
+
+/// Posição de um rotor na máquina.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum RotorSlot {
+    Left,
+    Middle,
+    Right,
+}
+
+/// Componente por onde o sinal passa. Vai para a UI como JSON com a variante em
+/// `type` (ex: `{"type": "Rotor", "slot": "Right", "name": "I"}`).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
+pub enum Component {
+    Plugboard,
+    /// Roda de entrada (Eintrittswalze). No M3 ela não troca letras e por isso
+    /// não aparece no caminho.
+    EntryWheel,
+    Rotor { slot: RotorSlot, name: &'static str },
+    Reflector { name: &'static str },
+}
+
+/// Representa um único passo do sinal elétrico através de um componente.
+/// Ex: (Plugboard, 'A' -> 'G', Forward)
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct PathEntry {
+    /// Componente (ex: Plugboard, Rotor I na direita, Refletor B)
+    pub component: Component,
+    /// Caractere de entrada no componente
+    pub input_char: char,
+    /// Caractere de saída do componente
+    pub output_char: char,
+    /// Contatos físicos (0-25) por onde o sinal entra e sai do componente. Nos
+    /// rotores já contam a posição e o anel (ex.: o rotor I em B recebe o A pelo
+    /// contato 1); nos outros componentes são as próprias letras.
+    pub input_contact: u8,
+    pub output_contact: u8,
+    /// Direção do sinal
+    pub direction: PathDirection,
+    /// O que aconteceu, em dados, para a UI explicar o passo no idioma do usuário
+    pub narration: Narration,
+}
+
+impl PathEntry {
+    /// Passo por `component` de `input` para `output` (índices 0..26). `offset` é
+    /// o deslocamento do rotor (ignorado nos outros componentes).
+    pub(crate) fn new(component: Component, direction: PathDirection, input: u8, output: u8, offset: u8) -> Self {
+        let (input_char, output_char) = (u8_to_char(input), u8_to_char(output));
+        let narration = Narration::new(&component, &direction, input_char, output_char, offset);
+        let offset = if matches!(component, Component::Rotor { .. }) { offset } else { 0 };
+        let (input_contact, output_contact) = ((input + offset) % 26, (output + offset) % 26);
+        Self { component, input_char, output_char, input_contact, output_contact, direction, narration }
+    }
+}
+
+/// Representa o processo completo de criptografia para um único caractere.
+/// Esta estrutura é o que será enviado à UI para visualização didática.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct EncryptionStep {
+    /// Caractere original inserido (ex: 'A')
+    pub input_char: char,
+    /// Caractere final criptografado (ex: 'Z')
+    pub output_char: char,
+    /// Posição dos rotores (Esquerda, Meio, Direita) *antes* do passo.
+    pub positions_before_step: (char, char, char),
+    /// Posição dos rotores (Esquerda, Meio, Direita) *depois* do passo.
+    pub positions_after_step: (char, char, char),
+    /// O caminho detalhado do sinal elétrico através de todos os componentes.
+    pub path: Vec<PathEntry>,
+}
+
+/// Fiação de cada rotor na posição atual, vista do lado da entrada (com a posição
+/// e o anel já aplicados): a letra `i` de cada texto é onde sai o sinal que entra
+/// pelo contato `i` na ida. Serve para a UI desenhar as colunas de fios girando.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct RotorWirings {
+    pub left: String,
+    pub middle: String,
+    pub right: String,
+}
+
+/// Substituição monoalfabética que a próxima tecla vai aplicar: a letra `i` de
+/// `alphabet` é a lâmpada que acende ao teclar a `i`-ésima letra do alfabeto.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct SubstitutionAlphabet {
+    /// Posições (Esquerda, Meio, Direita) em que os rotores estarão ao fechar o
+    /// circuito, já depois do passo.
+    pub positions: (char, char, char),
+    pub alphabet: String,
+}
+
+/// Configuração para um único rotor, vinda da UI.
+/// `Deserialize` permite que o Tauri converta o JSON da UI para esta struct.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RotorConfig {
+    /// Nome do rotor ("I", "II", ou "III")
+    pub name: String,
+    /// Posição inicial do rotor (letra visível na janela, 'A' a 'Z')
+    pub position: char,
+    /// Configuração do anel (Ringstellung, 'A' a 'Z' ou 1 a 26)
+    pub ring: char,
+}
+
+/// Configuração completa da máquina Enigma, vinda da UI.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EnigmaConfig {
+    /// Configuração dos três rotores, da *direita para a esquerda* (Rotor R, M, L).
+    /// A ordem é importante: o primeiro rotor é o que gira a cada tecla.
+    pub rotors: (RotorConfig, RotorConfig, RotorConfig),
+    /// Nome do refletor ("B" ou "C")
+    pub reflector: String,
+    /// Pares do painel de conexões (ex: "AB CD EF")
+    pub plugboard_pairs: String,
+}
+
+impl EnigmaConfig {
+    /// Confere a configuração e a devolve em maiúsculas. `EnigmaMachine::new` entra
+    /// em pânico com nomes desconhecidos ou letras minúsculas; quem recebe a
+    /// configuração de fora (WebAssembly, C) passa por aqui antes.
+    pub fn normalized(mut self) -> Result<Self, String> {
+        for rotor in [&mut self.rotors.0, &mut self.rotors.1, &mut self.rotors.2] {
+            rotor.name = rotor.name.to_ascii_uppercase();
+            rotor.position = rotor.position.to_ascii_uppercase();
+            rotor.ring = rotor.ring.to_ascii_uppercase();
+            match components::rotor_name(&rotor.name) {
+                Some(name) => rotor.name = name.to_string(),
+                None => return Err(format!("Rotor desconhecido: {}", rotor.name)),
+            }
+            if !rotor.position.is_ascii_uppercase() || !rotor.ring.is_ascii_uppercase() {
+                return Err(format!("Posição e anel do rotor {} devem ser letras de A a Z.", rotor.name));
+            }
+        }
+        self.reflector = self.reflector.to_ascii_uppercase();
+        match components::reflector_name(&self.reflector) {
+            Some(name) => self.reflector = name.to_string(),
+            None => return Err(format!("Refletor desconhecido: {}", self.reflector)),
+        }
+        self.plugboard_pairs = self.plugboard_pairs.to_ascii_uppercase();
+        // A máquina junta as letras duas a duas: uma repetida ou sem par deixaria o
+        // plugboard sem ser recíproco, e a mensagem não decifraria de volta
+        let mut plugged = [false; 26];
+        for c in self.plugboard_pairs.chars().filter(|&c| c != ' ') {
+            if !c.is_ascii_uppercase() {
+                return Err("O plugboard só aceita letras de A a Z separadas por espaços.".to_string());
+            }
+            if core::mem::replace(&mut plugged[char_to_u8(c) as usize], true) {
+                return Err(format!("A letra {} aparece mais de uma vez no plugboard.", c));
+            }
+        }
+        if !plugged.iter().filter(|&&p| p).count().is_multiple_of(2) {
+            return Err("O plugboard tem uma letra sem par.".to_string());
+        }
+        Ok(self)
+    }
+}
+
+// --- Peças da Enigma ---
+
+/// Monta um rotor da Enigma a partir da configuração (embutido ou de um pacote).
+fn rotor(config: &RotorConfig) -> Rotor<26> {
+    let (wiring, notch, name) = components::rotor_parts(&config.name)
+        .unwrap_or_else(|| panic!("Rotor desconhecido: {}. Use um de {:?}.", config.name, components::rotor_names()));
+    Rotor::new(wiring, notch, char_to_u8(config.position), char_to_u8(config.ring), name)
+}
+
+/// Monta um refletor da Enigma com base no nome ("A", "B", "C" ou de um pacote).
+fn reflector(name: &str) -> Reflector<26> {
+    let (wiring, name) = components::reflector_parts(name)
+        .unwrap_or_else(|| panic!("Refletor desconhecido: {}. Use um de {:?}.", name, components::reflector_names()));
+    Reflector::new(wiring, name)
+}
+
+/// A máquina Enigma completa, contendo o estado atual.
+#[derive(Debug, Clone)]
+pub struct EnigmaMachine {
+    /// O rotor rápido (direita)
+    rotor_r: Rotor<26>,
+    /// O rotor do meio
+    rotor_m: Rotor<26>,
+    /// O rotor lento (esquerda)
+    rotor_l: Rotor<26>,
+    reflector: Reflector<26>,
+    plugboard: Plugboard<26>,
+}
+
+impl EnigmaMachine {
+    /// Cria uma nova instância da máquina com base na configuração da UI.
+    pub fn new(config: EnigmaConfig) -> Self {
+        Self {
+            // Nota: A ordem na tupla da config é (Direita, Meio, Esquerda)
+            rotor_r: rotor(&config.rotors.0),
+            rotor_m: rotor(&config.rotors.1),
+            rotor_l: rotor(&config.rotors.2),
+            reflector: reflector(&config.reflector),
+            plugboard: Plugboard::new(&LATIN, &config.plugboard_pairs),
+        }
+    }
+
+    /// Retorna as posições atuais dos rotores (L, M, R) como caracteres.
+    pub fn get_positions(&self) -> (char, char, char) {
+        (
+            u8_to_char(self.rotor_l.position),
+            u8_to_char(self.rotor_m.position),
+            u8_to_char(self.rotor_r.position),
+        )
+    }
+
+    /// Implementa a mecânica de passo dos rotores (antes de criptografar).
+    /// Esta é a lógica correta do M3, incluindo o "double-step anomaly".
+    pub fn step_rotors(&mut self) {
+        // 1. Verifica as ranhuras *antes* de qualquer passo.
+        let m_at_notch = self.rotor_m.at_notch();
+        let r_at_notch = self.rotor_r.at_notch();
+
+        // 2. Lógica de passo (M3 Wehrmacht)
+        if m_at_notch {
+            // Se M está na ranhura, M gira E L gira.
+            self.rotor_m.step();
+            self.rotor_l.step();
+        } else if r_at_notch {
+            // Senão, se R está na ranhura, apenas M gira.
+            self.rotor_m.step();
+        }
+
+        // 3. O rotor R (direita) sempre gira.
+        self.rotor_r.step();
+    }
+
+    /// Passa um sinal pelo circuito completo (plugboard, rotores, refletor e volta)
+    /// sem girar os rotores e sem registrar o caminho.
+    pub fn scramble(&self, c: u8) -> u8 {
+        let mut x = self.plugboard.process(c);
+        x = self.rotor_r.forward(x);
+        x = self.rotor_m.forward(x);
+        x = self.rotor_l.forward(x);
+        x = self.reflector.reflect(x);
+        x = self.rotor_l.backward(x);
+        x = self.rotor_m.backward(x);
+        x = self.rotor_r.backward(x);
+        self.plugboard.process(x)
+    }
+
+    /// Permutação composta da parte lenta do circuito: rotor do meio, rotor da
+    /// esquerda, refletor e a volta pelos dois. Só muda quando o rotor do meio ou o
+    /// da esquerda gira, isto é, uma vez a cada 26 teclas na maior parte do tempo.
+    fn inner_table(&self) -> [u8; 26] {
+        core::array::from_fn(|x| {
+            let mut c = self.rotor_m.forward(x as u8);
+            c = self.rotor_l.forward(c);
+            c = self.reflector.reflect(c);
+            c = self.rotor_l.backward(c);
+            self.rotor_m.backward(c)
+        })
+    }
+
+    /// Permutação completa do circuito na posição atual, sem girar os rotores:
+    /// `permutation()[x]` é a letra que sai ao teclar `x`.
+    pub fn permutation(&self) -> [u8; 26] {
+        let inner = self.inner_table();
+        core::array::from_fn(|x| {
+            let c = self.rotor_r.forward(self.plugboard.process(x as u8));
+            self.plugboard.process(self.rotor_r.backward(inner[c as usize]))
+        })
+    }
+
+    /// Alfabeto de substituição da próxima tecla, sem girar os rotores desta
+    /// máquina: muda a cada tecla, o que a torna polialfabética.
+    pub fn next_substitution(&self) -> SubstitutionAlphabet {
+        let mut next = self.clone();
+        next.step_rotors();
+        SubstitutionAlphabet {
+            positions: next.get_positions(),
+            alphabet: next.permutation().iter().map(|&c| u8_to_char(c)).collect(),
+        }
+    }
+
+    /// Fiação atual de cada rotor, vista da entrada.
+    pub fn rotor_wirings(&self) -> RotorWirings {
+        let table = |rotor: &Rotor<26>| (0..26).map(|c| u8_to_char(rotor.forward(c))).collect();
+        RotorWirings { left: table(&self.rotor_l), middle: table(&self.rotor_m), right: table(&self.rotor_r) }
+    }
+
+    /// Letra ligada a cada letra no plugboard, na ordem do alfabeto (ex.: "BACD..."
+    /// com um cabo entre A e B).
+    pub fn plugboard_wiring(&self) -> String {
+        (0..26).map(|c| u8_to_char(self.plugboard.process(c))).collect()
+    }
+
+    /// Letra ligada a cada letra no refletor, na ordem do alfabeto.
+    pub fn reflector_wiring(&self) -> String {
+        (0..26).map(|c| u8_to_char(self.reflector.reflect(c))).collect()
+    }
+
+    /// Permutações completas para cada uma das 26 posições do rotor da direita,
+    /// com os outros rotores parados na posição atual. `[p][x]` é a letra que sai
+    /// ao teclar `x` com o rotor da direita na posição `p`.
+    pub fn right_rotor_sweep(&self) -> [[u8; 26]; 26] {
+        let inner = self.inner_table();
+        let mut rotor = self.rotor_r.clone();
+        core::array::from_fn(|position| {
+            rotor.position = position as u8;
+            core::array::from_fn(|x| {
+                let c = rotor.forward(self.plugboard.process(x as u8));
+                self.plugboard.process(rotor.backward(inner[c as usize]))
+            })
+        })
+    }
+
+    /// Ranhuras do rotor do meio e do da direita (0-25).
+    pub fn notches(&self) -> (u8, u8) {
+        (self.rotor_m.notch, self.rotor_r.notch)
+    }
+
+    /// Caminho rápido para textos longos e ataques: cifra no lugar letras 0-25,
+    /// girando os rotores a cada letra, sem alocar nem montar o caminho. A parte
+    /// lenta do circuito (`inner_table`) só é recalculada quando o rotor do meio
+    /// ou o da esquerda gira.
+    pub fn process_bytes(&mut self, text: &mut [u8]) {
+        let mut slow = (self.rotor_m.position, self.rotor_l.position);
+        let mut inner = self.inner_table();
+        for c in text.iter_mut() {
+            self.step_rotors();
+            if slow != (self.rotor_m.position, self.rotor_l.position) {
+                slow = (self.rotor_m.position, self.rotor_l.position);
+                inner = self.inner_table();
+            }
+            let x = self.rotor_r.forward(self.plugboard.process(*c));
+            *c = self.plugboard.process(self.rotor_r.backward(inner[x as usize]));
+        }
+    }
+
+    /// Como `process_bytes`, mas divide textos grandes em pedaços e cifra os pedaços
+    /// em paralelo. Cada pedaço começa numa cópia da máquina avançada (`advance`)
+    /// até o início dele, então a saída e o estado final são os mesmos da versão
+    /// sequencial.
+    pub fn process_bytes_parallel(&mut self, text: &mut [u8]) {
+        if text.len() <= PARALLEL_CHUNK {
+            return self.process_bytes(text);
+        }
+        let mut starts = Vec::with_capacity(text.len().div_ceil(PARALLEL_CHUNK));
+        for chunk in text.chunks(PARALLEL_CHUNK) {
+            starts.push(self.clone());
+            self.advance(chunk.len());
+        }
+        #[cfg(feature = "parallel")]
+        let chunks = text.par_chunks_mut(PARALLEL_CHUNK).zip(starts.into_par_iter());
+        #[cfg(not(feature = "parallel"))]
+        let chunks = text.chunks_mut(PARALLEL_CHUNK).zip(starts);
+        chunks.for_each(|(chunk, mut machine)| machine.process_bytes(chunk));
+    }
+
+    /// Gira os rotores `steps` vezes sem cifrar nada. Longe das ranhuras só o rotor
+    /// da direita anda, então ele salta direto até a próxima ranhura.
+    pub fn advance(&mut self, mut steps: usize) {
+        while steps > 0 {
+            if self.rotor_m.at_notch() || self.rotor_r.at_notch() {
+                self.step_rotors();
+                steps -= 1;
+            } else {
+                let to_notch = (self.rotor_r.notch + 26 - self.rotor_r.position) % 26;
+                let jump = steps.min(to_notch as usize);
+                self.rotor_r.position = (self.rotor_r.position + jump as u8) % 26;
+                steps -= jump;
+            }
+        }
+    }
+
+    /// Posições (L, M, R) depois de cada uma das próximas `keys` teclas, sem cifrar
+    /// nada: o suficiente para desenhar a linha do tempo dos rotores.
+    pub fn position_timeline(&mut self, keys: usize) -> Vec<(char, char, char)> {
+        (0..keys)
+            .map(|_| {
+                self.step_rotors();
+                self.get_positions()
+            })
+            .collect()
+    }
+
+    /// Processa um único caractere (A-Z) sem montar o caminho detalhado.
+    /// Útil para rotinas que cifram muitos caracteres, como os ataques.
+    pub fn process_char(&mut self, c: char) -> char {
+        self.step_rotors();
+        u8_to_char(self.scramble(char_to_u8(c)))
+    }
+
+    /// Processa um único caractere e retorna o resultado e os passos detalhados.
+    /// Esta é a função central para fins didáticos.
+    pub fn process_char_detailed(&mut self, c: char) -> (char, EncryptionStep) {
+        let input_u8 = char_to_u8(c);
+        let mut path: Vec<PathEntry> = Vec::with_capacity(9);
+
+        let positions_before = self.get_positions();
+
+        // 1. Girar os rotores (ACONTECE ANTES da criptografia)
+        self.step_rotors();
+        let positions_after = self.get_positions();
+
+        let mut current_u8 = input_u8;
+        let mut next_u8;
+
+        // --- Caminho de Ida (Forward) ---
+
+        // 2. Plugboard (Entrada)
+        next_u8 = self.plugboard.process(current_u8);
+        path.push(PathEntry::new(Component::Plugboard, PathDirection::Forward, current_u8, next_u8, 0));
+        current_u8 = next_u8;
+
+        // 3. Rotor R (Direita)
+        next_u8 = self.rotor_r.forward(current_u8);
+        path.push(PathEntry::new(
+            Component::Rotor { slot: RotorSlot::Right, name: self.rotor_r.name },
+            PathDirection::Forward,
+            current_u8,
+            next_u8,
+            self.rotor_r.offset(),
+        ));
+        current_u8 = next_u8;
+
+        // 4. Rotor M (Meio)
+        next_u8 = self.rotor_m.forward(current_u8);
+        path.push(PathEntry::new(
+            Component::Rotor { slot: RotorSlot::Middle, name: self.rotor_m.name },
+            PathDirection::Forward,
+            current_u8,
+            next_u8,
+            self.rotor_m.offset(),
+        ));
+        current_u8 = next_u8;
+
+        // 5. Rotor L (Esquerda)
+        next_u8 = self.rotor_l.forward(current_u8);
+        path.push(PathEntry::new(
+            Component::Rotor { slot: RotorSlot::Left, name: self.rotor_l.name },
+            PathDirection::Forward,
+            current_u8,
+            next_u8,
+            self.rotor_l.offset(),
+        ));
+        current_u8 = next_u8;
+
+        // --- Refletor ---
+
+        // 6. Refletor
+        next_u8 = self.reflector.reflect(current_u8);
+        path.push(PathEntry::new(
+            Component::Reflector { name: self.reflector.name },
+            PathDirection::Reflect,
+            current_u8,
+            next_u8,
+            0,
+        ));
+        current_u8 = next_u8;
+
+        // --- Caminho de Volta (Backward) ---
+
+        // 7. Rotor L (Esquerda)
+        next_u8 = self.rotor_l.backward(current_u8);
+        path.push(PathEntry::new(
+            Component::Rotor { slot: RotorSlot::Left, name: self.rotor_l.name },
+            PathDirection::Backward,
+            current_u8,
+            next_u8,
+            self.rotor_l.offset(),
+        ));
+        current_u8 = next_u8;
+
+        // 8. Rotor M (Meio)
+        next_u8 = self.rotor_m.backward(current_u8);
+        path.push(PathEntry::new(
+            Component::Rotor { slot: RotorSlot::Middle, name: self.rotor_m.name },
+            PathDirection::Backward,
+            current_u8,
+            next_u8,
+            self.rotor_m.offset(),
+        ));
+        current_u8 = next_u8;
+
+        // 9. Rotor R (Direita)
+        next_u8 = self.rotor_r.backward(current_u8);
+        path.push(PathEntry::new(
+            Component::Rotor { slot: RotorSlot::Right, name: self.rotor_r.name },
+            PathDirection::Backward,
+            current_u8,
+            next_u8,
+            self.rotor_r.offset(),
+        ));
+        current_u8 = next_u8;
+
+        // 10. Plugboard (Saída)
+        next_u8 = self.plugboard.process(current_u8);
+        path.push(PathEntry::new(Component::Plugboard, PathDirection::Backward, current_u8, next_u8, 0));
+        
+        let output_char = u8_to_char(next_u8);
+
+        let step_details = EncryptionStep {
+            input_char: c,
+            output_char,
+            positions_before_step: positions_before, // Declarar essas variáveis em algum lugar acima
+            positions_after_step: positions_after, // Declarar essas variáveis em algum lugar acima
+            path,
+        };
+
+        (output_char, step_details)
+    }
+
+    /// Processa uma string completa, retornando apenas o texto final.
+    /// Ignora caracteres não alfabéticos.
+    pub fn process_string(&mut self, text: &str) -> String {
+        let mut letters: Vec<u8> = text
+            .bytes()
+            .filter(|c| c.is_ascii_alphabetic())
+            .map(|c| c.to_ascii_uppercase() - b'A')
+            .collect();
+        self.process_bytes_parallel(&mut letters);
+        letters.into_iter().map(u8_to_char).collect()
+    }
+
+    /// Processa uma string completa, retornando a lista de passos detalhados.
+    /// Ignora caracteres não alfabéticos.
+    pub fn process_string_detailed(&mut self, text: &str) -> Vec<EncryptionStep> {
+        self.encrypt_iter_detailed(text.chars()).collect()
+    }
+
+    /// Cifra `chars` sob demanda: cada letra só é cifrada (e os rotores só giram)
+    /// quando o iterador é consumido. Ignora caracteres não alfabéticos.
+    pub fn encrypt_iter<'a, I>(&'a mut self, chars: I) -> impl Iterator<Item = char> + 'a
+    where
+        I: IntoIterator<Item = char>,
+        I::IntoIter: 'a,
+    {
+        chars
+            .into_iter()
+            .filter(|c| c.is_ascii_alphabetic())
+            .map(move |c| self.process_char(c.to_ascii_uppercase()))
+    }
+
+    /// Como `encrypt_iter`, mas entrega o passo detalhado de cada letra.
+    pub fn encrypt_iter_detailed<'a, I>(&'a mut self, chars: I) -> impl Iterator<Item = EncryptionStep> + 'a
+    where
+        I: IntoIterator<Item = char>,
+        I::IntoIter: 'a,
+    {
+        chars
+            .into_iter()
+            .filter(|c| c.is_ascii_alphabetic())
+            .map(move |c| self.process_char_detailed(c.to_ascii_uppercase()).1)
+    }
+}
+
+// --- Funções Auxiliares (Helpers) ---
+
+/// Converte um caractere (A-Z) para u8 (0-25).
+/// Assume entrada maiúscula e alfabética.
+#[inline]
+pub fn char_to_u8(c: char) -> u8 {
+    c as u8 - b'A'
+}
+
+/// Converte um u8 (0-25) para caractere (A-Z).
+#[inline]
+pub fn u8_to_char(i: u8) -> char {
+    (i + b'A') as char
+}
+
+// --- Testes Unitários ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cria uma configuração padrão para testes (Rotores I, II, III; Refletor B; Posições A-A-A; Anéis A-A-A; Sem Plugboard).
+    fn default_config() -> EnigmaConfig {
+        EnigmaConfig {
+            rotors: (
+                RotorConfig { name: "I".to_string(), position: 'A', ring: 'A' }, // Direita
+                RotorConfig { name: "II".to_string(), position: 'A', ring: 'A' }, // Meio
+                RotorConfig { name: "III".to_string(), position: 'A', ring: 'A' }, // Esquerda
+            ),
+            reflector: "B".to_string(),
+            plugboard_pairs: "".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_char_to_u8_conversions() {
+        assert_eq!(char_to_u8('A'), 0);
+        assert_eq!(char_to_u8('Z'), 25);
+        assert_eq!(u8_to_char(0), 'A');
+        assert_eq!(u8_to_char(25), 'Z');
+    }
+
+    #[test]
+    fn test_plugboard() {
+        let pb = Plugboard::new(&LATIN, "AB XY ZW");
+        assert_eq!(pb.process(char_to_u8('A')), char_to_u8('B'));
+        assert_eq!(pb.process(char_to_u8('B')), char_to_u8('A'));
+        assert_eq!(pb.process(char_to_u8('C')), char_to_u8('C')); // Não mapeado
+        assert_eq!(pb.process(char_to_u8('X')), char_to_u8('Y'));
+    }
+
+    #[test]
+    fn test_rotor_stepping() {
+        let mut cfg = default_config();
+        cfg.rotors.0.position = 'Q'; // Rotor I (Direita)
+        cfg.rotors.1.position = 'E'; // Rotor II (Meio)
+
+        let mut machine = EnigmaMachine::new(cfg);
+
+        // Posição inicial: (III, II, I) -> (A, E, Q)
+        assert_eq!(machine.get_positions(), ('A', 'E', 'Q'));
+
+        // 1. Pressionar a tecla
+        machine.step_rotors();
+        // Rotor I (Direita) gira para R. Ele estava na ranhura (Q),
+        // então o Rotor II (Meio) gira para F.
+        // O Rotor II (Meio) *também* estava na ranhura (E),
+        // então o Rotor III (Esquerda) gira para B.
+        // (Isso testa o double-step)
+        assert_eq!(machine.get_positions(), ('B', 'F', 'R'));
+
+        // 2. Pressionar a tecla novamente
+        machine.step_rotors();
+        // Apenas o Rotor I (Direita) gira (R -> S), pois não estava na ranhura.
+        assert_eq!(machine.get_positions(), ('B', 'F', 'S'));
+    }
+
+    #[test]
+    /// Teste de criptografia/descriptografia (Reciprocidade).
+    /// Criptografar "HELLO" deve dar "MFNCZ".
+    /// Criptografar "MFNCZ" (com a mesma config) deve dar "HELLO".
+    fn test_encryption_reciprocity() {
+        let config = default_config();
+        let mut machine_encrypt = EnigmaMachine::new(config);
+        let encrypted = machine_encrypt.process_string("HELLO");
+        assert_eq!(encrypted, "MFNCZ");
+
+        let config_reset = default_config(); // Reseta a máquina para A-A-A
+        let mut machine_decrypt = EnigmaMachine::new(config_reset);
+        let decrypted = machine_decrypt.process_string("MFNCZ");
+        assert_eq!(decrypted, "HELLO");
+    }
+
+    #[test]
+    /// Teste completo com configuração complexa (posições, anéis, plugboard).
+    fn test_complex_config_encryption() {
+        let config = EnigmaConfig {
+            rotors: (
+                RotorConfig { name: "I".to_string(), position: 'G', ring: 'B' }, // 1
+                RotorConfig { name: "II".to_string(), position: 'O', ring: 'M' }, // 12
+                RotorConfig { name: "III".to_string(), position: 'X', ring: 'V' }, // 21
+            ),
+            reflector: "B".to_string(),
+            plugboard_pairs: "AV BS CG DL FU HZ IN KM OW RX".to_string(),
+        };
+
+        let mut machine = EnigmaMachine::new(config);
+        let text = "HELLOWORLD";
+        let expected = "SPNTMVLLTU"; // Valor de referência conhecido
+        assert_eq!(machine.process_string(text), expected);
+    }
+    
+    #[test]
+    fn test_detailed_steps() {
+        let config = default_config();
+        let mut machine = EnigmaMachine::new(config);
+        let steps = machine.process_string_detailed("A");
+        
+        assert_eq!(steps.len(), 1);
+        let step = &steps[0];
+
+        // 1. Verificações do passo
+        assert_eq!(step.input_char, 'A');
+        assert_eq!(step.output_char, 'F');
+        assert_eq!(step.positions_before_step, ('A', 'A', 'A'));
+        assert_eq!(step.positions_after_step, ('A', 'A', 'B')); // Só o rotor da direita girou
+
+        // 2. Verificações do caminho (path)
+        assert_eq!(step.path.len(), 9); // Plug, R, M, L, Ref, L, M, R, Plug
+        
+        // Pelo menos o refletor deve estar correto (sem plugboard, rotor da direita em B)
+        // R-I(A=0, em B) -> J(9)
+        // R-II(J=9) -> B(1)
+        // R-III(B=1) -> D(3)
+        // Ref-B(D=3) -> H(7)
+        assert_eq!(step.path[0].component, Component::Plugboard);
+        assert_eq!(step.path[1].component, Component::Rotor { slot: RotorSlot::Right, name: "I" });
+        assert_eq!(step.path[4].component, Component::Reflector { name: "B" });
+        assert_eq!(step.path[4].input_char, 'D');
+        assert_eq!(step.path[4].output_char, 'H');
+
+        // Contatos físicos: o rotor I em B recebe o A pelo contato 1 (B), que a
+        // fiação liga ao 10 (K); fora dos rotores, contato e letra coincidem.
+        assert_eq!((step.path[1].input_contact, step.path[1].output_contact), (1, 10));
+        assert_eq!((step.path[4].input_contact, step.path[4].output_contact), (3, 7));
+    }
+
+    #[test]
+    fn test_narration_keys() {
+        let mut config = default_config();
+        config.rotors.0.ring = 'C';
+        config.plugboard_pairs = "AQ".to_string();
+        let step = EnigmaMachine::new(config).process_char_detailed('A').1;
+
+        assert_eq!(step.path[0].narration, Narration::PlugboardSwap { from: 'A', to: 'Q' });
+        // Rotor da direita em B com anel C: deslocamento 1 - 2 = 25
+        assert_eq!(step.path[1].narration, Narration::RotorForward { rotor: "I", slot: RotorSlot::Right, offset: 25 });
+        assert_eq!(step.path[4].narration, Narration::Reflect { reflector: "B" });
+        assert_eq!(step.path[5].narration, Narration::RotorBackward { rotor: "III", slot: RotorSlot::Left, offset: 0 });
+        let last = &step.path[8];
+        let expected = if last.input_char == last.output_char {
+            Narration::PlugboardPass { letter: last.input_char }
+        } else {
+            Narration::PlugboardSwap { from: last.input_char, to: last.output_char }
+        };
+        assert_eq!(last.narration, expected);
+    }
+
+    #[test]
+    fn test_fast_path_matches_detailed() {
+        let mut config = default_config();
+        config.rotors.0 = RotorConfig { name: "III".to_string(), position: 'S', ring: 'D' };
+        config.rotors.1 = RotorConfig { name: "II".to_string(), position: 'D', ring: 'K' };
+        config.rotors.2 = RotorConfig { name: "I".to_string(), position: 'Z', ring: 'Q' };
+        config.plugboard_pairs = "AV BS CG DL FU HZ IN KM OW RX".to_string();
+
+        // 2000 teclas passam várias vezes pelas ranhuras, incluindo o passo duplo
+        let plain: Vec<u8> = (0..2000u32).map(|i| (i * 7 % 26) as u8).collect();
+        let mut detailed = EnigmaMachine::new(config.clone());
+        let expected: Vec<u8> =
+            plain.iter().map(|&c| char_to_u8(detailed.process_char_detailed(u8_to_char(c)).0)).collect();
+
+        let mut fast = EnigmaMachine::new(config.clone());
+        let mut text = plain.clone();
+        fast.process_bytes(&mut text);
+        assert_eq!(text, expected);
+        assert_eq!(fast.get_positions(), detailed.get_positions());
+
+        let mut machine = EnigmaMachine::new(config);
+        machine.step_rotors();
+        let permutation = machine.permutation();
+        assert!((0..26).all(|x| permutation[x] == machine.scramble(x as u8)));
+    }
+
+    #[test]
+    fn test_normalized_config() {
+        let mut config = default_config();
+        config.rotors.0.name = "i".to_string();
+        config.rotors.1.position = 'b';
+        config.reflector = "b".to_string();
+        config.plugboard_pairs = "ab cd".to_string();
+        let config = config.normalized().unwrap();
+        assert_eq!(config.rotors.0.name, "I");
+        assert_eq!(config.rotors.1.position, 'B');
+        assert_eq!(config.plugboard_pairs, "AB CD");
+
+        let mut bad = default_config();
+        bad.reflector = "Z".to_string();
+        assert!(bad.normalized().is_err());
+        let mut bad = default_config();
+        bad.plugboard_pairs = "A1".to_string();
+        assert!(bad.normalized().is_err());
+        let mut bad = default_config();
+        bad.plugboard_pairs = "AB AC".to_string();
+        assert!(bad.normalized().is_err());
+        let mut bad = default_config();
+        bad.plugboard_pairs = "ABC".to_string();
+        assert!(bad.normalized().is_err());
+        let mut bad = default_config();
+        bad.rotors.1.name = "IX".to_string();
+        assert!(bad.normalized().is_err());
+    }
+
+    #[test]
+    fn test_advance_matches_stepping() {
+        let mut config = default_config();
+        config.rotors.0.position = 'O';
+        config.rotors.1.position = 'D';
+        for steps in [0, 1, 2, 25, 26, 27, 700, 17_000, 100_003] {
+            let mut stepped = EnigmaMachine::new(config.clone());
+            for _ in 0..steps {
+                stepped.step_rotors();
+            }
+            let mut advanced = EnigmaMachine::new(config.clone());
+            advanced.advance(steps);
+            assert_eq!(advanced.get_positions(), stepped.get_positions(), "{} passos", steps);
+        }
+    }
+
+    #[test]
+    fn test_position_timeline_matches_detailed() {
+        let mut config = default_config();
+        config.rotors.0.position = 'P';
+        config.rotors.1.position = 'D';
+        let timeline = EnigmaMachine::new(config.clone()).position_timeline(30);
+        let steps = EnigmaMachine::new(config).process_string_detailed(&"A".repeat(30));
+        assert!(timeline.iter().eq(steps.iter().map(|step| &step.positions_after_step)));
+        // Passo duplo: o rotor do meio gira em duas teclas seguidas
+        assert_eq!(timeline[..3], [('A', 'D', 'Q'), ('A', 'E', 'R'), ('B', 'F', 'S')]);
+    }
+
+    #[test]
+    fn test_rotor_wirings_follow_position_and_ring() {
+        let mut config = default_config();
+        let wirings = EnigmaMachine::new(config.clone()).rotor_wirings();
+        assert_eq!(wirings.right, "EKMFLGDQVZNTOWYHXUSPAIBRCJ");
+        assert_eq!(wirings.left, "BDFHJLCPRTXVZNYEIWGAKMUSQO");
+        // Girar o rotor e o anel juntos não muda a fiação vista da entrada
+        config.rotors.0.position = 'B';
+        assert_eq!(EnigmaMachine::new(config.clone()).rotor_wirings().right, "JLEKFCPUYMSNVXGWTROZHAQBID");
+        config.rotors.0.ring = 'B';
+        config.plugboard_pairs = "AB".into();
+        let machine = EnigmaMachine::new(config);
+        assert_eq!(machine.rotor_wirings(), wirings);
+        assert!(machine.plugboard_wiring().starts_with("BAC"));
+        assert_eq!(machine.reflector_wiring(), "YRUHQSLDPXNGOKMIEBFZCWVJAT");
+    }
+
+    #[test]
+    fn test_next_substitution_does_not_step() {
+        let mut machine = EnigmaMachine::new(default_config());
+        let next = machine.next_substitution();
+        assert_eq!(next.positions, ('A', 'A', 'B'));
+        assert_eq!(machine.get_positions(), ('A', 'A', 'A'));
+        // Uma involução sem pontos fixos, igual à tecla de verdade
+        for (i, c) in next.alphabet.chars().enumerate() {
+            let letter = u8_to_char(i as u8);
+            assert_ne!(c, letter);
+            assert_eq!(next.alphabet.as_bytes()[char_to_u8(c) as usize] as char, letter);
+            assert_eq!(machine.clone().process_char(letter), c);
+        }
+        assert_eq!(Some(machine.process_char('A')), next.alphabet.chars().next());
+        assert_ne!(machine.next_substitution(), next);
+    }
+
+    #[test]
+    fn test_encrypt_iter_is_lazy() {
+        let mut machine = EnigmaMachine::new(default_config());
+        let mut iter = machine.encrypt_iter("ab-c".chars());
+        assert!(iter.next().is_some());
+        drop(iter);
+        // Só a primeira letra foi consumida
+        assert_eq!(machine.get_positions(), ('A', 'A', 'B'));
+
+        let mut machine = EnigmaMachine::new(default_config());
+        let streamed: String = machine.encrypt_iter("Hello, world".chars()).collect();
+        assert_eq!(streamed, EnigmaMachine::new(default_config()).process_string("HELLOWORLD"));
+
+        let mut machine = EnigmaMachine::new(default_config());
+        let steps: Vec<EncryptionStep> = machine.encrypt_iter_detailed("he!y".chars()).collect();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[2].input_char, 'Y');
+    }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let mut config = default_config();
+        config.plugboard_pairs = "AV BS CG DL".to_string();
+        let plain: Vec<u8> = (0..3 * PARALLEL_CHUNK + 1234).map(|i| (i * 7 % 26) as u8).collect();
+
+        let mut sequential = EnigmaMachine::new(config.clone());
+        let mut expected = plain.clone();
+        sequential.process_bytes(&mut expected);
+
+        let mut parallel = EnigmaMachine::new(config);
+        let mut text = plain;
+        parallel.process_bytes_parallel(&mut text);
+        assert_eq!(text, expected);
+        assert_eq!(parallel.get_positions(), sequential.get_positions());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,361 @@
+//! Lorenz SZ40/SZ42 (Tunny em Bletchley Park), a cifra de teleimpressor do alto
+//! comando alemão, para contar a história do Colossus no mesmo app.
+//!
+//! Cada caractere é um código ITA2 de cinco impulsos. Doze rodas com cames
+//! geram a chave:
+//! - χ1 a χ5: uma por impulso, giram a cada caractere;
+//! - ψ1 a ψ5: uma por impulso, giram juntas quando o motor manda;
+//! - μ61 e μ37: o motor. A μ61 gira sempre e a μ37 gira quando o came da μ61 está
+//!   ativo; o came da μ37 é o motor básico, que move as ψ.
+//!
+//! A chave é χ ⊕ ψ e a cifra é texto ⊕ chave, impulso a impulso: cifrar e decifrar
+//! são a mesma operação. As rodas giram depois de cada caractere, com os cames da
+//! posição atual decidindo o movimento.
+//!
+//! Na SZ42 a limitação para as ψ mesmo com o motor básico ativo: na SZ42A quando o
+//! came anterior da χ2 está ativo, na SZ42B quando χ2 ⊕ ψ1 (ambos um atrás) está.
+//!
+//! O texto segue a notação de Bletchley: letras, `/` (nulo), `9` (espaço), `3`
+//! (nova linha), `4` (retorno do carro), `+` (letras) e `-` (números). Os cames
+//! se escrevem com `x` (ativo) e `.` (inativo), e as posições começam em 1.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::CipherMachine;
+
+/// Caracteres ITA2 na notação de Bletchley, pelo código (impulso 1 no bit mais
+/// alto).
+pub const ITA2: [char; 32] = [
+    '/', 'T', '4', 'O', '9', 'H', 'N', 'M', '3', 'L', 'R', 'G', 'I', 'P', 'C', 'V', 'E', 'Z', 'D', 'B', 'S', 'Y',
+    'F', 'X', 'A', 'W', 'J', '-', 'U', 'Q', 'K', '+',
+];
+
+/// Cames das rodas χ1 a χ5.
+pub const CHI_SIZES: [usize; 5] = [41, 31, 29, 26, 23];
+/// Cames das rodas ψ1 a ψ5.
+pub const PSI_SIZES: [usize; 5] = [43, 47, 51, 53, 59];
+/// Cames das rodas do motor, μ61 e μ37.
+pub const MU_SIZES: [usize; 2] = [61, 37];
+
+const CHI_NAMES: [&str; 5] = ["χ1", "χ2", "χ3", "χ4", "χ5"];
+const PSI_NAMES: [&str; 5] = ["ψ1", "ψ2", "ψ3", "ψ4", "ψ5"];
+const MU_NAMES: [&str; 2] = ["μ61", "μ37"];
+
+/// Código ITA2 de `c` na notação de Bletchley. O espaço vale como `9`, e `5` e
+/// `8` como `+` e `-`, grafias que também aparecem nos relatórios.
+pub fn ita2_code(c: char) -> Option<u8> {
+    let c = match c.to_ascii_uppercase() {
+        ' ' => '9',
+        '5' => '+',
+        '8' => '-',
+        c => c,
+    };
+    ITA2.iter().position(|&symbol| symbol == c).map(|code| code as u8)
+}
+
+/// Os cinco impulsos de `code` na notação de Bletchley, do 1 ao 5 (ex.: `xx...`
+/// para o A).
+pub fn impulses(code: u8) -> String {
+    (0..5).map(|i| if code & (0b10000 >> i) != 0 { 'x' } else { '.' }).collect()
+}
+
+/// Uma roda, como vem da UI.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LorenzWheelConfig {
+    /// Cames, com `x` (ativo) e `.` (inativo), um por posição.
+    pub pattern: String,
+    /// Posição inicial, a partir de 1.
+    pub start: usize,
+}
+
+/// Modelo da máquina, pela limitação do motor.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum LorenzLimitation {
+    /// SZ40: as ψ seguem só o motor básico.
+    #[default]
+    None,
+    /// SZ42A: χ2 um atrás.
+    Chi2,
+    /// SZ42B: χ2 ⊕ ψ1, ambos um atrás.
+    Chi2Psi1,
+}
+
+/// Configuração da Lorenz: padrões de cames e posições iniciais das doze rodas.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LorenzConfig {
+    pub chi: [LorenzWheelConfig; 5],
+    pub psi: [LorenzWheelConfig; 5],
+    /// μ61 e μ37, nessa ordem.
+    pub mu: [LorenzWheelConfig; 2],
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub limitation: LorenzLimitation,
+}
+
+/// Posições das rodas, a partir de 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LorenzPositions {
+    pub chi: [usize; 5],
+    pub psi: [usize; 5],
+    pub mu: [usize; 2],
+}
+
+/// Um caractere na Lorenz. Os códigos são ITA2 (impulso 1 no bit mais alto).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct LorenzStep {
+    pub input_char: char,
+    pub output_char: char,
+    /// Código do caractere que entrou.
+    pub input: u8,
+    /// Impulsos das χ e das ψ; a chave é `chi ^ psi`.
+    pub chi: u8,
+    pub psi: u8,
+    pub key: u8,
+    /// Código do caractere que saiu (`input ^ key`).
+    pub output: u8,
+    /// Came da μ37 neste caractere.
+    pub basic_motor: bool,
+    /// Limitação neste caractere, na SZ42.
+    pub limitation: Option<bool>,
+    /// Se as ψ giraram.
+    pub psi_moved: bool,
+    pub positions_before: LorenzPositions,
+    pub positions_after: LorenzPositions,
+}
+
+#[derive(Debug, Clone)]
+struct Wheel {
+    cams: Vec<bool>,
+    position: usize,
+}
+
+impl Wheel {
+    fn new(name: &str, size: usize, config: &LorenzWheelConfig) -> Result<Self, String> {
+        let cams = config
+            .pattern
+            .chars()
+            .map(|c| match c {
+                'x' | 'X' => Ok(true),
+                '.' => Ok(false),
+                c => Err(format!("Came inválido na roda {}: {}", name, c)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if cams.len() != size {
+            return Err(format!("A roda {} tem {} cames, não {}", name, size, cams.len()));
+        }
+        if !(1..=size).contains(&config.start) {
+            return Err(format!("Posição inválida na roda {}: {}", name, config.start));
+        }
+        Ok(Self { cams, position: config.start - 1 })
+    }
+
+    fn cam(&self) -> bool {
+        self.cams[self.position]
+    }
+
+    /// Came da posição anterior ("um atrás").
+    fn previous(&self) -> bool {
+        self.cams[(self.position + self.cams.len() - 1) % self.cams.len()]
+    }
+
+    fn step(&mut self) {
+        self.position = (self.position + 1) % self.cams.len();
+    }
+}
+
+/// Junta os cames atuais de cinco rodas nos impulsos 1 a 5.
+fn impulse_code(wheels: &[Wheel]) -> u8 {
+    wheels.iter().fold(0, |code, wheel| code << 1 | wheel.cam() as u8)
+}
+
+fn wheels(names: &[&str], sizes: &[usize], configs: &[LorenzWheelConfig]) -> Result<Vec<Wheel>, String> {
+    (0..configs.len()).map(|i| Wheel::new(names[i], sizes[i], &configs[i])).collect()
+}
+
+/// Posições, a partir de 1, de `N` rodas.
+fn positions<const N: usize>(wheels: &[Wheel]) -> [usize; N] {
+    core::array::from_fn(|i| wheels[i].position + 1)
+}
+
+/// A Lorenz SZ40/SZ42.
+#[derive(Debug, Clone)]
+pub struct LorenzMachine {
+    chi: Vec<Wheel>,
+    psi: Vec<Wheel>,
+    mu: Vec<Wheel>,
+    limitation: LorenzLimitation,
+}
+
+impl LorenzMachine {
+    pub fn new(config: LorenzConfig) -> Result<Self, String> {
+        Ok(Self {
+            chi: wheels(&CHI_NAMES, &CHI_SIZES, &config.chi)?,
+            psi: wheels(&PSI_NAMES, &PSI_SIZES, &config.psi)?,
+            mu: wheels(&MU_NAMES, &MU_SIZES, &config.mu)?,
+            limitation: config.limitation,
+        })
+    }
+
+    /// Limitação na posição atual, ou `None` na SZ40.
+    fn limitation(&self) -> Option<bool> {
+        match self.limitation {
+            LorenzLimitation::None => None,
+            LorenzLimitation::Chi2 => Some(self.chi[1].previous()),
+            LorenzLimitation::Chi2Psi1 => Some(self.chi[1].previous() ^ self.psi[0].previous()),
+        }
+    }
+
+    /// Gira as rodas depois de um caractere. Retorna o motor básico, a limitação e
+    /// se as ψ giraram.
+    fn step_wheels(&mut self) -> (bool, Option<bool>, bool) {
+        let basic_motor = self.mu[1].cam();
+        let limitation = self.limitation();
+        let psi_moved = basic_motor && limitation != Some(true);
+        for wheel in &mut self.chi {
+            wheel.step();
+        }
+        if psi_moved {
+            for wheel in &mut self.psi {
+                wheel.step();
+            }
+        }
+        if self.mu[0].cam() {
+            self.mu[1].step();
+        }
+        self.mu[0].step();
+        (basic_motor, limitation, psi_moved)
+    }
+}
+
+impl CipherMachine for LorenzMachine {
+    type Step = LorenzStep;
+    type State = LorenzPositions;
+
+    fn name(&self) -> &'static str {
+        "Lorenz SZ42"
+    }
+
+    fn accepts(&self, c: char) -> bool {
+        ita2_code(c).is_some()
+    }
+
+    fn process_char(&mut self, c: char) -> char {
+        self.process_detailed(c).0
+    }
+
+    fn process_detailed(&mut self, c: char) -> (char, LorenzStep) {
+        let input = ita2_code(c).unwrap_or(0);
+        let positions_before = self.snapshot();
+        let (chi, psi) = (impulse_code(&self.chi), impulse_code(&self.psi));
+        let key = chi ^ psi;
+        let output = input ^ key;
+        let (basic_motor, limitation, psi_moved) = self.step_wheels();
+        let output_char = ITA2[output as usize];
+        let step = LorenzStep {
+            input_char: ITA2[input as usize],
+            output_char,
+            input,
+            chi,
+            psi,
+            key,
+            output,
+            basic_motor,
+            limitation,
+            psi_moved,
+            positions_before,
+            positions_after: self.snapshot(),
+        };
+        (output_char, step)
+    }
+
+    fn snapshot(&self) -> LorenzPositions {
+        LorenzPositions {
+            chi: positions(&self.chi),
+            psi: positions(&self.psi),
+            mu: positions(&self.mu),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Padrão de cames de exemplo, com cerca de metade ativos.
+    fn wheel(size: usize, seed: usize) -> LorenzWheelConfig {
+        let pattern = (0..size).map(|i| if (i * 7 + seed) % 5 < 2 || i % 3 == seed % 3 { 'x' } else { '.' }).collect();
+        LorenzWheelConfig { pattern, start: 1 + seed % size }
+    }
+
+    fn config(limitation: LorenzLimitation) -> LorenzConfig {
+        LorenzConfig {
+            chi: core::array::from_fn(|i| wheel(CHI_SIZES[i], i)),
+            psi: core::array::from_fn(|i| wheel(PSI_SIZES[i], i + 5)),
+            mu: core::array::from_fn(|i| wheel(MU_SIZES[i], i + 10)),
+            limitation,
+        }
+    }
+
+    #[test]
+    fn test_ita2_is_a_bijection_in_bletchley_notation() {
+        for (code, &c) in ITA2.iter().enumerate() {
+            assert_eq!(ita2_code(c), Some(code as u8));
+        }
+        assert_eq!(ita2_code(' '), ita2_code('9'));
+        assert_eq!(ita2_code('5'), Some(31));
+        assert_eq!(impulses(ita2_code('A').unwrap()), "xx...");
+        assert_eq!(ita2_code('*'), None);
+    }
+
+    #[test]
+    fn test_same_settings_decipher_the_message() {
+        for limitation in [LorenzLimitation::None, LorenzLimitation::Chi2, LorenzLimitation::Chi2Psi1] {
+            let cipher = LorenzMachine::new(config(limitation)).unwrap().process_string("Attack at dawn");
+            assert_eq!(cipher.len(), 14);
+            let mut machine = LorenzMachine::new(config(limitation)).unwrap();
+            assert_eq!(machine.process_string(&cipher), "ATTACK9AT9DAWN");
+            assert_eq!(machine.name(), "Lorenz SZ42");
+        }
+    }
+
+    #[test]
+    fn test_chi_always_move_and_psi_follow_the_motor() {
+        let mut machine = LorenzMachine::new(config(LorenzLimitation::Chi2)).unwrap();
+        let steps = machine.process_string_detailed(&"9".repeat(120));
+        for step in &steps {
+            assert_eq!(step.key, step.chi ^ step.psi);
+            assert_eq!(step.output, step.input ^ step.key);
+            let moved = step.positions_after.psi != step.positions_before.psi;
+            assert_eq!(moved, step.psi_moved);
+            assert_eq!(step.psi_moved, step.basic_motor && step.limitation == Some(false));
+            let chi: Vec<usize> = (0..5).map(|i| step.positions_before.chi[i] % CHI_SIZES[i] + 1).collect();
+            assert_eq!(step.positions_after.chi, chi[..]);
+        }
+        // As ψ ficam paradas em parte dos caracteres, e a limitação pesa em alguns
+        assert!(steps.iter().any(|step| step.psi_moved) && steps.iter().any(|step| !step.psi_moved));
+        assert!(steps.iter().any(|step| step.basic_motor && !step.psi_moved));
+        assert_eq!(machine.snapshot().mu[0], (10 + 120) % 61 + 1);
+    }
+
+    #[test]
+    fn test_rejects_bad_wheels() {
+        let mut bad = config(LorenzLimitation::None);
+        bad.chi[0].pattern.pop();
+        assert!(LorenzMachine::new(bad).unwrap_err().contains("χ1"));
+        let mut bad = config(LorenzLimitation::None);
+        bad.psi[4].start = 60;
+        assert!(LorenzMachine::new(bad).is_err());
+        let mut bad = config(LorenzLimitation::None);
+        bad.mu[1].pattern.replace_range(0..1, "1");
+        assert!(LorenzMachine::new(bad).unwrap_err().contains("μ37"));
+    }
+}
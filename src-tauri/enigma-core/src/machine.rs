@@ -0,0 +1,124 @@
+//! Interface comum das máquinas de cifra.
+//!
+//! Os comandos do Tauri e a UI falam com a máquina por este trait: teclar, ver o
+//! caminho de uma tecla e ler o estado visível. Uma nova máquina (Typex, Lorenz, uma
+//! cifra de comparação) entra implementando `CipherMachine`, sem mudar os contratos.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{EncryptionStep, EnigmaMachine};
+
+/// Uma máquina de cifra operada tecla a tecla.
+pub trait CipherMachine {
+    /// Detalhes de uma tecla, para a visualização didática.
+    type Step;
+    /// Estado visível da máquina (ex.: as letras nas janelas dos rotores).
+    type State;
+
+    /// Nome da máquina para a UI (ex.: "Enigma M3").
+    fn name(&self) -> &'static str;
+
+    /// Indica se a máquina tem tecla para `c`. As outras são ignoradas ao processar
+    /// um texto.
+    fn accepts(&self, c: char) -> bool {
+        c.is_ascii_alphabetic()
+    }
+
+    /// Tecla `c` (já aceita e em maiúscula) e retorna a letra que acende.
+    fn process_char(&mut self, c: char) -> char;
+
+    /// Como `process_char`, mas também retorna o caminho do sinal.
+    fn process_detailed(&mut self, c: char) -> (char, Self::Step);
+
+    /// Estado visível atual.
+    fn snapshot(&self) -> Self::State;
+
+    /// Processa um texto, ignorando o que a máquina não aceita.
+    fn process_string(&mut self, text: &str) -> String {
+        let mut output = String::new();
+        for c in text.chars() {
+            if self.accepts(c) {
+                output.push(self.process_char(c.to_ascii_uppercase()));
+            }
+        }
+        output
+    }
+
+    /// Processa um texto e retorna o caminho de cada tecla.
+    fn process_string_detailed(&mut self, text: &str) -> Vec<Self::Step> {
+        let mut steps = Vec::new();
+        for c in text.chars() {
+            if self.accepts(c) {
+                steps.push(self.process_detailed(c.to_ascii_uppercase()).1);
+            }
+        }
+        steps
+    }
+}
+
+/// Estado visível da Enigma.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EnigmaState {
+    /// Letras nas janelas dos rotores (Esquerda, Meio, Direita).
+    pub positions: (char, char, char),
+}
+
+impl CipherMachine for EnigmaMachine {
+    type Step = EncryptionStep;
+    type State = EnigmaState;
+
+    fn name(&self) -> &'static str {
+        "Enigma M3"
+    }
+
+    fn process_char(&mut self, c: char) -> char {
+        EnigmaMachine::process_char(self, c)
+    }
+
+    fn process_detailed(&mut self, c: char) -> (char, EncryptionStep) {
+        self.process_char_detailed(c)
+    }
+
+    fn snapshot(&self) -> EnigmaState {
+        EnigmaState { positions: self.get_positions() }
+    }
+
+    // O caminho rápido da própria máquina
+    fn process_string(&mut self, text: &str) -> String {
+        EnigmaMachine::process_string(self, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EnigmaConfig, RotorConfig};
+
+    /// Usa a máquina só pelo trait, como um comando genérico faria.
+    fn type_text<M: CipherMachine>(machine: &mut M, text: &str) -> (String, usize, M::State) {
+        let output = machine.process_string(text);
+        let steps = machine.process_string_detailed(text).len();
+        (output, steps, machine.snapshot())
+    }
+
+    #[test]
+    fn test_enigma_through_trait() {
+        let rotor = |name: &str, position| RotorConfig { name: name.into(), position, ring: 'A' };
+        let config = EnigmaConfig {
+            rotors: (rotor("I", 'A'), rotor("II", 'A'), rotor("III", 'A')),
+            reflector: "B".into(),
+            plugboard_pairs: "".into(),
+        };
+        let mut machine = EnigmaMachine::new(config.clone());
+        let (output, steps, state) = type_text(&mut machine, "Hello, world");
+        assert_eq!(output, EnigmaMachine::new(config).process_string("HELLOWORLD"));
+        assert_eq!(steps, 10);
+        // 20 teclas: o rotor I (direita) passou pela ranhura em Q
+        assert_eq!(state, EnigmaState { positions: ('A', 'B', 'U') });
+        assert_eq!(machine.name(), "Enigma M3");
+    }
+}
@@ -0,0 +1,47 @@
+//! Explicação de cada passo do caminho em dados, não em texto: uma chave e os
+//! parâmetros (ex.: `{"key": "rotor_forward", "rotor": "I", "slot": "Right",
+//! "offset": 5}`). A UI e os leitores de tela montam a frase no idioma do
+//! usuário sem precisar interpretar os nomes dos componentes.
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::{Component, PathDirection, RotorSlot};
+
+/// O que aconteceu com o sinal num componente. As letras de entrada e de saída
+/// ficam no `PathEntry`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "key", rename_all = "snake_case"))]
+pub enum Narration {
+    /// A letra tem um cabo no plugboard e sai trocada pelo par.
+    PlugboardSwap { from: char, to: char },
+    /// A letra não tem cabo e passa direto pelo plugboard.
+    PlugboardPass { letter: char },
+    /// Roda de entrada, que no M3 não troca letras.
+    EntryWheel,
+    /// Ida pelo rotor. `offset` é a posição menos o anel (0..26): quanto a fiação
+    /// está girada em relação à posição de referência.
+    RotorForward { rotor: &'static str, slot: RotorSlot, offset: u8 },
+    /// Volta pelo rotor, pela fiação inversa.
+    RotorBackward { rotor: &'static str, slot: RotorSlot, offset: u8 },
+    /// O refletor manda o sinal de volta por outro contato.
+    Reflect { reflector: &'static str },
+}
+
+impl Narration {
+    /// Explicação de um passo por `component`, de `input` para `output`. `offset`
+    /// só é usado nos rotores.
+    pub fn new(component: &Component, direction: &PathDirection, input: char, output: char, offset: u8) -> Self {
+        match *component {
+            Component::Plugboard if input == output => Narration::PlugboardPass { letter: input },
+            Component::Plugboard => Narration::PlugboardSwap { from: input, to: output },
+            Component::EntryWheel => Narration::EntryWheel,
+            Component::Rotor { slot, name: rotor } => match direction {
+                PathDirection::Backward => Narration::RotorBackward { rotor, slot, offset },
+                PathDirection::Forward | PathDirection::Reflect => Narration::RotorForward { rotor, slot, offset },
+            },
+            Component::Reflector { name } => Narration::Reflect { reflector: name },
+        }
+    }
+}
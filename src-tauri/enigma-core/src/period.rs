@@ -0,0 +1,89 @@
+//! A demonstração clássica de apertar a mesma tecla sem parar: as lâmpadas nunca
+//! repetem a tecla e a sequência só se repete quando os rotores voltam a uma
+//! posição por onde já passaram.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::{char_to_u8, EnigmaConfig, EnigmaMachine};
+
+/// Resultado de `repeated_letter`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct RepeatedLetter {
+    pub letter: char,
+    /// Lâmpadas acesas nas `n` teclas.
+    pub output: String,
+    /// Tecla (a partir de 0) em que a sequência entra no ciclo. É 0, a não ser que
+    /// a máquina comece com os rotores do meio e da direita nas ranhuras: o passo
+    /// duplo da primeira tecla leva a uma posição que não volta mais.
+    pub cycle_start: usize,
+    /// Teclas até a sequência se repetir (26 × 25 × 26 = 16.900 no M3).
+    pub period: usize,
+    /// Tecla em que a primeira repetição começa, se couber nas `n` teclas.
+    pub first_repeat: Option<usize>,
+}
+
+/// Aperta `letter` `n` vezes na máquina de `config` e procura o período da
+/// sequência: as posições dos rotores depois de cada tecla decidem a lâmpada, então
+/// a sequência se repete a partir da primeira posição que volta.
+pub fn repeated_letter(config: EnigmaConfig, letter: char, n: usize) -> Result<RepeatedLetter, String> {
+    if !letter.is_ascii_alphabetic() {
+        return Err(format!("Letra inválida: {}", letter));
+    }
+    let letter = letter.to_ascii_uppercase();
+    let config = config.normalized()?;
+    let mut machine = EnigmaMachine::new(config.clone());
+    let output = (0..n).map(|_| machine.process_char(letter)).collect();
+
+    // Volta ao começo e anota em que tecla cada posição apareceu pela primeira vez
+    machine = EnigmaMachine::new(config);
+    let mut seen = vec![usize::MAX; 26 * 26 * 26];
+    let mut key = 0;
+    let (cycle_start, period) = loop {
+        machine.step_rotors();
+        let (l, m, r) = machine.get_positions();
+        let state = (char_to_u8(l) as usize * 26 + char_to_u8(m) as usize) * 26 + char_to_u8(r) as usize;
+        if seen[state] != usize::MAX {
+            break (seen[state], key - seen[state]);
+        }
+        seen[state] = key;
+        key += 1;
+    };
+    let first_repeat = Some(cycle_start + period).filter(|&start| start < n);
+    Ok(RepeatedLetter { letter, output, cycle_start, period, first_repeat })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(code: &str) -> EnigmaConfig {
+        EnigmaConfig::from_share_code(code).unwrap()
+    }
+
+    #[test]
+    fn test_full_period_and_no_self_encryption() {
+        let result = repeated_letter(config("ENIGMA:I.II.III:B:AAA:AAA:"), 'a', 20).unwrap();
+        assert!(result.output.starts_with("BDZGO"));
+        assert!(!result.output.contains('A'));
+        assert_eq!((result.cycle_start, result.period, result.first_repeat), (0, 16_900, None));
+
+        let result = repeated_letter(config("ENIGMA:I.II.III:B:AAA:AAA:"), 'A', 16_905).unwrap();
+        assert_eq!(result.first_repeat, Some(16_900));
+        assert_eq!(result.output[16_900..], result.output[..5]);
+        assert!(repeated_letter(config("ENIGMA:I.II.III:B:AAA:AAA:"), '5', 1).is_err());
+    }
+
+    #[test]
+    fn test_double_step_leaves_the_start_out_of_the_cycle() {
+        // Rotor do meio (II) na ranhura E e o da direita (III) na V
+        let result = repeated_letter(config("ENIGMA:I.II.III:B:AEV:AAA:"), 'X', 16_910).unwrap();
+        assert_eq!((result.cycle_start, result.period, result.first_repeat), (1, 16_900, Some(16_901)));
+        assert_eq!(result.output[16_901..], result.output[1..10]);
+        assert_ne!(result.output[16_900..16_901], result.output[..1]);
+    }
+}
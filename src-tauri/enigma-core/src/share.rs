@@ -0,0 +1,75 @@
+//! Código curto de uma configuração, para passar a chave do projetor para os
+//! aparelhos dos alunos sem digitar (por QR code, por exemplo).
+//!
+//! Formato: `ENIGMA:I.II.III:B:ABC:AAA:ABCD`, com rotores, posições e anéis da
+//! esquerda para a direita e os pares do plugboard sem espaços. Só usa maiúsculas,
+//! dígitos, `:` e `.`, que o modo alfanumérico do QR code codifica de forma compacta.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::{EnigmaConfig, RotorConfig};
+
+const PREFIX: &str = "ENIGMA";
+
+impl EnigmaConfig {
+    /// Código curto desta configuração (validada e em maiúsculas).
+    pub fn to_share_code(&self) -> Result<String, String> {
+        let config = self.clone().normalized()?;
+        let (r, m, l) = &config.rotors;
+        let plugboard: String = config.plugboard_pairs.chars().filter(|c| *c != ' ').collect();
+        Ok(format!(
+            "{}:{}.{}.{}:{}:{}{}{}:{}{}{}:{}",
+            PREFIX, l.name, m.name, r.name, config.reflector, l.position, m.position, r.position, l.ring, m.ring,
+            r.ring, plugboard
+        ))
+    }
+
+    /// Lê um código gerado por `to_share_code`.
+    pub fn from_share_code(code: &str) -> Result<Self, String> {
+        let invalid = || format!("Código de configuração inválido: {}", code);
+        let parts: Vec<&str> = code.trim().split(':').collect();
+        let [prefix, rotors, reflector, positions, rings, plugboard] = parts[..] else {
+            return Err(invalid());
+        };
+        if !prefix.eq_ignore_ascii_case(PREFIX) {
+            return Err(invalid());
+        }
+        let names: Vec<&str> = rotors.split('.').collect();
+        let positions: Vec<char> = positions.chars().collect();
+        let rings: Vec<char> = rings.chars().collect();
+        if names.len() != 3 || positions.len() != 3 || rings.len() != 3 || plugboard.len() % 2 != 0 {
+            return Err(invalid());
+        }
+        let rotor = |i: usize| RotorConfig { name: names[i].to_string(), position: positions[i], ring: rings[i] };
+        let pairs: Vec<&str> = (0..plugboard.len()).step_by(2).filter_map(|i| plugboard.get(i..i + 2)).collect();
+        let config = EnigmaConfig {
+            rotors: (rotor(2), rotor(1), rotor(0)),
+            reflector: reflector.to_string(),
+            plugboard_pairs: pairs.join(" "),
+        };
+        config.normalized()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_share_code_round_trip() {
+        let rotor = |name: &str, position, ring| RotorConfig { name: name.to_string(), position, ring };
+        let config = EnigmaConfig {
+            rotors: (rotor("iii", 'c', 'A'), rotor("II", 'B', 'L'), rotor("I", 'A', 'X')),
+            reflector: "b".into(),
+            plugboard_pairs: "ab cd".into(),
+        };
+        let code = config.to_share_code().unwrap();
+        assert_eq!(code, "ENIGMA:I.II.III:B:ABC:XLA:ABCD");
+        assert_eq!(EnigmaConfig::from_share_code(&code).unwrap(), config.clone().normalized().unwrap());
+        assert!(EnigmaConfig::from_share_code("ENIGMA:I.II:B:AB:AA:").is_err());
+        assert!(EnigmaConfig::from_share_code("ENIGMA:I.II.III:B:ABC:XLA:ABC").is_err());
+        assert!(EnigmaConfig::from_share_code("ENIGMA:I.II.IX:B:ABC:XLA:").is_err());
+    }
+}
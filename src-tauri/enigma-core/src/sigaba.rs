@@ -0,0 +1,404 @@
+//! SIGABA (ECM Mark II), a máquina americana que nunca foi quebrada, atrás do
+//! mesmo `CipherMachine`: o contraste com a Enigma no material do curso.
+//!
+//! Três bancos de cinco rotores:
+//! - cifra: 26 contatos, por onde passa a letra. Não há refletor, então a máquina
+//!   não é recíproca: decifrar percorre o banco ao contrário.
+//! - controle: 26 contatos. A cada tecla as entradas F, G, H e I recebem corrente;
+//!   o rotor do meio gira sempre, o quarto quando o do meio completa uma volta e o
+//!   segundo quando o quarto completa uma volta. O primeiro e o último ficam
+//!   parados.
+//! - índice: 10 contatos, ajustados à mão e parados. As saídas do controle, juntas
+//!   em grupos, entram no índice, e cada par de saídas do índice move um rotor da
+//!   cifra.
+//!
+//! Assim de um a quatro rotores da cifra giram a cada tecla, numa ordem irregular
+//! que a análise de frequências e os cribs não conseguem explorar como na Enigma.
+//! A letra passa pela cifra antes de os rotores girarem.
+//!
+//! Ao cifrar, o Z vira X e o espaço vira Z; ao decifrar, o Z sai como espaço. As
+//! fiações das máquinas de serviço não foram publicadas: as daqui são de exemplo.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{reversed_wiring, Alphabet, Rotor, LATIN};
+use crate::{char_to_u8, u8_to_char, CipherMachine, OutputFormat, PathDirection};
+
+/// Rotores de 26 contatos, usados na cifra ou no controle: nome e fiação.
+pub const SIGABA_ROTORS: [(&str, &str); 10] = [
+    ("I", "UGALEJVRSXQKYNTMZCWHDFOBIP"),
+    ("II", "ZEQYJIGUOWNFKBPDTSLARHVXCM"),
+    ("III", "JQWENCLDUVPZHTRGYSMBIOFAXK"),
+    ("IV", "GBKYJIPLDCARENHUQTSMZOVXFW"),
+    ("V", "NIDETFVWXQULBZMKAHYSOPGJRC"),
+    ("VI", "MZYANRFLQCOBEHUPIVJXDSKTGW"),
+    ("VII", "YNMOSIRGDLHAQBZEPKWUCTVFXJ"),
+    ("VIII", "ZOKJIVXCFGSWBLUETAQMHPRNYD"),
+    ("IX", "VCZFLIBWQROEAKNHJYMGTXPUDS"),
+    ("X", "SCLFPBORGVENUQDKJTWXIAYHZM"),
+];
+
+/// Rotores de 10 contatos, do banco de índice: nome e fiação.
+pub const SIGABA_INDEX_ROTORS: [(&str, &str); 5] =
+    [("1", "0925416873"), ("2", "2398754016"), ("3", "4718392650"), ("4", "2831704569"), ("5", "6293470518")];
+
+/// Rotores em cada banco.
+pub const SIGABA_SLOTS: usize = 5;
+
+/// Entradas do banco de controle que recebem corrente a cada tecla.
+pub const CONTROL_INPUTS: [char; 4] = ['F', 'G', 'H', 'I'];
+
+const DIGITS: Alphabet<10> = Alphabet::new(['0', '1', '2', '3', '4', '5', '6', '7', '8', '9']);
+
+/// Entrada do índice ligada a cada saída do controle (A a Z): B → 1, C → 2,
+/// DE → 3, FGH → 4, IJK → 5, LMNO → 6, PQRST → 7, UVWXYZ → 8 e A → 9. A entrada 0
+/// fica sem ligação.
+const CONTROL_TO_INDEX: [u8; 26] = [9, 1, 2, 3, 3, 4, 4, 4, 5, 5, 5, 6, 6, 6, 6, 7, 7, 7, 7, 7, 8, 8, 8, 8, 8, 8];
+
+/// Rotor da cifra movido por cada saída do índice (0 a 9), em pares: 12, 34, 56,
+/// 78 e 90.
+const INDEX_TO_CIPHER: [usize; 10] = [4, 0, 0, 1, 1, 2, 2, 3, 3, 4];
+
+/// Um rotor num banco, como vem da UI.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SigabaRotorConfig {
+    pub name: String,
+    /// Símbolo na janela: uma letra, ou um dígito no banco de índice.
+    pub position: char,
+    /// Posto ao contrário no banco.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reversed: bool,
+}
+
+/// Se a máquina está cifrando ou decifrando.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SigabaMode {
+    #[default]
+    Encipher,
+    Decipher,
+}
+
+/// Configuração da SIGABA. Os bancos vão da esquerda para a direita; um rotor de
+/// 26 contatos só pode estar num lugar, na cifra ou no controle.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SigabaConfig {
+    pub cipher: [SigabaRotorConfig; SIGABA_SLOTS],
+    pub control: [SigabaRotorConfig; SIGABA_SLOTS],
+    pub index: [SigabaRotorConfig; SIGABA_SLOTS],
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mode: SigabaMode,
+}
+
+/// A letra passando por um rotor do banco da cifra.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct SigabaPathEntry {
+    pub slot: usize,
+    pub name: &'static str,
+    pub input_char: char,
+    pub output_char: char,
+    /// `Forward` ao cifrar (da esquerda para a direita), `Backward` ao decifrar.
+    pub direction: PathDirection,
+}
+
+/// Uma tecla na SIGABA.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct SigabaStep {
+    /// Letra que entra na cifra (já com as trocas do Z e do espaço, ao cifrar).
+    pub input_char: char,
+    /// Letra que sai da cifra.
+    pub output_char: char,
+    /// O que a impressora escreve (o Z vira espaço, ao decifrar).
+    pub printed: char,
+    /// Posições da cifra e do controle antes e depois da tecla.
+    pub cipher_before: [char; SIGABA_SLOTS],
+    pub cipher_after: [char; SIGABA_SLOTS],
+    pub control_before: [char; SIGABA_SLOTS],
+    pub control_after: [char; SIGABA_SLOTS],
+    /// Caminho da letra pela cifra.
+    pub path: Vec<SigabaPathEntry>,
+    /// Onde sai do controle a corrente de cada uma das entradas F, G, H e I.
+    pub control_outputs: [char; 4],
+    /// Saídas do índice com corrente, em ordem.
+    pub index_outputs: Vec<char>,
+    /// Rotores da cifra que giraram.
+    pub stepped: [bool; SIGABA_SLOTS],
+}
+
+/// Estado visível da SIGABA: as janelas dos três bancos.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SigabaState {
+    pub cipher: [char; SIGABA_SLOTS],
+    pub control: [char; SIGABA_SLOTS],
+    pub index: [char; SIGABA_SLOTS],
+}
+
+/// Monta um rotor de `N` contatos da tabela `table`.
+fn rotor<const N: usize>(
+    alphabet: &Alphabet<N>,
+    table: &[(&'static str, &'static str)],
+    config: &SigabaRotorConfig,
+) -> Result<Rotor<N>, String> {
+    let &(name, wiring) = table
+        .iter()
+        .find(|(name, _)| *name == config.name)
+        .ok_or_else(|| format!("Rotor da SIGABA desconhecido: {}", config.name))?;
+    let mut wiring = alphabet.wiring(wiring)?;
+    if config.reversed {
+        wiring = reversed_wiring(&wiring);
+    }
+    let position = alphabet
+        .index(config.position.to_ascii_uppercase())
+        .ok_or_else(|| format!("Posição inválida no rotor {}: {}", name, config.position))?;
+    Ok(Rotor::new(wiring, 0, position, 0, name))
+}
+
+/// Monta um banco, recusando rotores repetidos (também os já usados em `used`).
+fn bank<const N: usize>(
+    alphabet: &Alphabet<N>,
+    table: &[(&'static str, &'static str)],
+    configs: &[SigabaRotorConfig; SIGABA_SLOTS],
+    used: &mut Vec<String>,
+) -> Result<[Rotor<N>; SIGABA_SLOTS], String> {
+    for config in configs {
+        if used.contains(&config.name) {
+            return Err(format!("Rotor da SIGABA repetido: {}", config.name));
+        }
+        used.push(config.name.clone());
+    }
+    let [a, b, c, d, e] = configs.each_ref().map(|config| rotor(alphabet, table, config));
+    Ok([a?, b?, c?, d?, e?])
+}
+
+/// A SIGABA.
+#[derive(Debug, Clone)]
+pub struct SigabaMachine {
+    cipher: [Rotor<26>; SIGABA_SLOTS],
+    control: [Rotor<26>; SIGABA_SLOTS],
+    index: [Rotor<10>; SIGABA_SLOTS],
+    mode: SigabaMode,
+}
+
+fn positions<const N: usize>(rotors: &[Rotor<N>; SIGABA_SLOTS], alphabet: &Alphabet<N>) -> [char; SIGABA_SLOTS] {
+    core::array::from_fn(|i| alphabet.symbol(rotors[i].position()))
+}
+
+impl SigabaMachine {
+    pub fn new(config: SigabaConfig) -> Result<Self, String> {
+        let mut used = Vec::new();
+        let cipher = bank(&LATIN, &SIGABA_ROTORS, &config.cipher, &mut used)?;
+        let control = bank(&LATIN, &SIGABA_ROTORS, &config.control, &mut used)?;
+        let index = bank(&DIGITS, &SIGABA_INDEX_ROTORS, &config.index, &mut Vec::new())?;
+        Ok(Self { cipher, control, index, mode: config.mode })
+    }
+
+    /// Imprime `text` como a SIGABA: cifrando, em grupos de cinco letras.
+    pub fn print(&mut self, text: &str) -> String {
+        let output = self.process_string(text);
+        match self.mode {
+            SigabaMode::Encipher => OutputFormat::default().apply(&output),
+            SigabaMode::Decipher => output,
+        }
+    }
+
+    /// Passa as entradas F, G, H e I pelo controle e pelo índice, na posição atual.
+    /// Retorna as saídas do controle, as saídas do índice com corrente e os rotores
+    /// da cifra que elas movem.
+    fn stepping(&self) -> ([char; 4], Vec<char>, [bool; SIGABA_SLOTS]) {
+        let control_outputs =
+            CONTROL_INPUTS.map(|c| self.control.iter().fold(char_to_u8(c), |signal, rotor| rotor.forward(signal)));
+        let mut energized = [false; 10];
+        for &output in &control_outputs {
+            let input = CONTROL_TO_INDEX[output as usize];
+            energized[self.index.iter().fold(input, |signal, rotor| rotor.forward(signal)) as usize] = true;
+        }
+        let mut stepped = [false; SIGABA_SLOTS];
+        let mut index_outputs = Vec::new();
+        for digit in (0..10).filter(|&d| energized[d]) {
+            stepped[INDEX_TO_CIPHER[digit]] = true;
+            index_outputs.push(DIGITS.symbol(digit as u8));
+        }
+        (control_outputs.map(u8_to_char), index_outputs, stepped)
+    }
+
+    /// Gira os rotores da cifra marcados em `stepped` e os do controle, como um
+    /// odômetro: o do meio sempre, o quarto quando o do meio volta ao A e o
+    /// segundo quando o quarto volta ao A.
+    fn step_rotors(&mut self, stepped: &[bool; SIGABA_SLOTS]) {
+        for (rotor, _) in self.cipher.iter_mut().zip(stepped).filter(|(_, &step)| step) {
+            rotor.step();
+        }
+        self.control[2].step();
+        if self.control[2].position() == 0 {
+            self.control[3].step();
+            if self.control[3].position() == 0 {
+                self.control[1].step();
+            }
+        }
+    }
+
+    /// Passa `c` pela cifra, anotando cada rotor em `path` se houver um.
+    fn scramble(&self, c: u8, mut path: Option<&mut Vec<SigabaPathEntry>>) -> u8 {
+        let decipher = self.mode == SigabaMode::Decipher;
+        let slots: [usize; SIGABA_SLOTS] = if decipher { [4, 3, 2, 1, 0] } else { [0, 1, 2, 3, 4] };
+        slots.iter().fold(c, |signal, &slot| {
+            let rotor = &self.cipher[slot];
+            let output = if decipher { rotor.backward(signal) } else { rotor.forward(signal) };
+            if let Some(path) = path.as_deref_mut() {
+                path.push(SigabaPathEntry {
+                    slot,
+                    name: rotor.name(),
+                    input_char: u8_to_char(signal),
+                    output_char: u8_to_char(output),
+                    direction: if decipher { PathDirection::Backward } else { PathDirection::Forward },
+                });
+            }
+            output
+        })
+    }
+
+    /// Troca da entrada ao cifrar: o Z vira X e o espaço vira Z.
+    fn typed(&self, c: char) -> char {
+        match (self.mode, c) {
+            (SigabaMode::Encipher, 'Z') => 'X',
+            (SigabaMode::Encipher, ' ') => 'Z',
+            _ => c,
+        }
+    }
+
+    /// O que a impressora escreve para a letra `c` que saiu da cifra.
+    fn printed(&self, c: char) -> char {
+        if self.mode == SigabaMode::Decipher && c == 'Z' {
+            ' '
+        } else {
+            c
+        }
+    }
+}
+
+impl CipherMachine for SigabaMachine {
+    type Step = SigabaStep;
+    type State = SigabaState;
+
+    fn name(&self) -> &'static str {
+        "SIGABA"
+    }
+
+    /// Cifrando, a tecla de espaço também vale (como Z).
+    fn accepts(&self, c: char) -> bool {
+        c.is_ascii_alphabetic() || (c == ' ' && self.mode == SigabaMode::Encipher)
+    }
+
+    fn process_char(&mut self, c: char) -> char {
+        let output = self.scramble(char_to_u8(self.typed(c)), None);
+        let (_, _, stepped) = self.stepping();
+        self.step_rotors(&stepped);
+        self.printed(u8_to_char(output))
+    }
+
+    fn process_detailed(&mut self, c: char) -> (char, SigabaStep) {
+        let input_char = self.typed(c);
+        let (cipher_before, control_before) = (positions(&self.cipher, &LATIN), positions(&self.control, &LATIN));
+        let mut path = Vec::with_capacity(SIGABA_SLOTS);
+        let output_char = u8_to_char(self.scramble(char_to_u8(input_char), Some(&mut path)));
+        let (control_outputs, index_outputs, stepped) = self.stepping();
+        self.step_rotors(&stepped);
+        let printed = self.printed(output_char);
+        let step = SigabaStep {
+            input_char,
+            output_char,
+            printed,
+            cipher_before,
+            cipher_after: positions(&self.cipher, &LATIN),
+            control_before,
+            control_after: positions(&self.control, &LATIN),
+            path,
+            control_outputs,
+            index_outputs,
+            stepped,
+        };
+        (printed, step)
+    }
+
+    fn snapshot(&self) -> SigabaState {
+        SigabaState {
+            cipher: positions(&self.cipher, &LATIN),
+            control: positions(&self.control, &LATIN),
+            index: positions(&self.index, &DIGITS),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bank(names: [&str; SIGABA_SLOTS], positions: &str) -> [SigabaRotorConfig; SIGABA_SLOTS] {
+        let mut positions = positions.chars();
+        names.map(|name| SigabaRotorConfig { name: name.into(), position: positions.next().unwrap(), reversed: false })
+    }
+
+    fn config(mode: SigabaMode) -> SigabaConfig {
+        let mut cipher = bank(["I", "II", "III", "IV", "V"], "ABCDE");
+        cipher[1].reversed = true;
+        SigabaConfig {
+            cipher,
+            control: bank(["VI", "VII", "VIII", "IX", "X"], "AAXAA"),
+            index: bank(["1", "2", "3", "4", "5"], "01234"),
+            mode,
+        }
+    }
+
+    #[test]
+    fn test_decipher_undoes_encipher_with_printing_conventions() {
+        let printed = SigabaMachine::new(config(SigabaMode::Encipher)).unwrap().print("ZULU AT DAWN");
+        assert_eq!(printed.split(' ').map(str::len).collect::<Vec<_>>(), [5, 5, 2]);
+        let mut machine = SigabaMachine::new(config(SigabaMode::Decipher)).unwrap();
+        assert_eq!(machine.print(&printed), "XULU AT DAWN");
+        assert!(!machine.accepts(' '));
+        assert_eq!(machine.name(), "SIGABA");
+    }
+
+    #[test]
+    fn test_irregular_stepping() {
+        let mut machine = SigabaMachine::new(config(SigabaMode::Encipher)).unwrap();
+        let steps = machine.process_string_detailed(&"A".repeat(60));
+        for step in &steps {
+            let count = step.stepped.iter().filter(|&&s| s).count();
+            assert!((1..=4).contains(&count), "{:?}", step.stepped);
+            assert_eq!(step.path.len(), SIGABA_SLOTS);
+            assert_eq!(step.control_after[0], 'A');
+            assert_eq!(step.control_after[4], 'A');
+        }
+        // Rotores da cifra giram em padrões diferentes de tecla em tecla
+        assert!(steps.windows(2).any(|pair| pair[0].stepped != pair[1].stepped));
+        // Controle como odômetro: o do meio sai de X, volta ao A na 3ª tecla e leva o
+        // quarto junto
+        assert_eq!(steps[1].control_after, ['A', 'A', 'Z', 'A', 'A']);
+        assert_eq!(steps[2].control_after, ['A', 'A', 'A', 'B', 'A']);
+        assert_eq!(machine.snapshot().index, ['0', '1', '2', '3', '4']);
+    }
+
+    #[test]
+    fn test_rejects_repeated_rotors_and_bad_positions() {
+        let mut bad = config(SigabaMode::Encipher);
+        bad.control[0].name = "III".into();
+        assert!(SigabaMachine::new(bad).unwrap_err().contains("repetido"));
+        let mut bad = config(SigabaMode::Encipher);
+        bad.index[2].position = 'A';
+        assert!(SigabaMachine::new(bad).is_err());
+        let mut bad = config(SigabaMode::Encipher);
+        bad.index[2].name = "I".into();
+        assert!(SigabaMachine::new(bad).is_err());
+    }
+}
@@ -0,0 +1,239 @@
+//! Formato compacto do traço de cifragem.
+//!
+//! O `EncryptionStep` leva para a UI letras como `char` e o nome de cada componente
+//! em todas as entradas do caminho, e em JSON o traço de uma mensagem longa chega a
+//! dezenas de megabytes. Aqui cada contato é um `u8` (0-25), o componente é um byte
+//! e os nomes dos rotores e do refletor ficam uma vez só, na configuração do traço.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    char_to_u8, u8_to_char, Component, EncryptionStep, EnigmaConfig, EnigmaMachine, PathDirection, PathEntry,
+    RotorSlot,
+};
+use crate::components::{reflector_name, rotor_name};
+
+/// Componente do caminho em um byte. O nome vem da configuração do traço.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "u8", try_from = "u8"))]
+#[repr(u8)]
+pub enum CompactComponent {
+    Plugboard = 0,
+    EntryWheel = 1,
+    RotorLeft = 2,
+    RotorMiddle = 3,
+    RotorRight = 4,
+    Reflector = 5,
+}
+
+impl From<CompactComponent> for u8 {
+    fn from(component: CompactComponent) -> u8 {
+        component as u8
+    }
+}
+
+impl TryFrom<u8> for CompactComponent {
+    type Error = String;
+
+    fn try_from(code: u8) -> Result<Self, String> {
+        Ok(match code {
+            0 => Self::Plugboard,
+            1 => Self::EntryWheel,
+            2 => Self::RotorLeft,
+            3 => Self::RotorMiddle,
+            4 => Self::RotorRight,
+            5 => Self::Reflector,
+            _ => return Err(format!("Componente desconhecido no traço: {}", code)),
+        })
+    }
+}
+
+impl From<&Component> for CompactComponent {
+    fn from(component: &Component) -> Self {
+        match component {
+            Component::Plugboard => Self::Plugboard,
+            Component::EntryWheel => Self::EntryWheel,
+            Component::Rotor { slot: RotorSlot::Left, .. } => Self::RotorLeft,
+            Component::Rotor { slot: RotorSlot::Middle, .. } => Self::RotorMiddle,
+            Component::Rotor { slot: RotorSlot::Right, .. } => Self::RotorRight,
+            Component::Reflector { .. } => Self::Reflector,
+        }
+    }
+}
+
+/// Uma entrada do caminho. A direção não é gravada: o que vem antes do refletor
+/// é ida, e o que vem depois é volta.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CompactEntry {
+    pub component: CompactComponent,
+    pub input: u8,
+    pub output: u8,
+}
+
+/// Um `EncryptionStep` com letras 0-25.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CompactStep {
+    pub input: u8,
+    pub output: u8,
+    /// Posições (Esquerda, Meio, Direita) antes do passo.
+    pub before: [u8; 3],
+    /// Posições (Esquerda, Meio, Direita) depois do passo.
+    pub after: [u8; 3],
+    pub path: Vec<CompactEntry>,
+}
+
+impl From<&EncryptionStep> for CompactStep {
+    fn from(step: &EncryptionStep) -> Self {
+        let positions = |(l, m, r): (char, char, char)| [char_to_u8(l), char_to_u8(m), char_to_u8(r)];
+        Self {
+            input: char_to_u8(step.input_char),
+            output: char_to_u8(step.output_char),
+            before: positions(step.positions_before_step),
+            after: positions(step.positions_after_step),
+            path: step
+                .path
+                .iter()
+                .map(|entry| CompactEntry {
+                    component: CompactComponent::from(&entry.component),
+                    input: char_to_u8(entry.input_char),
+                    output: char_to_u8(entry.output_char),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Traço de uma mensagem: a configuração inicial e os passos compactos.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Trace {
+    pub config: EnigmaConfig,
+    pub steps: Vec<CompactStep>,
+}
+
+impl Trace {
+    /// Cifra `text` com `config` e grava o caminho de cada letra.
+    pub fn record(config: EnigmaConfig, text: &str) -> Result<Self, String> {
+        let config = config.normalized()?;
+        let mut machine = EnigmaMachine::new(config.clone());
+        let steps = machine.encrypt_iter_detailed(text.chars()).map(|step| CompactStep::from(&step)).collect();
+        Ok(Self { config, steps })
+    }
+
+    /// Volta aos `EncryptionStep` que a UI mostra.
+    pub fn expand(&self) -> Result<Vec<EncryptionStep>, String> {
+        let config = self.config.clone().normalized()?;
+        let rotor = |name: &str| rotor_name(name).unwrap_or_default();
+        let right = rotor(&config.rotors.0.name);
+        let middle = rotor(&config.rotors.1.name);
+        let left = rotor(&config.rotors.2.name);
+        let reflector = reflector_name(&config.reflector).unwrap_or_default();
+        // Anéis (L, M, R), para o deslocamento de cada rotor na narração
+        let rings = [config.rotors.2.ring, config.rotors.1.ring, config.rotors.0.ring].map(char_to_u8);
+
+        let letter = |c: u8| {
+            if c < 26 {
+                Ok(u8_to_char(c))
+            } else {
+                Err(format!("Letra inválida no traço: {}", c))
+            }
+        };
+        let positions =
+            |p: [u8; 3]| -> Result<(char, char, char), String> { Ok((letter(p[0])?, letter(p[1])?, letter(p[2])?)) };
+
+        self.steps
+            .iter()
+            .map(|step| {
+                let after = positions(step.after)?;
+                let after = [after.0, after.1, after.2].map(char_to_u8);
+                let mut direction = PathDirection::Forward;
+                let mut path = Vec::with_capacity(step.path.len());
+                for entry in &step.path {
+                    let offset = |i: usize| (after[i] + 26 - rings[i]) % 26;
+                    let (component, offset) = match entry.component {
+                        CompactComponent::Plugboard => (Component::Plugboard, 0),
+                        CompactComponent::EntryWheel => (Component::EntryWheel, 0),
+                        CompactComponent::RotorLeft => {
+                            (Component::Rotor { slot: RotorSlot::Left, name: left }, offset(0))
+                        }
+                        CompactComponent::RotorMiddle => {
+                            (Component::Rotor { slot: RotorSlot::Middle, name: middle }, offset(1))
+                        }
+                        CompactComponent::RotorRight => {
+                            (Component::Rotor { slot: RotorSlot::Right, name: right }, offset(2))
+                        }
+                        CompactComponent::Reflector => (Component::Reflector { name: reflector }, 0),
+                    };
+                    let entry_direction = if entry.component == CompactComponent::Reflector {
+                        direction = PathDirection::Backward;
+                        PathDirection::Reflect
+                    } else {
+                        direction.clone()
+                    };
+                    // Confere as letras antes de montar a entrada
+                    letter(entry.input)?;
+                    letter(entry.output)?;
+                    path.push(PathEntry::new(component, entry_direction, entry.input, entry.output, offset));
+                }
+                Ok(EncryptionStep {
+                    input_char: letter(step.input)?,
+                    output_char: letter(step.output)?,
+                    positions_before_step: positions(step.before)?,
+                    positions_after_step: positions(step.after)?,
+                    path,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RotorConfig;
+
+    fn config() -> EnigmaConfig {
+        let rotor = |name: &str, position, ring| RotorConfig { name: name.into(), position, ring };
+        EnigmaConfig {
+            rotors: (rotor("iii", 'v', 'c'), rotor("I", 'K', 'B'), rotor("II", 'X', 'A')),
+            reflector: "c".into(),
+            plugboard_pairs: "AV BS CG".into(),
+        }
+    }
+
+    #[test]
+    fn test_expand_restores_steps() {
+        let trace = Trace::record(config(), "Ataque ao amanhecer").unwrap();
+        assert_eq!(trace.config.reflector, "C");
+        let expanded = trace.expand().unwrap();
+        let expected = EnigmaMachine::new(trace.config.clone()).process_string_detailed("ATAQUEAOAMANHECER");
+        assert_eq!(expanded.len(), expected.len());
+        for (got, want) in expanded.iter().zip(&expected) {
+            assert_eq!(got.output_char, want.output_char);
+            assert_eq!(got.positions_after_step, want.positions_after_step);
+            assert_eq!(got.path.len(), want.path.len());
+            for (a, b) in got.path.iter().zip(&want.path) {
+                assert_eq!(a.component, b.component);
+                assert_eq!((a.input_char, a.output_char), (b.input_char, b.output_char));
+                assert_eq!(core::mem::discriminant(&a.direction), core::mem::discriminant(&b.direction));
+                assert_eq!(a.narration, b.narration);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_invalid_trace() {
+        let mut trace = Trace::record(config(), "AB").unwrap();
+        trace.steps[1].path[0].output = 30;
+        assert!(trace.expand().is_err());
+        assert!(CompactComponent::try_from(9).is_err());
+        assert_eq!(CompactComponent::try_from(u8::from(CompactComponent::Reflector)), Ok(CompactComponent::Reflector));
+    }
+}
@@ -0,0 +1,378 @@
+//! Typex, a contraparte britânica da Enigma, atrás do mesmo `CipherMachine`.
+//!
+//! Cinco rotores numa fila: os três da esquerda giram como na Enigma (lento, médio
+//! e rápido, com o passo duplo do médio), mas cada um com várias ranhuras; os dois
+//! da direita são estatores, ajustados à mão e parados durante a mensagem. Todo
+//! rotor pode ser posto ao contrário, o que inverte a fiação. O refletor torna a
+//! máquina recíproca, como a Enigma.
+//!
+//! A Typex imprimia em fita em vez de acender lâmpadas. Ao cifrar, o espaço vira X
+//! e o texto cifrado sai em grupos de cinco letras; ao decifrar, o X sai como
+//! espaço.
+//!
+//! As fiações das Typex de serviço nunca foram publicadas: as daqui são de
+//! exemplo, para mostrar a mecânica.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{reversed_wiring, Reflector, Rotor, LATIN};
+use crate::{char_to_u8, u8_to_char, CipherMachine, OutputFormat, PathDirection};
+
+/// Rotores da Typex: nome, fiação e ranhuras.
+pub const TYPEX_ROTORS: [(&str, &str, &str); 8] = [
+    ("A", "FKTYWCQXGZAOIVJHPLBDMRNUES", "CLOUY"),
+    ("B", "XSGFZYWIUVQKBPTDLNOHAJCREM", "AIKMO"),
+    ("C", "GJZTSIFDEWRBYLUMAXVPKHOQNC", "DINRX"),
+    ("D", "TJBZUIHXQMRCAEDYGKLPVFOSNW", "GIKRS"),
+    ("E", "PUSBRJWXGCFIQAHEOVTNYZKDML", "DLOUY"),
+    ("F", "HXRGDJUTBOCMYWVESLKQIAPZNF", "DHJLW"),
+    ("G", "PQSXHRDFNZIOJYLECBVMWTAUGK", "EGVYZ"),
+    ("H", "PNFQGXJMALTOEBKRUCHWYDSZIV", "AINWY"),
+];
+
+/// Fiação do refletor da Typex.
+pub const TYPEX_REFLECTOR: &str = "UXPWNVTRSMQYJEZCKHIGAFDBLO";
+
+/// Número de rotores na fila; os dois últimos são os estatores.
+pub const TYPEX_SLOTS: usize = 5;
+
+/// Um rotor na fila, como vem da UI.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TypexRotorConfig {
+    /// Nome do rotor ("A" a "H").
+    pub name: String,
+    /// Letra na janela.
+    pub position: char,
+    /// Anel (letra).
+    pub ring: char,
+    /// Posto ao contrário na fila.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reversed: bool,
+}
+
+/// Se a máquina está cifrando ou decifrando: muda só a impressão.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TypexMode {
+    #[default]
+    Encipher,
+    Decipher,
+}
+
+/// Configuração da Typex.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TypexConfig {
+    /// Da esquerda para a direita: os rotores lento, médio e rápido e os dois
+    /// estatores.
+    pub rotors: [TypexRotorConfig; TYPEX_SLOTS],
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mode: TypexMode,
+}
+
+/// Componente por onde o sinal passa na Typex, com a variante em `type`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
+pub enum TypexComponent {
+    /// `slot` conta da esquerda (0) para a direita (4).
+    Rotor { slot: usize, name: &'static str, stator: bool, reversed: bool },
+    Reflector,
+}
+
+/// Um trecho do caminho do sinal na Typex.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct TypexPathEntry {
+    pub component: TypexComponent,
+    pub input_char: char,
+    pub output_char: char,
+    /// Contatos físicos (0-25) por onde o sinal entra e sai, já com a posição e o
+    /// anel do rotor.
+    pub input_contact: u8,
+    pub output_contact: u8,
+    pub direction: PathDirection,
+}
+
+/// Uma tecla na Typex.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct TypexStep {
+    /// Letra teclada (o espaço já trocado por X, ao cifrar).
+    pub input_char: char,
+    /// Letra que sai do circuito.
+    pub output_char: char,
+    /// O que a impressora escreve (o X vira espaço, ao decifrar).
+    pub printed: char,
+    /// Letras nas janelas, da esquerda para a direita, antes e depois do passo.
+    pub positions_before_step: [char; TYPEX_SLOTS],
+    pub positions_after_step: [char; TYPEX_SLOTS],
+    pub path: Vec<TypexPathEntry>,
+}
+
+/// Estado visível da Typex.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TypexState {
+    /// Letras nas janelas, da esquerda para a direita.
+    pub positions: [char; TYPEX_SLOTS],
+}
+
+#[derive(Debug, Clone)]
+struct TypexRotor {
+    rotor: Rotor<26>,
+    notches: [bool; 26],
+    reversed: bool,
+}
+
+impl TypexRotor {
+    fn new(config: &TypexRotorConfig) -> Result<Self, String> {
+        let &(name, wiring, notch_letters) = TYPEX_ROTORS
+            .iter()
+            .find(|(name, ..)| *name == config.name)
+            .ok_or_else(|| format!("Rotor da Typex desconhecido: {}", config.name))?;
+        let letter = |c: char, what: &str| {
+            let c = c.to_ascii_uppercase();
+            if c.is_ascii_uppercase() {
+                Ok(char_to_u8(c))
+            } else {
+                Err(format!("{} inválido no rotor {}: {}", what, name, c))
+            }
+        };
+        let mut wiring = LATIN.wiring(wiring)?;
+        if config.reversed {
+            wiring = reversed_wiring(&wiring);
+        }
+        let mut notches = [false; 26];
+        for c in notch_letters.chars() {
+            notches[char_to_u8(c) as usize] = true;
+        }
+        let rotor = Rotor::new(wiring, 0, letter(config.position, "Posição")?, letter(config.ring, "Anel")?, name);
+        Ok(Self { rotor, notches, reversed: config.reversed })
+    }
+
+    fn at_notch(&self) -> bool {
+        self.notches[self.rotor.position() as usize]
+    }
+}
+
+/// A Typex.
+#[derive(Debug, Clone)]
+pub struct TypexMachine {
+    /// Da esquerda para a direita.
+    rotors: [TypexRotor; TYPEX_SLOTS],
+    reflector: Reflector<26>,
+    mode: TypexMode,
+}
+
+impl TypexMachine {
+    pub fn new(config: TypexConfig) -> Result<Self, String> {
+        for (i, rotor) in config.rotors.iter().enumerate() {
+            if config.rotors[..i].iter().any(|other| other.name == rotor.name) {
+                return Err(format!("Rotor da Typex repetido: {}", rotor.name));
+            }
+        }
+        let [a, b, c, d, e] = config.rotors.each_ref().map(TypexRotor::new);
+        Ok(Self {
+            rotors: [a?, b?, c?, d?, e?],
+            reflector: Reflector::new(LATIN.wiring(TYPEX_REFLECTOR)?, "Typex"),
+            mode: config.mode,
+        })
+    }
+
+    /// Letras nas janelas, da esquerda para a direita.
+    pub fn positions(&self) -> [char; TYPEX_SLOTS] {
+        core::array::from_fn(|i| u8_to_char(self.rotors[i].rotor.position()))
+    }
+
+    /// Gira os três rotores da esquerda, como na Enigma: o rápido sempre, o médio
+    /// quando o rápido está numa ranhura (ou ele mesmo, no passo duplo) e o lento
+    /// quando o médio está numa ranhura. Os estatores não giram.
+    pub fn step_rotors(&mut self) {
+        let medium_at_notch = self.rotors[1].at_notch();
+        if medium_at_notch {
+            self.rotors[0].rotor.step();
+        }
+        if medium_at_notch || self.rotors[2].at_notch() {
+            self.rotors[1].rotor.step();
+        }
+        self.rotors[2].rotor.step();
+    }
+
+    /// Imprime `text` como a Typex: cifrando, em grupos de cinco letras.
+    pub fn print(&mut self, text: &str) -> String {
+        let output = self.process_string(text);
+        match self.mode {
+            TypexMode::Encipher => OutputFormat::default().apply(&output),
+            TypexMode::Decipher => output,
+        }
+    }
+
+    /// Passa `c` pelo circuito, anotando cada trecho em `path` se houver um.
+    fn scramble(&self, c: u8, mut path: Option<&mut Vec<TypexPathEntry>>) -> u8 {
+        let mut signal = c;
+        let mut pass = |slot: Option<usize>, direction: PathDirection, input: u8, output: u8| {
+            let Some(path) = path.as_deref_mut() else { return };
+            let (component, offset) = match slot {
+                Some(slot) => {
+                    let rotor = &self.rotors[slot];
+                    let stator = slot >= 3;
+                    let component =
+                        TypexComponent::Rotor { slot, name: rotor.rotor.name(), stator, reversed: rotor.reversed };
+                    (component, rotor.rotor.offset())
+                }
+                None => (TypexComponent::Reflector, 0),
+            };
+            path.push(TypexPathEntry {
+                component,
+                input_char: u8_to_char(input),
+                output_char: u8_to_char(output),
+                input_contact: (input + offset) % 26,
+                output_contact: (output + offset) % 26,
+                direction,
+            });
+        };
+        for slot in (0..TYPEX_SLOTS).rev() {
+            let output = self.rotors[slot].rotor.forward(signal);
+            pass(Some(slot), PathDirection::Forward, signal, output);
+            signal = output;
+        }
+        let output = self.reflector.reflect(signal);
+        pass(None, PathDirection::Reflect, signal, output);
+        signal = output;
+        for slot in 0..TYPEX_SLOTS {
+            let output = self.rotors[slot].rotor.backward(signal);
+            pass(Some(slot), PathDirection::Backward, signal, output);
+            signal = output;
+        }
+        signal
+    }
+
+    /// O que a impressora escreve para a letra `c` que saiu do circuito.
+    fn printed(&self, c: char) -> char {
+        if self.mode == TypexMode::Decipher && c == 'X' {
+            ' '
+        } else {
+            c
+        }
+    }
+}
+
+impl CipherMachine for TypexMachine {
+    type Step = TypexStep;
+    type State = TypexState;
+
+    fn name(&self) -> &'static str {
+        "Typex"
+    }
+
+    /// Cifrando, a tecla de espaço também vale (como X).
+    fn accepts(&self, c: char) -> bool {
+        c.is_ascii_alphabetic() || (c == ' ' && self.mode == TypexMode::Encipher)
+    }
+
+    fn process_char(&mut self, c: char) -> char {
+        let c = if c == ' ' { 'X' } else { c };
+        self.step_rotors();
+        self.printed(u8_to_char(self.scramble(char_to_u8(c), None)))
+    }
+
+    fn process_detailed(&mut self, c: char) -> (char, TypexStep) {
+        let input_char = if c == ' ' { 'X' } else { c };
+        let positions_before_step = self.positions();
+        self.step_rotors();
+        let mut path = Vec::with_capacity(2 * TYPEX_SLOTS + 1);
+        let output_char = u8_to_char(self.scramble(char_to_u8(input_char), Some(&mut path)));
+        let printed = self.printed(output_char);
+        let step = TypexStep {
+            input_char,
+            output_char,
+            printed,
+            positions_before_step,
+            positions_after_step: self.positions(),
+            path,
+        };
+        (printed, step)
+    }
+
+    fn snapshot(&self) -> TypexState {
+        TypexState { positions: self.positions() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(positions: &str, mode: TypexMode) -> TypexConfig {
+        let names = ["A", "B", "C", "D", "E"];
+        let mut positions = positions.chars();
+        let rotors = names.map(|name| TypexRotorConfig {
+            name: name.into(),
+            position: positions.next().unwrap(),
+            ring: 'A',
+            reversed: name == "B",
+        });
+        TypexConfig { rotors, mode }
+    }
+
+    #[test]
+    fn test_reciprocal_with_printing_conventions() {
+        let mut typex = TypexMachine::new(config("AAAAA", TypexMode::Encipher)).unwrap();
+        let printed = typex.print("attack at dawn");
+        assert_eq!(printed.len(), 16);
+        assert_eq!(printed.split(' ').map(str::len).collect::<Vec<_>>(), [5, 5, 4]);
+
+        let mut typex = TypexMachine::new(config("AAAAA", TypexMode::Decipher)).unwrap();
+        assert_eq!(typex.print(&printed), "ATTACK AT DAWN");
+        assert!(!typex.accepts(' '));
+
+        // Como a Enigma, nenhuma letra cifra nela mesma
+        let mut typex = TypexMachine::new(config("MNOPQ", TypexMode::Encipher)).unwrap();
+        let output = typex.process_string(&"E".repeat(200));
+        assert!(!output.contains('E'));
+    }
+
+    #[test]
+    fn test_stators_stay_and_medium_double_steps() {
+        // O rotor C (rápido) tem ranhura em D e o A (médio, no meio da fila agora)
+        // em C
+        let mut cfg = config("ABCKZ", TypexMode::Encipher);
+        cfg.rotors.swap(1, 0);
+        cfg.rotors[1].position = 'B';
+        cfg.rotors[0].position = 'A';
+        let mut typex = TypexMachine::new(cfg).unwrap();
+        let steps = typex.process_string_detailed("AAAA");
+        let after: Vec<String> = steps.iter().map(|step| step.positions_after_step.iter().collect()).collect();
+        assert_eq!(after, ["ABDKZ", "ACEKZ", "BDFKZ", "BDGKZ"]);
+        assert_eq!(steps[0].path.len(), 11);
+        assert!(matches!(steps[0].path[0].component, TypexComponent::Rotor { slot: 4, stator: true, .. }));
+        assert_eq!(typex.snapshot().positions, ['B', 'D', 'G', 'K', 'Z']);
+        assert_eq!(typex.name(), "Typex");
+    }
+
+    #[test]
+    fn test_reversed_rotor_changes_the_wiring() {
+        let rotor = |reversed| TypexRotor::new(&TypexRotorConfig { name: "A".into(), position: 'A', ring: 'A', reversed });
+        let (forward, reversed) = (rotor(false).unwrap(), rotor(true).unwrap());
+        // A fiação virada é a inversa espelhada: w'(x) = -w⁻¹(-x)
+        for x in 0..26 {
+            assert_eq!((26 - forward.rotor.forward((26 - reversed.rotor.forward(x)) % 26)) % 26, x);
+        }
+        assert_ne!(forward.rotor.forward(0), reversed.rotor.forward(0));
+
+        let mut bad = config("AAAAA", TypexMode::Encipher);
+        bad.rotors[4].name = "A".into();
+        assert!(TypexMachine::new(bad).unwrap_err().contains("repetido"));
+        let mut bad = config("AAAAA", TypexMode::Encipher);
+        bad.rotors[2].name = "Z".into();
+        assert!(TypexMachine::new(bad).is_err());
+        assert!(TypexMachine::new(config("AA1AA", TypexMode::Encipher)).is_err());
+    }
+}
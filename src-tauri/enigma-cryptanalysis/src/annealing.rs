@@ -0,0 +1,122 @@
+//! Recozimento simulado (simulated annealing).
+//!
+//! A subida de encosta só aceita melhorias e costuma empacar em máximos locais,
+//! principalmente com textos curtos. O recozimento aceita também pioras, com
+//! probabilidade que cai conforme a "temperatura" esfria, e recomeça do zero
+//! algumas vezes, guardando o melhor estado visto em todas as tentativas.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+
+/// Parâmetros do recozimento, vindos da UI (todos opcionais).
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct AnnealingOptions {
+    /// Temperatura inicial, relativa à pontuação: com 0,05, uma piora de 5% é aceita
+    /// com probabilidade 1/e no início.
+    pub initial_temperature: f64,
+    /// Temperatura em que cada tentativa termina.
+    pub final_temperature: f64,
+    /// Fator de resfriamento aplicado a cada patamar (entre 0 e 1).
+    pub cooling_rate: f64,
+    /// Movimentos testados em cada patamar de temperatura.
+    pub moves_per_temperature: usize,
+    /// Número de recomeços a partir do estado inicial.
+    pub restarts: usize,
+    /// Semente do gerador aleatório, para repetir uma execução (aleatória se ausente).
+    pub seed: Option<u64>,
+}
+
+impl Default for AnnealingOptions {
+    fn default() -> Self {
+        Self {
+            initial_temperature: 0.05,
+            final_temperature: 0.001,
+            cooling_rate: 0.95,
+            moves_per_temperature: 200,
+            restarts: 3,
+            seed: None,
+        }
+    }
+}
+
+/// Resultado do recozimento: melhor estado, sua pontuação e movimentos aceitos.
+pub struct Annealed<S> {
+    pub state: S,
+    pub score: f64,
+    pub accepted: usize,
+}
+
+/// Executa o recozimento a partir de `initial`. `neighbour` sorteia um estado
+/// vizinho (ou `None` se o sorteio não gerou movimento válido) e `evaluate` o pontua.
+pub fn anneal<S: Clone>(
+    initial: &S,
+    options: &AnnealingOptions,
+    evaluate: impl Fn(&S) -> f64,
+    mut neighbour: impl FnMut(&S, &mut StdRng) -> Option<S>,
+) -> Annealed<S> {
+    let mut rng = match options.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let cooling = options.cooling_rate.clamp(0.5, 0.999);
+    let final_temperature = options.final_temperature.max(1e-9);
+
+    let mut best = Annealed { state: initial.clone(), score: evaluate(initial), accepted: 0 };
+
+    for _ in 0..options.restarts.max(1) {
+        let mut current = initial.clone();
+        let mut current_score = evaluate(&current);
+        let mut temperature = options.initial_temperature;
+
+        while temperature > final_temperature {
+            for _ in 0..options.moves_per_temperature {
+                let Some(candidate) = neighbour(&current, &mut rng) else {
+                    continue;
+                };
+                let candidate_score = evaluate(&candidate);
+                // Piora relativa, para a temperatura valer em qualquer escala de pontuação
+                let delta = (candidate_score - current_score) / current_score.abs().max(1e-9);
+                if delta >= 0.0 || rng.gen::<f64>() < (delta / temperature).exp() {
+                    current = candidate;
+                    current_score = candidate_score;
+                    best.accepted += 1;
+                    if current_score > best.score {
+                        best.state = current.clone();
+                        best.score = current_score;
+                    }
+                }
+            }
+            temperature *= cooling;
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escapes_local_maximum() {
+        // Máximo local em 10 e global em 30; a subida de encosta a partir de 0,
+        // com passos de no máximo 2, pararia em 10.
+        let evaluate = |x: &i32| {
+            let x = *x as f64;
+            1.0 + 2.0 * (-(x - 10.0).powi(2) / 50.0).exp() + 4.0 * (-(x - 30.0).powi(2) / 50.0).exp()
+        };
+        let options = AnnealingOptions { seed: Some(7), initial_temperature: 0.3, ..Default::default() };
+        let result = anneal(&0, &options, evaluate, |x, rng| Some(x + rng.gen_range(-2..=2)));
+
+        assert_eq!(result.state, 30);
+    }
+
+    #[test]
+    fn test_same_seed_same_result() {
+        let options = AnnealingOptions { seed: Some(42), restarts: 1, ..Default::default() };
+        let evaluate = |x: &i32| 100.0 - (*x as f64 - 3.0).abs();
+        let run = || anneal(&0, &options, evaluate, |x, rng| Some(x + rng.gen_range(-1..=1)));
+        assert_eq!(run().state, run().state);
+    }
+}
@@ -0,0 +1,131 @@
+//! Análise "e se": o quanto a saída muda quando uma configuração muda.
+//!
+//! Cifra o mesmo texto com duas configurações e compara as saídas letra a letra.
+//! Mudar a posição do rotor da direita muda quase tudo desde a primeira letra; já
+//! mudar o anel do rotor da esquerda ou um par do plugboard com letras raras pode
+//! mudar pouco, e é isso que tornava alguns atalhos da criptoanálise possíveis.
+
+use serde::Serialize;
+
+use super::validate_config;
+use crate::enigma::{EnigmaConfig, EnigmaMachine, RotorConfig};
+
+/// Uma configuração que difere entre as duas chaves.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct SettingChange {
+    pub setting: String,
+    pub a: String,
+    pub b: String,
+}
+
+/// Comparação das duas saídas.
+#[derive(Debug, Serialize, Clone)]
+pub struct SettingsComparison {
+    pub output_a: String,
+    pub output_b: String,
+    /// Para cada posição, se as saídas diferem.
+    pub differs: Vec<bool>,
+    /// Número de posições diferentes (distância de Hamming).
+    pub hamming_distance: usize,
+    /// Fração das posições diferentes. Saídas sem relação ficam perto de 25/26.
+    pub ratio: f64,
+    /// Primeira posição em que as saídas diferem.
+    pub first_difference: Option<usize>,
+    /// O que muda de uma configuração para a outra.
+    pub changes: Vec<SettingChange>,
+}
+
+/// Cifra `text` com as duas configurações e compara as saídas. Os caracteres não
+/// alfabéticos são ignorados, como na máquina.
+pub fn compare_settings(a: &EnigmaConfig, b: &EnigmaConfig, text: &str) -> Result<SettingsComparison, String> {
+    let a = &validate_config(a)?;
+    let b = &validate_config(b)?;
+    let output_a = EnigmaMachine::new(a.clone()).process_string(text);
+    let output_b = EnigmaMachine::new(b.clone()).process_string(text);
+    if output_a.is_empty() {
+        return Err("O texto não contém letras.".to_string());
+    }
+
+    let differs: Vec<bool> = output_a.chars().zip(output_b.chars()).map(|(x, y)| x != y).collect();
+    let hamming_distance = differs.iter().filter(|&&d| d).count();
+    Ok(SettingsComparison {
+        ratio: hamming_distance as f64 / differs.len() as f64,
+        first_difference: differs.iter().position(|&d| d),
+        hamming_distance,
+        differs,
+        output_a,
+        output_b,
+        changes: setting_changes(a, b),
+    })
+}
+
+/// Lista as configurações que diferem entre `a` e `b`.
+pub fn setting_changes(a: &EnigmaConfig, b: &EnigmaConfig) -> Vec<SettingChange> {
+    let mut changes = Vec::new();
+    let mut push = |setting: String, x: String, y: String| {
+        if x != y {
+            changes.push(SettingChange { setting, a: x, b: y });
+        }
+    };
+
+    let rotors: [(&str, &RotorConfig, &RotorConfig); 3] = [
+        ("esquerdo", &a.rotors.2, &b.rotors.2),
+        ("do meio", &a.rotors.1, &b.rotors.1),
+        ("direito", &a.rotors.0, &b.rotors.0),
+    ];
+    for (slot, x, y) in rotors {
+        push(format!("Rotor {}", slot), x.name.clone(), y.name.clone());
+        push(format!("Posição do rotor {}", slot), x.position.to_string(), y.position.to_string());
+        push(format!("Anel do rotor {}", slot), x.ring.to_string(), y.ring.to_string());
+    }
+    push("Refletor".to_string(), a.reflector.to_uppercase(), b.reflector.to_uppercase());
+    push("Plugboard".to_string(), plugboard(&a.plugboard_pairs), plugboard(&b.plugboard_pairs));
+    changes
+}
+
+/// Pares do plugboard em forma canônica (cada par e a lista em ordem alfabética).
+fn plugboard(pairs: &str) -> String {
+    let mut pairs: Vec<String> = pairs
+        .split_whitespace()
+        .map(|pair| {
+            let mut letters: Vec<char> = pair.to_uppercase().chars().collect();
+            letters.sort_unstable();
+            letters.into_iter().collect()
+        })
+        .collect();
+    pairs.sort();
+    pairs.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_config;
+
+    const TEXT: &str = "WETTERBERICHT FUER DIE NORDSEE";
+
+    #[test]
+    fn test_identical_settings() {
+        let config = key_config(("I", "II", "III"), "B", ('A', 'B', 'C'), ('A', 'A', 'A'), "AB CD");
+        let mut same = config.clone();
+        same.plugboard_pairs = "DC BA".to_string();
+        let comparison = compare_settings(&config, &same, TEXT).unwrap();
+        assert_eq!(comparison.hamming_distance, 0);
+        assert_eq!(comparison.first_difference, None);
+        assert!(comparison.changes.is_empty());
+        assert_eq!(comparison.differs.len(), 27);
+    }
+
+    #[test]
+    fn test_one_setting_changed() {
+        let a = key_config(("I", "II", "III"), "B", ('A', 'B', 'C'), ('A', 'A', 'A'), "");
+        let b = key_config(("I", "II", "III"), "B", ('A', 'B', 'D'), ('A', 'A', 'A'), "");
+        let comparison = compare_settings(&a, &b, TEXT).unwrap();
+        assert_eq!(comparison.first_difference, Some(0));
+        assert!(comparison.ratio > 0.7);
+        assert_eq!(
+            comparison.changes,
+            vec![SettingChange { setting: "Posição do rotor direito".to_string(), a: "C".to_string(), b: "D".to_string() }]
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! Banburismus (Bletchley Park, 1941).
+//!
+//! Mensagens navais do mesmo dia partiam da mesma Grundstellung, com chaves de
+//! mensagem diferentes. Se duas chaves diferem só no rotor da direita, os textos
+//! cifrados ficam "em profundidade" quando deslocados pela distância entre elas:
+//! nas posições alinhadas a máquina está no mesmo estado, e letras iguais no texto
+//! claro geram letras iguais no cifrado. Texto alemão repete letras com frequência
+//! perto de 1/17, contra 1/26 para texto aleatório; Turing pesava cada evidência em
+//! decibans (décimos de ordem de grandeza) para escolher o deslocamento mais provável.
+
+use serde::Serialize;
+
+/// Taxa de repetição de letras no texto claro naval alemão (cerca de 1/17).
+pub const NAVAL_GERMAN_REPEAT_RATE: f64 = 1.0 / 17.0;
+
+/// Taxa de repetição para letras aleatórias (1/26).
+const RANDOM_REPEAT_RATE: f64 = 1.0 / 26.0;
+
+/// Pontuação de um deslocamento entre duas mensagens.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Alignment {
+    /// Deslocamento da segunda mensagem em relação à primeira: com `offset = k`,
+    /// a letra `i` da segunda fica sob a letra `i + k` da primeira.
+    pub offset: i32,
+    /// Número de letras sobrepostas.
+    pub overlap: usize,
+    /// Número de repetições (letras iguais alinhadas).
+    pub repeats: usize,
+    /// Maior sequência de repetições consecutivas (bigramas, trigramas...).
+    pub longest_run: usize,
+    /// Peso da evidência a favor de "em profundidade", em decibans.
+    pub decibans: f64,
+}
+
+/// Peso em decibans de uma repetição e de uma não-repetição.
+fn weights(repeat_rate: f64) -> (f64, f64) {
+    let repeat = 10.0 * (repeat_rate / RANDOM_REPEAT_RATE).log10();
+    let miss = 10.0 * ((1.0 - repeat_rate) / (1.0 - RANDOM_REPEAT_RATE)).log10();
+    (repeat, miss)
+}
+
+/// Pontua um único deslocamento entre duas mensagens (apenas letras A-Z).
+pub fn score_offset(first: &[u8], second: &[u8], offset: i32, repeat_rate: f64) -> Alignment {
+    let (repeat_weight, miss_weight) = weights(repeat_rate);
+    let (start_first, start_second) = if offset >= 0 {
+        (offset as usize, 0)
+    } else {
+        (0, offset.unsigned_abs() as usize)
+    };
+
+    let mut overlap = 0;
+    let mut repeats = 0;
+    let mut run = 0;
+    let mut longest_run = 0;
+    for (a, b) in first.iter().skip(start_first).zip(second.iter().skip(start_second)) {
+        overlap += 1;
+        if a == b {
+            repeats += 1;
+            run += 1;
+            longest_run = longest_run.max(run);
+        } else {
+            run = 0;
+        }
+    }
+
+    let decibans = repeats as f64 * repeat_weight + (overlap - repeats) as f64 * miss_weight;
+    Alignment { offset, overlap, repeats, longest_run, decibans }
+}
+
+/// Desliza a segunda mensagem sobre a primeira em todos os deslocamentos de
+/// `-max_offset` a `max_offset` e ordena os resultados do mais ao menos provável.
+/// Os caracteres não alfabéticos são ignorados.
+pub fn rank_distances(first: &str, second: &str, max_offset: usize, repeat_rate: f64) -> Vec<Alignment> {
+    let first = letters(first);
+    let second = letters(second);
+    // Além da soma dos comprimentos não há sobreposição
+    let max_offset = max_offset.min(first.len() + second.len()) as i32;
+
+    let mut alignments: Vec<Alignment> = (-max_offset..=max_offset)
+        .map(|offset| score_offset(&first, &second, offset, repeat_rate))
+        .filter(|alignment| alignment.overlap > 0)
+        .collect();
+    alignments.sort_by(|a, b| b.decibans.total_cmp(&a.decibans));
+    alignments
+}
+
+/// Mantém apenas as letras, em maiúsculas, como bytes.
+fn letters(text: &str) -> Vec<u8> {
+    text.bytes()
+        .filter(|b| b.is_ascii_alphabetic())
+        .map(|b| b.to_ascii_uppercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enigma::{EnigmaConfig, EnigmaMachine, RotorConfig};
+
+    fn config(right_position: char) -> EnigmaConfig {
+        EnigmaConfig {
+            rotors: (
+                RotorConfig { name: "II".to_string(), position: right_position, ring: 'A' },
+                RotorConfig { name: "I".to_string(), position: 'K', ring: 'A' },
+                RotorConfig { name: "III".to_string(), position: 'D', ring: 'A' },
+            ),
+            reflector: "B".to_string(),
+            plugboard_pairs: "AR GK OX".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_weights() {
+        let (repeat, miss) = weights(NAVAL_GERMAN_REPEAT_RATE);
+        assert!((repeat - 1.84).abs() < 0.01);
+        assert!(miss < 0.0 && miss > -0.1);
+    }
+
+    #[test]
+    fn test_score_offset_counts() {
+        let alignment = score_offset(b"ABCDE", b"XCDEY", 1, NAVAL_GERMAN_REPEAT_RATE);
+        // B-X, C-C, D-D, E-E
+        assert_eq!(alignment.overlap, 4);
+        assert_eq!(alignment.repeats, 3);
+        assert_eq!(alignment.longest_run, 3);
+        assert_eq!(rank_distances("ABCDE", "XCDEY", usize::MAX, NAVAL_GERMAN_REPEAT_RATE).len(), 9);
+    }
+
+    #[test]
+    fn test_recovers_right_wheel_distance() {
+        // Mensagens com trechos estereotipados (saudação, boletim do tempo, fecho),
+        // como as que davam a Banburismus evidência suficiente
+        let first_plain = "VONXOBKXXBEFEHLSHABERDERUBOOTEXANXALLEBOOTEXSOFORTMELDENXPOSITIONXKURSXUNDXGESCHWINDIGKEITXWETTERBERICHTXWINDSTAERKEDREIXSEEGANGZWEIXSICHTNEUNSEEMEILENXKEINEBESONDERENEREIGNISSEXENDE";
+        let second_plain = "XXBEFEHLSHABERDERUBOOTEXEIGENEPOSITIONXQUADRATXANTONXDREIVIERXKURSXNORDWESTXFAHRTXZWWETTERBERICHTXWINDSTAERKEVIERXSEEGANGDREIXSICHTGUTXNEBELXXXXXKEINEBESONDERENEREIGNISSEXENDE";
+        // Chaves F e M: distância 7, sem passar pela ranhura do rotor II ('E') entre elas
+        let first = EnigmaMachine::new(config('F')).process_string(first_plain);
+        let second = EnigmaMachine::new(config('M')).process_string(second_plain);
+
+        let ranking = rank_distances(&first, &second, 25, NAVAL_GERMAN_REPEAT_RATE);
+        assert_eq!(ranking[0].offset, 7);
+        assert!(ranking[0].decibans > 0.0);
+    }
+}
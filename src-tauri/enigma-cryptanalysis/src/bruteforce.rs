@@ -0,0 +1,567 @@
+//! Busca exaustiva da chave, em paralelo em todos os núcleos.
+//!
+//! Percorre ordens de rotores, refletores e as 26³ posições iniciais (anéis em 'A',
+//! sem plugboard) e pontua cada decifração com um `FitnessScorer`: tipicamente as
+//! coincidências com um crib (texto claro provável no início da mensagem) ou o
+//! índice de coincidência, que sobe quando o texto decifrado se parece com uma
+//! língua natural mesmo que o plugboard ainda esteja errado.
+//!
+//! A busca anda em lotes de blocos, sempre na mesma ordem. Ao fim de cada lote o
+//! estado (`SearchCursor`) é repassado a quem chamou, que pode salvá-lo e retomar a
+//! busca dali mais tarde. A cada bloco, um `SearchEvent` descreve o que acabou de
+//! ser testado e o melhor candidato até ali, para a UI animar a busca.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::fitness::{FitnessScorer, LanguageGuess};
+use super::kernel::ScramblerTable;
+use super::plugboard::MAX_PAIRS;
+use super::stats::letters;
+use super::{
+    decrypt, key_config, language_of, machine_at, order_names, parse_reflector, parse_rotor, parse_rotor_order,
+    preview, rotor_orders, rotor_orders_from, RotorOrder,
+};
+use crate::enigma::{EnigmaConfig, EnigmaMachine, RotorConfig, REFLECTOR_NAMES};
+
+/// Faixa de posições de um rotor, de `from` a `to` inclusive, dando a volta no
+/// alfabeto se `to` vier antes de `from` (ex.: 'X' a 'C').
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct PositionRange {
+    pub from: char,
+    pub to: char,
+}
+
+impl Default for PositionRange {
+    fn default() -> Self {
+        Self { from: 'A', to: 'Z' }
+    }
+}
+
+impl PositionRange {
+    /// Posições da faixa, em ordem.
+    pub fn positions(&self) -> Result<Vec<char>, String> {
+        let (from, to) = (self.from.to_ascii_uppercase(), self.to.to_ascii_uppercase());
+        if !from.is_ascii_uppercase() || !to.is_ascii_uppercase() {
+            return Err(format!("Faixa de posições inválida: {}-{}", self.from, self.to));
+        }
+        let len = (to as u8 + 26 - from as u8) % 26 + 1;
+        Ok((0..len).map(|i| ((from as u8 - b'A' + i) % 26 + b'A') as char).collect())
+    }
+}
+
+/// Restrições da busca, vindas da UI. Servem para dimensionar exercícios que
+/// terminem em segundos: cada restrição divide o número de chaves testadas.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SearchSpace {
+    /// Ordens de rotores a testar (Direita, Meio, Esquerda). Vazio = todas as
+    /// ordens formadas com `rotors`.
+    #[serde(default)]
+    pub rotor_orders: Vec<(String, String, String)>,
+    /// Rotores disponíveis para montar as ordens. Vazio = todos.
+    #[serde(default)]
+    pub rotors: Vec<String>,
+    /// Refletores a testar. Vazio = todos.
+    #[serde(default)]
+    pub reflectors: Vec<String>,
+    /// Faixas de posições (Esquerda, Meio, Direita). Padrão: A-Z.
+    #[serde(default)]
+    pub positions: (PositionRange, PositionRange, PositionRange),
+    /// Limite de pares do plugboard nas etapas que o recuperam.
+    #[serde(default = "default_max_plugboard_pairs")]
+    pub max_plugboard_pairs: usize,
+    /// Quantos candidatos retornar.
+    #[serde(default = "default_top_n")]
+    pub top_n: usize,
+    /// Só os blocos de `start` a `end` (exclusivo) da busca completa, na ordem da
+    /// busca. Serve para dividir a busca entre várias máquinas (`partition`).
+    #[serde(default)]
+    pub block_range: Option<(usize, usize)>,
+}
+
+fn default_top_n() -> usize {
+    10
+}
+
+fn default_max_plugboard_pairs() -> usize {
+    MAX_PAIRS
+}
+
+impl Default for SearchSpace {
+    fn default() -> Self {
+        Self {
+            rotor_orders: Vec::new(),
+            rotors: Vec::new(),
+            reflectors: Vec::new(),
+            positions: Default::default(),
+            max_plugboard_pairs: default_max_plugboard_pairs(),
+            top_n: default_top_n(),
+            block_range: None,
+        }
+    }
+}
+
+impl SearchSpace {
+    /// Ordens de rotores a testar.
+    pub fn orders(&self) -> Result<Vec<RotorOrder>, String> {
+        if !self.rotor_orders.is_empty() {
+            return self.rotor_orders.iter().map(parse_rotor_order).collect();
+        }
+        if self.rotors.is_empty() {
+            return Ok(rotor_orders());
+        }
+        let mut pool: Vec<&'static str> = self.rotors.iter().map(|r| parse_rotor(r)).collect::<Result<_, _>>()?;
+        pool.sort_unstable();
+        pool.dedup();
+        if pool.len() < 3 {
+            return Err("Escolha pelo menos três rotores diferentes.".to_string());
+        }
+        Ok(rotor_orders_from(&pool))
+    }
+
+    /// Refletores a testar.
+    pub fn reflector_names(&self) -> Result<Vec<&'static str>, String> {
+        if self.reflectors.is_empty() {
+            Ok(REFLECTOR_NAMES.to_vec())
+        } else {
+            self.reflectors.iter().map(|r| parse_reflector(r)).collect()
+        }
+    }
+
+    /// Número de blocos da busca completa, sem `block_range`. Um bloco são as
+    /// posições do meio e da direita para uma ordem, um refletor e uma posição do
+    /// rotor esquerdo.
+    pub fn block_count(&self) -> Result<usize, String> {
+        Ok(self.orders()?.len() * self.reflector_names()?.len() * self.positions.0.positions()?.len())
+    }
+
+    /// Blocos da busca completa a percorrer, validando `block_range`.
+    pub fn blocks(&self) -> Result<std::ops::Range<usize>, String> {
+        let count = self.block_count()?;
+        match self.block_range {
+            None => Ok(0..count),
+            Some((start, end)) if start <= end && end <= count => Ok(start..end),
+            Some((start, end)) => Err(format!("Faixa de blocos inválida: {}-{} (a busca tem {}).", start, end, count)),
+        }
+    }
+
+    /// Número de chaves que a busca exaustiva vai testar.
+    pub fn size(&self) -> Result<usize, String> {
+        Ok(self.blocks()?.len() * self.positions.1.positions()?.len() * self.positions.2.positions()?.len())
+    }
+}
+
+/// Uma chave candidata encontrada pela busca.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BruteforceCandidate {
+    /// Ordem dos rotores (Direita, Meio, Esquerda), como em `EnigmaConfig`.
+    pub rotors: (String, String, String),
+    pub reflector: String,
+    /// Posição inicial dos rotores (Esquerda, Meio, Direita), com anéis em 'A'.
+    pub positions: (char, char, char),
+    /// Pontuação dada pelo `FitnessScorer` da busca.
+    pub score: f64,
+    /// Início do texto decifrado com esta chave.
+    pub preview: String,
+    /// Língua mais provável do texto decifrado.
+    pub language: Option<LanguageGuess>,
+}
+
+impl BruteforceCandidate {
+    /// Configuração da máquina para este candidato (anéis em 'A', sem plugboard).
+    pub fn config(&self) -> EnigmaConfig {
+        let rotor = |name: &str, position: char| RotorConfig { name: name.to_string(), position, ring: 'A' };
+        EnigmaConfig {
+            rotors: (
+                rotor(&self.rotors.0, self.positions.2),
+                rotor(&self.rotors.1, self.positions.1),
+                rotor(&self.rotors.2, self.positions.0),
+            ),
+            reflector: self.reflector.clone(),
+            plugboard_pairs: String::new(),
+        }
+    }
+}
+
+/// Resultado da busca.
+#[derive(Debug, Serialize)]
+pub struct BruteforceResult {
+    /// Melhores candidatos, do maior para o menor score.
+    pub candidates: Vec<BruteforceCandidate>,
+    /// Número de chaves efetivamente testadas.
+    pub keys_tried: usize,
+    /// Indica se a busca foi interrompida antes do fim.
+    pub cancelled: bool,
+}
+
+/// Candidato interno, antes de montar o texto de pré-visualização.
+struct Scored {
+    rotors: RotorOrder,
+    reflector: &'static str,
+    positions: (char, char, char),
+    score: f64,
+}
+
+/// Candidato guardado num `SearchCursor`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct SavedCandidate {
+    /// Ordem dos rotores (Direita, Meio, Esquerda).
+    pub rotors: (String, String, String),
+    pub reflector: String,
+    /// Posições (Esquerda, Meio, Direita).
+    pub positions: (char, char, char),
+    pub score: f64,
+}
+
+/// Ponto de retomada da busca: quantos blocos, na ordem da busca, já foram
+/// concluídos e os melhores candidatos encontrados neles.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct SearchCursor {
+    pub blocks_done: usize,
+    pub keys_tried: usize,
+    pub best: Vec<SavedCandidate>,
+}
+
+/// Andamento da busca, repassado ao fim de cada bloco.
+#[derive(Debug, Serialize, Clone)]
+pub struct SearchEvent {
+    /// Chaves testadas até agora e total da busca.
+    pub done: usize,
+    pub total: usize,
+    /// Bloco recém-testado: ordem dos rotores (Direita, Meio, Esquerda), refletor e
+    /// posição do rotor esquerdo.
+    pub rotors: (String, String, String),
+    pub reflector: String,
+    pub left: char,
+    /// Melhor pontuação dentro do bloco.
+    pub block_score: f64,
+    /// Melhor candidato até agora, com o início da decifração.
+    pub best: Option<BruteforceCandidate>,
+}
+
+/// Blocos por lote entre dois pontos de retomada (um bloco são as posições do meio
+/// e da direita para uma posição do rotor esquerdo).
+const BLOCKS_PER_BATCH: usize = 64;
+
+/// Executa a busca. `progress` recebe (chaves testadas, total) a cada bloco de
+/// posições do meio e da direita; se `cancel` for marcado, os blocos restantes são
+/// descartados e o resultado parcial é retornado.
+pub fn attack_bruteforce(
+    ciphertext: &str,
+    scorer: &dyn FitnessScorer,
+    space: &SearchSpace,
+    progress: impl Fn(usize, usize) + Sync,
+    cancel: &AtomicBool,
+) -> Result<BruteforceResult, String> {
+    let observe = |event: &SearchEvent| progress(event.done, event.total);
+    resume_bruteforce(ciphertext, scorer, space, &SearchCursor::default(), observe, |_| {}, cancel)
+}
+
+/// Continua uma busca a partir de `cursor` (`SearchCursor::default()` começa do
+/// início). `observe` recebe um `SearchEvent` a cada bloco e `checkpoint` o novo
+/// ponto de retomada ao fim de cada lote; o lote interrompido por `cancel` não gera
+/// ponto de retomada, para não pular blocos.
+pub fn resume_bruteforce(
+    ciphertext: &str,
+    scorer: &dyn FitnessScorer,
+    space: &SearchSpace,
+    cursor: &SearchCursor,
+    observe: impl Fn(&SearchEvent) + Sync,
+    mut checkpoint: impl FnMut(&SearchCursor),
+    cancel: &AtomicBool,
+) -> Result<BruteforceResult, String> {
+    let text = letters(ciphertext);
+    if text.is_empty() {
+        return Err("O texto cifrado não contém letras.".to_string());
+    }
+
+    let orders = space.orders()?;
+    let reflectors = space.reflector_names()?;
+    let left = space.positions.0.positions()?;
+    let middle = space.positions.1.positions()?;
+    let right = space.positions.2.positions()?;
+    let top_n = space.top_n.max(1);
+    let window = scorer.window().map_or(text.len(), |w| w.min(text.len())).max(1);
+
+    // Um bloco de trabalho por (ordem, refletor, posição do rotor esquerdo)
+    let blocks: Vec<(RotorOrder, &'static str, char)> = orders
+        .iter()
+        .flat_map(|&order| reflectors.iter().map(move |&reflector| (order, reflector)))
+        .flat_map(|(order, reflector)| left.iter().map(move |&l| (order, reflector, l)))
+        .collect();
+    let blocks = &blocks[space.blocks()?];
+    let per_block = middle.len() * right.len();
+    let total = blocks.len() * per_block;
+    if cursor.blocks_done > blocks.len() {
+        return Err("O ponto de retomada não corresponde a esta busca.".to_string());
+    }
+    let done = AtomicUsize::new(cursor.keys_tried);
+    let mut best: Vec<Scored> = cursor.best.iter().map(restore).collect::<Result<_, _>>()?;
+    let mut blocks_done = cursor.blocks_done;
+    let live_best = Mutex::new(best.first().map(|scored| candidate(scored, &text)));
+
+    for batch in blocks[blocks_done..].chunks(BLOCKS_PER_BATCH) {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        let found: Vec<Scored> = batch
+            .par_iter()
+            .flat_map(|&(rotors, reflector, left)| {
+                if cancel.load(Ordering::Relaxed) {
+                    return Vec::new();
+                }
+                // Decifra o bloco com o kernel em lote, uma posição do rotor do meio por vez
+                let config = key_config(rotors, reflector, (left, 'A', 'A'), ('A', 'A', 'A'), "");
+                let table = ScramblerTable::new(&config, window);
+                let mut plain = vec![0u8; right.len() * window];
+                let mut block = Vec::with_capacity(per_block);
+                for &middle in &middle {
+                    let starts: Vec<(u8, u8)> =
+                        right.iter().map(|&r| (middle as u8 - b'A', r as u8 - b'A')).collect();
+                    table.decrypt(&starts, &text[..window], &mut plain);
+                    for (&right, plain) in right.iter().zip(plain.chunks(window)) {
+                        let positions = (left, middle, right);
+                        block.push(Scored { rotors, reflector, positions, score: scorer.score(plain) });
+                    }
+                }
+                keep_best(&mut block, top_n);
+                let best = {
+                    let mut live_best = live_best.lock().unwrap();
+                    if live_best.as_ref().is_none_or(|best| block[0].score > best.score) {
+                        *live_best = Some(candidate(&block[0], &text));
+                    }
+                    live_best.clone()
+                };
+                observe(&SearchEvent {
+                    done: done.fetch_add(per_block, Ordering::Relaxed) + per_block,
+                    total,
+                    rotors: order_names(rotors),
+                    reflector: reflector.to_string(),
+                    left,
+                    block_score: block[0].score,
+                    best,
+                });
+                block
+            })
+            .collect();
+        best.extend(found);
+        keep_best(&mut best, top_n);
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        blocks_done += batch.len();
+        checkpoint(&SearchCursor {
+            blocks_done,
+            keys_tried: done.load(Ordering::Relaxed),
+            best: best.iter().map(save).collect(),
+        });
+    }
+
+    let candidates = best.iter().map(|scored| candidate(scored, &text)).collect();
+
+    Ok(BruteforceResult {
+        candidates,
+        keys_tried: done.load(Ordering::Relaxed),
+        cancelled: cancel.load(Ordering::Relaxed),
+    })
+}
+
+/// Pontua a decifração a partir do estado atual da máquina, decifrando só o
+/// trecho que o pontuador precisa.
+pub(super) fn score(machine: &mut EnigmaMachine, text: &[u8], scorer: &dyn FitnessScorer) -> f64 {
+    let len = scorer.window().map_or(text.len(), |w| w.min(text.len()));
+    scorer.score(&decrypt(machine, &text[..len]))
+}
+
+/// Monta o candidato para a UI, com a pré-visualização e a língua.
+fn candidate(scored: &Scored, text: &[u8]) -> BruteforceCandidate {
+    let machine = || machine_at(scored.rotors, scored.reflector, scored.positions);
+    BruteforceCandidate {
+        rotors: order_names(scored.rotors),
+        reflector: scored.reflector.to_string(),
+        positions: scored.positions,
+        score: scored.score,
+        preview: preview(&mut machine(), text),
+        language: language_of(&mut machine(), text),
+    }
+}
+
+/// Converte um candidato interno para guardar num `SearchCursor`.
+fn save(scored: &Scored) -> SavedCandidate {
+    SavedCandidate {
+        rotors: order_names(scored.rotors),
+        reflector: scored.reflector.to_string(),
+        positions: scored.positions,
+        score: scored.score,
+    }
+}
+
+/// Converte de volta um candidato guardado, validando os nomes.
+fn restore(saved: &SavedCandidate) -> Result<Scored, String> {
+    Ok(Scored {
+        rotors: parse_rotor_order(&saved.rotors)?,
+        reflector: parse_reflector(&saved.reflector)?,
+        positions: saved.positions,
+        score: saved.score,
+    })
+}
+
+/// Ordena por score decrescente e mantém só os `n` melhores.
+fn keep_best(candidates: &mut Vec<Scored>, n: usize) {
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    candidates.truncate(n);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fitness::{CribScorer, IndexOfCoincidence};
+
+    const PLAINTEXT: &str = "WETTERBERICHTFUERDIENORDSEEXWINDAUSWESTMITSTAERKEVIERBISFUENFXSEEGANGDREIXSICHTGUTXBEWOELKUNGWECHSELNDXAMABENDAUFFRISCHENDERWINDAUSNORDWESTXKEINEBESONDERENEREIGNISSE";
+
+    fn ciphertext() -> String {
+        let config = EnigmaConfig {
+            rotors: (
+                RotorConfig { name: "II".to_string(), position: 'E', ring: 'A' },
+                RotorConfig { name: "I".to_string(), position: 'K', ring: 'A' },
+                RotorConfig { name: "III".to_string(), position: 'X', ring: 'A' },
+            ),
+            reflector: "B".to_string(),
+            plugboard_pairs: String::new(),
+        };
+        EnigmaMachine::new(config).process_string(PLAINTEXT)
+    }
+
+    #[test]
+    fn test_crib_search_finds_key() {
+        let cancel = AtomicBool::new(false);
+        let crib = CribScorer::new("WETTERBERICHT").unwrap();
+        let result = attack_bruteforce(&ciphertext(), &crib, &SearchSpace::default(), |_, _| {}, &cancel).unwrap();
+
+        assert_eq!(result.keys_tried, 6 * 2 * 26 * 26 * 26);
+        let best = &result.candidates[0];
+        assert_eq!(best.rotors, ("II".to_string(), "I".to_string(), "III".to_string()));
+        assert_eq!(best.positions, ('X', 'K', 'E'));
+        assert!(best.preview.starts_with("WETTERBERICHT"));
+        assert_eq!(EnigmaMachine::new(best.config()).process_string(&ciphertext()), PLAINTEXT);
+    }
+
+    #[test]
+    fn test_ciphertext_only_search_finds_key() {
+        let space = SearchSpace {
+            rotor_orders: vec![("II".to_string(), "I".to_string(), "III".to_string())],
+            reflectors: vec!["B".to_string()],
+            top_n: 3,
+            ..Default::default()
+        };
+        let cancel = AtomicBool::new(false);
+        let result = attack_bruteforce(&ciphertext(), &IndexOfCoincidence, &space, |_, _| {}, &cancel).unwrap();
+
+        assert_eq!(result.candidates.len(), 3);
+        assert_eq!(result.candidates[0].positions, ('X', 'K', 'E'));
+    }
+
+    #[test]
+    fn test_restricted_search() {
+        let space = SearchSpace {
+            rotors: vec!["i".to_string(), "II".to_string(), "III".to_string(), "II".to_string()],
+            reflectors: vec!["B".to_string()],
+            positions: (
+                PositionRange { from: 'V', to: 'B' },
+                PositionRange { from: 'K', to: 'K' },
+                PositionRange { from: 'A', to: 'J' },
+            ),
+            ..Default::default()
+        };
+        assert_eq!(space.size().unwrap(), 6 * 7 * 10);
+        let cancel = AtomicBool::new(false);
+        let crib = CribScorer::new("WETTERBERICHT").unwrap();
+        let result = attack_bruteforce(&ciphertext(), &crib, &space, |_, _| {}, &cancel).unwrap();
+
+        assert_eq!(result.keys_tried, 6 * 7 * 10);
+        assert_eq!(result.candidates[0].positions, ('X', 'K', 'E'));
+
+        let too_few = SearchSpace { rotors: vec!["I".to_string(), "II".to_string()], ..Default::default() };
+        assert!(too_few.orders().is_err());
+        assert!(PositionRange { from: '1', to: 'Z' }.positions().is_err());
+    }
+
+    #[test]
+    fn test_resumed_search_matches_full_search() {
+        let space = SearchSpace { reflectors: vec!["B".to_string()], ..Default::default() };
+        let crib = CribScorer::new("WETTERBERICHT").unwrap();
+        let full = attack_bruteforce(&ciphertext(), &crib, &space, |_, _| {}, &AtomicBool::new(false)).unwrap();
+
+        // Interrompe a busca depois do primeiro lote e retoma do ponto salvo
+        let cancel = AtomicBool::new(false);
+        let mut saved = SearchCursor::default();
+        let partial = resume_bruteforce(
+            &ciphertext(),
+            &crib,
+            &space,
+            &SearchCursor::default(),
+            |_| {},
+            |cursor| {
+                saved = cursor.clone();
+                cancel.store(true, Ordering::Relaxed);
+            },
+            &cancel,
+        )
+        .unwrap();
+        assert!(partial.cancelled);
+        assert_eq!(saved.blocks_done, BLOCKS_PER_BATCH);
+        assert_eq!(saved.keys_tried, BLOCKS_PER_BATCH * 26 * 26);
+
+        let mut checkpoints = 0;
+        let resumed = resume_bruteforce(
+            &ciphertext(),
+            &crib,
+            &space,
+            &saved,
+            |_| {},
+            |_| checkpoints += 1,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+        assert_eq!(resumed.keys_tried, full.keys_tried);
+        assert_eq!(checkpoints, (6 * 26usize).div_ceil(BLOCKS_PER_BATCH) - 1);
+        assert_eq!(resumed.candidates[0].positions, full.candidates[0].positions);
+        assert_eq!(resumed.candidates[0].rotors, full.candidates[0].rotors);
+    }
+
+    #[test]
+    fn test_events_track_best_candidate() {
+        let space = SearchSpace {
+            rotor_orders: vec![("II".to_string(), "I".to_string(), "III".to_string())],
+            reflectors: vec!["B".to_string()],
+            ..Default::default()
+        };
+        let crib = CribScorer::new("WETTERBERICHT").unwrap();
+        let events = Mutex::new(Vec::new());
+        let observe = |event: &SearchEvent| events.lock().unwrap().push(event.clone());
+        let default = SearchCursor::default();
+        resume_bruteforce(&ciphertext(), &crib, &space, &default, observe, |_| {}, &AtomicBool::new(false)).unwrap();
+
+        let events = events.into_inner().unwrap();
+        assert_eq!(events.len(), 26);
+        assert!(events.iter().all(|e| e.total == 26 * 26 * 26 && e.rotors.0 == "II"));
+        let best = events.iter().max_by_key(|e| e.done).unwrap().best.clone().unwrap();
+        assert_eq!(best.positions, ('X', 'K', 'E'));
+        assert!(best.preview.starts_with("WETTERBERICHT"));
+        let block = events.iter().find(|e| e.left == 'X').unwrap();
+        assert_eq!(block.block_score, best.score);
+    }
+
+    #[test]
+    fn test_cancelled_search() {
+        let cancel = AtomicBool::new(true);
+        let result = attack_bruteforce(&ciphertext(), &IndexOfCoincidence, &SearchSpace::default(), |_, _| {}, &cancel).unwrap();
+
+        assert!(result.cancelled);
+        assert_eq!(result.keys_tried, 0);
+        assert!(result.candidates.is_empty());
+    }
+}
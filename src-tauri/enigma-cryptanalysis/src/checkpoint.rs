@@ -0,0 +1,168 @@
+//! Pontos de retomada da busca exaustiva.
+//!
+//! Uma busca com as 60 ordens de rotores pode levar horas. Durante a busca, o
+//! ponto de retomada (`SearchCursor`) é salvo periodicamente em disco junto com
+//! tudo o que é preciso para repeti-la: texto cifrado, pontuador e restrições. Se
+//! o app fechar no meio, a busca continua do último ponto salvo.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::bruteforce::{SearchCursor, SearchSpace};
+
+/// Uma busca exaustiva salva em disco.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AttackCheckpoint {
+    /// Identificador, usado como nome do arquivo.
+    pub id: String,
+    pub ciphertext: String,
+    /// Crib ou nome do pontuador, como foram passados ao ataque.
+    pub crib: Option<String>,
+    pub scorer: Option<String>,
+    pub search_space: SearchSpace,
+    pub cursor: SearchCursor,
+    /// Total de chaves da busca.
+    pub total: usize,
+    /// Momento do último salvamento (segundos desde 1970).
+    pub saved_at: u64,
+}
+
+/// Resumo de uma busca salva, para a UI listar.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct CheckpointSummary {
+    pub id: String,
+    pub keys_tried: usize,
+    pub total: usize,
+    pub saved_at: u64,
+    /// Melhor pontuação até o ponto salvo.
+    pub best_score: Option<f64>,
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// Caminho do arquivo de uma busca salva. O identificador só pode ter letras
+/// minúsculas, dígitos e '-', para não sair da pasta.
+pub fn checkpoint_path(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    if id.is_empty() || !id.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-') {
+        return Err(format!("Identificador de busca inválido: {}", id));
+    }
+    Ok(dir.join(format!("{}.json", id)))
+}
+
+impl AttackCheckpoint {
+    /// Cria o ponto de retomada de uma busca que ainda vai começar.
+    pub fn new(
+        ciphertext: &str,
+        crib: Option<String>,
+        scorer: Option<String>,
+        search_space: SearchSpace,
+    ) -> Result<Self, String> {
+        let total = search_space.size()?;
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos());
+        Ok(Self {
+            id: format!("bruteforce-{:x}", nanos),
+            ciphertext: ciphertext.to_string(),
+            crib,
+            scorer,
+            search_space,
+            cursor: SearchCursor::default(),
+            total,
+            saved_at: now(),
+        })
+    }
+
+    /// Atualiza o ponto de retomada.
+    pub fn advance(&mut self, cursor: &SearchCursor) {
+        self.cursor = cursor.clone();
+        self.saved_at = now();
+    }
+
+    /// Resumo para a UI.
+    pub fn summary(&self) -> CheckpointSummary {
+        CheckpointSummary {
+            id: self.id.clone(),
+            keys_tried: self.cursor.keys_tried,
+            total: self.total,
+            saved_at: self.saved_at,
+            best_score: self.cursor.best.first().map(|c| c.score),
+        }
+    }
+
+    /// Carrega uma busca salva com `save`.
+    pub fn load(dir: &Path, id: &str) -> Result<Self, String> {
+        let path = checkpoint_path(dir, id)?;
+        let file = File::open(path).map_err(|e| format!("Erro ao abrir a busca salva: {}", e))?;
+        serde_json::from_reader(BufReader::new(file)).map_err(|e| format!("Busca salva inválida: {}", e))
+    }
+
+    /// Salva em disco (JSON). Grava num arquivo temporário e o renomeia, para que
+    /// um fechamento no meio da gravação não estrague o ponto anterior.
+    pub fn save(&self, dir: &Path) -> Result<(), String> {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Erro ao criar a pasta das buscas salvas: {}", e))?;
+        let path = checkpoint_path(dir, &self.id)?;
+        let temporary = path.with_extension("tmp");
+        let file = File::create(&temporary).map_err(|e| format!("Erro ao criar a busca salva: {}", e))?;
+        serde_json::to_writer(BufWriter::new(file), self).map_err(|e| format!("Erro ao salvar a busca: {}", e))?;
+        std::fs::rename(&temporary, &path).map_err(|e| format!("Erro ao salvar a busca: {}", e))
+    }
+
+    /// Apaga uma busca salva (não é erro se ela não existir).
+    pub fn remove(dir: &Path, id: &str) -> Result<(), String> {
+        match std::fs::remove_file(checkpoint_path(dir, id)?) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Erro ao apagar a busca salva: {}", e)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Lista as buscas salvas em `dir`, da mais recente para a mais antiga. Arquivos
+    /// ilegíveis são ignorados.
+    pub fn list(dir: &Path) -> Vec<CheckpointSummary> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut summaries: Vec<CheckpointSummary> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                if path.extension()? != "json" {
+                    return None;
+                }
+                Self::load(dir, path.file_stem()?.to_str()?).ok().map(|checkpoint| checkpoint.summary())
+            })
+            .collect();
+        summaries.sort_by_key(|summary| std::cmp::Reverse(summary.saved_at));
+        summaries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_path_escapes() {
+        let dir = Path::new("buscas");
+        assert_eq!(checkpoint_path(dir, "bruteforce-1a").unwrap(), dir.join("bruteforce-1a.json"));
+        assert!(checkpoint_path(dir, "../config").is_err());
+        assert!(checkpoint_path(dir, "").is_err());
+    }
+
+    #[test]
+    fn test_new_checkpoint() {
+        let space = SearchSpace { reflectors: vec!["B".to_string()], ..Default::default() };
+        let mut checkpoint = AttackCheckpoint::new("ABC", None, Some("ic".to_string()), space).unwrap();
+        assert_eq!(checkpoint.total, 6 * 26 * 26 * 26);
+        assert!(checkpoint_path(Path::new("."), &checkpoint.id).is_ok());
+
+        checkpoint.advance(&SearchCursor { blocks_done: 2, keys_tried: 2 * 26 * 26, best: Vec::new() });
+        let summary = checkpoint.summary();
+        assert_eq!(summary.keys_tried, 1352);
+        assert_eq!(summary.best_score, None);
+    }
+}
@@ -0,0 +1,222 @@
+//! Detecção de "cillies" (descuidos dos operadores).
+//!
+//! Em Bletchley Park, "cillies" eram chaves de mensagem previsíveis: três letras
+//! iguais, sequências do teclado, letras consecutivas do alfabeto, ou simplesmente
+//! a posição em que os rotores tinham parado ao fim da mensagem anterior. Dada a
+//! chave do dia, este módulo decifra os indicadores de um lote de mensagens e
+//! aponta quais chaves seguem algum desses padrões.
+
+use serde::{Deserialize, Serialize};
+
+use super::validate_config;
+use crate::enigma::{EnigmaConfig, EnigmaMachine};
+
+/// Fileiras do teclado da Enigma (QWERTZ), de cima para baixo.
+pub use crate::enigma::KEYBOARD_ROWS;
+
+/// Maior comprimento de mensagem aceito, bem acima das mensagens reais (que
+/// raramente passavam de 250 letras).
+pub const MAX_MESSAGE_LENGTH: usize = 10_000;
+
+/// Uma mensagem interceptada: o indicador transmitido e o comprimento do texto.
+#[derive(Debug, Deserialize, Clone)]
+pub struct IndicatorMessage {
+    /// Indicador cifrado na Grundstellung (3 letras, ou 6 no procedimento duplicado).
+    pub indicator: String,
+    /// Número de letras do texto cifrado da mensagem.
+    pub length: usize,
+}
+
+/// Padrão de descuido encontrado numa chave de mensagem.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub enum CilliePattern {
+    /// Três letras iguais (ex: "AAA").
+    RepeatedLetters,
+    /// Três teclas vizinhas numa fileira ou coluna do teclado (ex: "QWE", "QAP").
+    KeyboardSequence,
+    /// Três letras consecutivas do alfabeto (ex: "ABC", "ZYX").
+    AlphabeticalSequence,
+    /// A chave é a posição final dos rotores após a mensagem anterior.
+    ContinuesPrevious,
+    /// A mesma chave já foi usada numa mensagem anterior do lote.
+    ReusedKey,
+}
+
+/// Uma mensagem sinalizada, com a chave deduzida do indicador.
+#[derive(Debug, Serialize, Clone)]
+pub struct CillieReport {
+    /// Índice da mensagem no lote.
+    pub index: usize,
+    pub indicator: String,
+    /// Chave da mensagem decifrada (Esquerda, Meio, Direita).
+    pub message_key: String,
+    pub patterns: Vec<CilliePattern>,
+}
+
+/// Decifra os indicadores com a chave do dia (`config`, cujas posições são a
+/// Grundstellung) e retorna as mensagens cujas chaves seguem algum padrão.
+/// Indicadores duplicados cujas metades não coincidem (erros de operação) são ignorados.
+pub fn detect(config: &EnigmaConfig, messages: &[IndicatorMessage]) -> Result<Vec<CillieReport>, String> {
+    let config = &validate_config(config)?;
+    if let Some(index) = messages.iter().position(|message| message.length > MAX_MESSAGE_LENGTH) {
+        return Err(format!("Mensagem {}: o limite é de {} letras.", index + 1, MAX_MESSAGE_LENGTH));
+    }
+    let mut reports = Vec::new();
+    let mut previous: Option<(String, usize)> = None;
+    let mut seen: Vec<String> = Vec::new();
+
+    for (index, message) in messages.iter().enumerate() {
+        let Some(key) = decipher_indicator(config, &message.indicator) else {
+            previous = None;
+            continue;
+        };
+
+        let mut patterns = pattern_of(&key);
+        if let Some((previous_key, length)) = &previous {
+            if end_position(config, previous_key, *length) == key {
+                patterns.push(CilliePattern::ContinuesPrevious);
+            }
+        }
+        if seen.contains(&key) {
+            patterns.push(CilliePattern::ReusedKey);
+        }
+
+        if !patterns.is_empty() {
+            reports.push(CillieReport {
+                index,
+                indicator: message.indicator.clone(),
+                message_key: key.clone(),
+                patterns,
+            });
+        }
+        seen.push(key.clone());
+        previous = Some((key, message.length));
+    }
+    Ok(reports)
+}
+
+/// Padrões que dependem só das três letras da chave.
+pub fn pattern_of(key: &str) -> Vec<CilliePattern> {
+    let bytes = key.as_bytes();
+    let mut patterns = Vec::new();
+    if bytes.len() != 3 {
+        return patterns;
+    }
+
+    if bytes[0] == bytes[1] && bytes[1] == bytes[2] {
+        patterns.push(CilliePattern::RepeatedLetters);
+    }
+
+    let step = |a: u8, b: u8| (b as i16 - a as i16).rem_euclid(26);
+    let (first, second) = (step(bytes[0], bytes[1]), step(bytes[1], bytes[2]));
+    if first == second && (first == 1 || first == 25) {
+        patterns.push(CilliePattern::AlphabeticalSequence);
+    }
+
+    let reversed: String = key.chars().rev().collect();
+    if keyboard_lines().iter().any(|line| line.contains(key) || line.contains(&reversed)) {
+        patterns.push(CilliePattern::KeyboardSequence);
+    }
+    patterns
+}
+
+/// Fileiras e colunas do teclado, como sequências de teclas vizinhas.
+fn keyboard_lines() -> Vec<String> {
+    let mut lines: Vec<String> = KEYBOARD_ROWS.iter().map(|row| row.to_string()).collect();
+    let rows: Vec<&[u8]> = KEYBOARD_ROWS.iter().map(|row| row.as_bytes()).collect();
+    for column in 0..rows[0].len() {
+        lines.push(rows.iter().filter_map(|row| row.get(column)).map(|&b| b as char).collect());
+    }
+    lines
+}
+
+/// Decifra um indicador na Grundstellung, retornando a chave da mensagem.
+fn decipher_indicator(config: &EnigmaConfig, indicator: &str) -> Option<String> {
+    let plain = EnigmaMachine::new(config.clone()).process_string(indicator);
+    match plain.len() {
+        3 => Some(plain),
+        6 if plain[..3] == plain[3..] => Some(plain[..3].to_string()),
+        _ => None,
+    }
+}
+
+/// Posição dos rotores (Esquerda, Meio, Direita) depois de cifrar `length` letras
+/// a partir da chave `key`.
+fn end_position(config: &EnigmaConfig, key: &str, length: usize) -> String {
+    let key: Vec<char> = key.chars().collect();
+    let mut config = config.clone();
+    config.rotors.2.position = key[0];
+    config.rotors.1.position = key[1];
+    config.rotors.0.position = key[2];
+
+    let mut machine = EnigmaMachine::new(config);
+    machine.advance(length);
+    let (l, m, r) = machine.get_positions();
+    [l, m, r].iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enigma::RotorConfig;
+
+    fn day_config() -> EnigmaConfig {
+        EnigmaConfig {
+            rotors: (
+                RotorConfig { name: "I".to_string(), position: 'W', ring: 'A' },
+                RotorConfig { name: "III".to_string(), position: 'X', ring: 'A' },
+                RotorConfig { name: "II".to_string(), position: 'T', ring: 'A' },
+            ),
+            reflector: "B".to_string(),
+            plugboard_pairs: "BQ CR DI EJ KW MT OS PX UZ GH".to_string(),
+        }
+    }
+
+    fn message(key: &str, length: usize) -> IndicatorMessage {
+        IndicatorMessage {
+            indicator: EnigmaMachine::new(day_config()).process_string(key),
+            length,
+        }
+    }
+
+    #[test]
+    fn test_patterns_of_keys() {
+        assert_eq!(pattern_of("AAA"), vec![CilliePattern::RepeatedLetters]);
+        assert_eq!(pattern_of("XYZ"), vec![CilliePattern::AlphabeticalSequence]);
+        assert_eq!(pattern_of("CBA"), vec![CilliePattern::AlphabeticalSequence]);
+        assert_eq!(pattern_of("QWE"), vec![CilliePattern::KeyboardSequence]);
+        assert_eq!(pattern_of("LMN"), vec![CilliePattern::AlphabeticalSequence, CilliePattern::KeyboardSequence]);
+        assert_eq!(pattern_of("QAP"), vec![CilliePattern::KeyboardSequence]);
+        assert!(pattern_of("KFR").is_empty());
+    }
+
+    #[test]
+    fn test_detects_batch() {
+        let next = end_position(&day_config(), "KFR", 97);
+        let messages = vec![
+            message("KFR", 97),
+            message(&next, 80),
+            message("MOV", 60),
+            message("QWE", 40),
+            message("MOVMOV", 50),
+        ];
+        let reports = detect(&day_config(), &messages).unwrap();
+
+        assert_eq!(reports.len(), 3);
+        assert_eq!(reports[0].index, 1);
+        assert_eq!(reports[0].message_key, next);
+        assert_eq!(reports[0].patterns, vec![CilliePattern::ContinuesPrevious]);
+        assert_eq!(reports[1].patterns, vec![CilliePattern::KeyboardSequence]);
+        assert_eq!(reports[2].index, 4);
+        assert_eq!(reports[2].patterns, vec![CilliePattern::ReusedKey]);
+    }
+
+    #[test]
+    fn test_rejects_bad_config_and_huge_lengths() {
+        let mut config = day_config();
+        config.rotors.0.name = "IX".to_string();
+        assert!(detect(&config, &[message("KFR", 97)]).is_err());
+        let messages = [message("KFR", 97), message("QWE", usize::MAX)];
+        assert!(detect(&day_config(), &messages).unwrap_err().starts_with("Mensagem 2"));
+    }
+}
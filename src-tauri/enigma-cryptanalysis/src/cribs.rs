@@ -0,0 +1,180 @@
+//! Dicionário de cribs (palavras prováveis).
+//!
+//! Os operadores alemães repetiam fórmulas: boletins do tempo, "nada a relatar",
+//! endereçamentos e assinaturas. Em Bletchley Park essas fórmulas eram os cribs que
+//! alimentavam as Bombes e o arrasto de cribs. Os espaços eram escritos como X e os
+//! números por extenso, por isso os cribs seguem essa convenção.
+
+use serde::{Deserialize, Serialize};
+
+/// Onde o crib costuma aparecer na mensagem.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CribPosition {
+    Start,
+    End,
+    Anywhere,
+}
+
+/// Tipo de mensagem em que o crib é comum.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CribContext {
+    Weather,
+    Naval,
+    Army,
+    Routine,
+}
+
+/// Uma palavra provável e seus metadados.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Crib {
+    pub text: &'static str,
+    pub translation: &'static str,
+    pub position: CribPosition,
+    pub contexts: &'static [CribContext],
+}
+
+/// Cribs clássicos.
+pub const CRIBS: &[Crib] = &[
+    Crib {
+        text: "WETTERBERICHT",
+        translation: "boletim do tempo",
+        position: CribPosition::Start,
+        contexts: &[CribContext::Weather, CribContext::Naval],
+    },
+    Crib {
+        text: "WETTERVORHERSAGE",
+        translation: "previsão do tempo",
+        position: CribPosition::Start,
+        contexts: &[CribContext::Weather],
+    },
+    Crib {
+        text: "KEINEBESONDERENEREIGNISSE",
+        translation: "nenhuma ocorrência especial",
+        position: CribPosition::Anywhere,
+        contexts: &[CribContext::Routine, CribContext::Army],
+    },
+    Crib {
+        text: "OBERKOMMANDODERWEHRMACHT",
+        translation: "Alto Comando das Forças Armadas",
+        position: CribPosition::Start,
+        contexts: &[CribContext::Army],
+    },
+    Crib {
+        text: "OBERKOMMANDO",
+        translation: "alto comando",
+        position: CribPosition::Start,
+        contexts: &[CribContext::Army, CribContext::Naval],
+    },
+    Crib {
+        text: "BEFEHLSHABERDERUBOOTE",
+        translation: "Comandante dos U-Boots",
+        position: CribPosition::Start,
+        contexts: &[CribContext::Naval],
+    },
+    Crib {
+        text: "FUEHRERHAUPTQUARTIER",
+        translation: "quartel-general do Führer",
+        position: CribPosition::Start,
+        contexts: &[CribContext::Army],
+    },
+    Crib {
+        text: "HEILHITLER",
+        translation: "saudação de encerramento",
+        position: CribPosition::End,
+        contexts: &[CribContext::Army, CribContext::Routine],
+    },
+    Crib {
+        text: "ANXOBERKOMMANDO",
+        translation: "ao alto comando",
+        position: CribPosition::Start,
+        contexts: &[CribContext::Army],
+    },
+    Crib {
+        text: "VONXVONX",
+        translation: "de (remetente, repetido)",
+        position: CribPosition::Start,
+        contexts: &[CribContext::Routine],
+    },
+    Crib {
+        text: "QUADRAT",
+        translation: "quadrícula (posição na carta naval)",
+        position: CribPosition::Anywhere,
+        contexts: &[CribContext::Naval],
+    },
+    Crib {
+        text: "NULLNULL",
+        translation: "zero zero",
+        position: CribPosition::Anywhere,
+        contexts: &[CribContext::Naval, CribContext::Weather],
+    },
+    Crib {
+        text: "EINS",
+        translation: "um (aparecia em quase toda mensagem)",
+        position: CribPosition::Anywhere,
+        contexts: &[CribContext::Routine, CribContext::Naval, CribContext::Army, CribContext::Weather],
+    },
+    Crib {
+        text: "SEEGANG",
+        translation: "estado do mar",
+        position: CribPosition::Anywhere,
+        contexts: &[CribContext::Weather, CribContext::Naval],
+    },
+    Crib {
+        text: "WINDSTAERKE",
+        translation: "força do vento",
+        position: CribPosition::Anywhere,
+        contexts: &[CribContext::Weather],
+    },
+];
+
+/// Um crib sugerido e em quantas posições ele pode ser testado.
+#[derive(Debug, Serialize, Clone)]
+pub struct CribSuggestion {
+    pub crib: Crib,
+    /// Posições possíveis no texto cifrado (1 para cribs de início ou fim).
+    pub placements: usize,
+}
+
+/// Sugere os cribs que cabem num texto cifrado de `ciphertext_len` letras, filtrando
+/// pelo tipo de mensagem se informado. Os de posição conhecida vêm primeiro e, entre
+/// eles, os mais longos (que dão mais restrições).
+pub fn suggest_cribs(ciphertext_len: usize, context: Option<CribContext>) -> Vec<CribSuggestion> {
+    let mut suggestions: Vec<CribSuggestion> = CRIBS
+        .iter()
+        .filter(|crib| crib.text.len() <= ciphertext_len)
+        .filter(|crib| context.is_none_or(|context| crib.contexts.contains(&context)))
+        .map(|crib| CribSuggestion {
+            crib: crib.clone(),
+            placements: match crib.position {
+                CribPosition::Anywhere => ciphertext_len - crib.text.len() + 1,
+                CribPosition::Start | CribPosition::End => 1,
+            },
+        })
+        .collect();
+    suggestions.sort_by_key(|s| (s.crib.position == CribPosition::Anywhere, std::cmp::Reverse(s.crib.text.len())));
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cribs_are_letters_only() {
+        assert!(CRIBS.iter().all(|crib| crib.text.bytes().all(|b| b.is_ascii_uppercase())));
+    }
+
+    #[test]
+    fn test_suggestions_fit_and_filter() {
+        let suggestions = suggest_cribs(15, Some(CribContext::Weather));
+        assert!(suggestions.iter().all(|s| s.crib.text.len() <= 15));
+        assert!(suggestions.iter().all(|s| s.crib.contexts.contains(&CribContext::Weather)));
+        assert_eq!(suggestions[0].crib.text, "WETTERBERICHT");
+        let eins = suggestions.iter().find(|s| s.crib.text == "EINS").unwrap();
+        assert_eq!(eins.placements, 12);
+
+        assert!(suggest_cribs(3, None).is_empty());
+    }
+}
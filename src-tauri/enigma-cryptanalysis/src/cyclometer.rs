@@ -0,0 +1,169 @@
+//! Catálogo do ciclômetro (Polônia, 1937).
+//!
+//! Os poloneses montaram um fichário com a característica de todas as ordens de
+//! rotores e posições, usando o ciclômetro para medir os ciclos de cada uma. Aqui
+//! o catálogo é gerado uma única vez, salvo em disco e consultado pelo ataque de
+//! Rejewski em vez de percorrer todas as posições a cada ataque.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::rejewski::{characteristic_of, products_at, Characteristic, RejewskiCandidate};
+use super::{all_positions, parse_reflector, rotor_orders};
+
+/// Uma "ficha" do catálogo: todas as configurações com a mesma característica.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CatalogCard {
+    pub characteristic: Characteristic,
+    pub settings: Vec<RejewskiCandidate>,
+}
+
+/// Catálogo completo para um refletor, indexado pela característica.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Catalog {
+    /// Refletor usado na geração ("B" ou "C").
+    pub reflector: String,
+    /// Fichas em ordem decrescente de número de configurações.
+    pub cards: Vec<CatalogCard>,
+    /// Índice característica -> posição em `cards` (reconstruído ao carregar).
+    #[serde(skip)]
+    index: HashMap<Characteristic, usize>,
+}
+
+/// Resumo do catálogo, enviado à UI após gerar ou carregar.
+#[derive(Debug, Serialize, Clone)]
+pub struct CatalogSummary {
+    pub reflector: String,
+    /// Total de configurações (ordens × posições) catalogadas.
+    pub settings: usize,
+    /// Número de características distintas (fichas).
+    pub cards: usize,
+    /// Maior número de configurações numa mesma ficha.
+    pub largest_card: usize,
+}
+
+impl Catalog {
+    /// Gera o catálogo percorrendo todas as ordens de rotores e as 26³ posições.
+    /// `progress` é chamado com (concluídas, total) a cada 26² posições.
+    pub fn generate(reflector: &str, mut progress: impl FnMut(usize, usize)) -> Result<Self, String> {
+        let reflector = parse_reflector(reflector)?;
+
+        let orders = rotor_orders();
+        let total = orders.len() * 26 * 26 * 26;
+        let mut done = 0;
+        let mut cards: Vec<CatalogCard> = Vec::new();
+        let mut index: HashMap<Characteristic, usize> = HashMap::new();
+
+        for rotors in orders {
+            for ground in all_positions() {
+                let characteristic = characteristic_of(&products_at(rotors, reflector, ground));
+                let setting = RejewskiCandidate::new(rotors, ground);
+                match index.get(&characteristic) {
+                    Some(&i) => cards[i].settings.push(setting),
+                    None => {
+                        index.insert(characteristic.clone(), cards.len());
+                        cards.push(CatalogCard { characteristic, settings: vec![setting] });
+                    }
+                }
+
+                done += 1;
+                if done % (26 * 26) == 0 {
+                    progress(done, total);
+                }
+            }
+        }
+
+        let mut catalog = Self { reflector: reflector.to_string(), cards, index: HashMap::new() };
+        catalog.cards.sort_by_key(|card| std::cmp::Reverse(card.settings.len()));
+        catalog.rebuild_index();
+        Ok(catalog)
+    }
+
+    /// Carrega um catálogo salvo anteriormente com `save`.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("Erro ao abrir o catálogo: {}", e))?;
+        let mut catalog: Self = serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| format!("Catálogo inválido: {}", e))?;
+        catalog.rebuild_index();
+        Ok(catalog)
+    }
+
+    /// Salva o catálogo em disco (JSON).
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Erro ao criar a pasta do catálogo: {}", e))?;
+        }
+        let file = File::create(path).map_err(|e| format!("Erro ao criar o catálogo: {}", e))?;
+        serde_json::to_writer(BufWriter::new(file), self).map_err(|e| format!("Erro ao salvar o catálogo: {}", e))
+    }
+
+    /// Retorna as configurações com a característica informada (vazio se não houver).
+    pub fn lookup(&self, characteristic: &Characteristic) -> &[RejewskiCandidate] {
+        match self.index.get(characteristic) {
+            Some(&i) => &self.cards[i].settings,
+            None => &[],
+        }
+    }
+
+    /// Resume o conteúdo do catálogo.
+    pub fn summary(&self) -> CatalogSummary {
+        CatalogSummary {
+            reflector: self.reflector.clone(),
+            settings: self.cards.iter().map(|card| card.settings.len()).sum(),
+            cards: self.cards.len(),
+            largest_card: self.cards.first().map_or(0, |card| card.settings.len()),
+        }
+    }
+
+    fn rebuild_index(&mut self) {
+        self.index = self
+            .cards
+            .iter()
+            .enumerate()
+            .map(|(i, card)| (card.characteristic.clone(), i))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rejewski::attack;
+    use crate::enigma::{EnigmaConfig, RotorConfig};
+
+    #[test]
+    fn test_catalog_covers_every_setting() {
+        let mut calls = 0;
+        let catalog = Catalog::generate("B", |_, _| calls += 1).unwrap();
+        let summary = catalog.summary();
+
+        assert_eq!(summary.settings, 6 * 26 * 26 * 26);
+        assert_eq!(calls, 6 * 26);
+        assert!(summary.cards > 1);
+
+        // A consulta deve concordar com o ataque que percorre todas as posições
+        let config = EnigmaConfig {
+            rotors: (
+                RotorConfig { name: "III".to_string(), position: 'E', ring: 'A' },
+                RotorConfig { name: "I".to_string(), position: 'N', ring: 'A' },
+                RotorConfig { name: "II".to_string(), position: 'W', ring: 'A' },
+            ),
+            reflector: "B".to_string(),
+            plugboard_pairs: "PO ML KI".to_string(),
+        };
+        let keys: Vec<String> = (b'A'..=b'Z').map(|b| (b as char).to_string().repeat(3)).collect();
+        let indicators = crate::rejewski::encipher_indicators(&config, &keys).unwrap();
+        let report = attack(&indicators, "B").unwrap();
+
+        assert_eq!(catalog.lookup(&report.characteristic), report.candidates.as_slice());
+    }
+
+    #[test]
+    fn test_unknown_reflector() {
+        assert!(Catalog::generate("Z", |_, _| {}).is_err());
+    }
+}
@@ -0,0 +1,276 @@
+//! Teste kappa (Friedman) para mensagens em profundidade.
+//!
+//! Dois textos cifrados com a máquina no mesmo estado, letra a letra, estão "em
+//! profundidade": onde o texto claro repete uma letra, o cifrado também repete.
+//! Deslizando um texto sobre o outro e contando as coincidências em cada
+//! deslocamento, a taxa (kappa) fica perto de 1/26 nos deslocamentos comuns e sobe
+//! para perto da taxa da língua (cerca de 0,07 em alemão) no deslocamento certo.
+//!
+//! Com as mensagens alinhadas, `DepthReader` ajuda a lê-las: cada coluna do
+//! alinhamento usa a mesma permutação da máquina nas duas mensagens, e essa
+//! permutação é uma involução. Uma palavra suposta numa mensagem revela pares da
+//! permutação de cada coluna; onde a letra cifrada da outra mensagem cai num par
+//! conhecido, o texto claro dela aparece.
+
+use serde::{Deserialize, Serialize};
+
+use super::banburismus::score_offset;
+use super::stats::letters;
+use crate::enigma::u8_to_char;
+
+/// Taxa de coincidência entre letras aleatórias.
+const RANDOM_KAPPA: f64 = 1.0 / 26.0;
+
+/// Desvios-padrão acima do acaso para marcar um deslocamento como provável
+/// profundidade. Com 3σ, um deslocamento qualquer passa por acaso ~0,1% das vezes.
+pub const DEPTH_SIGMA: f64 = 3.0;
+
+/// Sobreposição mínima para o teste ter algum valor.
+pub const MIN_OVERLAP: usize = 20;
+
+/// Resultado do teste kappa em um deslocamento.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct KappaOffset {
+    /// Deslocamento, na mesma convenção de `banburismus::Alignment`.
+    pub offset: i32,
+    /// Número de letras sobrepostas.
+    pub overlap: usize,
+    /// Letras iguais alinhadas.
+    pub coincidences: usize,
+    /// Taxa de coincidência (coincidências / sobreposição).
+    pub kappa: f64,
+    /// Desvios-padrão acima do esperado para texto aleatório.
+    pub sigma: f64,
+    /// Indica provável profundidade (`sigma >= DEPTH_SIGMA` com sobreposição suficiente).
+    pub in_depth: bool,
+}
+
+/// Aplica o teste kappa em todos os deslocamentos de `-max_offset` a `max_offset`,
+/// em ordem de deslocamento (para desenhar o gráfico). Os caracteres não
+/// alfabéticos são ignorados.
+pub fn kappa_test(first: &str, second: &str, max_offset: usize) -> Vec<KappaOffset> {
+    let first = letters(first);
+    let second = letters(second);
+    // Além da soma dos comprimentos não há sobreposição
+    let max_offset = max_offset.min(first.len() + second.len()) as i32;
+
+    (-max_offset..=max_offset)
+        .map(|offset| score_offset(&first, &second, offset, RANDOM_KAPPA))
+        .filter(|alignment| alignment.overlap > 0)
+        .map(|alignment| {
+            let n = alignment.overlap as f64;
+            let expected = n * RANDOM_KAPPA;
+            let deviation = (n * RANDOM_KAPPA * (1.0 - RANDOM_KAPPA)).sqrt();
+            let sigma = (alignment.repeats as f64 - expected) / deviation;
+            KappaOffset {
+                offset: alignment.offset,
+                overlap: alignment.overlap,
+                coincidences: alignment.repeats,
+                kappa: alignment.repeats as f64 / n,
+                sigma,
+                in_depth: sigma >= DEPTH_SIGMA && alignment.overlap >= MIN_OVERLAP,
+            }
+        })
+        .collect()
+}
+
+/// Marca de letra sem par conhecido numa coluna.
+const UNKNOWN: u8 = u8::MAX;
+
+/// Uma das duas mensagens em profundidade.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DepthMessage {
+    First,
+    Second,
+}
+
+/// Texto claro suposto numa posição de uma mensagem.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct DepthGuess {
+    pub message: DepthMessage,
+    pub position: usize,
+    pub word: String,
+}
+
+/// Estado da leitura para a UI: o texto claro conhecido das duas mensagens ('-'
+/// onde ainda é desconhecido) e as suposições feitas.
+#[derive(Debug, Serialize, Clone)]
+pub struct DepthView {
+    pub offset: i32,
+    pub first: String,
+    pub second: String,
+    pub guesses: Vec<DepthGuess>,
+}
+
+/// Sessão de leitura de duas mensagens em profundidade.
+pub struct DepthReader {
+    first: Vec<u8>,
+    second: Vec<u8>,
+    offset: i32,
+    guesses: Vec<DepthGuess>,
+    /// Pares conhecidos da permutação de cada coluna, indexados pela posição na
+    /// primeira mensagem deslocada de `column_base`.
+    columns: Vec<[u8; 26]>,
+    column_base: i32,
+}
+
+impl DepthReader {
+    /// Abre uma sessão com as mensagens alinhadas em `offset` (mesma convenção do
+    /// teste kappa: a letra `i` da segunda fica sob a letra `i + offset` da primeira).
+    pub fn new(first: &str, second: &str, offset: i32) -> Result<Self, String> {
+        let first = letters(first);
+        let second = letters(second);
+        if first.is_empty() || second.is_empty() {
+            return Err("As duas mensagens precisam ter letras.".to_string());
+        }
+        let column_base = offset.min(0);
+        let end = (first.len() as i32).max(second.len() as i32 + offset);
+        let columns = vec![[UNKNOWN; 26]; (end - column_base) as usize];
+        Ok(Self { first, second, offset, guesses: Vec::new(), columns, column_base })
+    }
+
+    /// Coluna do alinhamento de uma posição de uma mensagem.
+    fn column(&self, message: DepthMessage, position: usize) -> usize {
+        let column = match message {
+            DepthMessage::First => position as i32,
+            DepthMessage::Second => position as i32 + self.offset,
+        };
+        (column - self.column_base) as usize
+    }
+
+    fn ciphertext(&self, message: DepthMessage) -> &[u8] {
+        match message {
+            DepthMessage::First => &self.first,
+            DepthMessage::Second => &self.second,
+        }
+    }
+
+    /// Supõe `word` como texto claro a partir de `position` em `message`. Se a
+    /// suposição contradiz a máquina (letra cifrada nela mesma) ou uma suposição
+    /// anterior, é recusada e nada muda.
+    pub fn guess(&mut self, message: DepthMessage, position: usize, word: &str) -> Result<DepthView, String> {
+        let plain = letters(word);
+        let cipher = self.ciphertext(message);
+        if plain.is_empty() || position + plain.len() > cipher.len() {
+            return Err("A palavra não cabe na mensagem nessa posição.".to_string());
+        }
+
+        let mut columns = self.columns.clone();
+        for (i, (&p, &c)) in plain.iter().zip(&cipher[position..]).enumerate() {
+            let column = &mut columns[self.column(message, position + i)];
+            if p == c {
+                return Err(format!(
+                    "A letra {} não pode ser cifrada nela mesma (posição {}).",
+                    u8_to_char(p),
+                    position + i
+                ));
+            }
+            if ![UNKNOWN, c].contains(&column[p as usize]) || ![UNKNOWN, p].contains(&column[c as usize]) {
+                return Err(format!("A suposição contradiz uma anterior na posição {}.", position + i));
+            }
+            column[p as usize] = c;
+            column[c as usize] = p;
+        }
+
+        self.columns = columns;
+        self.guesses.push(DepthGuess { message, position, word: plain.iter().map(|&c| u8_to_char(c)).collect() });
+        Ok(self.view())
+    }
+
+    /// Desfaz a última suposição.
+    pub fn undo(&mut self) -> DepthView {
+        if self.guesses.pop().is_some() {
+            let guesses = std::mem::take(&mut self.guesses);
+            self.columns.iter_mut().for_each(|column| *column = [UNKNOWN; 26]);
+            for guess in guesses {
+                // As suposições restantes já foram aceitas juntas antes
+                let _ = self.guess(guess.message, guess.position, &guess.word);
+            }
+        }
+        self.view()
+    }
+
+    /// Texto claro conhecido das duas mensagens.
+    pub fn view(&self) -> DepthView {
+        let read = |message: DepthMessage| -> String {
+            self.ciphertext(message)
+                .iter()
+                .enumerate()
+                .map(|(i, &c)| match self.columns[self.column(message, i)][c as usize] {
+                    UNKNOWN => '-',
+                    p => u8_to_char(p),
+                })
+                .collect()
+        };
+        DepthView {
+            offset: self.offset,
+            first: read(DepthMessage::First),
+            second: read(DepthMessage::Second),
+            guesses: self.guesses.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enigma::{EnigmaConfig, EnigmaMachine, RotorConfig};
+
+    const FIRST: &str = "DIEENIGMAWAREINEROTORSCHLUESSELMASCHINEDIEIMZWEITENWELTKRIEGZURVERSCHLUESSELUNGDESNACHRICHTENVERKEHRSDERWEHRMACHTVERWENDETWURDEAUCHANDEREDIENSTSTELLENWIEPOLIZEIGEHEIMDIENSTEDIPLOMATISCHEDIENSTESOWIEREICHSBAHNUNDREICHSPOSTSETZTENSIEZURGEHEIMENKOMMUNIKATIONEIN";
+    const SECOND: &str = "TROTZMANNIGFALTIGERVORDEMKRIEGUNDWAEHRENDDESKRIEGESEINGEFUEHRTERVERBESSERUNGENDERVERSCHLUESSELUNGSQUALITAETGELANGESDENALLIIERTENMITHOHEMPERSONELLENUNDMASCHINELLENAUFWANDDIEDEUTSCHENFUNKSPRUECHENAHEZUKONTINUIERLICHZUENTZIFFERNUNDDIEMELDUNGENZULESEN";
+
+    fn machine() -> EnigmaMachine {
+        EnigmaMachine::new(EnigmaConfig {
+            rotors: (
+                RotorConfig { name: "III".to_string(), position: 'P', ring: 'C' },
+                RotorConfig { name: "II".to_string(), position: 'E', ring: 'A' },
+                RotorConfig { name: "I".to_string(), position: 'W', ring: 'A' },
+            ),
+            reflector: "B".to_string(),
+            plugboard_pairs: "AM FI NV PS TU WZ".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_flags_depth_offset() {
+        let first = machine().process_string(FIRST);
+        // A segunda mensagem começa 9 letras depois da primeira, com a mesma chave
+        let second: String = machine().process_string(&format!("XXXXXXXXX{}", SECOND)).chars().skip(9).collect();
+
+        let results = kappa_test(&first, &second, 30);
+        assert_eq!(results.len(), 61);
+        let flagged: Vec<i32> = results.iter().filter(|r| r.in_depth).map(|r| r.offset).collect();
+        assert_eq!(flagged, vec![9]);
+        assert!(results.iter().find(|r| r.offset == 9).unwrap().kappa > 0.06);
+    }
+
+    #[test]
+    fn test_guess_reveals_other_message() {
+        let first = machine().process_string(FIRST);
+        let second: String = machine().process_string(&format!("XXXXXXXXX{}", SECOND)).chars().skip(9).collect();
+        let mut reader = DepthReader::new(&first, &second, 9).unwrap();
+
+        let view = reader.guess(DepthMessage::First, 14, "NEROTORSCHLUESSELMASCHINEDIEIMZWEITENWELTKRIEG").unwrap();
+        assert!(view.first.contains("NEROTORSCHLUESSEL"));
+        // Tudo o que aparece da segunda mensagem tem de bater com o texto claro dela
+        let revealed: Vec<(char, char)> = view.second.chars().zip(SECOND.chars()).filter(|(r, _)| *r != '-').collect();
+        assert!(!revealed.is_empty());
+        assert!(revealed.iter().all(|(r, p)| r == p));
+
+        // Uma suposição incompatível é recusada e o estado não muda
+        assert!(reader.guess(DepthMessage::Second, 0, "AAAAAAAAAA").is_err());
+        assert_eq!(reader.view().second, view.second);
+
+        assert!(reader.undo().first.chars().all(|c| c == '-'));
+    }
+
+    #[test]
+    fn test_requires_min_overlap() {
+        let results = kappa_test("ABCDEFGHIJKLMNOPQRSTUVWXY", "ABCDEFGHIJKLMNOPQRSTUVWXY", 0);
+        assert_eq!(results[0].coincidences, 25);
+        assert!(results[0].in_depth);
+        assert!(!kappa_test("ABCDEFGHIJ", "ABCDEFGHIJ", 0)[0].in_depth);
+        assert_eq!(kappa_test("ABC", "ABC", usize::MAX).len(), 5);
+    }
+}
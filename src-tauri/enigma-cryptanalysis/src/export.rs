@@ -0,0 +1,213 @@
+//! Exportação do relatório de um ataque.
+//!
+//! A UI junta o que sabe do ataque (entradas, restrições, eventos recebidos e o
+//! `AttackReport` final) num `AttackExport`; aqui ele é completado com a chave final
+//! e o texto decifrado e gravado em dois arquivos: JSON, para reabrir ou processar,
+//! e Markdown, para ler ou entregar como trabalho.
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::report::AttackReport;
+use super::stats::letters;
+use crate::enigma::{EnigmaConfig, EnigmaMachine};
+
+/// Um evento da linha do tempo do ataque.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TimelineEntry {
+    /// Milissegundos desde o início do ataque.
+    pub elapsed_ms: u64,
+    pub event: String,
+    #[serde(default)]
+    pub detail: String,
+}
+
+/// O que a UI envia para exportar.
+#[derive(Debug, Deserialize, Clone)]
+pub struct AttackExport {
+    /// Nome do ataque (ex.: "Busca exaustiva").
+    pub attack: String,
+    pub ciphertext: String,
+    #[serde(default)]
+    pub scorer: Option<String>,
+    #[serde(default)]
+    pub crib: Option<String>,
+    /// Restrições e opções do ataque, como foram enviadas a ele.
+    #[serde(default)]
+    pub constraints: serde_json::Value,
+    #[serde(default)]
+    pub timeline: Vec<TimelineEntry>,
+    pub report: AttackReport,
+}
+
+/// Relatório completo, como é gravado.
+#[derive(Debug, Serialize, Clone)]
+pub struct ExportedReport {
+    pub attack: String,
+    pub ciphertext: String,
+    pub scorer: Option<String>,
+    pub crib: Option<String>,
+    pub constraints: serde_json::Value,
+    pub timeline: Vec<TimelineEntry>,
+    pub report: AttackReport,
+    /// Melhor chave encontrada.
+    pub final_key: Option<EnigmaConfig>,
+    /// Texto cifrado inteiro decifrado com a chave final.
+    pub decrypted: Option<String>,
+    /// Momento da exportação (segundos desde 1970).
+    pub exported_at: u64,
+}
+
+impl ExportedReport {
+    /// Completa o que a UI enviou com a chave final e o texto decifrado.
+    pub fn new(export: AttackExport) -> Self {
+        let final_key = export.report.candidates.first().map(|c| c.config.clone());
+        let decrypted =
+            final_key.as_ref().map(|config| EnigmaMachine::new(config.clone()).process_string(&export.ciphertext));
+        Self {
+            attack: export.attack,
+            ciphertext: export.ciphertext,
+            scorer: export.scorer,
+            crib: export.crib,
+            constraints: export.constraints,
+            timeline: export.timeline,
+            report: export.report,
+            final_key,
+            decrypted,
+            exported_at: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+        }
+    }
+
+    /// Relatório em Markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("# Relatório de ataque: {}\n\n", self.attack);
+
+        md.push_str("## Entrada\n\n");
+        md.push_str(&format!("- Texto cifrado: {} letras\n", letters(&self.ciphertext).len()));
+        md.push_str(&format!("- Pontuador: {}\n", self.scorer.as_deref().unwrap_or("padrão")));
+        if let Some(crib) = &self.crib {
+            md.push_str(&format!("- Crib: `{}`\n", crib));
+        }
+        md.push_str(&format!("\n```\n{}\n```\n\n", self.ciphertext));
+
+        if self.constraints != serde_json::Value::Null {
+            let constraints = serde_json::to_string_pretty(&self.constraints).unwrap_or_default();
+            md.push_str(&format!("## Restrições\n\n```json\n{}\n```\n\n", constraints));
+        }
+
+        if !self.timeline.is_empty() {
+            md.push_str("## Linha do tempo\n\n| Tempo | Evento | Detalhe |\n|---|---|---|\n");
+            for entry in &self.timeline {
+                md.push_str(&format!("| {} | {} | {} |\n", seconds(entry.elapsed_ms), entry.event, entry.detail));
+            }
+            md.push('\n');
+        }
+
+        md.push_str("## Candidatos\n\n");
+        md.push_str("| # | Pontuação | Confiança | Rotores | Posições | Anéis | Refletor | Plugboard | Início |\n");
+        md.push_str("|---|---|---|---|---|---|---|---|---|\n");
+        for (i, candidate) in self.report.candidates.iter().enumerate() {
+            let (rotors, positions, rings) = settings(&candidate.config);
+            md.push_str(&format!(
+                "| {} | {:.4} | {:.1}% | {} | {} | {} | {} | {} | `{}` |\n",
+                i + 1,
+                candidate.score,
+                candidate.confidence * 100.0,
+                rotors,
+                positions,
+                rings,
+                candidate.config.reflector,
+                candidate.config.plugboard_pairs,
+                candidate.preview
+            ));
+        }
+
+        let stats = &self.report.stats;
+        md.push_str("\n## Estatísticas\n\n");
+        md.push_str(&format!("- Chaves testadas: {}\n", stats.keys_tried));
+        md.push_str(&format!("- Duração: {}\n", seconds(stats.elapsed_ms)));
+        md.push_str(&format!("- Interrompido: {}\n\n", if stats.cancelled { "sim" } else { "não" }));
+
+        if let (Some(key), Some(decrypted)) = (&self.final_key, &self.decrypted) {
+            let (rotors, positions, rings) = settings(key);
+            md.push_str("## Chave final\n\n");
+            md.push_str(&format!("- Rotores (esquerda para direita): {}\n", rotors));
+            md.push_str(&format!("- Posições: {}\n", positions));
+            md.push_str(&format!("- Anéis: {}\n", rings));
+            md.push_str(&format!("- Refletor: {}\n", key.reflector));
+            md.push_str(&format!("- Plugboard: {}\n\n", key.plugboard_pairs));
+            md.push_str(&format!("## Texto decifrado\n\n```\n{}\n```\n", decrypted));
+        }
+        md
+    }
+
+    /// Grava o relatório em `path` com as extensões .json e .md (a extensão de
+    /// `path`, se houver, é trocada). Retorna os caminhos gravados.
+    pub fn write(&self, path: &Path) -> Result<Vec<PathBuf>, String> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Erro ao criar a pasta do relatório: {}", e))?;
+        }
+        let json_path = path.with_extension("json");
+        let json = serde_json::to_string_pretty(self).map_err(|e| format!("Erro ao gerar o relatório: {}", e))?;
+        std::fs::write(&json_path, json).map_err(|e| format!("Erro ao salvar o relatório: {}", e))?;
+        let md_path = path.with_extension("md");
+        std::fs::write(&md_path, self.to_markdown()).map_err(|e| format!("Erro ao salvar o relatório: {}", e))?;
+        Ok(vec![json_path, md_path])
+    }
+}
+
+/// Duração em segundos, com vírgula decimal.
+fn seconds(ms: u64) -> String {
+    format!("{:.1} s", ms as f64 / 1000.0).replace('.', ",")
+}
+
+/// Rotores, posições e anéis da esquerda para a direita (ex.: "III II I", "XKE", "AAA").
+fn settings(config: &EnigmaConfig) -> (String, String, String) {
+    let (right, middle, left) = &config.rotors;
+    let wheels = [left, middle, right];
+    (
+        wheels.iter().map(|r| r.name.as_str()).collect::<Vec<_>>().join(" "),
+        wheels.iter().map(|r| r.position).collect(),
+        wheels.iter().map(|r| r.ring).collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_config;
+    use crate::report::{CandidateKey, SearchStats};
+
+    #[test]
+    fn test_markdown_report() {
+        let key = key_config(("II", "I", "III"), "B", ('X', 'K', 'E'), ('A', 'B', 'C'), "AV BS");
+        let ciphertext = EnigmaMachine::new(key.clone()).process_string("WETTERBERICHT");
+        let candidate = CandidateKey {
+            config: key,
+            score: 13.0,
+            confidence: 0.0,
+            preview: "WETTERBERICHT".to_string(),
+            language: None,
+        };
+        let stats = SearchStats { keys_tried: 17576, elapsed_ms: 1300, cancelled: false };
+        let report = ExportedReport::new(AttackExport {
+            attack: "Busca exaustiva".to_string(),
+            ciphertext,
+            scorer: None,
+            crib: Some("WETTERBERICHT".to_string()),
+            constraints: serde_json::Value::Null,
+            timeline: vec![TimelineEntry { elapsed_ms: 0, event: "início".to_string(), detail: String::new() }],
+            report: AttackReport::new(vec![candidate], stats),
+        });
+
+        assert_eq!(report.decrypted.as_deref(), Some("WETTERBERICHT"));
+        let md = report.to_markdown();
+        assert!(md.starts_with("# Relatório de ataque: Busca exaustiva"));
+        assert!(md.contains("| 1 | 13.0000 | 100.0% | III I II | XKE | ABC | B | AV BS | `WETTERBERICHT` |"));
+        assert!(md.contains("- Duração: 1,3 s"));
+        assert!(md.contains("## Texto decifrado\n\n```\nWETTERBERICHT\n```"));
+        assert!(!md.contains("## Restrições"));
+    }
+}
@@ -0,0 +1,467 @@
+//! Funções de aptidão (fitness) usadas pelos ataques.
+//!
+//! Os motores de busca (força bruta, anéis, plugboard) só precisam saber o quanto
+//! um texto decifrado "parece" texto claro. Cada estratégia de pontuação implementa
+//! `FitnessScorer`, e novas estratégias entram no `ScorerRegistry` sem mexer nas
+//! buscas. Em todas elas, quanto maior a pontuação, melhor.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use super::stats::{index_of_coincidence, letter_counts, letters};
+
+/// Frequências das letras A-Z no alemão, em %.
+pub const GERMAN_FREQUENCIES: [f64; 26] = [
+    6.51, 1.89, 3.06, 5.08, 17.40, 1.66, 3.01, 4.76, 7.55, 0.27, 1.21, 3.44, 2.53,
+    9.78, 2.51, 0.79, 0.02, 7.00, 7.27, 6.15, 4.35, 0.67, 1.89, 0.03, 0.04, 1.13,
+];
+
+/// Frequências das letras A-Z no inglês, em %.
+pub const ENGLISH_FREQUENCIES: [f64; 26] = [
+    8.17, 1.49, 2.78, 4.25, 12.70, 2.23, 2.02, 6.09, 6.97, 0.15, 0.77, 4.03, 2.41,
+    6.75, 7.51, 1.93, 0.10, 5.99, 6.33, 9.06, 2.76, 0.98, 2.36, 0.15, 1.97, 0.07,
+];
+
+/// Frequências das letras A-Z no francês, em %.
+pub const FRENCH_FREQUENCIES: [f64; 26] = [
+    7.64, 0.90, 3.26, 3.67, 14.72, 1.07, 0.87, 0.74, 7.53, 0.61, 0.07, 5.46, 2.97,
+    7.10, 5.80, 2.52, 1.36, 6.69, 7.95, 7.24, 6.31, 1.84, 0.05, 0.43, 0.13, 0.33,
+];
+
+/// Frequências das letras A-Z no espanhol, em %.
+pub const SPANISH_FREQUENCIES: [f64; 26] = [
+    11.53, 2.22, 4.02, 5.01, 12.18, 0.69, 1.77, 0.70, 6.25, 0.49, 0.01, 4.97, 3.16,
+    6.71, 8.68, 2.51, 0.88, 6.87, 7.98, 4.63, 2.93, 1.14, 0.02, 0.22, 1.01, 0.47,
+];
+
+/// Frequências das letras A-Z no italiano, em %.
+pub const ITALIAN_FREQUENCIES: [f64; 26] = [
+    11.75, 0.93, 4.50, 3.74, 11.79, 1.15, 1.64, 0.64, 10.14, 0.01, 0.01, 6.51, 2.51,
+    6.88, 9.83, 3.06, 0.51, 6.37, 4.98, 5.62, 3.01, 2.10, 0.03, 0.01, 0.02, 1.18,
+];
+
+/// Frequências das letras A-Z no português, em %.
+pub const PORTUGUESE_FREQUENCIES: [f64; 26] = [
+    14.63, 1.04, 3.88, 4.99, 12.57, 1.02, 1.30, 1.28, 6.19, 0.40, 0.02, 2.78, 4.74,
+    4.45, 9.74, 2.52, 1.20, 6.53, 6.81, 4.34, 3.64, 1.58, 0.04, 0.25, 0.01, 0.47,
+];
+
+/// Línguas embutidas: código, nome e frequências das letras.
+pub const LANGUAGES: [(&str, &str, &[f64; 26]); 6] = [
+    ("de", "Alemão", &GERMAN_FREQUENCIES),
+    ("en", "Inglês", &ENGLISH_FREQUENCIES),
+    ("es", "Espanhol", &SPANISH_FREQUENCIES),
+    ("fr", "Francês", &FRENCH_FREQUENCIES),
+    ("it", "Italiano", &ITALIAN_FREQUENCIES),
+    ("pt", "Português", &PORTUGUESE_FREQUENCIES),
+];
+
+/// Estratégia de pontuação de um texto decifrado (letras 0-25).
+pub trait FitnessScorer: Send + Sync {
+    /// Nome usado para escolher a estratégia na UI.
+    fn name(&self) -> &str;
+
+    /// Pontua o texto; quanto maior, mais parecido com texto claro.
+    fn score(&self, text: &[u8]) -> f64;
+
+    /// Quantas letras do início do texto bastam para pontuar (`None` = todas).
+    /// Permite que a busca decifre só o necessário.
+    fn window(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Índice de coincidência: não depende da língua e sobe mesmo com o plugboard errado.
+pub struct IndexOfCoincidence;
+
+impl FitnessScorer for IndexOfCoincidence {
+    fn name(&self) -> &str {
+        "ic"
+    }
+
+    fn score(&self, text: &[u8]) -> f64 {
+        index_of_coincidence(text)
+    }
+}
+
+/// Qui-quadrado das frequências de letras contra as de uma língua, negado para que
+/// valores maiores sejam melhores.
+pub struct ChiSquared {
+    name: String,
+    expected: [f64; 26],
+}
+
+impl ChiSquared {
+    /// Cria o pontuador a partir de frequências (em qualquer escala; são normalizadas).
+    pub fn new(name: &str, frequencies: &[f64; 26]) -> Self {
+        let total: f64 = frequencies.iter().sum();
+        Self {
+            name: name.to_string(),
+            expected: frequencies.map(|f| f / total),
+        }
+    }
+}
+
+impl FitnessScorer for ChiSquared {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn score(&self, text: &[u8]) -> f64 {
+        let n = text.len() as f64;
+        if n == 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        let chi: f64 = letter_counts(text)
+            .iter()
+            .zip(&self.expected)
+            .map(|(&observed, &p)| {
+                let expected = (p * n).max(1e-9);
+                (observed as f64 - expected).powi(2) / expected
+            })
+            .sum();
+        -chi
+    }
+}
+
+/// Língua mais provável de um texto decifrado.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct LanguageGuess {
+    /// Código da língua ("de", "en", ...).
+    pub code: String,
+    pub name: String,
+    /// Qui-quadrado das frequências do texto contra as da língua (menor é melhor).
+    pub chi_squared: f64,
+}
+
+/// Compara o texto com todas as línguas embutidas e retorna a mais provável, ou
+/// `None` para texto vazio.
+pub fn detect_language(text: &[u8]) -> Option<LanguageGuess> {
+    if text.is_empty() {
+        return None;
+    }
+    LANGUAGES
+        .iter()
+        .map(|&(code, name, frequencies)| LanguageGuess {
+            code: code.to_string(),
+            name: name.to_string(),
+            chi_squared: -ChiSquared::new(code, frequencies).score(text),
+        })
+        .min_by(|a, b| a.chi_squared.total_cmp(&b.chi_squared))
+}
+
+/// Qui-quadrado contra a língua que melhor se ajusta ao texto, para pontuar
+/// candidatos sem saber a língua da mensagem.
+pub struct BestLanguageFit {
+    languages: Vec<ChiSquared>,
+}
+
+impl Default for BestLanguageFit {
+    fn default() -> Self {
+        Self { languages: LANGUAGES.iter().map(|&(code, _, frequencies)| ChiSquared::new(code, frequencies)).collect() }
+    }
+}
+
+impl FitnessScorer for BestLanguageFit {
+    fn name(&self) -> &str {
+        "chi2-auto"
+    }
+
+    fn score(&self, text: &[u8]) -> f64 {
+        self.languages.iter().map(|language| language.score(text)).fold(f64::NEG_INFINITY, f64::max)
+    }
+}
+
+/// Modelo de n-gramas: média do log10 da probabilidade de cada n-grama do texto.
+/// N-gramas ausentes do modelo recebem uma probabilidade mínima (`floor`).
+pub struct NgramScorer {
+    name: String,
+    n: usize,
+    log_probs: Vec<f32>,
+    floor: f32,
+}
+
+impl NgramScorer {
+    /// Monta o modelo a partir de contagens de n-gramas (ex.: ("EINE", 1234)).
+    /// Entradas com tamanho diferente de `n` ou com símbolos não alfabéticos são
+    /// rejeitadas.
+    pub fn from_counts(
+        name: &str,
+        n: usize,
+        counts: impl IntoIterator<Item = (String, u64)>,
+    ) -> Result<Self, String> {
+        if !(1..=5).contains(&n) {
+            return Err(format!("Tamanho de n-grama inválido: {}", n));
+        }
+        let mut table = vec![0u64; 26usize.pow(n as u32)];
+        let mut total = 0u64;
+        for (gram, count) in counts {
+            let gram_letters = letters(&gram);
+            if gram_letters.len() != n || gram.trim().len() != n {
+                return Err(format!("N-grama inválido: {}", gram));
+            }
+            // Cada célula é no máximo o total, então basta conferir o total
+            total = total.checked_add(count).ok_or("As contagens do modelo são grandes demais.")?;
+            table[Self::index(&gram_letters)] += count;
+        }
+        if total == 0 {
+            return Err("O modelo não contém n-gramas.".to_string());
+        }
+
+        let total = total as f64;
+        let floor = (0.01 / total).log10() as f32;
+        let log_probs = table
+            .into_iter()
+            .map(|c| if c == 0 { floor } else { (c as f64 / total).log10() as f32 })
+            .collect();
+        Ok(Self { name: name.to_string(), n, log_probs, floor })
+    }
+
+    /// Lê um arquivo de frequências com uma linha "NGRAMA CONTAGEM" por n-grama
+    /// (separados por espaço, tabulação ou vírgula), como as tabelas de quadrigramas
+    /// usadas em criptoanálise clássica. O tamanho dos n-gramas vem da primeira linha;
+    /// linhas vazias ou iniciadas por '#' são ignoradas.
+    pub fn parse(name: &str, contents: &str) -> Result<Self, String> {
+        let mut counts = Vec::new();
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split(|c: char| c.is_whitespace() || c == ',').filter(|f| !f.is_empty());
+            let (Some(gram), Some(count), None) = (fields.next(), fields.next(), fields.next()) else {
+                return Err(format!("Linha {} inválida: {}", number + 1, line));
+            };
+            let count = count
+                .parse::<u64>()
+                .map_err(|_| format!("Contagem inválida na linha {}: {}", number + 1, count))?;
+            counts.push((gram.to_string(), count));
+        }
+        let Some(n) = counts.first().map(|(gram, _)| gram.len()) else {
+            return Err("O modelo não contém n-gramas.".to_string());
+        };
+        Self::from_counts(name, n, counts)
+    }
+
+    /// Carrega um arquivo de frequências (ver `parse`).
+    pub fn load(name: &str, path: &Path) -> Result<Self, String> {
+        let contents =
+            std::fs::read_to_string(path).map_err(|e| format!("Erro ao abrir o modelo de n-gramas: {}", e))?;
+        Self::parse(name, &contents)
+    }
+
+    /// Tamanho dos n-gramas do modelo.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Resumo do modelo para a UI.
+    pub fn info(&self) -> NgramModelInfo {
+        NgramModelInfo {
+            name: self.name.clone(),
+            n: self.n,
+            distinct: self.log_probs.iter().filter(|&&p| p > self.floor).count(),
+        }
+    }
+
+    fn index(gram: &[u8]) -> usize {
+        gram.iter().fold(0, |acc, &c| acc * 26 + c as usize)
+    }
+}
+
+/// Resumo de um modelo de n-gramas carregado.
+#[derive(Debug, Serialize, Clone)]
+pub struct NgramModelInfo {
+    /// Nome com que o modelo foi registrado.
+    pub name: String,
+    /// Tamanho dos n-gramas (4 para quadrigramas).
+    pub n: usize,
+    /// Número de n-gramas distintos presentes no arquivo.
+    pub distinct: usize,
+}
+
+impl FitnessScorer for NgramScorer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn score(&self, text: &[u8]) -> f64 {
+        if text.len() < self.n {
+            return self.floor as f64;
+        }
+        let windows = text.windows(self.n);
+        let count = windows.len();
+        let sum: f64 = windows.map(|gram| self.log_probs[Self::index(gram)] as f64).sum();
+        sum / count as f64
+    }
+}
+
+/// Coincidências com um crib (texto claro provável no início da mensagem).
+pub struct CribScorer {
+    crib: Vec<u8>,
+}
+
+impl CribScorer {
+    /// Cria o pontuador; `None` se o crib não tiver letras.
+    pub fn new(crib: &str) -> Option<Self> {
+        let crib = letters(crib);
+        (!crib.is_empty()).then_some(Self { crib })
+    }
+}
+
+impl FitnessScorer for CribScorer {
+    fn name(&self) -> &str {
+        "crib"
+    }
+
+    fn score(&self, text: &[u8]) -> f64 {
+        text.iter().zip(&self.crib).filter(|(a, b)| a == b).count() as f64
+    }
+
+    fn window(&self) -> Option<usize> {
+        Some(self.crib.len())
+    }
+}
+
+/// Pontuador definido pelo usuário a partir de uma função qualquer.
+pub struct CustomScorer<F> {
+    name: String,
+    function: F,
+}
+
+impl<F: Fn(&[u8]) -> f64 + Send + Sync> CustomScorer<F> {
+    pub fn new(name: &str, function: F) -> Self {
+        Self { name: name.to_string(), function }
+    }
+}
+
+impl<F: Fn(&[u8]) -> f64 + Send + Sync> FitnessScorer for CustomScorer<F> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn score(&self, text: &[u8]) -> f64 {
+        (self.function)(text)
+    }
+}
+
+/// Pontuadores disponíveis, por nome. Já vem com "ic", um qui-quadrado por língua
+/// embutida ("chi2-de", "chi2-en", ...) e "chi2-auto", que usa a língua mais provável.
+pub struct ScorerRegistry {
+    scorers: RwLock<BTreeMap<String, Arc<dyn FitnessScorer>>>,
+}
+
+impl Default for ScorerRegistry {
+    fn default() -> Self {
+        let registry = Self { scorers: RwLock::new(BTreeMap::new()) };
+        registry.register(Arc::new(IndexOfCoincidence));
+        for (code, _, frequencies) in LANGUAGES {
+            registry.register(Arc::new(ChiSquared::new(&format!("chi2-{}", code), frequencies)));
+        }
+        registry.register(Arc::new(BestLanguageFit::default()));
+        registry
+    }
+}
+
+impl ScorerRegistry {
+    /// Registra (ou substitui) um pontuador com o nome dele.
+    pub fn register(&self, scorer: Arc<dyn FitnessScorer>) {
+        self.scorers.write().unwrap().insert(scorer.name().to_string(), scorer);
+    }
+
+    /// Busca um pontuador pelo nome.
+    pub fn get(&self, name: &str) -> Result<Arc<dyn FitnessScorer>, String> {
+        self.scorers
+            .read()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("Pontuador desconhecido: {}", name))
+    }
+
+    /// Nomes dos pontuadores registrados, em ordem alfabética.
+    pub fn names(&self) -> Vec<String> {
+        self.scorers.read().unwrap().keys().cloned().collect()
+    }
+
+    /// Escolhe o pontuador de um ataque: o crib, se houver; senão o pontuador
+    /// pedido pelo nome; senão o índice de coincidência.
+    pub fn resolve(&self, crib: Option<&str>, name: Option<&str>) -> Result<Arc<dyn FitnessScorer>, String> {
+        if let Some(crib) = crib.and_then(CribScorer::new) {
+            return Ok(Arc::new(crib));
+        }
+        self.get(name.unwrap_or("ic"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GERMAN: &str = "DIEMASCHINEWURDEVONDERWEHRMACHTWAEHRENDDESKRIEGESVERWENDET";
+    const RANDOM: &str = "QXZKJVWPYBGMFHTLCRUOSNAIEDQZXJKVWPYMBGFHCTLRUSONAIEDXQZJKV";
+
+    #[test]
+    fn test_chi_squared_prefers_language() {
+        let scorer = ChiSquared::new("chi2-de", &GERMAN_FREQUENCIES);
+        assert!(scorer.score(&letters(GERMAN)) > scorer.score(&letters(RANDOM)));
+    }
+
+    #[test]
+    fn test_detect_language() {
+        let german = detect_language(&letters(GERMAN)).unwrap();
+        assert_eq!(german.code, "de");
+        let portuguese = "AMAQUINAFOIUSADAPELASFORCASARMADASALEMAESDURANTEASEGUNDAGUERRAMUNDIALPARAPROTEGERASCOMUNICACOES";
+        assert_eq!(detect_language(&letters(portuguese)).unwrap().code, "pt");
+        let english = "THEMACHINEWASUSEDBYTHEGERMANARMEDFORCESTHROUGHOUTTHESECONDWORLDWARTOPROTECTTHEIRCOMMUNICATIONS";
+        assert_eq!(detect_language(&letters(english)).unwrap().code, "en");
+        assert!(detect_language(&[]).is_none());
+
+        let auto = BestLanguageFit::default();
+        assert!(auto.score(&letters(portuguese)) > auto.score(&letters(RANDOM)));
+    }
+
+    #[test]
+    fn test_ngram_scorer() {
+        let counts = [("EI", 10), ("IN", 8), ("NE", 6)].map(|(g, c)| (g.to_string(), c));
+        let scorer = NgramScorer::from_counts("bigramas", 2, counts).unwrap();
+
+        assert!(scorer.score(&letters("EINE")) > scorer.score(&letters("QXZJ")));
+        assert!(NgramScorer::from_counts("ruim", 2, [("E1".to_string(), 1)]).is_err());
+        let huge = [("EN".to_string(), u64::MAX), ("ER".to_string(), 1)];
+        assert!(NgramScorer::from_counts("grande", 2, huge).is_err());
+    }
+
+    #[test]
+    fn test_parse_ngram_file() {
+        let contents = "# quadrigramas\nEINE 120\nDERS\t80\n\nICHT,95\n";
+        let model = NgramScorer::parse("de", contents).unwrap();
+        let info = model.info();
+        assert_eq!((info.n, info.distinct), (4, 3));
+        assert!(model.score(&letters("EINEICHT")) > model.score(&letters("QXZJQXZJ")));
+
+        assert!(NgramScorer::parse("vazio", "# nada\n").is_err());
+        assert!(NgramScorer::parse("misto", "EINE 1\nDER 2\n").is_err());
+        assert!(NgramScorer::parse("ruim", "EINE muitas\n").is_err());
+    }
+
+    #[test]
+    fn test_registry_resolution() {
+        let registry = ScorerRegistry::default();
+        assert_eq!(
+            registry.names(),
+            vec!["chi2-auto", "chi2-de", "chi2-en", "chi2-es", "chi2-fr", "chi2-it", "chi2-pt", "ic"]
+        );
+        assert_eq!(registry.resolve(Some("WETTER"), Some("chi2-de")).unwrap().name(), "crib");
+        assert_eq!(registry.resolve(Some("123"), None).unwrap().name(), "ic");
+        assert!(registry.resolve(None, Some("quadgramas")).is_err());
+
+        registry.register(Arc::new(CustomScorer::new("vogais", |text: &[u8]| {
+            text.iter().filter(|&&c| [0, 4, 8, 14, 20].contains(&c)).count() as f64
+        })));
+        assert_eq!(registry.get("vogais").unwrap().score(&letters("AEIOU")), 5.0);
+    }
+}
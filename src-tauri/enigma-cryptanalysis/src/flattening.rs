@@ -0,0 +1,104 @@
+//! Demonstração: por que a análise de frequências não funciona contra a Enigma.
+//!
+//! Numa cifra de substituição simples (como a de César) cada letra vira sempre a
+//! mesma letra, então o histograma do texto cifrado é o do texto claro embaralhado:
+//! os picos continuam lá e o índice de coincidência não muda. Na Enigma o alfabeto
+//! muda a cada tecla, e o histograma do texto cifrado fica quase plano, com o
+//! índice de coincidência perto do de letras aleatórias (1/26).
+
+use serde::Serialize;
+
+use super::stats::{index_of_coincidence, letter_counts, letters};
+use super::{validate_config, PREVIEW_LEN};
+use crate::enigma::{EnigmaConfig, EnigmaMachine};
+
+/// Texto de exemplo em alemão, longo o bastante para os histogramas se firmarem.
+pub const SAMPLE_TEXT: &str = "Die Enigma war eine Rotor-Schluesselmaschine, die im Zweiten Weltkrieg \
+zur Verschluesselung des Nachrichtenverkehrs der Wehrmacht verwendet wurde. Auch andere Dienststellen \
+wie Polizei, Geheimdienste, diplomatische Dienste sowie Reichsbahn und Reichspost setzten sie zur \
+geheimen Kommunikation ein. Trotz mannigfaltiger, vor dem Krieg und waehrend des Krieges eingefuehrter \
+Verbesserungen der Verschluesselungsqualitaet gelang es den Alliierten mit hohem personellen und \
+maschinellen Aufwand, die deutschen Funksprueche nahezu kontinuierlich zu entziffern und die Meldungen \
+zu lesen. Die Wetterberichte der Unterseeboote begannen fast immer mit denselben Worten, und die \
+Operateure waehlten oft bequeme Schluessel. Genau solche Gewohnheiten, nicht die Haeufigkeit der \
+Buchstaben, oeffneten den Weg in die Maschine.";
+
+/// Deslocamento da cifra de César usada como comparação.
+const CAESAR_SHIFT: u8 = 3;
+
+/// Histograma de um texto.
+#[derive(Debug, Serialize, Clone)]
+pub struct LetterDistribution {
+    /// Contagem de cada letra, de A a Z.
+    pub counts: Vec<usize>,
+    /// Frequência relativa de cada letra, de A a Z.
+    pub frequencies: Vec<f64>,
+    pub index_of_coincidence: f64,
+}
+
+impl LetterDistribution {
+    fn of(text: &[u8]) -> Self {
+        let counts = letter_counts(text).to_vec();
+        let frequencies = counts.iter().map(|&c| c as f64 / text.len() as f64).collect();
+        Self { counts, frequencies, index_of_coincidence: index_of_coincidence(text) }
+    }
+}
+
+/// Dados da lição: o texto claro, a mesma mensagem numa cifra de César e na Enigma.
+#[derive(Debug, Serialize, Clone)]
+pub struct FlatteningDemo {
+    /// Número de letras do texto.
+    pub letters: usize,
+    pub plaintext: LetterDistribution,
+    pub caesar: LetterDistribution,
+    pub enigma: LetterDistribution,
+    /// Índice de coincidência de letras aleatórias.
+    pub random_ic: f64,
+    /// Início do texto cifrado pela Enigma.
+    pub ciphertext: String,
+}
+
+/// Cifra `text` (ou `SAMPLE_TEXT`) com `config` e com uma cifra de César, e
+/// devolve os três histogramas.
+pub fn flattening_demo(config: &EnigmaConfig, text: Option<&str>) -> Result<FlatteningDemo, String> {
+    let config = &validate_config(config)?;
+    let plain = letters(text.unwrap_or(SAMPLE_TEXT));
+    if plain.is_empty() {
+        return Err("O texto não contém letras.".to_string());
+    }
+
+    let caesar: Vec<u8> = plain.iter().map(|&c| (c + CAESAR_SHIFT) % 26).collect();
+    let ciphertext = EnigmaMachine::new(config.clone()).process_string(text.unwrap_or(SAMPLE_TEXT));
+    Ok(FlatteningDemo {
+        letters: plain.len(),
+        plaintext: LetterDistribution::of(&plain),
+        caesar: LetterDistribution::of(&caesar),
+        enigma: LetterDistribution::of(&letters(&ciphertext)),
+        random_ic: 1.0 / 26.0,
+        ciphertext: ciphertext.chars().take(PREVIEW_LEN).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_config;
+
+    #[test]
+    fn test_enigma_flattens_distribution() {
+        let plugboard = "AT BS DE FM IR KN LZ OW PV XY";
+        let config = key_config(("I", "II", "III"), "B", ('Q', 'E', 'V'), ('A', 'A', 'A'), plugboard);
+        let demo = flattening_demo(&config, None).unwrap();
+
+        assert!(demo.letters > 500);
+        // César só troca as letras de lugar
+        assert!((demo.caesar.index_of_coincidence - demo.plaintext.index_of_coincidence).abs() < 1e-12);
+        // O E do texto claro vira H
+        assert_eq!(demo.caesar.counts[7], demo.plaintext.counts[4]);
+        // A Enigma aproxima o texto cifrado de letras aleatórias
+        assert!(demo.plaintext.index_of_coincidence > 0.06);
+        assert!(demo.enigma.index_of_coincidence < 0.045);
+        let max = |d: &LetterDistribution| d.frequencies.iter().cloned().fold(0.0, f64::max);
+        assert!(max(&demo.enigma) < max(&demo.plaintext) * 0.7);
+    }
+}
@@ -0,0 +1,112 @@
+//! Dica de Herivel (1940).
+//!
+//! John Herivel supôs que um operador apressado, depois de ajustar os anéis com os
+//! rotores fora da máquina, os colocaria de volta e usaria quase sem mexer a posição
+//! que aparecia nas janelas como Grundstellung da primeira mensagem do dia. Essas
+//! posições ficariam então agrupadas perto da própria Ringstellung. Marcando as
+//! primeiras posições de vários operadores no "quadrado de Herivel", o centro do
+//! agrupamento indica a configuração dos anéis.
+
+use serde::Serialize;
+
+use super::all_positions;
+
+/// Um palpite de Ringstellung, com a evidência que o sustenta.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RingGuess {
+    /// Ringstellung sugerida (Esquerda, Meio, Direita).
+    pub ring_setting: (char, char, char),
+    /// Quantas Grundstellungen estão a no máximo `radius` passos em cada rotor.
+    pub support: usize,
+    /// Distância média (soma dos três rotores) das Grundstellungen que apoiam o palpite.
+    pub mean_distance: f64,
+}
+
+/// Distância circular entre duas letras (0 a 13).
+fn letter_distance(a: u8, b: u8) -> u8 {
+    let d = (a as i16 - b as i16).rem_euclid(26) as u8;
+    d.min(26 - d)
+}
+
+/// Ordena as Ringstellungen candidatas pela concentração de Grundstellungen ao redor
+/// delas. Posições inválidas (que não tenham três letras) são ignoradas.
+pub fn rank_ring_settings(grundstellungen: &[String], radius: u8, top: usize) -> Vec<RingGuess> {
+    let settings: Vec<[u8; 3]> = grundstellungen
+        .iter()
+        .map(|s| {
+            s.bytes()
+                .filter(|b| b.is_ascii_alphabetic())
+                .map(|b| b.to_ascii_uppercase() - b'A')
+                .collect::<Vec<u8>>()
+        })
+        .filter_map(|letters| letters.try_into().ok())
+        .collect();
+
+    let mut guesses: Vec<RingGuess> = all_positions()
+        .filter_map(|(l, m, r)| {
+            let candidate = [l as u8 - b'A', m as u8 - b'A', r as u8 - b'A'];
+            let distances: Vec<u32> = settings
+                .iter()
+                .map(|setting| {
+                    setting
+                        .iter()
+                        .zip(candidate.iter())
+                        .map(|(&a, &b)| letter_distance(a, b))
+                        .collect::<Vec<u8>>()
+                })
+                .filter(|d| d.iter().all(|&x| x <= radius))
+                .map(|d| d.iter().map(|&x| x as u32).sum())
+                .collect();
+            if distances.is_empty() {
+                return None;
+            }
+            Some(RingGuess {
+                ring_setting: (l, m, r),
+                support: distances.len(),
+                mean_distance: distances.iter().sum::<u32>() as f64 / distances.len() as f64,
+            })
+        })
+        .collect();
+
+    guesses.sort_by(|a, b| {
+        b.support
+            .cmp(&a.support)
+            .then(a.mean_distance.total_cmp(&b.mean_distance))
+    });
+    guesses.truncate(top);
+    guesses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_letter_distance_wraps() {
+        assert_eq!(letter_distance(0, 25), 1);
+        assert_eq!(letter_distance(3, 3), 0);
+        assert_eq!(letter_distance(0, 13), 13);
+    }
+
+    #[test]
+    fn test_cluster_points_to_ring_setting() {
+        // Anéis "DFJ": primeiras posições de vários operadores, mais algumas aleatórias
+        let grundstellungen = positions(&[
+            "DFJ", "EFJ", "DGJ", "CFK", "DEI", "QWE", "ZZA", "MMM", "xpt",
+        ]);
+        let guesses = rank_ring_settings(&grundstellungen, 1, 5);
+
+        assert_eq!(guesses[0].ring_setting, ('D', 'F', 'J'));
+        assert_eq!(guesses[0].support, 5);
+    }
+
+    #[test]
+    fn test_ignores_invalid_positions() {
+        let guesses = rank_ring_settings(&positions(&["AB", "ABCD", ""]), 2, 10);
+        assert!(guesses.is_empty());
+    }
+}
@@ -0,0 +1,133 @@
+//! Kernel de decifração em lote para os ataques.
+//!
+//! Um bloco da busca exaustiva decifra o mesmo texto com centenas de chaves que só
+//! diferem nas posições dos rotores do meio e da direita. Em vez de montar uma
+//! máquina por chave, a `ScramblerTable` guarda a permutação completa de cada
+//! posição por onde essas chaves podem passar, e `decrypt` avança `LANES` chaves
+//! juntas: a cada letra, o passo dos rotores é feito em vetores de posições (que o
+//! compilador vetoriza) e a cifragem vira uma única consulta à tabela por chave.
+//! As consultas das várias chaves não dependem umas das outras, então o
+//! processador as executa em paralelo.
+
+use crate::enigma::{EnigmaConfig, EnigmaMachine};
+
+/// Número de chaves decifradas juntas.
+pub const LANES: usize = 16;
+
+/// Letras que o rotor da esquerda leva, no mínimo, para girar de novo. Depois de
+/// girar, o rotor do meio precisa de mais 25 passos, e cada um deles (fora o passo
+/// duplo) espera uma volta do rotor da direita.
+const LETTERS_PER_LEFT_STEP: usize = 24 * 26;
+
+/// Posições do rotor da esquerda por onde passa um texto de `len` letras.
+fn lefts_for(len: usize) -> usize {
+    (len / LETTERS_PER_LEFT_STEP + 2).min(26)
+}
+
+/// Permutações de todas as posições (Meio, Direita) para algumas posições seguidas
+/// do rotor da esquerda, com a ordem de rotores, anéis, refletor e plugboard fixos.
+pub struct ScramblerTable {
+    /// `rows[(l * 26 + m) * 26 + r]`, com `l` contado a partir da posição inicial
+    /// do rotor da esquerda.
+    rows: Vec<[u8; 26]>,
+    /// Posições do rotor da esquerda cobertas pela tabela.
+    lefts: usize,
+    /// Ranhuras do rotor do meio e do da direita.
+    notches: (u8, u8),
+}
+
+impl ScramblerTable {
+    /// Monta a tabela para decifrar textos de até `len` letras a partir da posição
+    /// do rotor da esquerda de `config` (as posições do meio e da direita de
+    /// `config` não importam). A configuração deve ter sido validada.
+    pub fn new(config: &EnigmaConfig, len: usize) -> Self {
+        let lefts = lefts_for(len);
+        let first = config.rotors.2.position as u8 - b'A';
+        let mut config = config.clone();
+        let mut rows = Vec::with_capacity(lefts * 26 * 26);
+        let mut notches = (0, 0);
+        for l in 0..lefts {
+            config.rotors.2.position = (b'A' + (first + l as u8) % 26) as char;
+            for m in 0..26 {
+                config.rotors.1.position = (b'A' + m) as char;
+                let machine = EnigmaMachine::new(config.clone());
+                rows.extend(machine.right_rotor_sweep());
+                notches = machine.notches();
+            }
+        }
+        Self { rows, lefts, notches }
+    }
+
+    /// Decifra `text` a partir de cada posição inicial (Meio, Direita) de `starts`,
+    /// com 0-25 nas posições e nas letras. `out` recebe `text.len()` letras por
+    /// posição, na ordem de `starts`.
+    pub fn decrypt(&self, starts: &[(u8, u8)], text: &[u8], out: &mut [u8]) {
+        assert!(lefts_for(text.len()) <= self.lefts, "Texto maior que o previsto na tabela.");
+        assert_eq!(out.len(), starts.len() * text.len());
+        if text.is_empty() {
+            return;
+        }
+        let (middle_notch, right_notch) = self.notches;
+
+        for (batch, out) in starts.chunks(LANES).zip(out.chunks_mut(LANES * text.len())) {
+            let mut left = [0u8; LANES];
+            let mut middle = [0u8; LANES];
+            let mut right = [0u8; LANES];
+            for (lane, &(m, r)) in batch.iter().enumerate() {
+                middle[lane] = m;
+                right[lane] = r;
+            }
+
+            for (i, &c) in text.iter().enumerate() {
+                // Passo dos rotores, como em `EnigmaMachine::step_rotors`, sem divisões
+                // para o compilador poder vetorizar. `left` não passa de `lefts`.
+                for lane in 0..LANES {
+                    let middle_at_notch = middle[lane] == middle_notch;
+                    let right_at_notch = right[lane] == right_notch;
+                    left[lane] = wrap(left[lane] + middle_at_notch as u8);
+                    middle[lane] = wrap(middle[lane] + (middle_at_notch || right_at_notch) as u8);
+                    right[lane] = wrap(right[lane] + 1);
+                }
+                for (lane, out) in out.chunks_exact_mut(text.len()).enumerate() {
+                    let row = (left[lane] as usize * 26 + middle[lane] as usize) * 26 + right[lane] as usize;
+                    out[i] = self.rows[row][c as usize];
+                }
+            }
+        }
+    }
+}
+
+/// Volta a 0 depois de Z.
+#[inline]
+fn wrap(x: u8) -> u8 {
+    if x == 26 {
+        0
+    } else {
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decrypt, key_config};
+
+    #[test]
+    fn test_matches_machine() {
+        let text: Vec<u8> = (0..1500u32).map(|i| (i * 11 % 26) as u8).collect();
+        let config = key_config(("III", "I", "II"), "C", ('Y', 'A', 'A'), ('C', 'B', 'Q'), "AV BS CG DL");
+        let table = ScramblerTable::new(&config, text.len());
+
+        // Posições próximas das ranhuras (passo duplo) e um lote incompleto
+        let starts: Vec<(u8, u8)> = (0..26).flat_map(|m| [(m, 20), (m, 21), (m, 25)]).collect();
+        let mut out = vec![0u8; starts.len() * text.len()];
+        table.decrypt(&starts, &text, &mut out);
+
+        for (&(m, r), plain) in starts.iter().zip(out.chunks(text.len())) {
+            let mut config = config.clone();
+            config.rotors.1.position = (b'A' + m) as char;
+            config.rotors.0.position = (b'A' + r) as char;
+            assert_eq!(plain, decrypt(&mut EnigmaMachine::new(config), &text));
+        }
+    }
+}
@@ -0,0 +1,373 @@
+//! Tamanho do espaço de chaves de cada modelo de Enigma.
+//!
+//! O número de chaves é o produto de fatores independentes: escolha e ordem dos
+//! rotores, refletor, posições iniciais, anéis e plugboard. As contagens passam de
+//! 2⁶⁴, então são calculadas em `u128` e enviadas à UI como texto decimal (números
+//! do JavaScript perdem precisão acima de 2⁵³).
+//!
+//! A distância de unicidade de Shannon, U = H(K) / D, estima quantas letras de texto
+//! cifrado bastam para que só uma chave produza um texto claro plausível: H(K) é a
+//! entropia da chave em bits e D a redundância da língua por letra.
+
+use serde::{Deserialize, Serialize};
+
+use super::stats::letter_counts;
+use crate::enigma::{EnigmaConfig, KNOWN_ROTOR_NAMES, REFLECTOR_NAMES, ROTOR_NAMES};
+
+/// Modelos de máquina com espaço de chaves conhecido.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MachineModel {
+    /// Enigma I do Exército e da Aeronáutica: 3 de 5 rotores, refletor B.
+    EnigmaI,
+    /// M3 naval: 3 de 8 rotores, refletor B ou C.
+    M3,
+    /// M4 naval: 3 de 8 rotores, rotor grego Beta ou Gama, refletor fino B ou C.
+    M4,
+    /// A máquina deste simulador (rotores e refletores implementados).
+    Simulator,
+}
+
+/// Características do modelo que entram na contagem.
+struct ModelSpec {
+    rotor_pool: usize,
+    rotor_slots: u32,
+    greek_rotors: usize,
+    reflectors: usize,
+}
+
+impl MachineModel {
+    fn spec(self) -> ModelSpec {
+        match self {
+            MachineModel::EnigmaI => ModelSpec { rotor_pool: 5, rotor_slots: 3, greek_rotors: 0, reflectors: 1 },
+            MachineModel::M3 => ModelSpec { rotor_pool: 8, rotor_slots: 3, greek_rotors: 0, reflectors: 2 },
+            MachineModel::M4 => ModelSpec { rotor_pool: 8, rotor_slots: 3, greek_rotors: 2, reflectors: 2 },
+            MachineModel::Simulator => ModelSpec {
+                rotor_pool: ROTOR_NAMES.len(),
+                rotor_slots: 3,
+                greek_rotors: 0,
+                reflectors: REFLECTOR_NAMES.len(),
+            },
+        }
+    }
+}
+
+/// Opções da contagem, vindas da UI.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct KeyspaceOptions {
+    /// Número de cabos do plugboard; `None` soma todas as quantidades de 0 a 13.
+    pub plugboard_pairs: Option<usize>,
+    /// Conta os anéis. A cifra "150 milhões de milhões de milhões" costuma deixá-los de fora.
+    pub include_rings: bool,
+    /// Conta só os anéis que mudam o texto: o do rotor mais à esquerda (e o do rotor
+    /// grego) nunca aciona outro rotor e equivale a girar a posição.
+    pub effective_rings_only: bool,
+}
+
+impl Default for KeyspaceOptions {
+    fn default() -> Self {
+        Self { plugboard_pairs: Some(10), include_rings: true, effective_rings_only: false }
+    }
+}
+
+/// Um fator do produto.
+#[derive(Debug, Serialize, Clone)]
+pub struct KeyspaceFactor {
+    pub name: String,
+    /// Contagem exata, em decimal.
+    pub count: String,
+    /// Como a contagem foi obtida.
+    pub formula: String,
+}
+
+/// Espaço de chaves de um modelo, com o detalhamento por fator.
+#[derive(Debug, Serialize, Clone)]
+pub struct Keyspace {
+    pub model: MachineModel,
+    /// Total exato, em decimal.
+    pub total: String,
+    /// Total em bits (log₂), para comparar com cifras modernas.
+    pub bits: f64,
+    pub factors: Vec<KeyspaceFactor>,
+}
+
+/// Número de formas de ligar `pairs` cabos no plugboard: 26! / ((26 - 2n)! · n! · 2ⁿ).
+pub fn plugboard_settings(pairs: usize) -> u128 {
+    if pairs > 13 {
+        return 0;
+    }
+    let free = 26 - 2 * pairs as u128;
+    let mut count: u128 = ((free + 1)..=26).product();
+    for n in 1..=pairs as u128 {
+        count /= 2 * n;
+    }
+    count
+}
+
+/// Conta o espaço de chaves do modelo.
+pub fn keyspace(model: MachineModel, options: &KeyspaceOptions) -> Keyspace {
+    let spec = model.spec();
+    let mut factors = Vec::new();
+    let mut push = |name: &str, count: u128, formula: String| {
+        factors.push((name.to_string(), count, formula));
+    };
+
+    let orders: u128 = ((spec.rotor_pool - spec.rotor_slots as usize + 1)..=spec.rotor_pool)
+        .map(|n| n as u128)
+        .product();
+    push("Ordem dos rotores", orders, format!("{} rotores em {} lugares, sem repetição", spec.rotor_pool, spec.rotor_slots));
+    if spec.greek_rotors > 0 {
+        push("Rotor grego", spec.greek_rotors as u128, "Beta ou Gama no quarto lugar".to_string());
+    }
+    push("Refletor", spec.reflectors as u128, format!("{} refletor(es) disponível(is)", spec.reflectors));
+
+    let wheels = spec.rotor_slots + u32::from(spec.greek_rotors > 0);
+    push("Posições iniciais", 26u128.pow(wheels), format!("26^{}", wheels));
+
+    if options.include_rings {
+        let rings = if options.effective_rings_only { spec.rotor_slots - 1 } else { wheels };
+        push("Anéis", 26u128.pow(rings), format!("26^{}", rings));
+    }
+
+    match options.plugboard_pairs {
+        Some(pairs) => push("Plugboard", plugboard_settings(pairs), format!("{} pares: 26! / ((26-2n)! n! 2^n)", pairs)),
+        None => push("Plugboard", (0..=13).map(plugboard_settings).sum(), "soma de 0 a 13 pares".to_string()),
+    }
+
+    let total: u128 = factors.iter().map(|(_, count, _)| count).product();
+    Keyspace {
+        model,
+        total: total.to_string(),
+        bits: (total as f64).log2(),
+        factors: factors
+            .into_iter()
+            .map(|(name, count, formula)| KeyspaceFactor { name, count: count.to_string(), formula })
+            .collect(),
+    }
+}
+
+/// Um termo de um fator, com o valor (ex.: "26!" e "403291461126605635584000000").
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct KeyspaceTerm {
+    pub label: String,
+    pub value: String,
+}
+
+/// Um passo da construção do número de chaves de uma configuração.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct KeyspaceStep {
+    pub name: String,
+    pub formula: String,
+    /// Números que entram na fórmula, na ordem em que ela os usa.
+    pub terms: Vec<KeyspaceTerm>,
+    pub count: String,
+    /// Produto dos fatores até este, inclusive.
+    pub running_total: String,
+    pub running_bits: f64,
+}
+
+/// O espaço de chaves de uma máquina como a de `config`, montado fator a fator.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct KeyspaceExplanation {
+    pub steps: Vec<KeyspaceStep>,
+    pub total: String,
+    pub bits: f64,
+}
+
+fn term(label: impl Into<String>, value: u128) -> KeyspaceTerm {
+    KeyspaceTerm { label: label.into(), value: value.to_string() }
+}
+
+fn factorial(n: u128) -> u128 {
+    (1..=n).product()
+}
+
+/// Quantas chaves tem uma Enigma como a de `config` (o refletor dela, a caixa de
+/// cinco rotores de 1938 e o mesmo número de cabos), fator a fator, com os produtos
+/// parciais para a UI animar a conta. Os anéis vêm por último: sem eles, com 10
+/// cabos, o produto é o famoso 158.962.555.217.826.360.000. Só entram os anéis que
+/// mudam o texto: o do rotor da esquerda nunca aciona outro rotor e equivale a
+/// girar a posição.
+pub fn explain_keyspace(config: &EnigmaConfig) -> Result<KeyspaceExplanation, String> {
+    let config = config.clone().normalized()?;
+    let letters = config.plugboard_pairs.chars().filter(|c| c.is_ascii_uppercase()).count();
+    let pairs = (letters / 2) as u128;
+    if 2 * pairs as usize != letters || pairs > 13 {
+        return Err(format!("Plugboard inválido: {}", config.plugboard_pairs));
+    }
+
+    let pool = KNOWN_ROTOR_NAMES.len() as u128;
+    let (free, pairs_factorial, swaps) = (factorial(26 - 2 * pairs), factorial(pairs), 2u128.pow(pairs as u32));
+    let factors = [
+        (
+            "Ordem dos rotores",
+            format!("{} × {} × {}: três rotores diferentes entre {}", pool, pool - 1, pool - 2, pool),
+            vec![term("esquerda", pool), term("meio", pool - 1), term("direita", pool - 2)],
+            pool * (pool - 1) * (pool - 2),
+        ),
+        (
+            "Posições iniciais",
+            "26³: uma letra na janela de cada rotor".to_string(),
+            vec![term("esquerda", 26), term("meio", 26), term("direita", 26)],
+            26u128.pow(3),
+        ),
+        (
+            "Plugboard",
+            format!("{} pares: 26! / ((26 − 2n)! · n! · 2ⁿ)", pairs),
+            vec![term("26!", factorial(26)), term("(26 − 2n)!", free), term("n!", pairs_factorial), term("2ⁿ", swaps)],
+            plugboard_settings(pairs as usize),
+        ),
+        (
+            "Anéis",
+            "26²: só os anéis do meio e da direita mudam o texto".to_string(),
+            vec![term("meio", 26), term("direita", 26)],
+            26u128.pow(2),
+        ),
+    ];
+
+    let mut running = 1u128;
+    let steps: Vec<KeyspaceStep> = factors
+        .into_iter()
+        .map(|(name, formula, terms, count)| {
+            running *= count;
+            KeyspaceStep {
+                name: name.to_string(),
+                formula,
+                terms,
+                count: count.to_string(),
+                running_total: running.to_string(),
+                running_bits: (running as f64).log2(),
+            }
+        })
+        .collect();
+    Ok(KeyspaceExplanation { steps, total: running.to_string(), bits: (running as f64).log2() })
+}
+
+/// Entropia máxima de uma letra do alfabeto de 26 letras (log₂ 26 ≈ 4,70 bits).
+pub fn max_letter_entropy() -> f64 {
+    26f64.log2()
+}
+
+/// Taxa de informação usual da língua, em bits por letra (estimativas clássicas a
+/// partir de Shannon; a redundância é log₂ 26 menos a taxa).
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Language {
+    English,
+    German,
+    /// Taxa informada pelo usuário, em bits por letra.
+    Custom { rate: f64 },
+}
+
+impl Language {
+    pub fn rate(self) -> f64 {
+        match self {
+            Language::English => 1.5,
+            Language::German => 1.3,
+            Language::Custom { rate } => rate,
+        }
+    }
+}
+
+/// Entropia de primeira ordem de um texto (letras 0-25), em bits por letra. Serve
+/// como limite superior da taxa real, que também considera dependências entre letras.
+pub fn letter_entropy(text: &[u8]) -> f64 {
+    let n = text.len() as f64;
+    letter_counts(text)
+        .iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / n;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Entropia da chave e distância de unicidade.
+#[derive(Debug, Serialize, Clone)]
+pub struct UnicityReport {
+    /// Entropia da chave, supondo todas as chaves igualmente prováveis (bits).
+    pub key_entropy: f64,
+    /// Taxa da língua usada (bits por letra).
+    pub language_rate: f64,
+    /// Redundância por letra: log₂ 26 menos a taxa.
+    pub redundancy: f64,
+    /// Letras de texto cifrado a partir das quais a chave fica, em teoria, determinada.
+    pub unicity_distance: f64,
+}
+
+/// Calcula a distância de unicidade para uma entropia de chave em bits.
+pub fn unicity(key_entropy: f64, language: Language) -> Result<UnicityReport, String> {
+    let language_rate = language.rate();
+    let redundancy = max_letter_entropy() - language_rate;
+    if !(language_rate >= 0.0 && redundancy > 0.0) {
+        return Err(format!("Taxa da língua inválida: {} bits por letra.", language_rate));
+    }
+    Ok(UnicityReport { key_entropy, language_rate, redundancy, unicity_distance: key_entropy / redundancy })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plugboard_settings() {
+        assert_eq!(plugboard_settings(0), 1);
+        assert_eq!(plugboard_settings(1), 325);
+        assert_eq!(plugboard_settings(10), 150_738_274_937_250);
+        assert_eq!(plugboard_settings(13), 7_905_853_580_625);
+        assert_eq!(plugboard_settings(14), 0);
+    }
+
+    #[test]
+    fn test_famous_enigma_i_figure() {
+        // 60 ordens × 26³ posições × plugboard com 10 cabos, sem os anéis
+        let options = KeyspaceOptions { include_rings: false, ..Default::default() };
+        let space = keyspace(MachineModel::EnigmaI, &options);
+        assert_eq!(space.total, "158962555217826360000");
+        assert_eq!(space.factors.len(), 4);
+        assert_eq!(space.factors[0].count, "60");
+    }
+
+    #[test]
+    fn test_m4_includes_greek_rotor() {
+        let space = keyspace(MachineModel::M4, &KeyspaceOptions::default());
+        let expected = 336u128 * 2 * 2 * 26u128.pow(4) * 26u128.pow(4) * 150_738_274_937_250;
+        assert_eq!(space.total, expected.to_string());
+        assert!(space.bits > 90.0);
+    }
+
+    #[test]
+    fn test_explain_keyspace_builds_the_total() {
+        let config = EnigmaConfig::from_share_code("ENIGMA:II.IV.V:B:BLA:BUL:AVBSCGDLFUHZINKMOWRX").unwrap();
+        let explanation = explain_keyspace(&config).unwrap();
+        let counts: Vec<&str> = explanation.steps.iter().map(|step| step.count.as_str()).collect();
+        assert_eq!(counts, ["60", "17576", "150738274937250", "676"]);
+        assert_eq!(explanation.steps[2].running_total, "158962555217826360000");
+        assert_eq!(explanation.steps.last().unwrap().running_total, explanation.total);
+        let expected = 158_962_555_217_826_360_000u128 * 676;
+        assert_eq!(explanation.total, expected.to_string());
+        assert_eq!(explanation.steps[2].terms[1].value, "720");
+
+        let mut odd = config;
+        odd.plugboard_pairs = "AB C".to_string();
+        assert!(explain_keyspace(&odd).is_err());
+    }
+
+    #[test]
+    fn test_unicity_distance() {
+        let space = keyspace(MachineModel::EnigmaI, &KeyspaceOptions { include_rings: false, ..Default::default() });
+        let report = unicity(space.bits, Language::English).unwrap();
+        // ~67,1 bits de chave sobre ~3,2 bits de redundância: cerca de 21 letras
+        assert!((report.unicity_distance - 21.0).abs() < 0.5, "{}", report.unicity_distance);
+        assert!(unicity(space.bits, Language::Custom { rate: 5.0 }).is_err());
+    }
+
+    #[test]
+    fn test_letter_entropy() {
+        assert_eq!(letter_entropy(&[0, 0, 0, 0]), 0.0);
+        let uniform: Vec<u8> = (0..26).collect();
+        assert!((letter_entropy(&uniform) - max_letter_entropy()).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,598 @@
+//! Ataque com texto claro conhecido e chave parcialmente conhecida.
+//!
+//! Com um par texto claro/texto cifrado completo, cada chave candidata pode ser
+//! testada exatamente: ou ela transforma um no outro, ou não. O usuário informa o
+//! que já sabe da chave (ordem dos rotores, refletor, posições, anéis, plugboard) e
+//! só as dimensões desconhecidas são percorridas.
+//!
+//! Com o plugboard desconhecido, cada chave passa por um teste de consistência no
+//! estilo da Bombe: supõe-se o par de uma letra do texto, deduzem-se os pares
+//! implicados por cada posição do par claro/cifrado e descarta-se a suposição ao
+//! surgir uma contradição. Letras que não aparecem no texto ficam sem par.
+//!
+//! O plugboard é simétrico: se S(x) = y, então S(y) = x. A Bombe de Turing não
+//! sabia disso; o quadro diagonal de Welchman passou a usar essa inferência e
+//! eliminou a maior parte das paradas falsas. `diagonal_board` liga ou desliga a
+//! inferência e `compare_diagonal_board` conta as paradas nos dois modos.
+//!
+//! `check_stops` faz o papel da máquina de verificação de Bletchley Park: decifra o
+//! trecho do crib com o plugboard deduzido em cada parada e separa as paradas
+//! verdadeiras, que reproduzem o texto claro, das falsas.
+//!
+//! `walkthrough` repete esse teste numa chave só, em câmera lenta: cada hipótese,
+//! cada par deduzido e cada contradição viram um `BombeStep`, para a UI ensinar
+//! como a Bombe raciocina, e não só mostrar as paradas.
+//!
+//! O anel do rotor esquerdo não muda o texto (ver `rings`), por isso fica em 'A'
+//! quando não é informado. Com textos curtos, chaves que só diferem no momento da
+//! virada de um rotor produzem o mesmo texto e são todas relatadas.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::plugboard::pairs_string;
+use super::stats::letters;
+use super::{key_config, parse_reflector, parse_rotor_order, rotor_orders, RotorOrder};
+use crate::enigma::{u8_to_char, EnigmaConfig, EnigmaMachine, REFLECTOR_NAMES};
+
+/// Limite de chaves testadas com o plugboard conhecido. Com o plugboard desconhecido
+/// o teste de cada chave é bem mais caro e o limite cai para 1/26 disso.
+pub const MAX_KEYS: usize = 20_000_000;
+
+/// Marca de letra ainda sem par na dedução do plugboard.
+const UNKNOWN: u8 = u8::MAX;
+
+/// Partes da chave já conhecidas; `None` indica dimensão a ser buscada.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct KeyConstraints {
+    /// Ordem dos rotores (Direita, Meio, Esquerda).
+    pub rotors: Option<(String, String, String)>,
+    pub reflector: Option<String>,
+    /// Posições iniciais (Esquerda, Meio, Direita).
+    pub positions: (Option<char>, Option<char>, Option<char>),
+    /// Anéis (Esquerda, Meio, Direita).
+    pub rings: (Option<char>, Option<char>, Option<char>),
+    /// Pares do plugboard, se conhecidos (texto vazio = sem plugboard).
+    pub plugboard_pairs: Option<String>,
+    /// Quantas chaves consistentes retornar (todas são contadas).
+    pub max_results: usize,
+    /// Usa a simetria do plugboard na dedução, como o quadro diagonal de Welchman.
+    pub diagonal_board: bool,
+}
+
+impl Default for KeyConstraints {
+    fn default() -> Self {
+        Self {
+            rotors: None,
+            reflector: None,
+            positions: (None, None, None),
+            rings: (None, None, None),
+            plugboard_pairs: None,
+            max_results: 100,
+            diagonal_board: true,
+        }
+    }
+}
+
+/// Resultado da busca com texto claro conhecido.
+#[derive(Debug, Serialize)]
+pub struct KnownPlaintextResult {
+    /// Chaves consistentes com o par, com o plugboard deduzido quando desconhecido.
+    pub keys: Vec<EnigmaConfig>,
+    /// Total de chaves consistentes encontradas (pode passar de `keys.len()`).
+    pub consistent: usize,
+    /// Número de chaves efetivamente testadas.
+    pub keys_tried: usize,
+    /// Indica se a busca foi interrompida antes do fim.
+    pub cancelled: bool,
+}
+
+/// Paradas da Bombe com e sem o quadro diagonal, nas mesmas chaves.
+#[derive(Debug, Serialize)]
+pub struct DiagonalBoardComparison {
+    /// Chaves que resistem à dedução usando a simetria do plugboard.
+    pub with_board: usize,
+    /// Chaves que resistem à dedução sem ela.
+    pub without_board: usize,
+    /// Número de chaves testadas em cada modo.
+    pub keys_tried: usize,
+    /// Indica se a comparação foi interrompida antes do fim.
+    pub cancelled: bool,
+}
+
+/// Veredito da máquina de verificação para uma parada da Bombe.
+#[derive(Debug, Serialize)]
+pub struct StopCheck {
+    /// A chave da parada, com os pares deduzidos pela verificação no plugboard.
+    pub key: EnigmaConfig,
+    /// O texto cifrado do crib decifrado com essa chave.
+    pub decrypted: String,
+    /// Quantas letras decifradas coincidem com o texto claro.
+    pub matching: usize,
+    /// Parada verdadeira: a decifração reproduz todo o texto claro.
+    pub genuine: bool,
+}
+
+/// Um passo do raciocínio da Bombe, na UI com o tipo em `step`.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum BombeStep {
+    /// Nova hipótese: `letter` ligada a `partner` no plugboard. `depth` conta as
+    /// hipóteses ainda em aberto, incluindo esta.
+    Hypothesis { letter: char, partner: char, depth: usize },
+    /// `letter` passa a estar ligada a `partner`.
+    Linked { letter: char, partner: char },
+    /// Na posição `position` do texto, o embaralhador (os rotores naquela posição)
+    /// leva `input` a `output`: o outro lado da posição, `letter`, fica ligado a
+    /// `output`.
+    Deduced { position: usize, input: char, output: char, letter: char, partner: char },
+    /// `letter` já estava ligada a `existing` e precisaria de `needed`.
+    Contradiction { letter: char, existing: char, needed: char },
+    /// A hipótese levou a uma contradição e foi descartada.
+    Rejected { letter: char, partner: char },
+    /// Fim: o plugboard deduzido, ou `None` se nenhuma hipótese se sustentou.
+    Finished { plugboard: Option<String> },
+}
+
+/// Valores possíveis de uma posição ou anel: o conhecido, ou todas as letras.
+fn choices(known: Option<char>, what: &str) -> Result<Vec<char>, String> {
+    match known.map(|c| c.to_ascii_uppercase()) {
+        Some(c) if c.is_ascii_uppercase() => Ok(vec![c]),
+        Some(c) => Err(format!("{} inválido: {}", what, c)),
+        None => Ok((b'A'..=b'Z').map(|b| b as char).collect()),
+    }
+}
+
+/// Busca as chaves que transformam `plaintext` em `ciphertext` respeitando as
+/// restrições. `progress` e `cancel` funcionam como na busca exaustiva.
+pub fn solve(
+    plaintext: &str,
+    ciphertext: &str,
+    constraints: &KeyConstraints,
+    progress: impl Fn(usize, usize) + Sync,
+    cancel: &AtomicBool,
+) -> Result<KnownPlaintextResult, String> {
+    let plain = letters(plaintext);
+    let cipher = letters(ciphertext);
+    if plain.is_empty() || plain.len() != cipher.len() {
+        return Err("Texto claro e texto cifrado devem ter o mesmo número de letras.".to_string());
+    }
+
+    let orders: Vec<RotorOrder> = match &constraints.rotors {
+        Some(rotors) => vec![parse_rotor_order(rotors)?],
+        None => rotor_orders(),
+    };
+    let reflectors: Vec<&'static str> = match &constraints.reflector {
+        Some(reflector) => vec![parse_reflector(reflector)?],
+        None => REFLECTOR_NAMES.to_vec(),
+    };
+    let (left, middle, right) = (
+        choices(constraints.positions.0, "Posição")?,
+        choices(constraints.positions.1, "Posição")?,
+        choices(constraints.positions.2, "Posição")?,
+    );
+    let left_ring = choices(constraints.rings.0.or(Some('A')), "Anel")?[0];
+    let (middle_rings, right_rings) = (
+        choices(constraints.rings.1, "Anel")?,
+        choices(constraints.rings.2, "Anel")?,
+    );
+    let plugboard = constraints.plugboard_pairs.as_deref();
+    let diagonal_board = constraints.diagonal_board;
+
+    let per_block = middle.len() * right.len() * middle_rings.len() * right_rings.len();
+    let blocks: Vec<(RotorOrder, &'static str, char)> = orders
+        .iter()
+        .flat_map(|&order| reflectors.iter().map(move |&reflector| (order, reflector)))
+        .flat_map(|(order, reflector)| left.iter().map(move |&l| (order, reflector, l)))
+        .collect();
+    let total = blocks.len() * per_block;
+    let limit = if plugboard.is_some() { MAX_KEYS } else { MAX_KEYS / 26 };
+    if total > limit {
+        return Err(format!(
+            "Espaço de busca grande demais ({} chaves, limite {}). Informe mais partes da chave.",
+            total, limit
+        ));
+    }
+
+    // A Enigma nunca cifra uma letra nela mesma: nenhuma chave é consistente
+    if plain.iter().zip(&cipher).any(|(p, c)| p == c) {
+        return Ok(KnownPlaintextResult { keys: Vec::new(), consistent: 0, keys_tried: 0, cancelled: false });
+    }
+
+    let max_results = constraints.max_results.max(1);
+    let done = AtomicUsize::new(0);
+    let consistent = AtomicUsize::new(0);
+
+    let mut keys: Vec<EnigmaConfig> = blocks
+        .par_iter()
+        .flat_map(|&(rotors, reflector, l)| {
+            if cancel.load(Ordering::Relaxed) {
+                return Vec::new();
+            }
+            let mut found = Vec::new();
+            for &m in &middle {
+                for &r in &right {
+                    for &middle_ring in &middle_rings {
+                        for &right_ring in &right_rings {
+                            let config = key_config(
+                                rotors,
+                                reflector,
+                                (l, m, r),
+                                (left_ring, middle_ring, right_ring),
+                                plugboard.unwrap_or(""),
+                            );
+                            if let Some(config) = check(config, &plain, &cipher, plugboard.is_some(), diagonal_board) {
+                                consistent.fetch_add(1, Ordering::Relaxed);
+                                if found.len() < max_results {
+                                    found.push(config);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            progress(done.fetch_add(per_block, Ordering::Relaxed) + per_block, total);
+            found
+        })
+        .collect();
+    keys.truncate(max_results);
+
+    Ok(KnownPlaintextResult {
+        keys,
+        consistent: consistent.load(Ordering::Relaxed),
+        keys_tried: done.load(Ordering::Relaxed),
+        cancelled: cancel.load(Ordering::Relaxed),
+    })
+}
+
+/// Conta as paradas da Bombe com e sem o quadro diagonal nas chaves permitidas por
+/// `constraints` (o plugboard informado é ignorado: é ele que a Bombe deduz). O
+/// progresso cobre as duas buscas.
+pub fn compare_diagonal_board(
+    plaintext: &str,
+    ciphertext: &str,
+    constraints: &KeyConstraints,
+    progress: impl Fn(usize, usize) + Sync,
+    cancel: &AtomicBool,
+) -> Result<DiagonalBoardComparison, String> {
+    let mut constraints = KeyConstraints { plugboard_pairs: None, diagonal_board: true, ..constraints.clone() };
+    let with_board = solve(plaintext, ciphertext, &constraints, |done, total| progress(done, 2 * total), cancel)?;
+    constraints.diagonal_board = false;
+    let without_board = solve(
+        plaintext,
+        ciphertext,
+        &constraints,
+        |done, total| progress(total + done, 2 * total),
+        cancel,
+    )?;
+    Ok(DiagonalBoardComparison {
+        with_board: with_board.consistent,
+        without_board: without_board.consistent,
+        keys_tried: with_board.keys_tried,
+        cancelled: with_board.cancelled || without_board.cancelled,
+    })
+}
+
+/// Verifica cada parada: deduz os pares do plugboard na posição dos rotores da
+/// parada, usando a simetria do plugboard (sem dedução possível, ficam os pares que
+/// a parada trouxe), decifra o texto cifrado do crib com eles e compara com o texto
+/// claro, letra a letra.
+pub fn check_stops(plaintext: &str, ciphertext: &str, stops: &[EnigmaConfig]) -> Result<Vec<StopCheck>, String> {
+    let plain = letters(plaintext);
+    let cipher = letters(ciphertext);
+    if plain.is_empty() || plain.len() != cipher.len() {
+        return Err("Texto claro e texto cifrado devem ter o mesmo número de letras.".to_string());
+    }
+    stops
+        .iter()
+        .map(|stop| {
+            let mut key = stop.clone().normalized()?;
+            let mut rotors = EnigmaMachine::new(EnigmaConfig { plugboard_pairs: String::new(), ..key.clone() });
+            let scramblers: Vec<[u8; 26]> = plain
+                .iter()
+                .map(|_| {
+                    rotors.step_rotors();
+                    rotors.permutation()
+                })
+                .collect();
+            if let Some(pairs) = plugboard(&scramblers, &plain, &cipher, true, &mut |_| {}) {
+                key.plugboard_pairs = pairs;
+            }
+            let mut machine = EnigmaMachine::new(key.clone());
+            let decrypted: Vec<u8> = cipher
+                .iter()
+                .map(|&c| {
+                    machine.step_rotors();
+                    machine.scramble(c)
+                })
+                .collect();
+            let matching = decrypted.iter().zip(&plain).filter(|(d, p)| d == p).count();
+            Ok(StopCheck {
+                key,
+                decrypted: decrypted.into_iter().map(u8_to_char).collect(),
+                matching,
+                genuine: matching == plain.len(),
+            })
+        })
+        .collect()
+}
+
+/// Testa uma chave. Com o plugboard conhecido, basta cifrar; senão o plugboard é
+/// deduzido e a chave volta com os pares encontrados.
+fn check(
+    mut config: EnigmaConfig,
+    plain: &[u8],
+    cipher: &[u8],
+    plugboard_known: bool,
+    diagonal_board: bool,
+) -> Option<EnigmaConfig> {
+    let mut machine = EnigmaMachine::new(config.clone());
+    if plugboard_known {
+        let matches = plain.iter().zip(cipher).all(|(&p, &c)| {
+            machine.step_rotors();
+            machine.scramble(p) == c
+        });
+        return matches.then_some(config);
+    }
+
+    // Permutação dos rotores (sem plugboard) em cada posição do texto
+    let scramblers: Vec<[u8; 26]> = plain
+        .iter()
+        .map(|_| {
+            machine.step_rotors();
+            machine.permutation()
+        })
+        .collect();
+    config.plugboard_pairs = plugboard(&scramblers, plain, cipher, diagonal_board, &mut |_| {})?;
+    Some(config)
+}
+
+/// O "menu" da Bombe: o embaralhador de cada posição do texto e o par claro/cifrado.
+struct Menu<'a> {
+    scramblers: &'a [[u8; 26]],
+    plain: &'a [u8],
+    cipher: &'a [u8],
+    /// Usa a simetria do plugboard (o quadro diagonal).
+    diagonal_board: bool,
+}
+
+/// Deduz o plugboard pelos embaralhadores de cada posição do texto, contando cada
+/// passo a `observe`. Letras fora do texto ficam sem par. Sem o quadro diagonal a
+/// dedução pode ligar uma letra a duas outras; só entram os pares que cabem num
+/// plugboard de verdade, na ordem do alfabeto.
+fn plugboard(
+    scramblers: &[[u8; 26]],
+    plain: &[u8],
+    cipher: &[u8],
+    diagonal_board: bool,
+    observe: &mut impl FnMut(BombeStep),
+) -> Option<String> {
+    let menu = Menu { scramblers, plain, cipher, diagonal_board };
+    let mut steckers = [UNKNOWN; 26];
+    if !deduce(&mut steckers, &menu, 1, observe) {
+        return None;
+    }
+    let mut map: [u8; 26] = core::array::from_fn(|x| x as u8);
+    for x in 0..26 {
+        let y = steckers[x];
+        if y != UNKNOWN && map[x] == x as u8 && map[y as usize] == y {
+            map[x] = y;
+            map[y as usize] = x as u8;
+        }
+    }
+    Some(pairs_string(&map))
+}
+
+/// Completa o plugboard por tentativa e erro: escolhe uma letra do texto ainda sem
+/// par, testa os 26 parceiros possíveis e segue recursivamente com os que não geram
+/// contradição.
+fn deduce(steckers: &mut [u8; 26], menu: &Menu, depth: usize, observe: &mut impl FnMut(BombeStep)) -> bool {
+    let Some(&letter) = menu.plain.iter().chain(menu.cipher).find(|&&x| steckers[x as usize] == UNKNOWN) else {
+        return true;
+    };
+    for partner in 0..26 {
+        let (letter_char, partner_char) = (u8_to_char(letter), u8_to_char(partner));
+        observe(BombeStep::Hypothesis { letter: letter_char, partner: partner_char, depth });
+        let mut trial = *steckers;
+        if propagate(&mut trial, letter, partner, menu, observe) && deduce(&mut trial, menu, depth + 1, observe)
+        {
+            *steckers = trial;
+            return true;
+        }
+        observe(BombeStep::Rejected { letter: letter_char, partner: partner_char });
+    }
+    false
+}
+
+/// Liga `a` a `b` e propaga as consequências: se S(x) = y e o texto tem x na
+/// posição i, o outro lado da posição i está ligado a E_i(y). Com o quadro diagonal,
+/// cada ligação x-y também vale no sentido y-x. Retorna `false` se alguma letra
+/// precisar de dois parceiros diferentes.
+fn propagate(
+    steckers: &mut [u8; 26],
+    a: u8,
+    b: u8,
+    menu: &Menu,
+    observe: &mut impl FnMut(BombeStep),
+) -> bool {
+    let Menu { scramblers, plain, cipher, diagonal_board } = *menu;
+    let links = if diagonal_board { 2 } else { 1 };
+    let mut pending = vec![(a, b)];
+    while let Some((x, y)) = pending.pop() {
+        for (from, to) in [(x, y), (y, x)].into_iter().take(links) {
+            match steckers[from as usize] {
+                UNKNOWN => {
+                    steckers[from as usize] = to;
+                    observe(BombeStep::Linked { letter: u8_to_char(from), partner: u8_to_char(to) });
+                    for (i, scrambler) in scramblers.iter().enumerate() {
+                        let other = if plain[i] == from {
+                            cipher[i]
+                        } else if cipher[i] == from {
+                            plain[i]
+                        } else {
+                            continue;
+                        };
+                        let output = scrambler[to as usize];
+                        observe(BombeStep::Deduced {
+                            position: i,
+                            input: u8_to_char(to),
+                            output: u8_to_char(output),
+                            letter: u8_to_char(other),
+                            partner: u8_to_char(output),
+                        });
+                        pending.push((other, output));
+                    }
+                }
+                current if current == to => {}
+                current => {
+                    let (letter, existing, needed) = (u8_to_char(from), u8_to_char(current), u8_to_char(to));
+                    observe(BombeStep::Contradiction { letter, existing, needed });
+                    return false;
+                }
+            }
+        }
+    }
+    true
+}
+
+/// Refaz, passo a passo, a dedução do plugboard de uma chave (os rotores, o
+/// refletor, as posições e os anéis de `config`; o plugboard dela é ignorado) com o
+/// par texto claro/texto cifrado, contando cada passo a `observe`. Retorna o
+/// plugboard deduzido, ou `None` se a chave não é consistente com o par.
+pub fn walkthrough(
+    config: &EnigmaConfig,
+    plaintext: &str,
+    ciphertext: &str,
+    mut observe: impl FnMut(BombeStep),
+) -> Result<Option<String>, String> {
+    let plain = letters(plaintext);
+    let cipher = letters(ciphertext);
+    if plain.is_empty() || plain.len() != cipher.len() {
+        return Err("Texto claro e texto cifrado devem ter o mesmo número de letras.".to_string());
+    }
+    let config = EnigmaConfig { plugboard_pairs: String::new(), ..config.clone().normalized()? };
+    let mut machine = EnigmaMachine::new(config);
+    let scramblers: Vec<[u8; 26]> = plain
+        .iter()
+        .map(|_| {
+            machine.step_rotors();
+            machine.permutation()
+        })
+        .collect();
+    let found = plugboard(&scramblers, &plain, &cipher, true, &mut observe);
+    observe(BombeStep::Finished { plugboard: found.clone() });
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAINTEXT: &str = "WETTERBERICHTFUERDIENORDSEEXWINDAUSWEST";
+
+    fn secret(plugboard: &str) -> EnigmaConfig {
+        key_config(("III", "I", "II"), "B", ('D', 'K', 'M'), ('A', 'C', 'F'), plugboard)
+    }
+
+    fn known(plugboard: Option<&str>) -> KeyConstraints {
+        KeyConstraints {
+            rotors: Some(("III".to_string(), "I".to_string(), "II".to_string())),
+            reflector: Some("B".to_string()),
+            rings: (Some('A'), Some('C'), Some('F')),
+            plugboard_pairs: plugboard.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_finds_positions_with_known_plugboard() {
+        let ciphertext = EnigmaMachine::new(secret("AZ BY")).process_string(PLAINTEXT);
+        let cancel = AtomicBool::new(false);
+        let result = solve(PLAINTEXT, &ciphertext, &known(Some("AZ BY")), |_, _| {}, &cancel).unwrap();
+
+        assert_eq!(result.keys_tried, 26 * 26 * 26);
+        assert_eq!(result.consistent, 1);
+        assert_eq!(result.keys[0], secret("AZ BY"));
+    }
+
+    #[test]
+    fn test_deduces_unknown_plugboard() {
+        let ciphertext = EnigmaMachine::new(secret("AV BS DL EK HZ")).process_string(PLAINTEXT);
+        let cancel = AtomicBool::new(false);
+        let result = solve(PLAINTEXT, &ciphertext, &known(None), |_, _| {}, &cancel).unwrap();
+
+        let found = result.keys.iter().find(|k| k.rotors == secret("").rotors).unwrap();
+        // Todas as letras do texto têm o par deduzido; as demais ficam livres
+        for pair in found.plugboard_pairs.split_whitespace() {
+            assert!(["AV", "BS", "DL", "EK", "HZ"].contains(&pair), "par inesperado {}", pair);
+        }
+        assert!(found.plugboard_pairs.contains("AV") && found.plugboard_pairs.contains("EK"));
+    }
+
+    #[test]
+    fn test_walkthrough_reports_each_deduction() {
+        let ciphertext = EnigmaMachine::new(secret("AV BS DL EK HZ")).process_string(PLAINTEXT);
+        let mut steps = Vec::new();
+        let found = walkthrough(&secret(""), PLAINTEXT, &ciphertext, |step| steps.push(step)).unwrap().unwrap();
+        assert!(found.contains("AV") && found.contains("EK"));
+        assert_eq!(steps.first(), Some(&BombeStep::Hypothesis { letter: 'W', partner: 'A', depth: 1 }));
+        assert_eq!(steps.last(), Some(&BombeStep::Finished { plugboard: Some(found) }));
+        assert!(steps.iter().any(|step| matches!(step, BombeStep::Contradiction { .. })));
+        // Todo par deduzido vem de um embaralhador que leva `input` a `output`
+        assert!(steps.iter().all(|step| match step {
+            BombeStep::Deduced { output, partner, .. } => output == partner,
+            _ => true,
+        }));
+
+        // Uma chave errada esgota as hipóteses
+        let wrong = key_config(("III", "I", "II"), "B", ('D', 'K', 'N'), ('A', 'C', 'F'), "");
+        let mut last = None;
+        assert_eq!(walkthrough(&wrong, PLAINTEXT, &ciphertext, |step| last = Some(step)).unwrap(), None);
+        assert_eq!(last, Some(BombeStep::Finished { plugboard: None }));
+    }
+
+    #[test]
+    fn test_diagonal_board_removes_stops() {
+        let crib = &PLAINTEXT[..12];
+        let ciphertext = EnigmaMachine::new(secret("AV BS DL EK HZ")).process_string(crib);
+        let cancel = AtomicBool::new(false);
+        let comparison = compare_diagonal_board(crib, &ciphertext, &known(None), |_, _| {}, &cancel).unwrap();
+
+        assert_eq!(comparison.keys_tried, 26 * 26 * 26);
+        assert!(comparison.with_board >= 1);
+        assert!(comparison.without_board > comparison.with_board, "{:?}", comparison);
+    }
+
+    #[test]
+    fn test_checking_machine_separates_true_and_false_stops() {
+        let crib = &PLAINTEXT[..12];
+        let ciphertext = EnigmaMachine::new(secret("AV BS DL EK HZ")).process_string(crib);
+        let cancel = AtomicBool::new(false);
+        let constraints = KeyConstraints { diagonal_board: false, max_results: usize::MAX, ..known(None) };
+        let stops = solve(crib, &ciphertext, &constraints, |_, _| {}, &cancel).unwrap().keys;
+        let checks = check_stops(crib, &ciphertext, &stops).unwrap();
+
+        assert_eq!(checks.len(), stops.len());
+        assert!(checks.iter().any(|check| !check.genuine && check.matching < crib.len()));
+        // As paradas verdadeiras decifram o crib inteiro; a chave secreta é uma delas
+        assert!(checks.iter().filter(|check| check.genuine).all(|check| check.decrypted == crib));
+        let secret_positions = secret("").rotors;
+        assert!(checks.iter().any(|check| check.genuine && check.key.rotors == secret_positions));
+
+        assert!(check_stops(crib, "ABC", &stops).is_err());
+    }
+
+    #[test]
+    fn test_rejects_impossible_pairs_and_huge_searches() {
+        let cancel = AtomicBool::new(false);
+        let result = solve("ABC", "AXY", &known(Some("")), |_, _| {}, &cancel).unwrap();
+        assert_eq!(result.consistent, 0);
+
+        let everything = KeyConstraints::default();
+        assert!(solve("ABC", "XYZ", &everything, |_, _| {}, &cancel).is_err());
+        assert!(solve("ABC", "XY", &known(Some("")), |_, _| {}, &cancel).is_err());
+    }
+}
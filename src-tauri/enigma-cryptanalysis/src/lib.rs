@@ -0,0 +1,158 @@
+//! Ferramentas de criptoanálise histórica da Enigma.
+//!
+//! Cada submódulo reproduz uma técnica usada contra a máquina real, operando
+//! sobre o mesmo motor de `crate::enigma` que a UI usa para cifrar. Não depende
+//! do Tauri: o app e o `enigma-cli` usam os mesmos ataques.
+
+// O motor fica no enigma-core; os ataques o chamam de `enigma`, como o app
+use enigma_core as enigma;
+
+pub mod annealing;
+pub mod avalanche;
+pub mod banburismus;
+pub mod bruteforce;
+pub mod checkpoint;
+pub mod cillies;
+pub mod cribs;
+pub mod cyclometer;
+pub mod depth;
+pub mod export;
+pub mod fitness;
+pub mod flattening;
+pub mod herivel;
+pub mod kernel;
+pub mod keyspace;
+pub mod known_plaintext;
+pub mod message_keys;
+pub mod partition;
+pub mod pipeline;
+pub mod plugboard;
+pub mod rejewski;
+pub mod report;
+pub mod rings;
+pub mod self_encryption;
+pub mod stats;
+pub mod zygalski;
+
+use crate::enigma::components::{reflector_name, rotor_name};
+use crate::enigma::{u8_to_char, EnigmaConfig, EnigmaMachine, RotorConfig, ROTOR_NAMES};
+use fitness::{detect_language, LanguageGuess};
+
+/// Quantidade de letras do texto decifrado mostrada nos resultados dos ataques.
+pub const PREVIEW_LEN: usize = 60;
+
+/// Ordem dos rotores na mesma convenção de `EnigmaConfig`: (Direita, Meio, Esquerda).
+pub type RotorOrder = (&'static str, &'static str, &'static str);
+
+/// Enumera todas as ordens possíveis dos rotores disponíveis (sem repetição).
+pub fn rotor_orders() -> Vec<RotorOrder> {
+    rotor_orders_from(&ROTOR_NAMES)
+}
+
+/// Enumera as ordens possíveis com os rotores de `pool` (sem repetição).
+pub fn rotor_orders_from(pool: &[&'static str]) -> Vec<RotorOrder> {
+    let mut orders = Vec::new();
+    for &right in pool {
+        for &middle in pool {
+            for &left in pool {
+                if right != middle && right != left && middle != left {
+                    orders.push((right, middle, left));
+                }
+            }
+        }
+    }
+    orders
+}
+
+/// Converte a ordem de rotores vinda da UI (Direita, Meio, Esquerda) para `RotorOrder`,
+/// validando os nomes e exigindo três rotores distintos.
+pub fn parse_rotor_order(rotors: &(String, String, String)) -> Result<RotorOrder, String> {
+    let order = (parse_rotor(&rotors.0)?, parse_rotor(&rotors.1)?, parse_rotor(&rotors.2)?);
+    if order.0 == order.1 || order.0 == order.2 || order.1 == order.2 {
+        return Err("A ordem dos rotores não pode repetir rotores.".to_string());
+    }
+    Ok(order)
+}
+
+/// Valida o nome de um rotor vindo da UI, retornando o nome canônico.
+pub fn parse_rotor(name: &str) -> Result<&'static str, String> {
+    rotor_name(name).ok_or_else(|| format!("Rotor desconhecido: {}", name))
+}
+
+/// Valida o nome de um refletor vindo da UI, retornando o nome canônico.
+pub fn parse_reflector(name: &str) -> Result<&'static str, String> {
+    reflector_name(name).ok_or_else(|| format!("Refletor desconhecido: {}", name))
+}
+
+/// Converte uma `RotorOrder` nos nomes que a UI envia e recebe.
+pub fn order_names(rotors: RotorOrder) -> (String, String, String) {
+    (rotors.0.to_string(), rotors.1.to_string(), rotors.2.to_string())
+}
+
+/// Enumera as 26³ posições dos rotores, na ordem (Esquerda, Meio, Direita).
+pub fn all_positions() -> impl Iterator<Item = (char, char, char)> {
+    let letters = || (b'A'..=b'Z').map(|b| b as char);
+    letters().flat_map(move |l| letters().flat_map(move |m| letters().map(move |r| (l, m, r))))
+}
+
+/// Monta uma máquina sem plugboard e com anéis em 'A', como nos catálogos históricos.
+/// `positions` segue a ordem (Esquerda, Meio, Direita), igual a `get_positions`.
+pub fn machine_at(rotors: RotorOrder, reflector: &str, positions: (char, char, char)) -> EnigmaMachine {
+    EnigmaMachine::new(key_config(rotors, reflector, positions, ('A', 'A', 'A'), ""))
+}
+
+/// Monta a configuração completa de uma chave. `positions` e `rings` seguem a
+/// ordem (Esquerda, Meio, Direita); a tupla de rotores segue `EnigmaConfig`.
+pub fn key_config(
+    rotors: RotorOrder,
+    reflector: &str,
+    positions: (char, char, char),
+    rings: (char, char, char),
+    plugboard_pairs: &str,
+) -> EnigmaConfig {
+    let rotor = |name: &str, position: char, ring: char| RotorConfig {
+        name: name.to_string(),
+        position,
+        ring,
+    };
+    EnigmaConfig {
+        rotors: (
+            rotor(rotors.0, positions.2, rings.2),
+            rotor(rotors.1, positions.1, rings.1),
+            rotor(rotors.2, positions.0, rings.0),
+        ),
+        reflector: reflector.to_string(),
+        plugboard_pairs: plugboard_pairs.to_string(),
+    }
+}
+
+/// Valida uma configuração vinda da UI (nomes, posições, anéis e plugboard, por
+/// `EnigmaConfig::normalized`, e sem rotores repetidos) e a devolve normalizada.
+/// `EnigmaMachine::new` entra em pânico com nomes desconhecidos e ignora pares em
+/// minúsculas, então os ataques validam e usam a configuração devolvida.
+pub fn validate_config(config: &EnigmaConfig) -> Result<EnigmaConfig, String> {
+    let config = config.clone().normalized()?;
+    let (r, m, l) = &config.rotors;
+    parse_rotor_order(&(r.name.clone(), m.name.clone(), l.name.clone()))?;
+    Ok(config)
+}
+
+/// Decifra letras 0-25 a partir do estado atual da máquina.
+pub fn decrypt(machine: &mut EnigmaMachine, text: &[u8]) -> Vec<u8> {
+    let mut plain = text.to_vec();
+    machine.process_bytes(&mut plain);
+    plain
+}
+
+/// Língua mais provável da decifração a partir do estado atual da máquina.
+pub fn language_of(machine: &mut EnigmaMachine, text: &[u8]) -> Option<LanguageGuess> {
+    detect_language(&decrypt(machine, text))
+}
+
+/// Início da decifração a partir do estado atual da máquina, como texto.
+pub fn preview(machine: &mut EnigmaMachine, text: &[u8]) -> String {
+    decrypt(machine, &text[..text.len().min(PREVIEW_LEN)])
+        .into_iter()
+        .map(u8_to_char)
+        .collect()
+}
@@ -0,0 +1,163 @@
+//! Ataque de dicionário às chaves de mensagem.
+//!
+//! Com a chave do dia conhecida (ordem, anéis, plugboard), falta só a chave da
+//! mensagem: a posição inicial escolhida pelo operador. Em vez de testar as 26³
+//! posições, testam-se primeiro as escolhas preguiçosas que Bletchley Park
+//! explorava: letras repetidas, sequências do teclado e do alfabeto (os mesmos
+//! padrões de `cillies`), nomes e palavras curtas, e chaves sugeridas pelo usuário.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use super::bruteforce::score;
+use super::cillies::{pattern_of, CilliePattern};
+use super::fitness::{FitnessScorer, LanguageGuess};
+use super::stats::letters;
+use super::{all_positions, language_of, preview, validate_config};
+use crate::enigma::{EnigmaConfig, EnigmaMachine};
+
+/// Nomes e palavras de três letras comuns como chave (namoradas, iniciais, saudações).
+pub const COMMON_KEYS: [&str; 16] = [
+    "CIL", "EVA", "ROS", "ANN", "MAR", "LIS", "KAT", "INA", "UTE", "OTT", "HEI", "BER", "LIN", "DIE", "UND", "ICH",
+];
+
+/// Por que uma chave entrou no dicionário.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySource {
+    /// Sugerida pelo usuário.
+    User,
+    /// Nome ou palavra comum.
+    CommonWord,
+    /// Segue um padrão de descuido.
+    Pattern(Vec<CilliePattern>),
+}
+
+/// Uma chave de mensagem testada.
+#[derive(Debug, Serialize, Clone)]
+pub struct MessageKeyCandidate {
+    /// Chave da mensagem (Esquerda, Meio, Direita).
+    pub message_key: String,
+    pub source: KeySource,
+    /// Chave do dia com as posições da chave da mensagem.
+    pub config: EnigmaConfig,
+    pub score: f64,
+    pub preview: String,
+    pub language: Option<LanguageGuess>,
+}
+
+/// Resultado do ataque de dicionário.
+#[derive(Debug, Serialize)]
+pub struct MessageKeyResult {
+    /// Melhores chaves, da maior para a menor pontuação.
+    pub candidates: Vec<MessageKeyCandidate>,
+    /// Tamanho do dicionário testado.
+    pub keys_tried: usize,
+}
+
+/// Monta o dicionário: chaves do usuário, palavras comuns e chaves com padrão, sem
+/// repetições e nessa ordem.
+pub fn predictable_keys(extra: &[String]) -> Result<Vec<(String, KeySource)>, String> {
+    let mut keys = Vec::new();
+    for key in extra {
+        let key = key.trim().to_ascii_uppercase();
+        if key.len() != 3 || !key.bytes().all(|b| b.is_ascii_uppercase()) {
+            return Err(format!("Chave de mensagem inválida: {}", key));
+        }
+        keys.push((key, KeySource::User));
+    }
+    keys.extend(COMMON_KEYS.iter().map(|key| (key.to_string(), KeySource::CommonWord)));
+    for (l, m, r) in all_positions() {
+        let key: String = [l, m, r].iter().collect();
+        let patterns = pattern_of(&key);
+        if !patterns.is_empty() {
+            keys.push((key, KeySource::Pattern(patterns)));
+        }
+    }
+
+    let mut seen = HashSet::new();
+    keys.retain(|(key, _)| seen.insert(key.clone()));
+    Ok(keys)
+}
+
+/// Testa as chaves previsíveis com a chave do dia `config` (as posições dela são
+/// ignoradas) e retorna as `top_n` mais bem pontuadas.
+pub fn attack_message_keys(
+    ciphertext: &str,
+    scorer: &dyn FitnessScorer,
+    config: &EnigmaConfig,
+    extra_keys: &[String],
+    top_n: usize,
+) -> Result<MessageKeyResult, String> {
+    let config = &validate_config(config)?;
+    let text = letters(ciphertext);
+    if text.is_empty() {
+        return Err("O texto cifrado não contém letras.".to_string());
+    }
+
+    let keys = predictable_keys(extra_keys)?;
+    let keys_tried = keys.len();
+    let mut candidates: Vec<MessageKeyCandidate> = keys
+        .into_iter()
+        .map(|(message_key, source)| {
+            let positions: Vec<char> = message_key.chars().collect();
+            let mut keyed = config.clone();
+            keyed.rotors.2.position = positions[0];
+            keyed.rotors.1.position = positions[1];
+            keyed.rotors.0.position = positions[2];
+            let score = score(&mut EnigmaMachine::new(keyed.clone()), &text, scorer);
+            MessageKeyCandidate { message_key, source, config: keyed, score, preview: String::new(), language: None }
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    candidates.truncate(top_n.max(1));
+
+    for candidate in &mut candidates {
+        candidate.preview = preview(&mut EnigmaMachine::new(candidate.config.clone()), &text);
+        candidate.language = language_of(&mut EnigmaMachine::new(candidate.config.clone()), &text);
+    }
+    Ok(MessageKeyResult { candidates, keys_tried })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fitness::CribScorer;
+    use crate::key_config;
+
+    const PLAINTEXT: &str = "WETTERBERICHTFUERDIENORDSEEXWINDAUSWESTMITSTAERKEVIER";
+
+    fn day_config() -> EnigmaConfig {
+        key_config(("II", "III", "I"), "B", ('A', 'A', 'A'), ('K', 'D', 'P'), "AK BM CQ EZ FX")
+    }
+
+    #[test]
+    fn test_dictionary_contents() {
+        let keys = predictable_keys(&["xyq".to_string()]).unwrap();
+        assert_eq!(keys[0], ("XYQ".to_string(), KeySource::User));
+        let find = |key: &str| keys.iter().find(|(k, _)| k == key).map(|(_, source)| source.clone());
+        assert_eq!(find("AAA"), Some(KeySource::Pattern(vec![CilliePattern::RepeatedLetters])));
+        assert_eq!(find("EVA"), Some(KeySource::CommonWord));
+        assert!(find("QAP").is_some());
+        assert!(find("QMZ").is_none());
+        assert!(predictable_keys(&["AB".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_finds_lazy_key() {
+        let mut secret = day_config();
+        secret.rotors.2.position = 'W';
+        secret.rotors.1.position = 'S';
+        secret.rotors.0.position = 'Y';
+        let ciphertext = EnigmaMachine::new(secret.clone()).process_string(PLAINTEXT);
+
+        let crib = CribScorer::new("WETTERBERICHT").unwrap();
+        let result = attack_message_keys(&ciphertext, &crib, &day_config(), &[], 3).unwrap();
+        assert_eq!(result.keys_tried, predictable_keys(&[]).unwrap().len());
+        let candidates = result.candidates;
+        assert_eq!(candidates[0].message_key, "WSY");
+        assert_eq!(candidates[0].config, secret);
+        assert!(candidates[0].preview.starts_with("WETTERBERICHT"));
+    }
+}
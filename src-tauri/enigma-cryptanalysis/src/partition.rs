@@ -0,0 +1,212 @@
+//! Divisão da busca exaustiva entre várias máquinas.
+//!
+//! Numa sala de aula, o professor divide a busca em pedaços (`partition_search`) e
+//! distribui os arquivos; cada aluno roda o seu (`run_chunk`) e devolve o
+//! resultado, que o professor junta (`merge_results`). Cada pedaço é uma faixa dos
+//! blocos da busca completa (`SearchSpace::block_range`), então os pedaços não se
+//! sobrepõem e, juntos, cobrem a busca inteira.
+
+use std::sync::atomic::AtomicBool;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::bruteforce::{attack_bruteforce, BruteforceCandidate, SearchSpace};
+use super::fitness::FitnessScorer;
+use super::report::{AttackReport, SearchStats};
+
+/// Um pedaço da busca, com tudo o que a máquina do aluno precisa para rodá-lo.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SearchChunk {
+    /// Identificador da busca dividida, igual em todos os pedaços.
+    pub job: String,
+    pub index: usize,
+    /// Total de pedaços da busca.
+    pub chunks: usize,
+    pub ciphertext: String,
+    /// Crib ou nome do pontuador, como no ataque exaustivo.
+    pub crib: Option<String>,
+    pub scorer: Option<String>,
+    /// Restrições da busca, com `block_range` limitado a este pedaço.
+    pub search_space: SearchSpace,
+    /// Chaves neste pedaço.
+    pub keys: usize,
+}
+
+/// Resultado de um pedaço, devolvido ao professor.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ChunkResult {
+    pub job: String,
+    pub index: usize,
+    pub chunks: usize,
+    pub candidates: Vec<BruteforceCandidate>,
+    pub keys_tried: usize,
+    pub elapsed_ms: u64,
+    /// Indica se o pedaço foi interrompido antes do fim.
+    pub cancelled: bool,
+}
+
+/// Resultados juntados.
+#[derive(Debug, Serialize, Clone)]
+pub struct MergedResult {
+    pub job: String,
+    pub chunks: usize,
+    /// Pedaços sem resultado completo (não recebidos ou interrompidos).
+    pub missing: Vec<usize>,
+    /// Melhores candidatos de todos os pedaços. O tempo é a soma dos tempos das
+    /// máquinas, e a busca conta como interrompida enquanto faltarem pedaços.
+    pub report: AttackReport,
+}
+
+/// Divide a busca em até `n_chunks` pedaços de tamanhos parecidos (nunca mais
+/// pedaços que blocos). Se `search_space` já for um pedaço, divide só ele.
+pub fn partition_search(
+    ciphertext: &str,
+    crib: Option<String>,
+    scorer: Option<String>,
+    search_space: &SearchSpace,
+    n_chunks: usize,
+) -> Result<Vec<SearchChunk>, String> {
+    let blocks = search_space.blocks()?;
+    if blocks.is_empty() {
+        return Err("A busca não tem chaves para dividir.".to_string());
+    }
+    let chunks = n_chunks.clamp(1, blocks.len());
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos());
+    let job = format!("job-{:x}", nanos);
+
+    (0..chunks)
+        .map(|index| {
+            let start = blocks.start + index * blocks.len() / chunks;
+            let end = blocks.start + (index + 1) * blocks.len() / chunks;
+            let search_space = SearchSpace { block_range: Some((start, end)), ..search_space.clone() };
+            Ok(SearchChunk {
+                job: job.clone(),
+                index,
+                chunks,
+                ciphertext: ciphertext.to_string(),
+                crib: crib.clone(),
+                scorer: scorer.clone(),
+                keys: search_space.size()?,
+                search_space,
+            })
+        })
+        .collect()
+}
+
+/// Roda um pedaço com o pontuador indicado nele (resolvido por quem chama).
+pub fn run_chunk(
+    chunk: &SearchChunk,
+    scorer: &dyn FitnessScorer,
+    progress: impl Fn(usize, usize) + Sync,
+    cancel: &AtomicBool,
+) -> Result<ChunkResult, String> {
+    let started = Instant::now();
+    let result = attack_bruteforce(&chunk.ciphertext, scorer, &chunk.search_space, progress, cancel)?;
+    Ok(ChunkResult {
+        job: chunk.job.clone(),
+        index: chunk.index,
+        chunks: chunk.chunks,
+        candidates: result.candidates,
+        keys_tried: result.keys_tried,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        cancelled: result.cancelled,
+    })
+}
+
+/// Junta os resultados dos pedaços de uma mesma busca. Resultados repetidos de um
+/// pedaço são ignorados (fica o primeiro completo).
+pub fn merge_results(results: &[ChunkResult]) -> Result<MergedResult, String> {
+    let first = results.first().ok_or("Nenhum resultado para juntar.")?;
+    if results.iter().any(|r| r.job != first.job || r.chunks != first.chunks) {
+        return Err("Os resultados são de buscas diferentes.".to_string());
+    }
+    if let Some(r) = results.iter().find(|r| r.index >= r.chunks) {
+        return Err(format!("Pedaço inválido: {} de {}.", r.index, r.chunks));
+    }
+
+    let mut chosen: Vec<Option<&ChunkResult>> = vec![None; first.chunks];
+    for result in results {
+        let slot = &mut chosen[result.index];
+        if slot.is_none_or(|current| current.cancelled && !result.cancelled) {
+            *slot = Some(result);
+        }
+    }
+
+    let missing = (0..first.chunks).filter(|&i| chosen[i].is_none_or(|r| r.cancelled)).collect::<Vec<_>>();
+    let received: Vec<&ChunkResult> = chosen.into_iter().flatten().collect();
+    let top_n = received.iter().map(|r| r.candidates.len()).max().unwrap_or(0);
+    let mut candidates: Vec<BruteforceCandidate> = received.iter().flat_map(|r| r.candidates.clone()).collect();
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    candidates.truncate(top_n);
+
+    let stats = SearchStats {
+        keys_tried: received.iter().map(|r| r.keys_tried).sum(),
+        elapsed_ms: received.iter().map(|r| r.elapsed_ms).sum(),
+        cancelled: !missing.is_empty(),
+    };
+    Ok(MergedResult {
+        job: first.job.clone(),
+        chunks: first.chunks,
+        missing,
+        report: AttackReport::new(candidates.into_iter().map(Into::into), stats),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fitness::CribScorer;
+    use crate::key_config;
+    use crate::enigma::EnigmaMachine;
+
+    const PLAINTEXT: &str = "WETTERBERICHTFUERDIENORDSEEXWINDAUSWESTMITSTAERKEVIER";
+
+    fn space() -> SearchSpace {
+        SearchSpace { reflectors: vec!["B".to_string()], top_n: 3, ..Default::default() }
+    }
+
+    #[test]
+    fn test_chunks_cover_search() {
+        let chunks = partition_search("ABC", None, None, &space(), 7).unwrap();
+        assert_eq!(chunks.len(), 7);
+        assert_eq!(chunks.iter().map(|c| c.keys).sum::<usize>(), space().size().unwrap());
+        assert_eq!(chunks[0].search_space.block_range, Some((0, 22)));
+        for pair in chunks.windows(2) {
+            assert_eq!(pair[0].search_space.block_range.unwrap().1, pair[1].search_space.block_range.unwrap().0);
+        }
+        assert_eq!(chunks[6].search_space.block_range.unwrap().1, 6 * 26);
+        assert_eq!(partition_search("ABC", None, None, &space(), 1000).unwrap().len(), 6 * 26);
+    }
+
+    #[test]
+    fn test_merged_chunks_find_key() {
+        let secret = key_config(("II", "I", "III"), "B", ('X', 'K', 'E'), ('A', 'A', 'A'), "");
+        let ciphertext = EnigmaMachine::new(secret.clone()).process_string(PLAINTEXT);
+        let crib = CribScorer::new("WETTERBERICHT").unwrap();
+        let chunks = partition_search(&ciphertext, Some("WETTERBERICHT".to_string()), None, &space(), 4).unwrap();
+
+        let cancel = AtomicBool::new(false);
+        let mut results: Vec<ChunkResult> =
+            chunks.iter().map(|chunk| run_chunk(chunk, &crib, |_, _| {}, &cancel).unwrap()).collect();
+        // Um aluno entrega duas vezes e outro não entrega
+        results.push(results[1].clone());
+        let last = results.remove(3);
+
+        let partial = merge_results(&results).unwrap();
+        assert_eq!(partial.missing, vec![3]);
+        assert!(partial.report.stats.cancelled);
+
+        results.push(last);
+        let merged = merge_results(&results).unwrap();
+        assert!(merged.missing.is_empty());
+        assert_eq!(merged.report.stats.keys_tried, space().size().unwrap());
+        assert_eq!(merged.report.candidates.len(), 3);
+        assert_eq!(merged.report.candidates[0].config, secret);
+
+        let mut other = results[0].clone();
+        other.job = "job-outro".to_string();
+        results.push(other);
+        assert!(merge_results(&results).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+//! Ataque completo só com o texto cifrado, no estilo de Gillogly (1995).
+//!
+//! Encadeia as etapas já existentes: busca exaustiva de rotores e posições pelo
+//! índice de coincidência, refinamento dos anéis e recuperação do plugboard, com um
+//! último ajuste dos anéis já com o plugboard encontrado. Cada etapa trabalha sobre
+//! os melhores candidatos da anterior, e o melhor candidato de cada etapa é
+//! repassado à UI assim que fica pronto.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use super::bruteforce::{attack_bruteforce, SearchSpace};
+use super::fitness::{FitnessScorer, IndexOfCoincidence};
+use super::plugboard::{recover_plugboard, Optimizer, PlugboardSolution};
+use super::rings::refine_rings;
+use crate::enigma::EnigmaConfig;
+
+/// Opções do ataque completo, vindas da UI.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct PipelineOptions {
+    /// Restrições da busca (`top_n` é substituído por `candidates`; o limite de pares
+    /// do plugboard vale para a última etapa).
+    pub search_space: SearchSpace,
+    /// Quantos candidatos da busca exaustiva seguem para as etapas seguintes.
+    pub candidates: usize,
+    /// Otimizador da etapa do plugboard.
+    pub optimizer: Optimizer,
+}
+
+impl Default for PipelineOptions {
+    fn default() -> Self {
+        Self {
+            search_space: SearchSpace::default(),
+            candidates: 3,
+            optimizer: Optimizer::default(),
+        }
+    }
+}
+
+/// Etapa do ataque completo.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    RotorSearch,
+    RingRefinement,
+    Plugboard,
+}
+
+/// Melhor candidato ao fim de uma etapa, repassado à UI.
+#[derive(Debug, Serialize, Clone)]
+pub struct PipelineUpdate {
+    pub stage: PipelineStage,
+    pub config: EnigmaConfig,
+    pub score: f64,
+    pub preview: String,
+}
+
+/// Resultado do ataque completo.
+#[derive(Debug, Serialize)]
+pub struct PipelineResult {
+    /// Chaves completas, da melhor para a pior.
+    pub solutions: Vec<PlugboardSolution>,
+    /// Chaves pontuadas somando todas as etapas.
+    pub keys_tried: usize,
+    /// Indica se o ataque foi interrompido antes do fim.
+    pub cancelled: bool,
+}
+
+/// Executa o ataque. A busca de rotores e o refinamento dos anéis usam o índice de
+/// coincidência, que funciona com o plugboard ainda errado; o plugboard é pontuado
+/// com `scorer` (um modelo de n-gramas dá os melhores resultados). `progress` recebe
+/// o andamento da busca exaustiva e `update` o melhor candidato de cada etapa.
+pub fn break_ciphertext(
+    ciphertext: &str,
+    scorer: &dyn FitnessScorer,
+    options: &PipelineOptions,
+    progress: impl Fn(usize, usize) + Sync,
+    update: impl Fn(PipelineUpdate),
+    cancel: &AtomicBool,
+) -> Result<PipelineResult, String> {
+    let space = SearchSpace { top_n: options.candidates.max(1), ..options.search_space.clone() };
+    let search = attack_bruteforce(ciphertext, &IndexOfCoincidence, &space, progress, cancel)?;
+    if let Some(best) = search.candidates.first() {
+        update(PipelineUpdate {
+            stage: PipelineStage::RotorSearch,
+            config: best.config(),
+            score: best.score,
+            preview: best.preview.clone(),
+        });
+    }
+    let mut keys_tried = search.keys_tried;
+    if search.cancelled {
+        return Ok(PipelineResult { solutions: Vec::new(), keys_tried, cancelled: true });
+    }
+
+    let mut refined = Vec::new();
+    for candidate in &search.candidates {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        let key = refine_rings(ciphertext, &IndexOfCoincidence, &candidate.config())?;
+        keys_tried += key.keys_tried;
+        refined.push(key);
+    }
+    refined.sort_by(|a, b| b.score.total_cmp(&a.score));
+    if let Some(best) = refined.first() {
+        update(PipelineUpdate {
+            stage: PipelineStage::RingRefinement,
+            config: best.config.clone(),
+            score: best.score,
+            preview: best.preview.clone(),
+        });
+    }
+
+    let mut solutions: Vec<PlugboardSolution> = Vec::new();
+    for key in &refined {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        let mut solution = recover_plugboard(
+            ciphertext,
+            scorer,
+            &key.config,
+            options.search_space.max_plugboard_pairs,
+            &options.optimizer,
+        )?;
+        // Com o plugboard no lugar, `scorer` distingue anéis que o índice de
+        // coincidência deixou empatados
+        let rings = refine_rings(ciphertext, scorer, &solution.config)?;
+        solution.keys_tried += rings.keys_tried;
+        keys_tried += solution.keys_tried;
+        if rings.score > solution.score {
+            solution.config = rings.config;
+            solution.score = rings.score;
+            solution.preview = rings.preview;
+            solution.language = rings.language;
+        }
+        if solutions.first().is_none_or(|best| solution.score > best.score) {
+            update(PipelineUpdate {
+                stage: PipelineStage::Plugboard,
+                config: solution.config.clone(),
+                score: solution.score,
+                preview: solution.preview.clone(),
+            });
+        }
+        solutions.push(solution);
+        solutions.sort_by(|a, b| b.score.total_cmp(&a.score));
+    }
+
+    Ok(PipelineResult { solutions, keys_tried, cancelled: cancel.load(Ordering::Relaxed) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fitness::NgramScorer;
+    use crate::key_config;
+    use crate::stats::letters;
+    use crate::enigma::{u8_to_char, EnigmaMachine};
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    /// Texto alemão (diferente da mensagem) usado para montar um modelo de bigramas.
+    const CORPUS: &str = "DIEENIGMAWAREINEROTORSCHLUESSELMASCHINEDIEIMZWEITENWELTKRIEGZURVERSCHLUESSELUNGDESNACHRICHTENVERKEHRSDERWEHRMACHTVERWENDETWURDEAUCHANDEREDIENSTSTELLENWIEPOLIZEIGEHEIMDIENSTEDIPLOMATISCHEDIENSTESOWIEREICHSBAHNUNDREICHSPOSTSETZTENSIEZURGEHEIMENKOMMUNIKATIONEINTROTZMANNIGFALTIGERVORDEMKRIEGUNDWAEHRENDDESKRIEGESEINGEFUEHRTERVERBESSERUNGENDERVERSCHLUESSELUNGSQUALITAETGELANGESDENALLIIERTENMITHOHEMPERSONELLENUNDMASCHINELLENAUFWANDDIEDEUTSCHENFUNKSPRUECHENAHEZUKONTINUIERLICHZUENTZIFFERN";
+
+    fn bigram_model() -> NgramScorer {
+        let mut counts: HashMap<String, u64> = HashMap::new();
+        for gram in letters(CORPUS).windows(2) {
+            *counts.entry(gram.iter().map(|&c| u8_to_char(c)).collect()).or_default() += 1;
+        }
+        NgramScorer::from_counts("bigramas-de", 2, counts).unwrap()
+    }
+
+    const PLAINTEXT: &str = "WETTERBERICHTFUERDIENORDSEEXWINDAUSWESTMITSTAERKEVIERBISFUENFXSEEGANGDREIXSICHTGUTXBEWOELKUNGWECHSELNDXAMABENDAUFFRISCHENDERWINDAUSNORDWESTXKEINEBESONDERENEREIGNISSEXDERKOMMANDANTXUBOOTEXNORDATLANTIKXMELDUNGENDEXANGRIFFAUFGELEITZUGERFOLGREICHDURCHGEFUEHRT";
+
+    #[test]
+    fn test_breaks_ciphertext_only() {
+        let secret = key_config(("I", "III", "II"), "B", ('H', 'D', 'B'), ('A', 'A', 'A'), "AV BS CG DL FU");
+        let ciphertext = EnigmaMachine::new(secret.clone()).process_string(PLAINTEXT);
+
+        let options = PipelineOptions {
+            search_space: SearchSpace {
+                rotor_orders: vec![("I".to_string(), "III".to_string(), "II".to_string())],
+                reflectors: vec!["B".to_string()],
+                max_plugboard_pairs: 5,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let stages = RefCell::new(Vec::new());
+        let cancel = AtomicBool::new(false);
+        let result = break_ciphertext(
+            &ciphertext,
+            &bigram_model(),
+            &options,
+            |_, _| {},
+            |update| stages.borrow_mut().push(update.stage),
+            &cancel,
+        )
+        .unwrap();
+
+        assert_eq!(stages.borrow()[..2], [PipelineStage::RotorSearch, PipelineStage::RingRefinement]);
+        assert_eq!(stages.borrow().last(), Some(&PipelineStage::Plugboard));
+        let best = &result.solutions[0];
+        assert_eq!(best.config, secret);
+        assert!(PLAINTEXT.starts_with(&best.preview));
+    }
+}
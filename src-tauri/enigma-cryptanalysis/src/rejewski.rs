@@ -0,0 +1,281 @@
+//! Ataque de Rejewski ao procedimento de indicador duplicado (1932-1938).
+//!
+//! O operador cifrava a chave da mensagem *duas vezes* na posição básica do dia
+//! (Grundstellung). Com indicadores suficientes, as seis letras revelam os produtos
+//! de permutações AD, BE e CF. A estrutura de ciclos desses produtos (a
+//! "característica" do dia) não depende do plugboard, então basta compará-la com
+//! um catálogo de todas as ordens de rotores e posições para recuperar a ordem dos
+//! rotores e a Grundstellung.
+
+use serde::{Deserialize, Serialize};
+
+use super::cyclometer::Catalog;
+use super::{all_positions, machine_at, order_names, parse_reflector, rotor_orders, validate_config, RotorOrder};
+use crate::enigma::{char_to_u8, EnigmaConfig};
+
+/// Permutação das 26 letras: `perm[x]` é a imagem de `x` (0-25).
+pub type Permutation = [u8; 26];
+
+/// Característica de um dia: comprimentos dos ciclos de AD, BE e CF,
+/// em ordem decrescente.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct Characteristic {
+    pub ad: Vec<u8>,
+    pub be: Vec<u8>,
+    pub cf: Vec<u8>,
+}
+
+/// Uma configuração do catálogo compatível com a característica observada.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RejewskiCandidate {
+    /// Ordem dos rotores (Direita, Meio, Esquerda), como em `EnigmaConfig`.
+    pub rotors: (String, String, String),
+    /// Grundstellung recuperada (Esquerda, Meio, Direita), com anéis em 'A'.
+    pub ground_setting: (char, char, char),
+}
+
+impl RejewskiCandidate {
+    pub fn new(rotors: RotorOrder, ground_setting: (char, char, char)) -> Self {
+        Self {
+            rotors: order_names(rotors),
+            ground_setting,
+        }
+    }
+}
+
+/// Resultado completo do ataque, enviado à UI.
+#[derive(Debug, Serialize)]
+pub struct RejewskiReport {
+    /// Característica calculada a partir dos indicadores.
+    pub characteristic: Characteristic,
+    /// Todas as entradas do catálogo com a mesma característica.
+    pub candidates: Vec<RejewskiCandidate>,
+}
+
+/// Cifra a chave de cada mensagem duas vezes na posição básica de `config`,
+/// gerando os indicadores de seis letras de um dia (para demonstrações).
+pub fn encipher_indicators(config: &EnigmaConfig, message_keys: &[String]) -> Result<Vec<String>, String> {
+    let config = &validate_config(config)?;
+    message_keys
+        .iter()
+        .map(|key| {
+            let key = normalize(key);
+            if key.len() != 3 {
+                return Err(format!("Chave de mensagem inválida: {}. Use três letras.", key));
+            }
+            let mut machine = crate::enigma::EnigmaMachine::new(config.clone());
+            Ok(machine.process_string(&key.repeat(2)))
+        })
+        .collect()
+}
+
+/// Reconstrói AD, BE e CF a partir dos indicadores de seis letras de um dia.
+/// Falha se algum indicador for inconsistente ou se faltarem letras para
+/// completar alguma das três permutações.
+pub fn products_from_indicators(indicators: &[String]) -> Result<[Permutation; 3], String> {
+    let mut partial = [[None::<u8>; 26]; 3];
+
+    for indicator in indicators {
+        let letters: Vec<u8> = normalize(indicator).chars().map(char_to_u8).collect();
+        if letters.len() != 6 {
+            return Err(format!("Indicador inválido: {}. Use seis letras.", indicator));
+        }
+        for (i, product) in partial.iter_mut().enumerate() {
+            let (from, to) = (letters[i], letters[i + 3]);
+            match product[from as usize] {
+                Some(existing) if existing != to => {
+                    return Err(format!("Indicador inconsistente com os anteriores: {}", indicator));
+                }
+                _ => product[from as usize] = Some(to),
+            }
+        }
+    }
+
+    let mut products = [[0u8; 26]; 3];
+    for (i, product) in partial.iter().enumerate() {
+        for (x, image) in product.iter().enumerate() {
+            match image {
+                Some(y) => products[i][x] = *y,
+                None => {
+                    return Err(format!(
+                        "Indicadores insuficientes: a permutação {} não tem imagem para '{}'.",
+                        ["AD", "BE", "CF"][i],
+                        (b'A' + x as u8) as char
+                    ));
+                }
+            }
+        }
+    }
+    Ok(products)
+}
+
+/// Comprimentos dos ciclos de uma permutação, em ordem decrescente.
+pub fn cycle_lengths(perm: &Permutation) -> Vec<u8> {
+    let mut visited = [false; 26];
+    let mut lengths = Vec::new();
+    for start in 0..26 {
+        if visited[start] {
+            continue;
+        }
+        let mut len = 0;
+        let mut x = start;
+        while !visited[x] {
+            visited[x] = true;
+            x = perm[x] as usize;
+            len += 1;
+        }
+        lengths.push(len);
+    }
+    lengths.sort_unstable_by(|a, b| b.cmp(a));
+    lengths
+}
+
+/// Calcula a característica de um trio de produtos (AD, BE, CF).
+pub fn characteristic_of(products: &[Permutation; 3]) -> Characteristic {
+    Characteristic {
+        ad: cycle_lengths(&products[0]),
+        be: cycle_lengths(&products[1]),
+        cf: cycle_lengths(&products[2]),
+    }
+}
+
+/// Calcula os produtos AD, BE e CF que a máquina produziria a partir de uma posição
+/// básica, sem plugboard e com anéis em 'A' (uma entrada do catálogo).
+pub fn products_at(rotors: RotorOrder, reflector: &str, ground: (char, char, char)) -> [Permutation; 3] {
+    let mut machine = machine_at(rotors, reflector, ground);
+
+    // Permutações A1..A6 aplicadas nas seis teclas do indicador
+    let mut steps = [[0u8; 26]; 6];
+    for step in steps.iter_mut() {
+        machine.step_rotors();
+        *step = machine.permutation();
+    }
+
+    // Como A1 é uma involução, AD leva A1(x) em A4(x): AD = A4 ∘ A1.
+    let mut products = [[0u8; 26]; 3];
+    for (i, product) in products.iter_mut().enumerate() {
+        for (y, image) in product.iter_mut().enumerate() {
+            *image = steps[i + 3][steps[i][y] as usize];
+        }
+    }
+    products
+}
+
+/// Executa o ataque completo: característica do dia e busca no catálogo.
+pub fn attack(indicators: &[String], reflector: &str) -> Result<RejewskiReport, String> {
+    let reflector = parse_reflector(reflector)?;
+    let characteristic = characteristic_of(&products_from_indicators(indicators)?);
+
+    let mut candidates = Vec::new();
+    for rotors in rotor_orders() {
+        for ground in all_positions() {
+            if characteristic_of(&products_at(rotors, reflector, ground)) == characteristic {
+                candidates.push(RejewskiCandidate::new(rotors, ground));
+            }
+        }
+    }
+
+    Ok(RejewskiReport { characteristic, candidates })
+}
+
+/// Mesmo ataque, mas consultando um catálogo já gerado (ver `cyclometer`)
+/// em vez de percorrer todas as posições.
+pub fn attack_with_catalog(indicators: &[String], catalog: &Catalog) -> Result<RejewskiReport, String> {
+    let characteristic = characteristic_of(&products_from_indicators(indicators)?);
+    let candidates = catalog.lookup(&characteristic).to_vec();
+    Ok(RejewskiReport { characteristic, candidates })
+}
+
+/// Mantém apenas as letras, em maiúsculas.
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enigma::RotorConfig;
+
+    /// Configuração do "dia": ordem II-III-I (direita para esquerda), com plugboard.
+    fn day_config() -> EnigmaConfig {
+        EnigmaConfig {
+            rotors: (
+                RotorConfig { name: "II".to_string(), position: 'K', ring: 'A' },
+                RotorConfig { name: "III".to_string(), position: 'D', ring: 'A' },
+                RotorConfig { name: "I".to_string(), position: 'R', ring: 'A' },
+            ),
+            reflector: "B".to_string(),
+            plugboard_pairs: "AQ BT CM EX".to_string(),
+        }
+    }
+
+    /// Chaves "AAA".."ZZZ" cobrem todas as letras nas seis posições.
+    fn full_keys() -> Vec<String> {
+        (b'A'..=b'Z').map(|b| (b as char).to_string().repeat(3)).collect()
+    }
+
+    #[test]
+    fn test_cycle_lengths() {
+        let mut perm: Permutation = core::array::from_fn(|i| i as u8);
+        perm.swap(0, 1); // ciclo (A B)
+        perm[2] = 3;
+        perm[3] = 4;
+        perm[4] = 2; // ciclo (C D E)
+        let lengths = cycle_lengths(&perm);
+        assert_eq!(&lengths[..2], &[3, 2]);
+        assert_eq!(lengths.iter().map(|&l| l as usize).sum::<usize>(), 26);
+    }
+
+    #[test]
+    fn test_cycles_come_in_pairs() {
+        // Produto de duas involuções sem pontos fixos: cada comprimento aparece um número par de vezes.
+        let products = products_at(("I", "II", "III"), "B", ('C', 'Q', 'M'));
+        for product in products.iter() {
+            let lengths = cycle_lengths(product);
+            for len in lengths.iter() {
+                assert_eq!(lengths.iter().filter(|&l| l == len).count() % 2, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_insufficient_indicators() {
+        let indicators = encipher_indicators(&day_config(), &["ABC".to_string()]).unwrap();
+        assert!(products_from_indicators(&indicators).is_err());
+    }
+
+    #[test]
+    fn test_rejects_invalid_day_config() {
+        let mut config = day_config();
+        config.rotors.1.name = "IX".to_string();
+        assert!(encipher_indicators(&config, &full_keys()).is_err());
+        let mut config = day_config();
+        config.rotors.2.position = '1';
+        assert!(encipher_indicators(&config, &full_keys()).is_err());
+    }
+
+    #[test]
+    fn test_plugboard_does_not_change_characteristic() {
+        let with_plugboard = encipher_indicators(&day_config(), &full_keys()).unwrap();
+        let mut plain = day_config();
+        plain.plugboard_pairs = String::new();
+        let without_plugboard = encipher_indicators(&plain, &full_keys()).unwrap();
+
+        assert_eq!(
+            characteristic_of(&products_from_indicators(&with_plugboard).unwrap()),
+            characteristic_of(&products_from_indicators(&without_plugboard).unwrap()),
+        );
+    }
+
+    #[test]
+    fn test_attack_recovers_order_and_ground_setting() {
+        let indicators = encipher_indicators(&day_config(), &full_keys()).unwrap();
+        let report = attack(&indicators, "B").unwrap();
+
+        let expected = RejewskiCandidate::new(("II", "III", "I"), ('R', 'D', 'K'));
+        assert!(report.candidates.contains(&expected));
+    }
+}
@@ -0,0 +1,186 @@
+//! Relatório padronizado dos ataques.
+//!
+//! Cada ataque tem seu próprio resultado, mas a UI mostra todos do mesmo jeito: uma
+//! lista de `CandidateKey` da melhor para a pior, com as estatísticas da busca.
+//!
+//! Pontuações de pontuadores diferentes não se comparam (índice de coincidência,
+//! letras do crib, log-probabilidade de n-gramas), então a confiança é relativa à
+//! própria lista: as pontuações viram desvios-padrão em torno da média (z) e a
+//! confiança de cada candidato é exp(z) / Σ exp(z). As confianças somam 1; um
+//! candidato que se destaca dos demais fica perto de 1, e candidatos empatados
+//! dividem a confiança igualmente.
+
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use super::bruteforce::BruteforceCandidate;
+use super::fitness::LanguageGuess;
+use super::message_keys::MessageKeyCandidate;
+use super::plugboard::PlugboardSolution;
+use super::rings::RefinedKey;
+use super::stats::letters;
+use super::{language_of, preview};
+use crate::enigma::{EnigmaConfig, EnigmaMachine};
+
+/// Uma chave candidata, no formato comum a todos os ataques.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CandidateKey {
+    /// Configuração pronta para ser usada na máquina.
+    pub config: EnigmaConfig,
+    /// Pontuação dada pelo ataque (maior é melhor).
+    pub score: f64,
+    /// Confiança relativa aos outros candidatos da lista, de 0 a 1.
+    pub confidence: f64,
+    /// Início do texto decifrado com esta chave.
+    pub preview: String,
+    /// Língua mais provável do texto decifrado.
+    pub language: Option<LanguageGuess>,
+}
+
+/// Estatísticas da busca.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct SearchStats {
+    /// Número de chaves pontuadas ou testadas.
+    pub keys_tried: usize,
+    /// Duração do ataque, em milissegundos.
+    pub elapsed_ms: u64,
+    /// Indica se o ataque foi interrompido antes do fim.
+    pub cancelled: bool,
+}
+
+impl SearchStats {
+    /// Estatísticas de um ataque iniciado em `started`.
+    pub fn since(started: Instant, keys_tried: usize, cancelled: bool) -> Self {
+        Self { keys_tried, elapsed_ms: started.elapsed().as_millis() as u64, cancelled }
+    }
+}
+
+/// Resultado de um ataque, como a UI o recebe.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AttackReport {
+    /// Candidatos, da maior para a menor pontuação.
+    pub candidates: Vec<CandidateKey>,
+    pub stats: SearchStats,
+}
+
+impl AttackReport {
+    /// Ordena os candidatos e calcula as confianças.
+    pub fn new(candidates: impl IntoIterator<Item = CandidateKey>, stats: SearchStats) -> Self {
+        let mut candidates: Vec<CandidateKey> = candidates.into_iter().collect();
+        candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+        let scores: Vec<f64> = candidates.iter().map(|c| c.score).collect();
+        for (candidate, confidence) in candidates.iter_mut().zip(confidences(&scores)) {
+            candidate.confidence = confidence;
+        }
+        Self { candidates, stats }
+    }
+}
+
+/// Confiança de cada pontuação em relação às outras (ver a documentação do módulo).
+pub fn confidences(scores: &[f64]) -> Vec<f64> {
+    let n = scores.len() as f64;
+    let mean = scores.iter().sum::<f64>() / n;
+    let deviation = (scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n).sqrt();
+    if !deviation.is_normal() {
+        return vec![1.0 / n; scores.len()];
+    }
+    let weights: Vec<f64> = scores.iter().map(|s| ((s - mean) / deviation).exp()).collect();
+    let total: f64 = weights.iter().sum();
+    weights.iter().map(|w| w / total).collect()
+}
+
+/// Candidatos do ataque com texto claro conhecido. Todas as chaves consistentes
+/// explicam o par igualmente bem, então recebem a mesma pontuação.
+pub fn known_keys(ciphertext: &str, keys: Vec<EnigmaConfig>) -> Vec<CandidateKey> {
+    let text = letters(ciphertext);
+    keys.into_iter()
+        .map(|config| CandidateKey {
+            preview: preview(&mut EnigmaMachine::new(config.clone()), &text),
+            language: language_of(&mut EnigmaMachine::new(config.clone()), &text),
+            config,
+            score: 1.0,
+            confidence: 0.0,
+        })
+        .collect()
+}
+
+impl From<BruteforceCandidate> for CandidateKey {
+    fn from(candidate: BruteforceCandidate) -> Self {
+        Self {
+            config: candidate.config(),
+            score: candidate.score,
+            confidence: 0.0,
+            preview: candidate.preview,
+            language: candidate.language,
+        }
+    }
+}
+
+impl From<RefinedKey> for CandidateKey {
+    fn from(key: RefinedKey) -> Self {
+        Self { config: key.config, score: key.score, confidence: 0.0, preview: key.preview, language: key.language }
+    }
+}
+
+impl From<PlugboardSolution> for CandidateKey {
+    fn from(solution: PlugboardSolution) -> Self {
+        Self {
+            config: solution.config,
+            score: solution.score,
+            confidence: 0.0,
+            preview: solution.preview,
+            language: solution.language,
+        }
+    }
+}
+
+impl From<MessageKeyCandidate> for CandidateKey {
+    fn from(candidate: MessageKeyCandidate) -> Self {
+        Self {
+            config: candidate.config,
+            score: candidate.score,
+            confidence: 0.0,
+            preview: candidate.preview,
+            language: candidate.language,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_config;
+
+    fn candidate(score: f64) -> CandidateKey {
+        CandidateKey {
+            config: key_config(("I", "II", "III"), "B", ('A', 'A', 'A'), ('A', 'A', 'A'), ""),
+            score,
+            confidence: 0.0,
+            preview: String::new(),
+            language: None,
+        }
+    }
+
+    #[test]
+    fn test_confidences() {
+        assert_eq!(confidences(&[3.0]), vec![1.0]);
+        assert_eq!(confidences(&[2.0, 2.0]), vec![0.5, 0.5]);
+
+        let standout = confidences(&[10.0, 1.0, 1.1, 0.9, 1.0]);
+        assert!(standout[0] > 0.6);
+        assert!((standout.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        // A escala da pontuação não muda a confiança
+        let scaled = confidences(&[-100.0, -1000.0, -990.0, -1010.0, -1000.0]);
+        assert!((standout[0] - scaled[0]).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_report_sorts_candidates() {
+        let stats = SearchStats { keys_tried: 3, elapsed_ms: 0, cancelled: false };
+        let report = AttackReport::new(vec![candidate(1.0), candidate(5.0), candidate(2.0)], stats);
+        let scores: Vec<f64> = report.candidates.iter().map(|c| c.score).collect();
+        assert_eq!(scores, vec![5.0, 2.0, 1.0]);
+        assert!(report.candidates[0].confidence > report.candidates[1].confidence);
+    }
+}
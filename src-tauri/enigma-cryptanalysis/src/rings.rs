@@ -0,0 +1,134 @@
+//! Refinamento da Ringstellung.
+//!
+//! A busca exaustiva fixa os anéis em 'A' e encontra apenas uma chave
+//! *equivalente*: o que importa para a fiação é a diferença entre posição e anel,
+//! mas o anel também decide *quando* o rotor vizinho gira. Girando anel e posição
+//! juntos, a fiação fica igual e só o momento da virada muda; a pontuação sobe
+//! quando a virada acontece no lugar certo. Primeiro ajusta-se o rotor da direita,
+//! depois o do meio. O anel do rotor esquerdo não aciona nenhum outro rotor e não
+//! pode ser distinguido pelo texto cifrado, por isso fica em 'A'.
+
+use serde::Serialize;
+
+use super::bruteforce::score;
+use super::fitness::{FitnessScorer, LanguageGuess};
+use super::stats::letters;
+use super::{language_of, preview, validate_config};
+use crate::enigma::{EnigmaConfig, EnigmaMachine, RotorConfig};
+
+/// Chave completa após o refinamento dos anéis.
+#[derive(Debug, Serialize, Clone)]
+pub struct RefinedKey {
+    /// Configuração pronta para ser usada na máquina.
+    pub config: EnigmaConfig,
+    /// Pontuação da chave refinada (mesma escala da busca exaustiva).
+    pub score: f64,
+    /// Início do texto decifrado com a chave refinada.
+    pub preview: String,
+    /// Língua mais provável do texto decifrado.
+    pub language: Option<LanguageGuess>,
+    /// Número de chaves pontuadas.
+    pub keys_tried: usize,
+}
+
+/// Gira anel e posição de um rotor juntos (mantendo a fiação no mesmo lugar).
+fn shift(rotor: &RotorConfig, delta: u8) -> RotorConfig {
+    let add = |c: char| ((c as u8 - b'A' + delta) % 26 + b'A') as char;
+    RotorConfig {
+        name: rotor.name.clone(),
+        position: add(rotor.position),
+        ring: add(rotor.ring),
+    }
+}
+
+/// Pontua uma configuração completa.
+fn score_config(config: &EnigmaConfig, text: &[u8], scorer: &dyn FitnessScorer) -> f64 {
+    score(&mut EnigmaMachine::new(config.clone()), text, scorer)
+}
+
+/// Refina os anéis do rotor da direita e do meio de uma chave encontrada com anéis
+/// em 'A'. Em caso de empate, mantém o menor deslocamento.
+pub fn refine_rings(ciphertext: &str, scorer: &dyn FitnessScorer, config: &EnigmaConfig) -> Result<RefinedKey, String> {
+    let config = &validate_config(config)?;
+    let text = letters(ciphertext);
+    if text.is_empty() {
+        return Err("O texto cifrado não contém letras.".to_string());
+    }
+
+    let mut best = config.clone();
+    let mut best_score = score_config(&best, &text, scorer);
+    let mut keys_tried = 1;
+
+    // 1. Rotor da direita (decide quando o do meio gira)
+    let base = best.clone();
+    for delta in 1..26 {
+        let mut candidate = base.clone();
+        candidate.rotors.0 = shift(&base.rotors.0, delta);
+        let candidate_score = score_config(&candidate, &text, scorer);
+        keys_tried += 1;
+        if candidate_score > best_score {
+            best = candidate;
+            best_score = candidate_score;
+        }
+    }
+
+    // 2. Rotor do meio (decide quando o da esquerda gira)
+    let base = best.clone();
+    for delta in 1..26 {
+        let mut candidate = base.clone();
+        candidate.rotors.1 = shift(&base.rotors.1, delta);
+        let candidate_score = score_config(&candidate, &text, scorer);
+        keys_tried += 1;
+        if candidate_score > best_score {
+            best = candidate;
+            best_score = candidate_score;
+        }
+    }
+
+    let preview = preview(&mut EnigmaMachine::new(best.clone()), &text);
+    let language = language_of(&mut EnigmaMachine::new(best.clone()), &text);
+    Ok(RefinedKey { config: best, score: best_score, preview, language, keys_tried })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fitness::CribScorer;
+
+    const PLAINTEXT: &str = "WETTERBERICHTFUERDIENORDSEEXWINDAUSWESTMITSTAERKEVIER";
+
+    fn true_config() -> EnigmaConfig {
+        EnigmaConfig {
+            rotors: (
+                RotorConfig { name: "I".to_string(), position: 'M', ring: 'H' },
+                RotorConfig { name: "II".to_string(), position: 'C', ring: 'A' },
+                RotorConfig { name: "III".to_string(), position: 'T', ring: 'A' },
+            ),
+            reflector: "B".to_string(),
+            plugboard_pairs: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_shift_keeps_wiring_offset() {
+        let rotor = RotorConfig { name: "I".to_string(), position: 'Y', ring: 'B' };
+        let shifted = shift(&rotor, 3);
+        assert_eq!((shifted.position, shifted.ring), ('B', 'E'));
+    }
+
+    #[test]
+    fn test_recovers_right_ring() {
+        let ciphertext = EnigmaMachine::new(true_config()).process_string(PLAINTEXT);
+
+        // Chave equivalente com anéis em 'A' (como a busca exaustiva encontraria):
+        // o rotor da direita fica na posição 'M' - 'H' = 'F'.
+        let mut equivalent = true_config();
+        equivalent.rotors.0 = RotorConfig { name: "I".to_string(), position: 'F', ring: 'A' };
+        assert_ne!(EnigmaMachine::new(equivalent.clone()).process_string(&ciphertext), PLAINTEXT);
+
+        let refined = refine_rings(&ciphertext, &CribScorer::new("WETTERBERICHT").unwrap(), &equivalent).unwrap();
+        assert_eq!(refined.config, true_config());
+        assert_eq!(refined.keys_tried, 51);
+        assert_eq!(EnigmaMachine::new(refined.config).process_string(&ciphertext), PLAINTEXT);
+    }
+}
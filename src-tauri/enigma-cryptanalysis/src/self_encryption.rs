@@ -0,0 +1,78 @@
+//! Demonstração: a Enigma nunca cifra uma letra nela mesma.
+//!
+//! O refletor devolve o sinal por outro caminho, e por isso nenhuma letra pode
+//! sair igual à que entrou, em nenhuma posição dos rotores. É essa fraqueza que
+//! permite descartar posições de um crib: onde uma letra do crib coincide com a
+//! do texto cifrado, o crib não pode estar ali.
+
+use serde::Serialize;
+
+use super::validate_config;
+use crate::enigma::{EnigmaConfig, EnigmaMachine};
+
+/// Máximo de posições da matriz.
+pub const MAX_STEPS: usize = 500;
+
+/// Matriz 26×N: a saída de cada letra de A a Z em cada uma das N posições.
+#[derive(Debug, Serialize, Clone)]
+pub struct SelfEncryptionDemo {
+    /// Posição dos rotores (Esquerda, Meio, Direita) em cada coluna, já depois do passo.
+    pub positions: Vec<String>,
+    /// `matrix[letra][coluna]`: a letra que sai ao teclar `letra` naquela posição.
+    pub matrix: Vec<Vec<char>>,
+    /// Quantas vezes uma letra saiu igual a si mesma (sempre 0 na Enigma).
+    pub fixed_points: usize,
+    /// Quantas seriam esperadas se cada coluna fosse uma permutação aleatória (1 por coluna).
+    pub expected_random: f64,
+}
+
+/// Cifra cada letra de A a Z nas `steps` primeiras posições a partir de `config`.
+pub fn self_encryption_demo(config: &EnigmaConfig, steps: usize) -> Result<SelfEncryptionDemo, String> {
+    let config = &validate_config(config)?;
+    if steps == 0 || steps > MAX_STEPS {
+        return Err(format!("O número de posições deve estar entre 1 e {}.", MAX_STEPS));
+    }
+
+    let mut machine = EnigmaMachine::new(config.clone());
+    let mut positions = Vec::with_capacity(steps);
+    let mut matrix: Vec<Vec<char>> = (0..26).map(|_| Vec::with_capacity(steps)).collect();
+    for _ in 0..steps {
+        machine.step_rotors();
+        let (l, m, r) = machine.get_positions();
+        positions.push([l, m, r].iter().collect());
+        for (letter, row) in matrix.iter_mut().enumerate() {
+            row.push((b'A' + machine.scramble(letter as u8)) as char);
+        }
+    }
+
+    let fixed_points = matrix
+        .iter()
+        .enumerate()
+        .map(|(letter, row)| row.iter().filter(|&&c| c == (b'A' + letter as u8) as char).count())
+        .sum();
+    Ok(SelfEncryptionDemo { positions, matrix, fixed_points, expected_random: steps as f64 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_config;
+
+    #[test]
+    fn test_no_letter_maps_to_itself() {
+        let config = key_config(("I", "II", "III"), "B", ('A', 'D', 'U'), ('A', 'A', 'A'), "AT BS DE");
+        let demo = self_encryption_demo(&config, 100).unwrap();
+        assert_eq!(demo.matrix.len(), 26);
+        assert!(demo.matrix.iter().all(|row| row.len() == 100));
+        assert_eq!(demo.fixed_points, 0);
+        assert_eq!(demo.positions[0], "ADV");
+
+        // Cada coluna é a mesma cifra que a máquina aplica à tecla naquela posição
+        let typed = EnigmaMachine::new(config.clone()).process_string("QQQ");
+        let column: String = (0..3).map(|i| demo.matrix[16][i]).collect();
+        assert_eq!(column, typed);
+
+        assert!(self_encryption_demo(&config, 0).is_err());
+        assert!(self_encryption_demo(&config, MAX_STEPS + 1).is_err());
+    }
+}
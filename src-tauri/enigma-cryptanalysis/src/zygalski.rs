@@ -0,0 +1,101 @@
+//! Folhas de Zygalski (1938).
+//!
+//! Quando as letras 1 e 4 de um indicador duplicado são iguais, temos uma "fêmea":
+//! o produto AD tem um ponto fixo. Só cerca de 40% das posições dos rotores
+//! produzem fêmeas, e isso não depende do plugboard. Cada folha corresponde a uma
+//! ordem de rotores e a uma posição do rotor esquerdo, com uma grade 26×26
+//! (meio × direita) perfurada nas posições que admitem fêmeas. Empilhando as folhas
+//! de vários indicadores com fêmeas, só as posições corretas deixam passar a luz.
+
+use serde::Serialize;
+
+use super::{machine_at, order_names, RotorOrder};
+
+/// Uma folha de Zygalski, pronta para a UI desenhar.
+#[derive(Debug, Serialize, Clone)]
+pub struct ZygalskiSheet {
+    /// Ordem dos rotores (Direita, Meio, Esquerda), como em `EnigmaConfig`.
+    pub rotors: (String, String, String),
+    /// Posição do rotor esquerdo a que a folha corresponde.
+    pub left_position: char,
+    /// `holes[m][r]` indica se a posição (esquerda, 'A'+m, 'A'+r) admite fêmea entre
+    /// as letras 1 e 4 do indicador. As folhas históricas repetiam a grade (51×51)
+    /// para poderem ser deslocadas; a UI pode repetir esta grade da mesma forma.
+    pub holes: Vec<Vec<bool>>,
+    /// Número de furos na folha.
+    pub hole_count: usize,
+}
+
+/// Indica se a posição básica `ground` (Esquerda, Meio, Direita) admite fêmea nas
+/// letras 1 e 4, isto é, se AD = A4 ∘ A1 tem algum ponto fixo.
+pub fn has_female(rotors: RotorOrder, reflector: &str, ground: (char, char, char)) -> bool {
+    let mut machine = machine_at(rotors, reflector, ground);
+
+    machine.step_rotors();
+    let first = machine.permutation();
+    for _ in 0..3 {
+        machine.step_rotors();
+    }
+
+    machine.permutation().iter().zip(first).any(|(&fourth, first)| fourth == first)
+}
+
+/// Gera a folha de uma ordem de rotores para uma posição do rotor esquerdo.
+pub fn sheet(rotors: RotorOrder, reflector: &str, left_position: char) -> ZygalskiSheet {
+    let holes: Vec<Vec<bool>> = (b'A'..=b'Z')
+        .map(|m| {
+            (b'A'..=b'Z')
+                .map(|r| has_female(rotors, reflector, (left_position, m as char, r as char)))
+                .collect()
+        })
+        .collect();
+    let hole_count = holes.iter().flatten().filter(|&&hole| hole).count();
+
+    ZygalskiSheet {
+        rotors: order_names(rotors),
+        left_position,
+        holes,
+        hole_count,
+    }
+}
+
+/// Gera as 26 folhas de uma ordem de rotores (uma por posição do rotor esquerdo).
+pub fn sheets(rotors: RotorOrder, reflector: &str) -> Vec<ZygalskiSheet> {
+    (b'A'..=b'Z').map(|l| sheet(rotors, reflector, l as char)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rejewski::encipher_indicators;
+    use crate::enigma::{EnigmaConfig, RotorConfig};
+
+    #[test]
+    fn test_sheet_dimensions_and_density() {
+        let sheet = sheet(("I", "II", "III"), "B", 'F');
+        assert_eq!(sheet.holes.len(), 26);
+        assert!(sheet.holes.iter().all(|row| row.len() == 26));
+        // Historicamente, cerca de 40% das posições admitem fêmeas
+        assert!(sheet.hole_count > 676 / 5 && sheet.hole_count < 676 * 3 / 5);
+    }
+
+    #[test]
+    fn test_female_matches_indicators() {
+        for ground in [('A', 'B', 'C'), ('Q', 'E', 'V'), ('Z', 'Z', 'Z'), ('M', 'H', 'D')] {
+            let config = EnigmaConfig {
+                rotors: (
+                    RotorConfig { name: "III".to_string(), position: ground.2, ring: 'A' },
+                    RotorConfig { name: "I".to_string(), position: ground.1, ring: 'A' },
+                    RotorConfig { name: "II".to_string(), position: ground.0, ring: 'A' },
+                ),
+                reflector: "B".to_string(),
+                plugboard_pairs: "AZ BY CX".to_string(),
+            };
+            let keys: Vec<String> = (b'A'..=b'Z').map(|b| (b as char).to_string().repeat(3)).collect();
+            let indicators = encipher_indicators(&config, &keys).unwrap();
+            let female = indicators.iter().any(|i| i.as_bytes()[0] == i.as_bytes()[3]);
+
+            assert_eq!(has_female(("III", "I", "II"), "B", ground), female);
+        }
+    }
+}
@@ -0,0 +1,16 @@
+// Gera o cabeçalho C a partir das funções `extern "C"` de src/lib.rs em OUT_DIR, sem
+// mexer nos fontes. Com ENIGMA_FFI_UPDATE_HEADER=1 também regrava include/enigma.h,
+// o cabeçalho distribuído; o teste `test_header_is_up_to_date` acusa quando ele
+// ficou para trás.
+fn main() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-env-changed=ENIGMA_FFI_UPDATE_HEADER");
+    let bindings = cbindgen::generate(&crate_dir).expect("Erro ao gerar o cabeçalho C");
+    bindings.write_to_file(format!("{}/enigma.h", out_dir));
+    if std::env::var_os("ENIGMA_FFI_UPDATE_HEADER").is_some() {
+        bindings.write_to_file(format!("{}/include/enigma.h", crate_dir));
+    }
+}
@@ -0,0 +1,76 @@
+/* Gerado pelo cbindgen a partir de src/lib.rs (ENIGMA_FFI_UPDATE_HEADER=1 cargo build -p enigma-ffi). Não edite à mão. */
+
+#ifndef ENIGMA_H
+#define ENIGMA_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Máquina Enigma, opaca para o C. Criada por `enigma_new` e liberada por
+ * `enigma_free`.
+ */
+typedef struct EnigmaHandle EnigmaHandle;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Cria uma máquina. `rotors` são os nomes da esquerda para a direita separados
+ * por espaços (ex.: "III II I"); `positions` e `rings` são três letras, também
+ * da esquerda para a direita (ex.: "XKE"); `plugboard` são os pares separados por
+ * espaços (ex.: "AV BS"), ou "" sem plugboard. Maiúsculas e minúsculas valem.
+ *
+ * Retorna nulo se algum ponteiro for nulo ou a configuração for inválida.
+ *
+ * # Safety
+ *
+ * Os ponteiros não nulos devem apontar para strings C terminadas em zero.
+ */
+struct EnigmaHandle *enigma_new(const char *rotors,
+                                const char *reflector,
+                                const char *positions,
+                                const char *rings,
+                                const char *plugboard);
+
+/**
+ * Cifra (ou decifra) `len` bytes de `input` em `output`, que pode ser o próprio
+ * `input`. Letras saem em maiúsculas e giram os rotores; os outros bytes são
+ * copiados sem mudança. Retorna o número de letras cifradas.
+ *
+ * # Safety
+ *
+ * `machine` deve vir de `enigma_new`; `input` e `output` devem ter `len` bytes.
+ */
+uintptr_t enigma_process(struct EnigmaHandle *machine,
+                         const uint8_t *input,
+                         uintptr_t len,
+                         uint8_t *output);
+
+/**
+ * Escreve em `out` as posições atuais dos rotores, da esquerda para a direita,
+ * como uma string C de três letras (4 bytes com o zero final).
+ *
+ * # Safety
+ *
+ * `machine` deve vir de `enigma_new`; `out` deve ter pelo menos 4 bytes.
+ */
+void enigma_positions(const struct EnigmaHandle *machine, char *out);
+
+/**
+ * Libera uma máquina criada por `enigma_new`. Aceita nulo.
+ *
+ * # Safety
+ *
+ * `machine` deve vir de `enigma_new` e não pode ser usado depois.
+ */
+void enigma_free(struct EnigmaHandle *machine);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ENIGMA_H */
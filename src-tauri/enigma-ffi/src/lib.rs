@@ -0,0 +1,194 @@
+//! API em C do `enigma-core`, para embutir a máquina em aplicações nativas (ex.:
+//! quiosques de museu). O cabeçalho `include/enigma.h` é gerado pelo cbindgen e
+//! atualizado com `ENIGMA_FFI_UPDATE_HEADER=1 cargo build -p enigma-ffi`; um
+//! `cargo build` comum só o gera em `OUT_DIR`.
+//!
+//! ```c
+//! EnigmaHandle *machine = enigma_new("III II I", "B", "XKE", "AAA", "AV BS");
+//! uint8_t text[] = "Wetter bericht";
+//! enigma_process(machine, text, sizeof text - 1, text);
+//! enigma_free(machine);
+//! ```
+
+use std::ffi::{c_char, CStr};
+use std::ptr;
+
+use enigma_core::{EnigmaConfig, EnigmaMachine, RotorConfig};
+
+/// Máquina Enigma, opaca para o C. Criada por `enigma_new` e liberada por
+/// `enigma_free`.
+pub struct EnigmaHandle(EnigmaMachine);
+
+/// Lê uma string C; `None` se o ponteiro for nulo ou o texto não for UTF-8.
+unsafe fn string(s: *const c_char) -> Option<String> {
+    if s.is_null() {
+        return None;
+    }
+    CStr::from_ptr(s).to_str().ok().map(str::to_string)
+}
+
+/// Monta a configuração a partir dos textos da API em C.
+fn config(rotors: &str, reflector: &str, positions: &str, rings: &str, plugboard: &str) -> Option<EnigmaConfig> {
+    let names: Vec<&str> = rotors.split_whitespace().collect();
+    let positions: Vec<char> = positions.chars().collect();
+    let rings: Vec<char> = rings.chars().collect();
+    if names.len() != 3 || positions.len() != 3 || rings.len() != 3 {
+        return None;
+    }
+    // A API recebe tudo da esquerda para a direita; `EnigmaConfig` guarda os
+    // rotores da direita para a esquerda
+    let rotor = |i: usize| RotorConfig { name: names[i].to_string(), position: positions[i], ring: rings[i] };
+    EnigmaConfig {
+        rotors: (rotor(2), rotor(1), rotor(0)),
+        reflector: reflector.to_string(),
+        plugboard_pairs: plugboard.to_string(),
+    }
+    .normalized()
+    .ok()
+}
+
+/// Cria uma máquina. `rotors` são os nomes da esquerda para a direita separados
+/// por espaços (ex.: "III II I"); `positions` e `rings` são três letras, também
+/// da esquerda para a direita (ex.: "XKE"); `plugboard` são os pares separados por
+/// espaços (ex.: "AV BS"), ou "" sem plugboard. Maiúsculas e minúsculas valem.
+///
+/// Retorna nulo se algum ponteiro for nulo ou a configuração for inválida.
+///
+/// # Safety
+///
+/// Os ponteiros não nulos devem apontar para strings C terminadas em zero.
+#[no_mangle]
+pub unsafe extern "C" fn enigma_new(
+    rotors: *const c_char,
+    reflector: *const c_char,
+    positions: *const c_char,
+    rings: *const c_char,
+    plugboard: *const c_char,
+) -> *mut EnigmaHandle {
+    let (Some(rotors), Some(reflector), Some(positions), Some(rings), Some(plugboard)) =
+        (string(rotors), string(reflector), string(positions), string(rings), string(plugboard))
+    else {
+        return ptr::null_mut();
+    };
+    match config(&rotors, &reflector, &positions, &rings, &plugboard) {
+        Some(config) => Box::into_raw(Box::new(EnigmaHandle(EnigmaMachine::new(config)))),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Cifra (ou decifra) `len` bytes de `input` em `output`, que pode ser o próprio
+/// `input`. Letras saem em maiúsculas e giram os rotores; os outros bytes são
+/// copiados sem mudança. Retorna o número de letras cifradas.
+///
+/// # Safety
+///
+/// `machine` deve vir de `enigma_new`; `input` e `output` devem ter `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn enigma_process(
+    machine: *mut EnigmaHandle,
+    input: *const u8,
+    len: usize,
+    output: *mut u8,
+) -> usize {
+    if machine.is_null() || input.is_null() || output.is_null() {
+        return 0;
+    }
+    let machine = &mut (*machine).0;
+    let mut letters = 0;
+    for i in 0..len {
+        // Byte a byte, sem montar fatias, porque `input` e `output` podem se sobrepor
+        let byte = input.add(i).read();
+        let byte = if byte.is_ascii_alphabetic() {
+            letters += 1;
+            machine.process_char(byte.to_ascii_uppercase() as char) as u8
+        } else {
+            byte
+        };
+        output.add(i).write(byte);
+    }
+    letters
+}
+
+/// Escreve em `out` as posições atuais dos rotores, da esquerda para a direita,
+/// como uma string C de três letras (4 bytes com o zero final).
+///
+/// # Safety
+///
+/// `machine` deve vir de `enigma_new`; `out` deve ter pelo menos 4 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn enigma_positions(machine: *const EnigmaHandle, out: *mut c_char) {
+    if machine.is_null() || out.is_null() {
+        return;
+    }
+    let (left, middle, right) = (*machine).0.get_positions();
+    for (i, c) in [left, middle, right, '\0'].into_iter().enumerate() {
+        out.add(i).write(c as c_char);
+    }
+}
+
+/// Libera uma máquina criada por `enigma_new`. Aceita nulo.
+///
+/// # Safety
+///
+/// `machine` deve vir de `enigma_new` e não pode ser usado depois.
+#[no_mangle]
+pub unsafe extern "C" fn enigma_free(machine: *mut EnigmaHandle) {
+    if !machine.is_null() {
+        drop(Box::from_raw(machine));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    unsafe fn new(rotors: &str, positions: &str, plugboard: &str) -> *mut EnigmaHandle {
+        let c = |s: &str| CString::new(s).unwrap();
+        let (rotors, positions, plugboard) = (c(rotors), c(positions), c(plugboard));
+        let (reflector, rings) = (c("B"), c("aaa"));
+        enigma_new(rotors.as_ptr(), reflector.as_ptr(), positions.as_ptr(), rings.as_ptr(), plugboard.as_ptr())
+    }
+
+    #[test]
+    fn test_round_trip_in_place() {
+        unsafe {
+            let machine = new("III II I", "xke", "av bs");
+            assert!(!machine.is_null());
+            let mut text = *b"Wetter bericht!";
+            let letters = enigma_process(machine, text.as_ptr(), text.len(), text.as_mut_ptr());
+            assert_eq!(letters, 13);
+            assert_eq!(text[6], b' ');
+            assert_eq!(text[14], b'!');
+
+            let mut positions = [0 as c_char; 4];
+            enigma_positions(machine, positions.as_mut_ptr());
+            assert_eq!(CStr::from_ptr(positions.as_ptr()).to_str().unwrap(), "XLR");
+            enigma_free(machine);
+
+            let machine = new("III II I", "XKE", "AV BS");
+            let mut plain = [0u8; 15];
+            enigma_process(machine, text.as_ptr(), text.len(), plain.as_mut_ptr());
+            assert_eq!(&plain, b"WETTER BERICHT!");
+            enigma_free(machine);
+        }
+    }
+
+    #[test]
+    fn test_invalid_config_returns_null() {
+        unsafe {
+            assert!(new("III II", "XKE", "").is_null());
+            assert!(new("III II IX", "XKE", "").is_null());
+            assert!(new("III II I", "XK", "").is_null());
+            assert!(enigma_new(ptr::null(), ptr::null(), ptr::null(), ptr::null(), ptr::null()).is_null());
+            enigma_free(ptr::null_mut());
+        }
+    }
+    #[test]
+    fn test_header_is_up_to_date() {
+        assert!(
+            include_str!("../include/enigma.h") == include_str!(concat!(env!("OUT_DIR"), "/enigma.h")),
+            "include/enigma.h desatualizado: rode ENIGMA_FFI_UPDATE_HEADER=1 cargo build -p enigma-ffi"
+        );
+    }
+}
@@ -0,0 +1,39 @@
+//! Fachada WebAssembly do `enigma-core`, para rodar a mesma máquina numa demo só no
+//! navegador, sem o Tauri.
+//!
+//! Compilar com `wasm-pack build --target web` e, no JavaScript:
+//!
+//! ```js
+//! import init, { process_string, process_string_detailed } from "./pkg/enigma_wasm.js";
+//! await init();
+//! process_string(config, "HELLO");
+//! ```
+//!
+//! `config` tem o mesmo formato do `EnigmaConfig` enviado ao Tauri, e os passos de
+//! `process_string_detailed` têm o mesmo formato dos devolvidos por
+//! `enigma_process_detailed`.
+
+use enigma_core::{EnigmaConfig, EnigmaMachine};
+use wasm_bindgen::prelude::*;
+
+/// Processa (criptografa/descriptografa) um texto e retorna só o resultado.
+/// Caracteres não alfabéticos são ignorados.
+#[wasm_bindgen]
+pub fn process_string(config: JsValue, text: &str) -> Result<String, JsValue> {
+    Ok(machine(config)?.process_string(text))
+}
+
+/// Processa um texto e retorna a lista de passos detalhados de cada letra.
+#[wasm_bindgen]
+pub fn process_string_detailed(config: JsValue, text: &str) -> Result<JsValue, JsValue> {
+    let steps = machine(config)?.process_string_detailed(text);
+    serde_wasm_bindgen::to_value(&steps).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Monta a máquina a partir da configuração vinda do JavaScript. A configuração é
+/// conferida antes: um pânico em `EnigmaMachine::new` derrubaria o módulo inteiro.
+fn machine(config: JsValue) -> Result<EnigmaMachine, JsValue> {
+    let config: EnigmaConfig = serde_wasm_bindgen::from_value(config)
+        .map_err(|e| JsValue::from_str(&format!("Configuração inválida: {}", e)))?;
+    Ok(EnigmaMachine::new(config.normalized().map_err(|e| JsValue::from_str(&e))?))
+}
@@ -0,0 +1,105 @@
+//! Mede a velocidade dos caminhos de cifragem da máquina no computador atual.
+//!
+//! O caminho didático (`process_char_detailed`) monta o caminho do sinal a cada
+//! tecla; o simples (`process_char`) só cifra; o rápido (`process_bytes`) cifra
+//! letras 0-25 no lugar, e o paralelo divide o texto entre os núcleos.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::enigma::{EnigmaConfig, EnigmaMachine, RotorConfig};
+
+/// Letras cifradas pelos caminhos lentos.
+const SLOW_LETTERS: usize = 100_000;
+/// Letras cifradas pelos caminhos rápidos.
+const FAST_LETTERS: usize = 2_000_000;
+
+/// Velocidade de um caminho.
+#[derive(Debug, Serialize, Clone)]
+pub struct PathSpeed {
+    /// Nome do método medido (ex: "process_bytes").
+    pub path: String,
+    pub letters: usize,
+    pub elapsed_ms: f64,
+    pub chars_per_sec: f64,
+    /// Quantas vezes mais rápido que o caminho didático.
+    pub speedup: f64,
+}
+
+/// Resultado do benchmark.
+#[derive(Debug, Serialize, Clone)]
+pub struct BenchmarkReport {
+    /// Do mais lento para o mais rápido: didático, simples, rápido e paralelo.
+    pub paths: Vec<PathSpeed>,
+    /// Núcleos disponíveis para o caminho paralelo.
+    pub threads: usize,
+}
+
+fn config() -> EnigmaConfig {
+    let rotor = |name: &str, position, ring| RotorConfig { name: name.to_string(), position, ring };
+    EnigmaConfig {
+        rotors: (rotor("III", 'V', 'C'), rotor("I", 'K', 'B'), rotor("II", 'X', 'A')),
+        reflector: "B".to_string(),
+        plugboard_pairs: "AV BS CG DL FU HZ IN KM OW RX".to_string(),
+    }
+}
+
+/// Cifra `letters` letras com `f` numa máquina nova e mede o tempo.
+fn measure(path: &str, letters: usize, f: impl Fn(&mut EnigmaMachine, &mut [u8])) -> PathSpeed {
+    let mut text: Vec<u8> = (0..letters).map(|i| (i * 7 % 26) as u8).collect();
+    let mut machine = EnigmaMachine::new(config());
+    let started = Instant::now();
+    f(&mut machine, &mut text);
+    black_box(&text);
+    let elapsed = started.elapsed().as_secs_f64().max(1e-9);
+    PathSpeed {
+        path: path.to_string(),
+        letters,
+        elapsed_ms: elapsed * 1000.0,
+        chars_per_sec: letters as f64 / elapsed,
+        speedup: 1.0,
+    }
+}
+
+/// Mede os quatro caminhos. Leva alguns décimos de segundo.
+pub fn run() -> BenchmarkReport {
+    let mut paths = vec![
+        measure("process_char_detailed", SLOW_LETTERS, |machine, text| {
+            for c in text.iter_mut() {
+                let (output, step) = machine.process_char_detailed((b'A' + *c) as char);
+                black_box(step);
+                *c = output as u8 - b'A';
+            }
+        }),
+        measure("process_char", SLOW_LETTERS, |machine, text| {
+            for c in text.iter_mut() {
+                *c = machine.process_char((b'A' + *c) as char) as u8 - b'A';
+            }
+        }),
+        measure("process_bytes", FAST_LETTERS, |machine, text| machine.process_bytes(text)),
+        measure("process_bytes_parallel", FAST_LETTERS, |machine, text| machine.process_bytes_parallel(text)),
+    ];
+    let baseline = paths[0].chars_per_sec;
+    for path in &mut paths {
+        path.speedup = path.chars_per_sec / baseline;
+    }
+    BenchmarkReport {
+        paths,
+        threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure_counts_letters() {
+        let speed = measure("process_bytes", 1000, |machine, text| machine.process_bytes(text));
+        assert_eq!(speed.letters, 1000);
+        assert!(speed.chars_per_sec > 0.0);
+        assert_eq!(speed.speedup, 1.0);
+    }
+}
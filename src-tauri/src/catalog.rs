@@ -0,0 +1,179 @@
+//! Catálogo remoto de pacotes de peças: uma lista em JSON, num endereço escolhido
+//! pelo usuário, com os pacotes selecionados (chaves diárias históricas, pacotes
+//! de aulas) e o SHA-256 de cada um.
+//!
+//! Um pacote só é instalado na pasta `components` se o conteúdo baixado confere
+//! com o SHA-256 do catálogo e passa pelas mesmas verificações dos pacotes locais:
+//! os arquivos podem vir de outro servidor que não o do catálogo.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::enigma::components::{self, ComponentPack};
+use crate::packs::PackStatus;
+
+/// Tamanho máximo de um download (catálogo ou pacote).
+const MAX_DOWNLOAD: usize = 1 << 20;
+
+/// Um pacote oferecido pelo catálogo.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CatalogEntry {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Nome do arquivo na pasta `components` (ex.: "chaves-1941.json").
+    pub file: String,
+    pub url: String,
+    /// SHA-256 do arquivo, em hexadecimal.
+    pub sha256: String,
+}
+
+/// O catálogo, como publicado no endereço configurado.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Catalog {
+    pub packs: Vec<CatalogEntry>,
+}
+
+/// Baixa `url` (só https), recusando respostas maiores que `MAX_DOWNLOAD`.
+async fn download(url: &str) -> Result<Vec<u8>, String> {
+    if !url.starts_with("https://") {
+        return Err(format!("O catálogo só aceita endereços https://: {}", url));
+    }
+    let mut response = reqwest::get(url)
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Erro ao baixar {}: {}", url, e))?;
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("Erro ao baixar {}: {}", url, e))? {
+        body.extend_from_slice(&chunk);
+        if body.len() > MAX_DOWNLOAD {
+            return Err(format!("Arquivo grande demais em {} (máximo de {} bytes).", url, MAX_DOWNLOAD));
+        }
+    }
+    Ok(body)
+}
+
+/// Lê o catálogo publicado em `url`.
+pub async fn fetch_catalog(url: &str) -> Result<Catalog, String> {
+    let body = download(url).await?;
+    serde_json::from_slice(&body).map_err(|e| format!("Catálogo inválido: {}", e))
+}
+
+/// SHA-256 de `bytes` em hexadecimal minúsculo.
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Caminho do arquivo de `entry` em `dir`. O nome vem de fora: só um nome simples
+/// terminado em ".json", sem pastas.
+fn target_path(dir: &Path, entry: &CatalogEntry) -> Result<PathBuf, String> {
+    let simple = entry.file.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !simple || entry.file.starts_with('.') || !entry.file.ends_with(".json") {
+        return Err(format!("Nome de arquivo inválido no catálogo: {}", entry.file));
+    }
+    Ok(dir.join(&entry.file))
+}
+
+/// Confere e instala o conteúdo já baixado de `entry` em `dir`, registrando as
+/// peças. Um pacote com o mesmo arquivo é substituído. O arquivo é gravado antes do
+/// registro, para um pacote registrado nunca faltar no disco ao reabrir o app; se o
+/// registro falhar, o arquivo sai e o pacote anterior volta.
+pub fn install_bytes(dir: &Path, entry: &CatalogEntry, bytes: &[u8]) -> Result<PackStatus, String> {
+    let path = target_path(dir, entry)?;
+    let digest = sha256_hex(bytes);
+    if !digest.eq_ignore_ascii_case(entry.sha256.trim()) {
+        return Err(format!("O pacote {} não confere com o catálogo (SHA-256 {}).", entry.name, digest));
+    }
+    let pack: ComponentPack =
+        serde_json::from_slice(bytes).map_err(|e| format!("Pacote de peças inválido: {}", e))?;
+
+    std::fs::create_dir_all(dir).map_err(|e| format!("Erro ao criar a pasta de pacotes: {}", e))?;
+    let temporary = path.with_extension("tmp");
+    let previous = path.with_extension("bak");
+    std::fs::write(&temporary, bytes).map_err(|e| format!("Erro ao instalar o pacote: {}", e))?;
+    let replacing = std::fs::rename(&path, &previous).is_ok();
+    if let Err(e) = std::fs::rename(&temporary, &path) {
+        if replacing {
+            let _ = std::fs::rename(&previous, &path);
+        }
+        return Err(format!("Erro ao instalar o pacote: {}", e));
+    }
+    if let Err(e) = components::register_pack(&pack) {
+        let _ = std::fs::remove_file(&path);
+        if replacing {
+            let _ = std::fs::rename(&previous, &path);
+        }
+        return Err(e);
+    }
+    if replacing {
+        let _ = std::fs::remove_file(&previous);
+    }
+    Ok(PackStatus { file: entry.file.clone(), name: pack.name.clone(), summary: pack.summary(), error: None })
+}
+
+/// Baixa, confere e instala o pacote de `entry` em `dir`. Um erro fica no
+/// `PackStatus`, para os outros pacotes do pedido seguirem.
+pub async fn install(dir: &Path, entry: &CatalogEntry) -> PackStatus {
+    let result = match download(&entry.url).await {
+        Ok(bytes) => install_bytes(dir, entry, &bytes),
+        Err(e) => Err(e),
+    };
+    result.unwrap_or_else(|e| PackStatus {
+        file: entry.file.clone(),
+        name: entry.name.clone(),
+        summary: String::new(),
+        error: Some(e),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(file: &str, sha256: &str) -> CatalogEntry {
+        CatalogEntry {
+            name: "Chaves de teste".to_string(),
+            description: String::new(),
+            file: file.to_string(),
+            url: "https://example.org/pacote.json".to_string(),
+            sha256: sha256.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_sha256_and_file_names() {
+        assert_eq!(sha256_hex(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        let dir = Path::new("/dados/components");
+        assert_eq!(target_path(dir, &entry("chaves-1941.json", "")).unwrap(), dir.join("chaves-1941.json"));
+        for file in ["../session.json", "sub/pacote.json", ".json", "pacote.txt", "C:pacote.json"] {
+            assert!(target_path(dir, &entry(file, "")).is_err(), "{}", file);
+        }
+    }
+
+    #[test]
+    fn test_install_rejects_a_wrong_checksum() {
+        let dir = std::env::temp_dir().join(format!("enigma-catalog-{}", std::process::id()));
+        let bytes = br#"{"name": "Vazio"}"#;
+        let error = install_bytes(&dir, &entry("vazio.json", &"0".repeat(64)), bytes).unwrap_err();
+        assert!(error.contains("não confere"));
+        assert!(!dir.join("vazio.json").exists());
+    }
+
+    #[test]
+    fn test_failed_registration_keeps_the_previous_pack() {
+        let dir = std::env::temp_dir().join(format!("enigma-catalog-replace-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let previous = br#"{"name": "Anterior", "rotors": [], "reflectors": [], "models": []}"#;
+        std::fs::write(dir.join("chaves.json"), previous).unwrap();
+
+        // Um rotor com o nome de um rotor embutido é recusado no registro
+        let bytes = br#"{"name": "Novo", "rotors": [{"name": "III", "wiring": "EKMFLGDQVZNTOWYHXUSPAIBRCJ",
+            "notch": "Q"}], "reflectors": [], "models": []}"#;
+        assert!(install_bytes(&dir, &entry("chaves.json", &sha256_hex(bytes)), bytes).is_err());
+        assert_eq!(std::fs::read(dir.join("chaves.json")).unwrap(), previous);
+        assert!(!dir.join("chaves.bak").exists() && !dir.join("chaves.tmp").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
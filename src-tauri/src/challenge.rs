@@ -0,0 +1,364 @@
+//! Desafios de criptoanálise: uma mensagem cifrada com uma chave sorteada que só
+//! o backend conhece. A UI recebe o texto cifrado (e, fora do nível difícil, um
+//! crib do início da mensagem), pede dicas que revelam a chave aos poucos e manda
+//! a solução, o texto claro ou uma chave, para conferir.
+//!
+//! Um exercício aberto de um arquivo (ver `exercise`) vira um desafio igual, com a
+//! chave e as regras de correção do arquivo no lugar das sorteadas.
+
+use std::collections::BTreeMap;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::enigma::{EnigmaConfig, EnigmaMachine};
+use crate::exercise::{self, Exercise, GradingRules, Tool};
+use crate::quiz::{random_config, Difficulty};
+
+/// Desafios em andamento guardados ao mesmo tempo; o mais antigo sai primeiro.
+const OPEN_CHALLENGES: usize = 32;
+
+/// Mensagens no estilo das originais (espaço como X, números por extenso) e o crib
+/// com que cada uma começa.
+const MESSAGES: [(&str, &str); 6] = [
+    ("WETTERBERICHTXWINDSTAERKEXVIERXSEEGANGXDREIXSICHTXGUT", "WETTERBERICHT"),
+    ("OBERKOMMANDODERWEHRMACHTXKEINEBESONDERENEREIGNISSEXHEILHITLER", "OBERKOMMANDODERWEHRMACHT"),
+    ("ANXOBERKOMMANDOXFEINDLICHEXPANZERXBEIXQUADRATXEINSXNULL", "ANXOBERKOMMANDO"),
+    ("WETTERVORHERSAGEXREGENXWINDXAUSXWESTXNULLNULLXZWEI", "WETTERVORHERSAGE"),
+    ("BEFEHLSHABERDERUBOOTEXTREFFPUNKTXQUADRATXEINSXSIEBEN", "BEFEHLSHABERDERUBOOTE"),
+    ("VONXVONXFUEHRERHAUPTQUARTIERXBEFEHLXFOLGTXHEILHITLER", "VONXVONX"),
+];
+
+/// Desafio novo, como vai para a UI (sem a chave nem o texto claro).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChallengeView {
+    pub id: u64,
+    pub difficulty: Difficulty,
+    pub ciphertext: String,
+    /// Palavra com que o texto claro começa, se o nível der uma.
+    pub crib: Option<String>,
+    /// Quantas dicas podem ser pedidas.
+    pub hints: usize,
+}
+
+/// Exercício aberto, como vai para a UI: o desafio, sem a chave, e o que o arquivo
+/// define para ele.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExerciseView {
+    pub challenge: ChallengeView,
+    pub title: String,
+    pub instructions: String,
+    pub tools: Vec<Tool>,
+    pub grading: GradingRules,
+    /// O que a assinatura do arquivo garante, e o que não (ver `exercise::NOTICE`).
+    pub notice: String,
+}
+
+/// Uma parte da chave revelada por uma dica. Vai para a UI com a parte em `part`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "part", rename_all = "snake_case")]
+pub enum Hint {
+    Reflector { reflector: String },
+    /// Rotores da esquerda para a direita.
+    Rotors { rotors: Vec<String> },
+    /// Anéis (L, M, R).
+    Rings { rings: String },
+    /// Posições iniciais (L, M, R).
+    Positions { positions: String },
+    Plugboard { pairs: String },
+}
+
+/// Resposta a um pedido de dica.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HintReveal {
+    pub hint: Hint,
+    pub used: usize,
+    pub left: usize,
+}
+
+/// Solução enviada pela UI: o texto claro ou uma chave que o decifre.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Solution {
+    Plaintext { text: String },
+    Key { config: EnigmaConfig },
+}
+
+/// Correção de uma solução.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Verdict {
+    pub solved: bool,
+    /// Tentativas até agora, contando esta.
+    pub attempts: usize,
+    pub hints_used: usize,
+    /// Fração das letras do texto claro enviado que estão certas (1.0 para uma
+    /// chave que decifra tudo).
+    pub accuracy: f64,
+    /// Texto claro e chave, revelados quando o desafio é resolvido.
+    pub plaintext: Option<String>,
+    pub config: Option<EnigmaConfig>,
+    /// Texto cifrado do desafio, que o identifica no progresso.
+    pub ciphertext: String,
+}
+
+#[derive(Debug, Clone)]
+struct Challenge {
+    config: EnigmaConfig,
+    plaintext: String,
+    ciphertext: String,
+    hints: Vec<Hint>,
+    hints_used: usize,
+    attempts: usize,
+    grading: GradingRules,
+}
+
+/// Desafios em andamento, pelo número.
+#[derive(Debug)]
+pub struct Challenges {
+    next_id: u64,
+    open: BTreeMap<u64, Challenge>,
+    /// Quantos ficam abertos antes de o mais antigo ser descartado.
+    limit: usize,
+}
+
+impl Default for Challenges {
+    fn default() -> Self {
+        Self { next_id: 0, open: BTreeMap::new(), limit: OPEN_CHALLENGES }
+    }
+}
+
+/// Dicas de `config`, da que menos revela para a que mais revela.
+fn hints(config: &EnigmaConfig) -> Vec<Hint> {
+    let (right, middle, left) = &config.rotors;
+    let mut hints = vec![
+        Hint::Reflector { reflector: config.reflector.clone() },
+        Hint::Rotors { rotors: vec![left.name.clone(), middle.name.clone(), right.name.clone()] },
+        Hint::Rings { rings: [left.ring, middle.ring, right.ring].iter().collect() },
+        Hint::Positions { positions: [left.position, middle.position, right.position].iter().collect() },
+    ];
+    if !config.plugboard_pairs.is_empty() {
+        hints.push(Hint::Plugboard { pairs: config.plugboard_pairs.clone() });
+    }
+    hints
+}
+
+fn letters(text: &str) -> String {
+    text.chars().filter(|c| c.is_ascii_alphabetic()).map(|c| c.to_ascii_uppercase()).collect()
+}
+
+/// Fração das letras de `expected` que `text` acerta, posição a posição.
+fn accuracy(text: &str, expected: &str) -> f64 {
+    let right = text.chars().zip(expected.chars()).filter(|(a, b)| a == b).count();
+    right as f64 / expected.len().max(1) as f64
+}
+
+impl Challenges {
+    /// Sorteia uma chave e uma mensagem e guarda o desafio.
+    pub fn generate(&mut self, rng: &mut impl Rng, difficulty: Difficulty) -> ChallengeView {
+        let config = random_config(rng, difficulty);
+        let &(plaintext, crib) = MESSAGES.choose(rng).unwrap();
+        let ciphertext = EnigmaMachine::new(config.clone()).process_string(plaintext);
+        let hints = hints(&config);
+        let crib = (difficulty != Difficulty::Hard).then(|| crib.to_string());
+        self.insert(difficulty, config, plaintext.to_string(), ciphertext, crib, hints, GradingRules::default())
+    }
+
+    /// Abre `exercise` como um desafio, com a chave e as regras dele.
+    pub fn open_exercise(&mut self, exercise: Exercise) -> ExerciseView {
+        let plaintext = EnigmaMachine::new(exercise.key.clone()).process_string(&exercise.ciphertext);
+        let hints = if exercise.grading.hints { hints(&exercise.key) } else { Vec::new() };
+        let challenge = self.insert(
+            exercise.difficulty,
+            exercise.key,
+            plaintext,
+            exercise.ciphertext,
+            exercise.crib,
+            hints,
+            exercise.grading.clone(),
+        );
+        ExerciseView {
+            challenge,
+            title: exercise.title,
+            instructions: exercise.instructions,
+            tools: exercise.tools,
+            grading: exercise.grading,
+            notice: exercise::NOTICE.to_string(),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn insert(
+        &mut self,
+        difficulty: Difficulty,
+        config: EnigmaConfig,
+        plaintext: String,
+        ciphertext: String,
+        crib: Option<String>,
+        hints: Vec<Hint>,
+        grading: GradingRules,
+    ) -> ChallengeView {
+        self.next_id += 1;
+        let view =
+            ChallengeView { id: self.next_id, difficulty, ciphertext: ciphertext.clone(), crib, hints: hints.len() };
+        if self.open.len() >= self.limit {
+            self.open.pop_first();
+        }
+        let challenge = Challenge { config, plaintext, ciphertext, hints, hints_used: 0, attempts: 0, grading };
+        self.open.insert(self.next_id, challenge);
+        view
+    }
+
+    /// Sem limite de desafios abertos: quem guarda os números (a turma) descarta
+    /// os que não usa mais com `discard`.
+    pub fn unlimited() -> Self {
+        Self { limit: usize::MAX, ..Self::default() }
+    }
+
+    /// Descarta o desafio `id`, se ainda estiver aberto.
+    pub fn discard(&mut self, id: u64) {
+        self.open.remove(&id);
+    }
+
+    fn get(&mut self, id: u64) -> Result<&mut Challenge, String> {
+        self.open.get_mut(&id).ok_or_else(|| format!("Desafio não encontrado: {}", id))
+    }
+
+    /// Revela a próxima parte da chave do desafio `id`.
+    pub fn hint(&mut self, id: u64) -> Result<HintReveal, String> {
+        let challenge = self.get(id)?;
+        let hint = challenge.hints.get(challenge.hints_used).cloned().ok_or("Não há mais dicas para este desafio.")?;
+        challenge.hints_used += 1;
+        Ok(HintReveal { hint, used: challenge.hints_used, left: challenge.hints.len() - challenge.hints_used })
+    }
+
+    /// Confere `solution` para o desafio `id`. Vale qualquer chave que decifre a
+    /// mensagem, não só a sorteada, e um texto claro com a fração de letras certas
+    /// que as regras pedem. Um desafio resolvido é encerrado.
+    pub fn submit(&mut self, id: u64, solution: &Solution) -> Result<Verdict, String> {
+        let challenge = self.get(id)?;
+        if challenge.grading.max_attempts.is_some_and(|max| challenge.attempts >= max) {
+            return Err("Não há mais tentativas para este desafio.".to_string());
+        }
+        let accuracy = match solution {
+            Solution::Plaintext { text } => accuracy(&letters(text), &challenge.plaintext),
+            Solution::Key { .. } if !challenge.grading.accept_key => {
+                return Err("Este desafio só aceita o texto claro.".to_string());
+            }
+            Solution::Key { config } => {
+                let config = config.clone().normalized()?;
+                let text = EnigmaMachine::new(config).process_string(&challenge.ciphertext);
+                if text == challenge.plaintext { 1.0 } else { 0.0 }
+            }
+        };
+        let solved = accuracy >= challenge.grading.min_accuracy;
+        challenge.attempts += 1;
+        let verdict = Verdict {
+            solved,
+            attempts: challenge.attempts,
+            hints_used: challenge.hints_used,
+            accuracy,
+            plaintext: solved.then(|| challenge.plaintext.clone()),
+            config: solved.then(|| challenge.config.clone()),
+            ciphertext: challenge.ciphertext.clone(),
+        };
+        if solved {
+            self.open.remove(&id);
+        }
+        Ok(verdict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_hints_reveal_the_key_until_it_solves() {
+        let mut rng = StdRng::seed_from_u64(1941);
+        let mut challenges = Challenges::default();
+        let view = challenges.generate(&mut rng, Difficulty::Medium);
+        assert!(view.crib.is_some());
+        assert_eq!(view.hints, 5);
+
+        let wrong = Solution::Plaintext { text: "WETTER".to_string() };
+        assert!(!challenges.submit(view.id, &wrong).unwrap().solved);
+
+        let mut config = EnigmaConfig::from_share_code("ENIGMA:I.II.III:B:AAA:AAA:").unwrap();
+        for used in 1..=view.hints {
+            let reveal = challenges.hint(view.id).unwrap();
+            assert_eq!((reveal.used, reveal.left), (used, view.hints - used));
+            let rotors = [&mut config.rotors.2, &mut config.rotors.1, &mut config.rotors.0];
+            match reveal.hint {
+                Hint::Reflector { reflector } => config.reflector = reflector,
+                Hint::Rotors { rotors: names } => rotors.into_iter().zip(names).for_each(|(r, name)| r.name = name),
+                Hint::Rings { rings } => rotors.into_iter().zip(rings.chars()).for_each(|(r, ring)| r.ring = ring),
+                Hint::Positions { positions } => {
+                    rotors.into_iter().zip(positions.chars()).for_each(|(r, position)| r.position = position)
+                }
+                Hint::Plugboard { pairs } => config.plugboard_pairs = pairs,
+            }
+        }
+        assert!(challenges.hint(view.id).is_err());
+
+        let verdict = challenges.submit(view.id, &Solution::Key { config }).unwrap();
+        assert!(verdict.solved);
+        assert_eq!((verdict.attempts, verdict.hints_used), (2, 5));
+        assert!(verdict.plaintext.unwrap().starts_with(&view.crib.unwrap()));
+        assert!(challenges.hint(view.id).is_err());
+    }
+
+    fn exercise(grading: GradingRules) -> Exercise {
+        let key = EnigmaConfig::from_share_code("ENIGMA:II.IV.V:B:BLA:BUL:AVBSCGDLFUHZ").unwrap();
+        Exercise {
+            title: "Boletim do tempo".to_string(),
+            instructions: String::new(),
+            difficulty: Difficulty::Easy,
+            ciphertext: EnigmaMachine::new(key.clone()).process_string("WETTERBERICHTXWINDSTAERKEXVIER"),
+            crib: None,
+            tools: vec![Tool::Bombe],
+            key,
+            grading,
+        }
+    }
+
+    #[test]
+    fn test_exercises_follow_their_grading_rules() {
+        let mut challenges = Challenges::default();
+        let grading = GradingRules { min_accuracy: 0.9, accept_key: false, hints: false, max_attempts: None };
+        let view = challenges.open_exercise(exercise(grading));
+        assert_eq!((view.challenge.hints, view.tools.as_slice()), (0, [Tool::Bombe].as_slice()));
+        assert_eq!(view.notice, exercise::NOTICE);
+        let id = view.challenge.id;
+        assert!(challenges.hint(id).is_err());
+        let config = exercise(GradingRules::default()).key;
+        assert!(challenges.submit(id, &Solution::Key { config }).unwrap_err().contains("texto claro"));
+
+        // 26 de 30 letras certas não bastam; 29 sim
+        let almost = Solution::Plaintext { text: "wetter bericht x wind staerke x xxxx".to_string() };
+        let verdict = challenges.submit(id, &almost).unwrap();
+        assert!(!verdict.solved && (verdict.accuracy - 26.0 / 30.0).abs() < 1e-9);
+        let close = Solution::Plaintext { text: "WETTERBERICHTXWINDSTAERKEXVIEX".to_string() };
+        assert!(challenges.submit(id, &close).unwrap().solved);
+
+        let grading = GradingRules { max_attempts: Some(2), ..GradingRules::default() };
+        let view = challenges.open_exercise(exercise(grading));
+        let wrong = Solution::Plaintext { text: "A".to_string() };
+        challenges.submit(view.challenge.id, &wrong).unwrap();
+        challenges.submit(view.challenge.id, &wrong).unwrap();
+        assert!(challenges.submit(view.challenge.id, &wrong).unwrap_err().contains("tentativas"));
+    }
+
+    #[test]
+    fn test_hard_challenges_have_no_crib() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut challenges = Challenges::default();
+        let view = challenges.generate(&mut rng, Difficulty::Hard);
+        assert_eq!(view.crib, None);
+        let plaintext = EnigmaMachine::new(challenges.open[&view.id].config.clone()).process_string(&view.ciphertext);
+        let verdict = challenges.submit(view.id, &Solution::Plaintext { text: plaintext.to_lowercase() }).unwrap();
+        assert!(verdict.solved && verdict.config.is_some());
+    }
+}
@@ -0,0 +1,396 @@
+//! Modo sala de aula: o app do professor abre um servidor WebSocket na rede local
+//! e os alunos entram nele com o próprio app.
+//!
+//! Cada aluno recebe um desafio só dele (`challenge`), sorteado e conferido aqui,
+//! no app do professor: a chave nunca sai do servidor, então não há o que procurar
+//! no código da página do aluno. O professor acompanha a turma pela visão geral
+//! (quem entrou, quantos resolveu, tentativas e dicas pedidas).
+//!
+//! As mensagens vão em JSON com o tipo em `type`: o aluno manda `join`, `hint` e
+//! `submit`; o professor responde com `challenge`, `hint`, `verdict` ou `error`.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::{accept_async, connect_async};
+
+use crate::challenge::{ChallengeView, Challenges, HintReveal, Solution, Verdict};
+use crate::quiz::Difficulty;
+use crate::radio::pump;
+
+/// Mensagem do aluno para o professor.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ToTeacher {
+    /// Entra na turma com um nome; é a primeira mensagem da ligação.
+    Join { name: String },
+    /// Pede a próxima dica do desafio atual.
+    Hint,
+    /// Manda a solução do desafio atual.
+    Submit { solution: Solution },
+}
+
+/// Mensagem do professor para um aluno.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ToStudent {
+    Challenge { challenge: ChallengeView },
+    Hint { reveal: HintReveal },
+    Verdict { verdict: Verdict },
+    Error { message: String },
+}
+
+/// Um aluno na visão geral da turma.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct StudentStatus {
+    pub id: u64,
+    pub name: String,
+    pub connected: bool,
+    /// Desafio em andamento, se houver.
+    pub challenge: Option<u64>,
+    pub solved: usize,
+    pub attempts: usize,
+    pub hints_used: usize,
+}
+
+/// O que acontece na turma, repassado à UI do professor.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum ClassroomEvent {
+    /// O servidor está esperando os alunos.
+    Listening { port: u16 },
+    /// A turma mudou: um aluno entrou, saiu, pediu dica ou mandou solução.
+    Overview { students: Vec<StudentStatus> },
+}
+
+/// O que acontece na ligação do aluno, repassado à UI dele.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum SeatEvent {
+    Connected { url: String },
+    Message { message: ToStudent },
+    Disconnected,
+}
+
+/// A turma: os alunos e os desafios deles. Não sabe nada da rede; cada operação
+/// retorna as mensagens a entregar.
+pub struct Class {
+    rng: StdRng,
+    challenges: Challenges,
+    students: BTreeMap<u64, StudentStatus>,
+    next_id: u64,
+    /// Nível do desafio passado pelo professor; quem entra depois também recebe um.
+    difficulty: Option<Difficulty>,
+}
+
+impl Class {
+    pub fn new(rng: StdRng) -> Self {
+        Self { rng, challenges: Challenges::unlimited(), students: BTreeMap::new(), next_id: 0, difficulty: None }
+    }
+
+    /// Sorteia um desafio novo para o aluno `id`, no lugar do que ele tinha.
+    fn deal(&mut self, id: u64, difficulty: Difficulty) -> ToStudent {
+        let challenge = self.challenges.generate(&mut self.rng, difficulty);
+        if let Some(student) = self.students.get_mut(&id) {
+            if let Some(previous) = student.challenge.replace(challenge.id) {
+                self.challenges.discard(previous);
+            }
+        }
+        ToStudent::Challenge { challenge }
+    }
+
+    /// Põe o aluno `name` na turma. Retorna o número dele e, se já houver um
+    /// desafio passado, o dele.
+    pub fn join(&mut self, name: &str) -> Result<(u64, Option<ToStudent>), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Informe um nome para entrar na turma.".to_string());
+        }
+        self.next_id += 1;
+        let id = self.next_id;
+        let student = StudentStatus {
+            id,
+            name: name.to_string(),
+            connected: true,
+            challenge: None,
+            solved: 0,
+            attempts: 0,
+            hints_used: 0,
+        };
+        self.students.insert(id, student);
+        Ok((id, self.difficulty.map(|difficulty| self.deal(id, difficulty))))
+    }
+
+    /// Passa um desafio novo de nível `difficulty` para cada aluno conectado.
+    pub fn assign(&mut self, difficulty: Difficulty) -> Vec<(u64, ToStudent)> {
+        self.difficulty = Some(difficulty);
+        let connected: Vec<u64> = self.students.values().filter(|s| s.connected).map(|s| s.id).collect();
+        connected.into_iter().map(|id| (id, self.deal(id, difficulty))).collect()
+    }
+
+    /// Atende um pedido do aluno `id`.
+    pub fn handle(&mut self, id: u64, request: ToTeacher) -> ToStudent {
+        self.try_handle(id, request).unwrap_or_else(|message| ToStudent::Error { message })
+    }
+
+    fn try_handle(&mut self, id: u64, request: ToTeacher) -> Result<ToStudent, String> {
+        let student = self.students.get_mut(&id).ok_or("Entre na turma antes.")?;
+        let challenge = student.challenge;
+        match request {
+            ToTeacher::Join { .. } => Err("Você já está na turma.".to_string()),
+            ToTeacher::Hint => {
+                let reveal = self.challenges.hint(challenge.ok_or("Nenhum desafio em andamento.")?)?;
+                student.hints_used += 1;
+                Ok(ToStudent::Hint { reveal })
+            }
+            ToTeacher::Submit { solution } => {
+                let verdict = self.challenges.submit(challenge.ok_or("Nenhum desafio em andamento.")?, &solution)?;
+                student.attempts += 1;
+                if verdict.solved {
+                    student.solved += 1;
+                    student.challenge = None;
+                }
+                Ok(ToStudent::Verdict { verdict })
+            }
+        }
+    }
+
+    /// Marca o aluno `id` como desconectado; ele continua na visão geral.
+    pub fn leave(&mut self, id: u64) {
+        if let Some(student) = self.students.get_mut(&id) {
+            student.connected = false;
+        }
+    }
+
+    /// Visão geral da turma, na ordem de chegada.
+    pub fn overview(&self) -> Vec<StudentStatus> {
+        self.students.values().cloned().collect()
+    }
+}
+
+/// Estado do servidor, dividido entre as ligações.
+struct Shared {
+    class: Class,
+    /// Fila de saída de cada aluno conectado.
+    seats: BTreeMap<u64, UnboundedSender<ToStudent>>,
+    /// Ligações abertas, encerradas junto com o servidor. As que já terminaram saem
+    /// a cada ligação nova, para a lista não crescer com reconexões.
+    connections: Vec<JoinHandle<()>>,
+}
+
+type EventHandler = Arc<dyn Fn(ClassroomEvent) + Send + Sync>;
+
+/// Servidor da turma, no app do professor. Fechado ao ser descartado.
+pub struct Classroom {
+    shared: Arc<Mutex<Shared>>,
+    on_event: EventHandler,
+    task: JoinHandle<()>,
+}
+
+impl Classroom {
+    /// Abre o servidor em `port` (em todas as interfaces) e espera os alunos.
+    pub async fn host(port: u16, on_event: impl Fn(ClassroomEvent) + Send + Sync + 'static) -> Result<Self, String> {
+        let on_event: EventHandler = Arc::new(on_event);
+        let listener = TcpListener::bind(("0.0.0.0", port))
+            .await
+            .map_err(|e| format!("Erro ao abrir a turma na porta {}: {}", port, e))?;
+        on_event(ClassroomEvent::Listening { port });
+        let class = Class::new(StdRng::from_entropy());
+        let shared = Arc::new(Mutex::new(Shared { class, seats: BTreeMap::new(), connections: Vec::new() }));
+        let task = tokio::spawn({
+            let shared = shared.clone();
+            let on_event = on_event.clone();
+            async move {
+                while let Ok((socket, _)) = listener.accept().await {
+                    let connection = tokio::spawn(seat(socket, shared.clone(), on_event.clone()));
+                    let connections = &mut shared.lock().unwrap().connections;
+                    connections.retain(|connection| !connection.is_finished());
+                    connections.push(connection);
+                }
+            }
+        });
+        Ok(Self { shared, on_event, task })
+    }
+
+    /// Passa um desafio novo de nível `difficulty` para a turma.
+    pub fn assign(&self, difficulty: Difficulty) -> Vec<StudentStatus> {
+        let mut shared = self.shared.lock().unwrap();
+        for (id, message) in shared.class.assign(difficulty) {
+            if let Some(outgoing) = shared.seats.get(&id) {
+                let _ = outgoing.send(message);
+            }
+        }
+        let students = shared.class.overview();
+        drop(shared);
+        (self.on_event)(ClassroomEvent::Overview { students: students.clone() });
+        students
+    }
+
+    /// Visão geral da turma.
+    pub fn overview(&self) -> Vec<StudentStatus> {
+        self.shared.lock().unwrap().class.overview()
+    }
+}
+
+impl Drop for Classroom {
+    fn drop(&mut self) {
+        self.task.abort();
+        for connection in &self.shared.lock().unwrap().connections {
+            connection.abort();
+        }
+    }
+}
+
+/// Atende um aluno da turma até ele sair.
+async fn seat<S>(socket: S, shared: Arc<Mutex<Shared>>, on_event: EventHandler)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let Ok(ws) = accept_async(socket).await else { return };
+    let (outgoing, receiver) = mpsc::unbounded_channel();
+    let mut id = None;
+    pump(ws, receiver, |request: ToTeacher| {
+        let mut state = shared.lock().unwrap();
+        let replies = match (id, request) {
+            (Some(id), request) => vec![state.class.handle(id, request)],
+            (None, ToTeacher::Join { name }) => match state.class.join(&name) {
+                Ok((student, challenge)) => {
+                    id = Some(student);
+                    state.seats.insert(student, outgoing.clone());
+                    challenge.into_iter().collect()
+                }
+                Err(message) => vec![ToStudent::Error { message }],
+            },
+            (None, _) => vec![ToStudent::Error { message: "Entre na turma antes.".to_string() }],
+        };
+        for reply in replies {
+            let _ = outgoing.send(reply);
+        }
+        let students = state.class.overview();
+        drop(state);
+        on_event(ClassroomEvent::Overview { students });
+    })
+    .await;
+
+    let Some(id) = id else { return };
+    let mut state = shared.lock().unwrap();
+    state.seats.remove(&id);
+    state.class.leave(id);
+    let students = state.class.overview();
+    drop(state);
+    on_event(ClassroomEvent::Overview { students });
+}
+
+/// Lugar do aluno na turma de um professor. Fechado ao ser descartado.
+pub struct ClassroomSeat {
+    outgoing: UnboundedSender<ToTeacher>,
+    task: JoinHandle<()>,
+}
+
+impl ClassroomSeat {
+    /// Entra na turma em `url` (ex.: "ws://192.168.0.10:9100") com o nome `name`.
+    pub async fn join(
+        url: &str,
+        name: &str,
+        on_event: impl Fn(SeatEvent) + Send + Sync + 'static,
+    ) -> Result<Self, String> {
+        let (ws, _) = connect_async(url).await.map_err(|e| format!("Erro ao entrar na turma {}: {}", url, e))?;
+        on_event(SeatEvent::Connected { url: url.to_string() });
+        let (outgoing, receiver) = mpsc::unbounded_channel();
+        outgoing.send(ToTeacher::Join { name: name.to_string() }).map_err(|_| "A ligação com a turma caiu.")?;
+        let task = tokio::spawn(async move {
+            pump(ws, receiver, |message| on_event(SeatEvent::Message { message })).await;
+            on_event(SeatEvent::Disconnected);
+        });
+        Ok(Self { outgoing, task })
+    }
+
+    /// Manda um pedido ao professor.
+    pub fn send(&self, request: ToTeacher) -> Result<(), String> {
+        self.outgoing.send(request).map_err(|_| "A ligação com a turma caiu.".to_string())
+    }
+}
+
+impl Drop for ClassroomSeat {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::challenge::Hint;
+    use crate::enigma::EnigmaConfig;
+
+    #[test]
+    fn test_students_get_their_own_challenges() {
+        let mut class = Class::new(StdRng::seed_from_u64(9));
+        let (ana, nothing) = class.join(" Ana ").unwrap();
+        assert_eq!(nothing, None);
+        assert!(class.join("  ").is_err());
+        assert!(matches!(class.handle(ana, ToTeacher::Hint), ToStudent::Error { .. }));
+
+        let dealt = class.assign(Difficulty::Easy);
+        assert_eq!(dealt.len(), 1);
+        let (bruno, Some(ToStudent::Challenge { challenge })) = class.join("Bruno").unwrap() else { panic!() };
+        assert_ne!(Some(challenge.id), class.overview()[0].challenge);
+
+        class.leave(ana);
+        assert_eq!(class.assign(Difficulty::Easy).len(), 1);
+        let overview = class.overview();
+        assert_eq!((overview[0].name.as_str(), overview[0].connected), ("Ana", false));
+        assert_eq!(overview[1].id, bruno);
+    }
+
+    #[test]
+    fn test_large_classes_keep_every_challenge() {
+        let mut class = Class::new(StdRng::seed_from_u64(3));
+        let (first, _) = class.join("Ana").unwrap();
+        for i in 0..40 {
+            class.join(&format!("Aluno {}", i)).unwrap();
+        }
+        assert_eq!(class.assign(Difficulty::Easy).len(), 41);
+        assert!(matches!(class.handle(first, ToTeacher::Hint), ToStudent::Hint { .. }));
+    }
+
+    #[test]
+    fn test_submissions_are_checked_and_counted() {
+        let mut class = Class::new(StdRng::seed_from_u64(12));
+        let (id, _) = class.join("Ana").unwrap();
+        let [(_, ToStudent::Challenge { challenge })] = &class.assign(Difficulty::Easy)[..] else { panic!() };
+        let wrong = Solution::Plaintext { text: challenge.ciphertext.clone() };
+        let ToStudent::Verdict { verdict } = class.handle(id, ToTeacher::Submit { solution: wrong }) else { panic!() };
+        assert!(!verdict.solved);
+
+        // No nível fácil as dicas revelam a chave toda (não há plugboard)
+        let mut config = EnigmaConfig::from_share_code("ENIGMA:I.II.III:B:AAA:AAA:").unwrap();
+        for _ in 0..challenge.hints {
+            let ToStudent::Hint { reveal } = class.handle(id, ToTeacher::Hint) else { panic!() };
+            let rotors = [&mut config.rotors.2, &mut config.rotors.1, &mut config.rotors.0];
+            match reveal.hint {
+                Hint::Reflector { reflector } => config.reflector = reflector,
+                Hint::Rotors { rotors: names } => rotors.into_iter().zip(names).for_each(|(r, name)| r.name = name),
+                Hint::Positions { positions } => {
+                    rotors.into_iter().zip(positions.chars()).for_each(|(r, position)| r.position = position)
+                }
+                Hint::Rings { .. } | Hint::Plugboard { .. } => {}
+            }
+        }
+        let ToStudent::Verdict { verdict } = class.handle(id, ToTeacher::Submit { solution: Solution::Key { config } })
+        else {
+            panic!()
+        };
+        assert!(verdict.solved);
+        let status = &class.overview()[0];
+        assert_eq!((status.solved, status.attempts, status.hints_used, status.challenge), (1, 2, 4, None));
+        assert!(matches!(class.handle(id, ToTeacher::Hint), ToStudent::Error { .. }));
+    }
+}
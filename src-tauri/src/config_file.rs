@@ -0,0 +1,106 @@
+//! Configurações e folhas de chaves em TOML ou YAML, texto simples que fica bem
+//! no controle de versão do material do curso.
+//!
+//! O formato vem da extensão do arquivo (`.toml`, `.yaml` ou `.yml`). Os campos
+//! são os mesmos do JSON trocado com a UI, mais o `schema_version` (ver `schema`).
+
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::enigma::EnigmaConfig;
+use crate::pdf::KeySheet;
+use crate::schema::{self, Kind};
+
+/// Formato de texto de um arquivo de configuração.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileFormat {
+    Toml,
+    Yaml,
+}
+
+impl FileFormat {
+    /// Formato pela extensão de `path`.
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_ascii_lowercase();
+        match extension.as_str() {
+            "toml" => Ok(Self::Toml),
+            "yaml" | "yml" => Ok(Self::Yaml),
+            _ => Err(format!("Use um arquivo .toml, .yaml ou .yml: {}", path.display())),
+        }
+    }
+}
+
+fn save<T: Serialize>(path: &Path, value: &T, kind: Kind) -> Result<(), String> {
+    let value = schema::to_value(value, kind)?;
+    let text = match FileFormat::from_path(path)? {
+        FileFormat::Toml => toml::to_string_pretty(&value).map_err(|e| format!("Erro ao gerar o TOML: {}", e))?,
+        FileFormat::Yaml => serde_yaml::to_string(&value).map_err(|e| format!("Erro ao gerar o YAML: {}", e))?,
+    };
+    std::fs::write(path, text).map_err(|e| format!("Erro ao salvar {}: {}", path.display(), e))
+}
+
+/// Lê o arquivo como dados genéricos, para migrar os de versões antigas antes de
+/// virar `T`.
+fn open<T: DeserializeOwned>(path: &Path, kind: Kind) -> Result<T, String> {
+    let format = FileFormat::from_path(path)?;
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Erro ao abrir {}: {}", path.display(), e))?;
+    let value: Value = match format {
+        FileFormat::Toml => toml::from_str(&text).map_err(|e| format!("TOML inválido: {}", e))?,
+        FileFormat::Yaml => serde_yaml::from_str(&text).map_err(|e| format!("YAML inválido: {}", e))?,
+    };
+    schema::from_value(value, kind)
+}
+
+/// Grava `config`, validada e em maiúsculas.
+pub fn save_config(path: &Path, config: &EnigmaConfig) -> Result<(), String> {
+    save(path, &config.clone().normalized()?, Kind::Config)
+}
+
+/// Abre uma configuração e a valida.
+pub fn open_config(path: &Path) -> Result<EnigmaConfig, String> {
+    open::<EnigmaConfig>(path, Kind::Config)?.normalized()
+}
+
+/// Grava uma folha de chaves, com as configurações validadas.
+pub fn save_key_sheet(path: &Path, sheet: &KeySheet) -> Result<(), String> {
+    save(path, &normalized(sheet.clone())?, Kind::KeySheet)
+}
+
+/// Abre uma folha de chaves e valida as configurações de todos os dias.
+pub fn open_key_sheet(path: &Path) -> Result<KeySheet, String> {
+    normalized(open(path, Kind::KeySheet)?)
+}
+
+fn normalized(mut sheet: KeySheet) -> Result<KeySheet, String> {
+    for day in &mut sheet.days {
+        day.config = day.config.clone().normalized().map_err(|e| format!("Dia {}: {}", day.day, e))?;
+    }
+    Ok(sheet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::KeySheetDay;
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(FileFormat::from_path(Path::new("aula/chave.toml")).unwrap(), FileFormat::Toml);
+        assert_eq!(FileFormat::from_path(Path::new("chave.YML")).unwrap(), FileFormat::Yaml);
+        assert!(FileFormat::from_path(Path::new("chave.json")).is_err());
+        assert!(FileFormat::from_path(Path::new("chave")).is_err());
+    }
+
+    #[test]
+    fn test_key_sheet_days_are_validated() {
+        let mut config = EnigmaConfig::from_share_code("ENIGMA:I.II.III:B:ABC:AAA:").unwrap();
+        config.reflector = "b".into();
+        let mut sheet = KeySheet { title: "Nr. 7".into(), days: vec![KeySheetDay { day: 3, config }] };
+        assert_eq!(normalized(sheet.clone()).unwrap().days[0].config.reflector, "B");
+        sheet.days[0].config.reflector = "Z".into();
+        assert!(normalized(sheet).unwrap_err().starts_with("Dia 3: "));
+    }
+}
@@ -0,0 +1,100 @@
+//! Links `enigma://`, para compartilhar desafios clicáveis.
+//!
+//! - `enigma://config?code=ENIGMA:I.II.III:B:ABC:AAA:ABCD` abre a configuração (o
+//!   código de `EnigmaConfig::to_share_code`);
+//! - `enigma://message?text=QBLTW+LDPFS&code=...` abre um texto cifrado, com a
+//!   chave opcional.
+
+use serde::Serialize;
+
+use crate::enigma::EnigmaConfig;
+
+/// Esquema registrado no sistema.
+pub const SCHEME: &str = "enigma";
+
+/// O que um link pede para abrir. Vai para a UI com a variante em `type`.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum DeepLink {
+    Config { config: EnigmaConfig },
+    Message { ciphertext: String, config: Option<EnigmaConfig> },
+}
+
+/// Valor de um hexadecimal ASCII.
+fn hex(b: u8) -> Option<u8> {
+    (b as char).to_digit(16).map(|d| d as u8)
+}
+
+/// Desfaz o `%XX` e o `+` (espaço) da query.
+fn decode(value: &str) -> Result<String, String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' => {
+                let byte = bytes.get(i + 1).and_then(|&h| hex(h)).zip(bytes.get(i + 2).and_then(|&l| hex(l)));
+                let (high, low) = byte.ok_or_else(|| format!("Escape inválido no link: {}", value))?;
+                decoded.push(high << 4 | low);
+                i += 2;
+            }
+            b => decoded.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8(decoded).map_err(|_| format!("Link com texto inválido: {}", value))
+}
+
+/// Lê um link `enigma://`.
+pub fn parse(url: &str) -> Result<DeepLink, String> {
+    let rest = url
+        .strip_prefix(SCHEME)
+        .and_then(|rest| rest.strip_prefix("://"))
+        .ok_or_else(|| format!("Link desconhecido: {}", url))?;
+    let (target, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let mut code = None;
+    let mut text = None;
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        match key {
+            "code" => code = Some(decode(value)?),
+            "text" => text = Some(decode(value)?),
+            _ => {}
+        }
+    }
+    let config = code.map(|code| EnigmaConfig::from_share_code(&code)).transpose()?;
+    match target.trim_end_matches('/') {
+        "config" => Ok(DeepLink::Config { config: config.ok_or("O link não tem a configuração (code).")? }),
+        "message" => {
+            Ok(DeepLink::Message { ciphertext: text.ok_or("O link não tem o texto cifrado (text).")?, config })
+        }
+        _ => Err(format!("Link desconhecido: {}", url)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_links() {
+        let code = "ENIGMA:I.II.III:B:ABC:AAA:ABCD";
+        let config = EnigmaConfig::from_share_code(code).unwrap();
+        assert_eq!(
+            parse("enigma://config?code=ENIGMA%3AI.II.III%3AB%3AABC%3AAAA%3AABCD").unwrap(),
+            DeepLink::Config { config: config.clone() }
+        );
+        assert_eq!(
+            parse(&format!("enigma://message/?text=QBLTW+LDPFS&code={}", code)).unwrap(),
+            DeepLink::Message { ciphertext: "QBLTW LDPFS".into(), config: Some(config) }
+        );
+        assert_eq!(
+            parse("enigma://message?text=%C3%9CBER").unwrap(),
+            DeepLink::Message { ciphertext: "ÜBER".into(), config: None }
+        );
+        assert!(parse("enigma://config").is_err());
+        assert!(parse("enigma://message?text=%Z1").is_err());
+        assert!(parse("https://example.com/config?code=x").is_err());
+    }
+}
@@ -0,0 +1,332 @@
+//! Diagrama SVG do caminho do sinal numa tecla, para apostilas em qualidade de
+//! impressão, e o diagrama da máquina inteira como grafo, para as interfaces
+//! desenharem todas a mesma fiação.
+//!
+//! Da esquerda para a direita: refletor, rotores esquerdo, do meio e direito,
+//! plugboard e teclado. Cada coluna tem os 26 contatos (A no alto); a ida sai do
+//! teclado em vermelho até o refletor e a volta chega à lâmpada em azul.
+
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::enigma::{char_to_u8, Component, EncryptionStep, EnigmaConfig, EnigmaMachine, PathDirection, RotorSlot};
+
+const MARGIN: f32 = 40.0;
+const TOP: f32 = 70.0;
+const ROW: f32 = 18.0;
+const COLUMN_WIDTH: f32 = 60.0;
+const COLUMN_GAP: f32 = 120.0;
+/// Refletor, três rotores, plugboard e teclado.
+const COLUMNS: usize = 6;
+const KEYBOARD: usize = 5;
+const FORWARD_COLOR: &str = "#c0392b";
+const BACKWARD_COLOR: &str = "#2471a3";
+
+/// Coluna do diagrama de cada componente.
+fn column(component: &Component) -> usize {
+    match component {
+        Component::Reflector { .. } => 0,
+        Component::Rotor { slot: RotorSlot::Left, .. } => 1,
+        Component::Rotor { slot: RotorSlot::Middle, .. } => 2,
+        Component::Rotor { slot: RotorSlot::Right, .. } => 3,
+        Component::Plugboard | Component::EntryWheel => 4,
+    }
+}
+
+fn left(column: usize) -> f32 {
+    MARGIN + column as f32 * (COLUMN_WIDTH + COLUMN_GAP)
+}
+
+fn right(column: usize) -> f32 {
+    left(column) + COLUMN_WIDTH
+}
+
+fn center(column: usize) -> f32 {
+    left(column) + COLUMN_WIDTH / 2.0
+}
+
+/// Altura do contato da letra `c`.
+fn y(c: char) -> f32 {
+    TOP + (c as u8 - b'A') as f32 * ROW + ROW / 2.0
+}
+
+fn polyline(svg: &mut String, points: &[(f32, f32)], color: &str) {
+    let points: Vec<String> = points.iter().map(|(x, y)| format!("{:.1},{:.1}", x, y)).collect();
+    let _ = writeln!(
+        svg,
+        r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="3" stroke-linejoin="round"/>"#,
+        points.join(" "),
+        color
+    );
+}
+
+/// SVG do caminho de `step`. As janelas dos rotores mostram as posições depois do
+/// passo, que são as usadas pelo sinal.
+pub fn step_svg(step: &EncryptionStep) -> String {
+    let width = 2.0 * MARGIN + COLUMNS as f32 * COLUMN_WIDTH + (COLUMNS - 1) as f32 * COLUMN_GAP;
+    let height = TOP + 26.0 * ROW + MARGIN;
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}""#,
+            r#" font-family="monospace">"#
+        ),
+        w = width,
+        h = height
+    );
+    let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+
+    // Colunas com os títulos e os contatos
+    let (l, m, r) = step.positions_after_step;
+    let mut titles = [String::new(), String::new(), String::new(), String::new(), "Plug".into(), "Teclas".into()];
+    for entry in &step.path {
+        match &entry.component {
+            Component::Reflector { name } => titles[0] = format!("UKW {}", name),
+            Component::Rotor { slot, name } => {
+                let window = match slot {
+                    RotorSlot::Left => l,
+                    RotorSlot::Middle => m,
+                    RotorSlot::Right => r,
+                };
+                titles[column(&entry.component)] = format!("{} [{}]", name, window);
+            }
+            _ => {}
+        }
+    }
+    for (i, title) in titles.iter().enumerate() {
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" font-size="14" text-anchor="middle" font-weight="bold">{}</text>"#,
+            center(i),
+            TOP - 20.0,
+            title
+        );
+        let _ = writeln!(
+            svg,
+            r##"<rect x="{:.1}" y="{:.1}" width="{}" height="{}" fill="#f4f1ea" stroke="#555"/>"##,
+            left(i),
+            TOP,
+            COLUMN_WIDTH,
+            26.0 * ROW
+        );
+        for c in 'A'..='Z' {
+            let _ = writeln!(
+                svg,
+                r##"<text x="{:.1}" y="{:.1}" font-size="11" text-anchor="middle" fill="#777">{}</text>"##,
+                center(i),
+                y(c) + 4.0,
+                c
+            );
+        }
+    }
+
+    // Caminho: cada componente liga o contato de entrada ao de saída
+    let mut forward = vec![(center(KEYBOARD), y(step.input_char))];
+    let mut backward = Vec::new();
+    for entry in &step.path {
+        let i = column(&entry.component);
+        let (input, output) = (y(entry.input_char), y(entry.output_char));
+        match entry.direction {
+            PathDirection::Forward => forward.extend([(right(i), input), (left(i), output)]),
+            PathDirection::Reflect => {
+                forward.extend([(right(i), input), (center(i), input)]);
+                backward.extend([(center(i), input), (center(i), output), (right(i), output)]);
+            }
+            PathDirection::Backward => backward.extend([(left(i), input), (right(i), output)]),
+        }
+    }
+    backward.push((center(KEYBOARD), y(step.output_char)));
+    polyline(&mut svg, &forward, FORWARD_COLOR);
+    polyline(&mut svg, &backward, BACKWARD_COLOR);
+
+    // Tecla e lâmpada
+    for (c, color) in [(step.input_char, FORWARD_COLOR), (step.output_char, BACKWARD_COLOR)] {
+        let _ = writeln!(
+            svg,
+            r#"<circle cx="{x:.1}" cy="{y:.1}" r="8" fill="{color}"/>"#,
+            x = center(KEYBOARD),
+            y = y(c),
+            color = color
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" font-size="11" text-anchor="middle" fill="white">{}</text>"#,
+            center(KEYBOARD),
+            y(c) + 4.0,
+            c
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Tipo de um nó do grafo da máquina.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Key,
+    Plug,
+    Rotor,
+    Reflector,
+}
+
+/// Um contato do diagrama.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphNode {
+    /// Ex.: "key:A", "plug_in:A", "right_out:C", "reflector:Y". Os componentes com
+    /// dois lados têm `_in` do lado do teclado e `_out` do lado do refletor.
+    pub id: String,
+    pub kind: NodeKind,
+    pub slot: Option<RotorSlot>,
+    /// Letra da linha onde o contato está agora (A no alto).
+    pub letter: char,
+    /// Contato físico (0-25): nos rotores, conta a posição e o anel.
+    pub contact: u8,
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Tipo de uma ligação do grafo.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    /// Da tecla ao plugboard.
+    Key,
+    /// Dentro do plugboard: reto ou por um cabo.
+    Plug,
+    /// Fiação interna de um rotor.
+    Rotor,
+    /// Fiação do refletor, entre dois contatos dele.
+    Reflector,
+    /// Contato de um componente encostado no do vizinho, na mesma linha.
+    Contact,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+}
+
+/// A máquina inteira como grafo, com as coordenadas do mesmo desenho do SVG.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MachineGraph {
+    pub width: f32,
+    pub height: f32,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+fn node_id(prefix: &str, row: u8) -> String {
+    format!("{}:{}", prefix, (b'A' + row) as char)
+}
+
+/// Grafo da máquina de `config` (já validada) na posição inicial dela: nós para
+/// as teclas e os contatos de cada componente, ligações para toda a fiação.
+pub fn machine_graph(config: &EnigmaConfig) -> MachineGraph {
+    let machine = EnigmaMachine::new(config.clone());
+    let wirings = machine.rotor_wirings();
+    let plugboard: Vec<u8> = machine.plugboard_wiring().chars().map(char_to_u8).collect();
+    let reflector: Vec<u8> = machine.reflector_wiring().chars().map(char_to_u8).collect();
+    let rotors = [
+        (RotorSlot::Right, "right", &config.rotors.0, &wirings.right, 3),
+        (RotorSlot::Middle, "middle", &config.rotors.1, &wirings.middle, 2),
+        (RotorSlot::Left, "left", &config.rotors.2, &wirings.left, 1),
+    ];
+
+    let mut nodes = Vec::with_capacity(26 * 10);
+    let mut edges = Vec::with_capacity(26 * 9 + 13);
+    let mut node = |id: String, kind, slot, row: u8, contact, x| {
+        let letter = (b'A' + row) as char;
+        nodes.push(GraphNode { id, kind, slot, letter, contact, x, y: y(letter) });
+    };
+    let mut edge = |from, to, kind| edges.push(GraphEdge { from, to, kind });
+
+    for row in 0..26 {
+        node(node_id("key", row), NodeKind::Key, None, row, row, center(KEYBOARD));
+        node(node_id("plug_in", row), NodeKind::Plug, None, row, row, right(4));
+        node(node_id("plug_out", row), NodeKind::Plug, None, row, row, left(4));
+        edge(node_id("key", row), node_id("plug_in", row), EdgeKind::Key);
+        edge(node_id("plug_in", row), node_id("plug_out", plugboard[row as usize]), EdgeKind::Plug);
+    }
+    let mut previous = "plug_out".to_string();
+    for (slot, name, rotor, wiring, column) in rotors {
+        let offset = (char_to_u8(rotor.position) + 26 - char_to_u8(rotor.ring)) % 26;
+        let (input, output) = (format!("{}_in", name), format!("{}_out", name));
+        for (row, out) in (0..26).zip(wiring.chars().map(char_to_u8)) {
+            let contact = (row + offset) % 26;
+            node(node_id(&input, row), NodeKind::Rotor, Some(slot), row, contact, right(column));
+            node(node_id(&output, row), NodeKind::Rotor, Some(slot), row, contact, left(column));
+            edge(node_id(&previous, row), node_id(&input, row), EdgeKind::Contact);
+            edge(node_id(&input, row), node_id(&output, out), EdgeKind::Rotor);
+        }
+        previous = output;
+    }
+    for row in 0..26 {
+        node(node_id("reflector", row), NodeKind::Reflector, None, row, row, right(0));
+        edge(node_id(&previous, row), node_id("reflector", row), EdgeKind::Contact);
+        if row < reflector[row as usize] {
+            edge(node_id("reflector", row), node_id("reflector", reflector[row as usize]), EdgeKind::Reflector);
+        }
+    }
+
+    MachineGraph {
+        width: 2.0 * MARGIN + COLUMNS as f32 * COLUMN_WIDTH + (COLUMNS - 1) as f32 * COLUMN_GAP,
+        height: TOP + 26.0 * ROW + MARGIN,
+        nodes,
+        edges,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enigma::{EnigmaConfig, EnigmaMachine, RotorConfig};
+
+    #[test]
+    fn test_step_svg_draws_both_halves() {
+        let rotor = |name: &str| RotorConfig { name: name.to_string(), position: 'A', ring: 'A' };
+        let config = EnigmaConfig {
+            rotors: (rotor("III"), rotor("II"), rotor("I")),
+            reflector: "B".into(),
+            plugboard_pairs: "AQ".into(),
+        };
+        let step = EnigmaMachine::new(config).process_string_detailed("A").remove(0);
+        let svg = step_svg(&step);
+        assert!(svg.starts_with("<svg ") && svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert!(svg.contains(">UKW B<") && svg.contains(">III [B]<") && svg.contains(">I [A]<"));
+        // Tecla, 2 pontos por rotor e no plugboard na ida, 2 no refletor
+        let forward = svg.lines().find(|l| l.contains(FORWARD_COLOR) && l.starts_with("<polyline")).unwrap();
+        let points = forward.split('"').nth(1).unwrap().split(' ').count();
+        assert_eq!(points, 1 + 2 * 4 + 2);
+    }
+
+    #[test]
+    fn test_machine_graph_follows_the_signal() {
+        let config = EnigmaConfig::from_share_code("ENIGMA:I.II.III:B:AAB:AAA:AQ").unwrap();
+        let graph = machine_graph(&config);
+        assert_eq!(graph.nodes.len(), 26 * 10);
+        assert_eq!(graph.edges.len(), 26 * 9 + 13);
+        let node = |id: &str| graph.nodes.iter().find(|node| node.id == id).unwrap();
+        assert_eq!(node("right_in:A").contact, 1);
+        assert_eq!((node("plug_out:Q").x, node("plug_out:Q").y), (left(4), y('Q')));
+
+        // Seguir as ligações da tecla A até o refletor passa pelos mesmos contatos
+        // que o caminho do sinal na primeira tecla (com o rotor da direita em B).
+        let mut id = "key:A".to_string();
+        let mut visited = Vec::new();
+        while !id.starts_with("reflector") {
+            id = graph.edges.iter().find(|edge| edge.from == id).unwrap().to.clone();
+            visited.push(id.clone());
+        }
+        let config = EnigmaConfig::from_share_code("ENIGMA:I.II.III:B:AAA:AAA:AQ").unwrap();
+        let step = EnigmaMachine::new(config).process_string_detailed("A").remove(0);
+        let reflector = step.path.iter().find(|entry| matches!(entry.direction, PathDirection::Reflect)).unwrap();
+        assert_eq!(id, format!("reflector:{}", reflector.input_char));
+        assert_eq!(visited[1], "plug_out:Q");
+    }
+}
@@ -0,0 +1,252 @@
+//! Exercícios para distribuir: um texto cifrado, as ferramentas de criptoanálise
+//! liberadas, a chave escondida e as regras de correção, num arquivo JSON que o
+//! professor grava numa máquina e os alunos abrem em outras.
+//!
+//! O arquivo leva um HMAC-SHA256 do exercício com um segredo do curso, conferido
+//! no backend ao abrir: um exercício corrompido, editado por engano ou assinado
+//! com outro segredo é recusado. Aberto, o exercício vira um desafio (ver
+//! `challenge`) e é corrigido por ele, igual em toda máquina.
+//!
+//! Isso não protege contra o próprio aluno. A chave vai no arquivo em claro (a
+//! correção precisa dela para decifrar o texto e dar as dicas), e o segredo do
+//! curso precisa estar na máquina do aluno para conferir a assinatura: quem abrir
+//! o JSON lê a resposta, e quem tiver o segredo assina um exercício alterado.
+//! Serve para exercícios de aula, não para provas; `NOTICE` diz isso na UI.
+
+use std::io::BufWriter;
+use std::path::Path;
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::enigma::{EnigmaConfig, EnigmaMachine};
+use crate::quiz::Difficulty;
+use crate::schema::{self, Kind};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Aviso mostrado na UI ao abrir um exercício.
+pub const NOTICE: &str = "A chave do exercício está no arquivo, sem cifragem, e o segredo do curso fica na \
+    máquina de cada aluno: a assinatura só recusa arquivos corrompidos ou de outro curso. Use para praticar, \
+    não para avaliar.";
+
+/// Ferramenta que o exercício libera na UI, além do simulador.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Tool {
+    Frequency,
+    IndexOfCoincidence,
+    BruteForce,
+    HillClimb,
+    KnownPlaintext,
+    Bombe,
+    Banburismus,
+}
+
+/// Como as soluções do exercício são corrigidas.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct GradingRules {
+    /// Fração mínima das letras do texto claro que precisam estar certas (1.0
+    /// exige todas).
+    pub min_accuracy: f64,
+    /// Aceita como solução uma chave que decifre o texto, e não só o texto claro.
+    pub accept_key: bool,
+    /// As dicas do desafio podem ser pedidas.
+    pub hints: bool,
+    /// Tentativas permitidas; `None` é sem limite.
+    pub max_attempts: Option<usize>,
+}
+
+impl Default for GradingRules {
+    fn default() -> Self {
+        GradingRules { min_accuracy: 1.0, accept_key: true, hints: true, max_attempts: None }
+    }
+}
+
+/// Um exercício, como vai no arquivo.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Exercise {
+    pub title: String,
+    #[serde(default)]
+    pub instructions: String,
+    pub difficulty: Difficulty,
+    pub ciphertext: String,
+    /// Palavra com que o texto claro começa, se o exercício der uma.
+    #[serde(default)]
+    pub crib: Option<String>,
+    #[serde(default)]
+    pub tools: Vec<Tool>,
+    /// Chave que cifrou o texto. Não vai para a UI, mas fica legível no arquivo.
+    pub key: EnigmaConfig,
+    #[serde(default)]
+    pub grading: GradingRules,
+}
+
+/// Exercício montado pelo professor na UI: o texto claro no lugar do cifrado.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExerciseDraft {
+    pub title: String,
+    #[serde(default)]
+    pub instructions: String,
+    pub difficulty: Difficulty,
+    pub plaintext: String,
+    #[serde(default)]
+    pub crib: Option<String>,
+    #[serde(default)]
+    pub tools: Vec<Tool>,
+    pub key: EnigmaConfig,
+    #[serde(default)]
+    pub grading: GradingRules,
+}
+
+/// Conteúdo do arquivo: o exercício e o HMAC dele, em hexadecimal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SignedExercise {
+    exercise: Exercise,
+    hmac: String,
+}
+
+impl Exercise {
+    /// Cifra o texto claro do rascunho com a chave dele.
+    pub fn from_draft(draft: ExerciseDraft) -> Result<Self, String> {
+        if draft.title.trim().is_empty() {
+            return Err("Dê um título ao exercício.".to_string());
+        }
+        if !(0.0..=1.0).contains(&draft.grading.min_accuracy) {
+            return Err("A fração mínima de letras certas deve estar entre 0 e 1.".to_string());
+        }
+        let key = draft.key.normalized()?;
+        let ciphertext = EnigmaMachine::new(key.clone()).process_string(&draft.plaintext);
+        if ciphertext.is_empty() {
+            return Err("O texto claro do exercício não tem letras.".to_string());
+        }
+        let mut tools = draft.tools;
+        tools.sort();
+        tools.dedup();
+        Ok(Exercise {
+            title: draft.title,
+            instructions: draft.instructions,
+            difficulty: draft.difficulty,
+            ciphertext,
+            crib: draft.crib.filter(|crib| !crib.trim().is_empty()),
+            tools,
+            key,
+            grading: draft.grading,
+        })
+    }
+
+    fn mac(&self, secret: &str) -> Result<HmacSha256, String> {
+        if secret.is_empty() {
+            return Err("Informe o segredo do curso.".to_string());
+        }
+        let bytes = serde_json::to_vec(self).map_err(|e| format!("Erro ao gerar o exercício: {}", e))?;
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).map_err(|e| e.to_string())?;
+        mac.update(&bytes);
+        Ok(mac)
+    }
+
+    /// HMAC-SHA256 do exercício com `secret`, em hexadecimal.
+    pub fn sign(&self, secret: &str) -> Result<String, String> {
+        Ok(self.mac(secret)?.finalize().into_bytes().iter().map(|byte| format!("{:02x}", byte)).collect())
+    }
+
+    /// Confere o HMAC `hmac` (em hexadecimal) do exercício.
+    pub fn verify(&self, secret: &str, hmac: &str) -> Result<(), String> {
+        let tag = from_hex(hmac).ok_or("Assinatura do exercício inválida.")?;
+        self.mac(secret)?
+            .verify_slice(&tag)
+            .map_err(|_| "Exercício alterado ou assinado com outro segredo.".to_string())
+    }
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    text.as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [high, low] => Some((hex_digit(*high)? << 4) | hex_digit(*low)?),
+            _ => None,
+        })
+        .collect()
+}
+
+fn hex_digit(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|digit| digit as u8)
+}
+
+/// Grava `exercise` em `path`, assinado com `secret`.
+pub fn export(path: &Path, exercise: &Exercise, secret: &str) -> Result<(), String> {
+    let signed = SignedExercise { exercise: exercise.clone(), hmac: exercise.sign(secret)? };
+    let file = std::fs::File::create(path).map_err(|e| format!("Erro ao salvar o exercício: {}", e))?;
+    serde_json::to_writer_pretty(BufWriter::new(file), &schema::to_value(&signed, Kind::Exercise)?)
+        .map_err(|e| format!("Erro ao salvar o exercício: {}", e))
+}
+
+/// Abre um exercício gravado por `export`, conferindo a assinatura com `secret`
+/// e a chave.
+pub fn import(path: &Path, secret: &str) -> Result<Exercise, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Erro ao abrir o exercício: {}", e))?;
+    let value = serde_json::from_str(&text).map_err(|e| format!("Exercício inválido: {}", e))?;
+    let signed: SignedExercise = schema::from_value(value, Kind::Exercise)?;
+    signed.exercise.verify(secret, &signed.hmac)?;
+    let mut exercise = signed.exercise;
+    exercise.key = exercise.key.normalized()?;
+    Ok(exercise)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draft() -> ExerciseDraft {
+        ExerciseDraft {
+            title: "Boletim do tempo".to_string(),
+            instructions: "Use o crib para achar a chave.".to_string(),
+            difficulty: Difficulty::Medium,
+            plaintext: "WETTERBERICHTXWINDSTAERKEXVIER".to_string(),
+            crib: Some("WETTER".to_string()),
+            tools: vec![Tool::KnownPlaintext, Tool::Frequency, Tool::KnownPlaintext],
+            key: EnigmaConfig::from_share_code("ENIGMA:II.IV.V:B:BLA:BUL:AVBSCGDLFUHZ").unwrap(),
+            grading: GradingRules { min_accuracy: 0.9, max_attempts: Some(3), ..GradingRules::default() },
+        }
+    }
+
+    #[test]
+    fn test_signature_detects_changes_and_other_secrets() {
+        let exercise = Exercise::from_draft(draft()).unwrap();
+        assert_eq!(exercise.tools, [Tool::Frequency, Tool::KnownPlaintext]);
+        assert_eq!(exercise.ciphertext.len(), 30);
+
+        let hmac = exercise.sign("curso-1941").unwrap();
+        assert_eq!(hmac.len(), 64);
+        assert!(exercise.verify("curso-1941", &hmac).is_ok());
+        assert!(exercise.verify("curso-1942", &hmac).unwrap_err().contains("outro segredo"));
+        assert!(exercise.verify("curso-1941", "zz").is_err());
+
+        let mut lenient = exercise.clone();
+        lenient.grading.min_accuracy = 0.1;
+        assert!(lenient.verify("curso-1941", &hmac).is_err());
+        assert!(exercise.sign("").is_err());
+    }
+
+    #[test]
+    fn test_export_and_import_round_trip() {
+        let dir = std::env::temp_dir().join(format!("enigma-exercise-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("boletim.json");
+        let exercise = Exercise::from_draft(draft()).unwrap();
+        export(&path, &exercise, "curso-1941").unwrap();
+        assert_eq!(import(&path, "curso-1941").unwrap(), exercise);
+        assert!(import(&path, "outro").is_err());
+
+        let tampered = std::fs::read_to_string(&path).unwrap().replace("Boletim", "Boletim falso");
+        std::fs::write(&path, tampered).unwrap();
+        assert!(import(&path, "curso-1941").unwrap_err().contains("alterado"));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(Exercise::from_draft(ExerciseDraft { title: " ".to_string(), ..draft() }).is_err());
+        let grading = GradingRules { min_accuracy: 1.5, ..GradingRules::default() };
+        assert!(Exercise::from_draft(ExerciseDraft { grading, ..draft() }).is_err());
+    }
+}
@@ -0,0 +1,220 @@
+//! Cifragem de arquivos de texto inteiros, para documentos grandes demais para
+//! copiar e colar.
+//!
+//! O arquivo passa pela máquina em blocos, sem ser carregado todo na memória;
+//! depois de cada bloco o progresso (bytes lidos do total) é repassado a quem
+//! chamou. Os outros caracteres são descartados (como em
+//! `EnigmaMachine::process_string`) ou mantidos no lugar, conforme `CharPolicy`.
+
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::enigma::{EnigmaConfig, EnigmaMachine, OutputFormat};
+
+/// Tamanho dos blocos lidos do arquivo.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Andamento da cifragem de um arquivo.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FileProgress {
+    pub path: PathBuf,
+    /// Bytes já lidos.
+    pub done: u64,
+    /// Tamanho do arquivo.
+    pub total: u64,
+}
+
+/// Arquivo solto na janela e o texto cifrado dele.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DroppedFile {
+    pub path: PathBuf,
+    pub output: String,
+}
+
+/// O que fazer com o que não é letra de A a Z.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CharPolicy {
+    /// Descarta: só as letras cifradas, em maiúsculas, como numa transmissão.
+    #[default]
+    Drop,
+    /// Mantém espaços, pontuação e quebras de linha no lugar, e a caixa das letras.
+    Keep,
+}
+
+/// Opções de `encrypt_file`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct FileOptions {
+    pub non_letters: CharPolicy,
+    /// Grupos, linhas e preâmbulo da saída. Só com `CharPolicy::Drop`.
+    pub format: Option<OutputFormat>,
+}
+
+/// Quantas letras `input` tem até o fim.
+fn count_letters(input: &mut impl Read) -> Result<usize, String> {
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut letters = 0;
+    loop {
+        match input.read(&mut buffer).map_err(|e| format!("Erro ao ler o arquivo: {}", e))? {
+            0 => return Ok(letters),
+            read => letters += buffer[..read].iter().filter(|b| b.is_ascii_alphabetic()).count(),
+        }
+    }
+}
+
+/// Cifra o que `input` tiver até o fim e escreve em `output`. `progress` recebe os
+/// bytes lidos depois de cada bloco. Retorna quantas letras foram cifradas.
+fn encrypt_stream(
+    input: &mut impl Read,
+    output: &mut impl Write,
+    machine: &mut EnigmaMachine,
+    options: &FileOptions,
+    mut progress: impl FnMut(u64),
+) -> Result<usize, String> {
+    let write_error = |e: std::io::Error| format!("Erro ao gravar o arquivo cifrado: {}", e);
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut encrypted = Vec::with_capacity(CHUNK_SIZE * 2);
+    let (mut letters, mut done) = (0, 0);
+    loop {
+        let read = input.read(&mut buffer).map_err(|e| format!("Erro ao ler o arquivo: {}", e))?;
+        if read == 0 {
+            output.flush().map_err(write_error)?;
+            return Ok(letters);
+        }
+        let chunk = &buffer[..read];
+        let mut cipher: Vec<u8> =
+            chunk.iter().filter(|b| b.is_ascii_alphabetic()).map(|b| b.to_ascii_uppercase() - b'A').collect();
+        machine.process_bytes(&mut cipher);
+        let mut cipher = cipher.into_iter().map(|c| c + b'A');
+        encrypted.clear();
+        match options.non_letters {
+            CharPolicy::Drop => {
+                for (i, c) in cipher.enumerate() {
+                    if let Some(separator) = options.format.as_ref().and_then(|f| f.separator(letters + i)) {
+                        encrypted.push(separator as u8);
+                    }
+                    encrypted.push(c);
+                }
+            }
+            CharPolicy::Keep => {
+                for &b in chunk {
+                    encrypted.push(match b {
+                        b'a'..=b'z' => cipher.next().unwrap_or(b).to_ascii_lowercase(),
+                        b'A'..=b'Z' => cipher.next().unwrap_or(b),
+                        _ => b,
+                    });
+                }
+            }
+        }
+        letters += chunk.iter().filter(|b| b.is_ascii_alphabetic()).count();
+        output.write_all(&encrypted).map_err(write_error)?;
+        done += read as u64;
+        progress(done);
+    }
+}
+
+/// Cifra o arquivo `in_path` com a chave `config` e grava o resultado em
+/// `out_path`. `progress` recebe os bytes lidos e o total depois de cada bloco.
+/// Retorna quantas letras foram cifradas.
+pub fn encrypt_file(
+    config: EnigmaConfig,
+    in_path: &Path,
+    out_path: &Path,
+    options: &FileOptions,
+    mut progress: impl FnMut(u64, u64),
+) -> Result<usize, String> {
+    if options.format.is_some() && options.non_letters == CharPolicy::Keep {
+        return Err("Os grupos de letras só valem descartando os outros caracteres.".to_string());
+    }
+    if in_path == out_path {
+        return Err("Escolha outro arquivo para gravar o texto cifrado.".to_string());
+    }
+    let mut machine = EnigmaMachine::new(config.normalized()?);
+    let open = || File::open(in_path).map_err(|e| format!("Erro ao abrir {}: {}", in_path.display(), e));
+    let mut input = open()?;
+    let total = input.metadata().map_err(|e| format!("Erro ao abrir {}: {}", in_path.display(), e))?.len();
+    let file = File::create(out_path).map_err(|e| format!("Erro ao criar {}: {}", out_path.display(), e))?;
+    let mut output = BufWriter::new(file);
+    // O preâmbulo traz o número de letras: conta antes de cifrar
+    if let Some(format) = &options.format {
+        if format.preamble.is_some() {
+            let letters = count_letters(&mut open()?)?;
+            let preamble = format.preamble_line(letters).unwrap_or_default();
+            output.write_all(preamble.as_bytes()).map_err(|e| format!("Erro ao gravar o arquivo cifrado: {}", e))?;
+        }
+    }
+    encrypt_stream(&mut input, &mut output, &mut machine, options, |done| progress(done, total))
+}
+
+/// Cifra as letras do arquivo `.txt` em `path` com a chave `config` e retorna o
+/// resultado. `progress` recebe os bytes lidos e o total depois de cada bloco.
+pub fn encrypt_txt(path: &Path, config: EnigmaConfig, mut progress: impl FnMut(u64, u64)) -> Result<String, String> {
+    let is_txt = path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("txt"));
+    if !is_txt {
+        return Err(format!("Só arquivos .txt podem ser cifrados: {}", path.display()));
+    }
+    let mut machine = EnigmaMachine::new(config.normalized()?);
+    let mut file = File::open(path).map_err(|e| format!("Erro ao abrir {}: {}", path.display(), e))?;
+    let total = file.metadata().map_err(|e| format!("Erro ao abrir {}: {}", path.display(), e))?.len();
+    let mut output = Vec::new();
+    let options = FileOptions::default();
+    encrypt_stream(&mut file, &mut output, &mut machine, &options, |done| progress(done, total))?;
+    // Só letras de A a Z saem com `CharPolicy::Drop`
+    Ok(String::from_utf8(output).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_txt_reports_progress() {
+        let config = EnigmaConfig::from_share_code("ENIGMA:I.II.III:B:ABC:AAA:ABCD").unwrap();
+        let text = "Wetterbericht, 0600 Uhr: klar.\n".repeat(4000);
+        let path = std::env::temp_dir().join(format!("enigma-file-cipher-{}.txt", std::process::id()));
+        std::fs::write(&path, &text).unwrap();
+        let mut reports = Vec::new();
+        let output = encrypt_txt(&path, config.clone(), |done, total| reports.push((done, total))).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(output, EnigmaMachine::new(config.clone()).process_string(&text));
+        assert_eq!(reports.len(), text.len().div_ceil(CHUNK_SIZE));
+        assert_eq!(reports.last(), Some(&(text.len() as u64, text.len() as u64)));
+        assert!(encrypt_txt(Path::new("relatorio.pdf"), config, |_, _| {}).is_err());
+    }
+
+    #[test]
+    fn test_encrypt_file_policies() {
+        let config = EnigmaConfig::from_share_code("ENIGMA:I.II.III:B:ABC:AAA:ABCD").unwrap();
+        let dir = std::env::temp_dir();
+        let in_path = dir.join(format!("enigma-in-{}.txt", std::process::id()));
+        let out_path = dir.join(format!("enigma-out-{}.txt", std::process::id()));
+        let plain = "Angriff um 0600, Nord!\n";
+        std::fs::write(&in_path, plain).unwrap();
+        let expected = EnigmaMachine::new(config.clone()).process_string("ANGRIFFUMNORD");
+
+        let keep = FileOptions { non_letters: CharPolicy::Keep, format: None };
+        assert_eq!(encrypt_file(config.clone(), &in_path, &out_path, &keep, |_, _| {}).unwrap(), 13);
+        let kept = std::fs::read_to_string(&out_path).unwrap();
+        assert_eq!(kept.chars().filter(char::is_ascii_alphabetic).collect::<String>().to_uppercase(), expected);
+        for (original, encrypted) in plain.chars().zip(kept.chars()) {
+            assert_eq!(original.is_ascii_lowercase(), encrypted.is_ascii_lowercase());
+            assert!(original.is_ascii_alphabetic() || original == encrypted);
+        }
+
+        let format = OutputFormat { group_size: 5, groups_per_line: 2, preamble: Some("{letters} =".into()) };
+        let grouped = FileOptions { non_letters: CharPolicy::Drop, format: Some(format.clone()) };
+        encrypt_file(config.clone(), &in_path, &out_path, &grouped, |_, _| {}).unwrap();
+        assert_eq!(std::fs::read_to_string(&out_path).unwrap(), format.apply(&expected));
+
+        let invalid = FileOptions { non_letters: CharPolicy::Keep, format: Some(format) };
+        assert!(encrypt_file(config.clone(), &in_path, &out_path, &invalid, |_, _| {}).is_err());
+        assert!(encrypt_file(config, &in_path, &in_path, &keep, |_, _| {}).is_err());
+        std::fs::remove_file(&in_path).unwrap();
+        std::fs::remove_file(&out_path).unwrap();
+    }
+}
@@ -0,0 +1,322 @@
+//! Histórico das mensagens cifradas, num banco SQLite local, para responder "que
+//! chave eu usei naquela mensagem da semana passada?".
+//!
+//! Cada mensagem guarda o momento, a configuração (e a sua impressão digital), o
+//! resumo da entrada e a saída. A entrada em si não é gravada: para achar uma
+//! mensagem pelo texto claro, `search` compara o resumo.
+//!
+//! A UI cifra o texto inteiro a cada tecla. Enquanto o texto só cresce, com a
+//! mesma chave e sem uma pausa longa (`DRAFT_GAP`), `record` atualiza a mensagem
+//! que está sendo digitada em vez de gravar uma nova por tecla.
+
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use rusqlite::{params, Connection, Row};
+use serde::Serialize;
+
+use crate::enigma::{EnigmaConfig, RotorConfig};
+
+/// Uma mensagem do histórico.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub id: i64,
+    /// Momento da cifragem (segundos desde 1970).
+    pub timestamp: u64,
+    /// Impressão digital da configuração: iguais para a mesma chave.
+    pub fingerprint: String,
+    pub config: EnigmaConfig,
+    /// Resumo das letras da entrada.
+    pub input_digest: String,
+    pub output: String,
+}
+
+/// FNV-1a de 64 bits em hexadecimal. Não é criptográfico; só identifica.
+fn fnv1a(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, &b| (hash ^ b as u64).wrapping_mul(0x100_0000_01b3));
+    format!("{:016x}", hash)
+}
+
+/// Impressão digital de `config`, a mesma para maiúsculas e minúsculas.
+pub fn fingerprint(config: &EnigmaConfig) -> String {
+    let (rotors, positions, rings) = columns(config);
+    let key = format!("{}|{}|{}|{}|{}", rotors, config.reflector, positions, rings, config.plugboard_pairs);
+    fnv1a(key.to_ascii_uppercase().as_bytes())
+}
+
+/// Letras de `text` em maiúsculas, sem o resto, como a máquina as vê.
+fn letters(text: &str) -> String {
+    text.chars().filter(|c| c.is_ascii_alphabetic()).map(|c| c.to_ascii_uppercase()).collect()
+}
+
+/// Resumo das letras de `text`, ignorando o resto e as maiúsculas, como a máquina.
+pub fn input_digest(text: &str) -> String {
+    fnv1a(letters(text).as_bytes())
+}
+
+/// Rotores ("I II III"), posições e anéis, da esquerda para a direita.
+fn columns(config: &EnigmaConfig) -> (String, String, String) {
+    let (r, m, l) = &config.rotors;
+    let rotors = format!("{} {} {}", l.name, m.name, r.name);
+    (rotors, [l.position, m.position, r.position].iter().collect(), [l.ring, m.ring, r.ring].iter().collect())
+}
+
+/// Monta a configuração de volta das colunas gravadas por `record`.
+fn config_from_columns(
+    rotors: &str,
+    reflector: String,
+    positions: &str,
+    rings: &str,
+    plugboard: String,
+) -> EnigmaConfig {
+    let mut slots = rotors
+        .split(' ')
+        .zip(positions.chars().zip(rings.chars()))
+        .map(|(name, (position, ring))| RotorConfig { name: name.to_string(), position, ring });
+    let mut next = || slots.next().unwrap_or(RotorConfig { name: String::new(), position: 'A', ring: 'A' });
+    let (l, m, r) = (next(), next(), next());
+    EnigmaConfig { rotors: (r, m, l), reflector, plugboard_pairs: plugboard }
+}
+
+fn entry(row: &Row) -> rusqlite::Result<HistoryEntry> {
+    let rotors: String = row.get(3)?;
+    let positions: String = row.get(5)?;
+    let rings: String = row.get(6)?;
+    Ok(HistoryEntry {
+        id: row.get(0)?,
+        timestamp: row.get::<_, i64>(1)? as u64,
+        fingerprint: row.get(2)?,
+        config: config_from_columns(&rotors, row.get(4)?, &positions, &rings, row.get(7)?),
+        input_digest: row.get(8)?,
+        output: row.get(9)?,
+    })
+}
+
+const COLUMNS: &str =
+    "id, timestamp, fingerprint, rotors, reflector, positions, rings, plugboard, input_digest, output";
+
+/// Pausa depois da qual a mensagem em digitação é dada por terminada: um texto
+/// cifrado depois dela é outra mensagem, mesmo que comece igual.
+const DRAFT_GAP: Duration = Duration::from_secs(60);
+
+/// Mensagem que ainda está sendo digitada: a última gravada, com as suas letras.
+struct Draft {
+    id: i64,
+    fingerprint: String,
+    letters: String,
+    /// Quando ela foi gravada pela última vez.
+    at: Instant,
+}
+
+/// O banco do histórico aberto.
+pub struct History {
+    connection: Connection,
+    draft: Option<Draft>,
+}
+
+impl History {
+    /// Abre (ou cria) o banco em `path`.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Erro ao criar a pasta de dados: {}", e))?;
+        }
+        let connection = Connection::open(path).map_err(|e| format!("Erro ao abrir o histórico: {}", e))?;
+        Self::with_connection(connection)
+    }
+
+    /// Banco só em memória, para testes.
+    pub fn open_in_memory() -> Result<Self, String> {
+        let connection = Connection::open_in_memory().map_err(|e| format!("Erro ao abrir o histórico: {}", e))?;
+        Self::with_connection(connection)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, String> {
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS messages (
+                    id INTEGER PRIMARY KEY,
+                    timestamp INTEGER NOT NULL,
+                    fingerprint TEXT NOT NULL,
+                    rotors TEXT NOT NULL,
+                    reflector TEXT NOT NULL,
+                    positions TEXT NOT NULL,
+                    rings TEXT NOT NULL,
+                    plugboard TEXT NOT NULL,
+                    input_digest TEXT NOT NULL,
+                    output TEXT NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS messages_timestamp ON messages (timestamp);",
+            )
+            .map_err(|e| format!("Erro ao preparar o histórico: {}", e))?;
+        Ok(Self { connection, draft: None })
+    }
+
+    /// Registra a cifragem de `input` com `config`, que produziu `output`. Se
+    /// `input` continua a última mensagem gravada (mesma chave, mais letras no
+    /// fim, menos de `DRAFT_GAP` depois), atualiza essa mensagem. Retorna se uma
+    /// mensagem nova foi gravada.
+    pub fn record(&mut self, config: &EnigmaConfig, input: &str, output: &str) -> Result<bool, String> {
+        self.record_at(Instant::now(), config, input, output)
+    }
+
+    fn record_at(&mut self, now: Instant, config: &EnigmaConfig, input: &str, output: &str) -> Result<bool, String> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        let (fingerprint, letters) = (fingerprint(config), letters(input));
+        let continues = |draft: &&mut Draft| {
+            draft.fingerprint == fingerprint
+                && letters.starts_with(&draft.letters)
+                && now.saturating_duration_since(draft.at) < DRAFT_GAP
+        };
+        if let Some(draft) = self.draft.as_mut().filter(continues) {
+            let updated = self
+                .connection
+                .execute(
+                    "UPDATE messages SET timestamp = ?1, input_digest = ?2, output = ?3 WHERE id = ?4",
+                    params![timestamp as i64, fnv1a(letters.as_bytes()), output, draft.id],
+                )
+                .map_err(|e| format!("Erro ao gravar no histórico: {}", e))?;
+            if updated > 0 {
+                draft.letters = letters;
+                draft.at = now;
+                return Ok(false);
+            }
+        }
+        let (rotors, positions, rings) = columns(config);
+        self.connection
+            .execute(
+                "INSERT INTO messages (timestamp, fingerprint, rotors, reflector, positions, rings, plugboard,
+                    input_digest, output) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    timestamp as i64,
+                    fingerprint,
+                    rotors,
+                    config.reflector,
+                    positions,
+                    rings,
+                    config.plugboard_pairs,
+                    fnv1a(letters.as_bytes()),
+                    output
+                ],
+            )
+            .map_err(|e| format!("Erro ao gravar no histórico: {}", e))?;
+        self.draft = Some(Draft { id: self.connection.last_insert_rowid(), fingerprint, letters, at: now });
+        Ok(true)
+    }
+
+    /// Até `limit` mensagens, da mais recente para a mais antiga.
+    pub fn recent(&self, limit: usize) -> Result<Vec<HistoryEntry>, String> {
+        self.query(&format!("SELECT {} FROM messages ORDER BY id DESC LIMIT ?1", COLUMNS), params![limit as i64])
+    }
+
+    /// Mensagens em que `term` é o texto de entrada, aparece na saída ou é a
+    /// impressão digital da configuração, da mais recente para a mais antiga.
+    pub fn search(&self, term: &str, limit: usize) -> Result<Vec<HistoryEntry>, String> {
+        let pattern = format!("%{}%", term.trim().to_ascii_uppercase());
+        self.query(
+            &format!(
+                "SELECT {} FROM messages WHERE input_digest = ?1 OR output LIKE ?2 OR fingerprint = ?3
+                    ORDER BY id DESC LIMIT ?4",
+                COLUMNS
+            ),
+            params![input_digest(term), pattern, term.trim().to_ascii_lowercase(), limit as i64],
+        )
+    }
+
+    /// Apaga as mensagens anteriores a `before` (segundos desde 1970), ou todas se
+    /// `before` for `None`. Retorna quantas foram apagadas.
+    pub fn purge(&mut self, before: Option<u64>) -> Result<usize, String> {
+        self.draft = None;
+        let result = match before {
+            Some(before) => {
+                self.connection.execute("DELETE FROM messages WHERE timestamp < ?1", params![before as i64])
+            }
+            None => self.connection.execute("DELETE FROM messages", params![]),
+        };
+        result.map_err(|e| format!("Erro ao limpar o histórico: {}", e))
+    }
+
+    fn query(&self, sql: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<HistoryEntry>, String> {
+        let error = |e: rusqlite::Error| format!("Erro ao consultar o histórico: {}", e);
+        let mut statement = self.connection.prepare(sql).map_err(error)?;
+        let rows = statement.query_map(params, entry).map_err(error)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> EnigmaConfig {
+        let rotor = |name: &str, position| RotorConfig { name: name.to_string(), position, ring: 'A' };
+        EnigmaConfig {
+            rotors: (rotor("III", 'C'), rotor("II", 'B'), rotor("I", 'A')),
+            reflector: "B".into(),
+            plugboard_pairs: "AB CD".into(),
+        }
+    }
+
+    #[test]
+    fn test_digests_ignore_case_and_punctuation() {
+        assert_eq!(input_digest("Hello, world"), input_digest("HELLOWORLD"));
+        assert_ne!(input_digest("HELLO"), input_digest("HELLP"));
+        let mut lower = config();
+        lower.plugboard_pairs = "ab cd".into();
+        assert_eq!(fingerprint(&lower), fingerprint(&config()));
+        assert_eq!(fingerprint(&config()).len(), 16);
+
+        let (rotors, positions, rings) = columns(&config());
+        assert_eq!((rotors.as_str(), positions.as_str(), rings.as_str()), ("I II III", "ABC", "AAA"));
+        assert_eq!(config_from_columns(&rotors, "B".into(), &positions, &rings, "AB CD".into()), config());
+    }
+
+    #[test]
+    fn test_record_search_and_purge() {
+        let mut history = History::open_in_memory().unwrap();
+        history.record(&config(), "Wetter", "QBLTWL").unwrap();
+        history.record(&config(), "Hello", "MFNCZ").unwrap();
+
+        let recent = history.recent(10).unwrap();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].output, "MFNCZ");
+        assert_eq!(recent[0].config, config());
+
+        assert_eq!(history.search("wetter", 10).unwrap()[0].output, "QBLTWL");
+        assert_eq!(history.search("fnc", 10).unwrap()[0].output, "MFNCZ");
+        assert_eq!(history.search(&fingerprint(&config()), 10).unwrap().len(), 2);
+
+        assert_eq!(history.purge(Some(0)).unwrap(), 0);
+        assert_eq!(history.purge(None).unwrap(), 2);
+        assert!(history.recent(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_growing_text_updates_the_same_message() {
+        let mut history = History::open_in_memory().unwrap();
+        assert!(history.record(&config(), "H", "M").unwrap());
+        assert!(!history.record(&config(), "He", "MF").unwrap());
+        assert!(!history.record(&config(), "Hello", "MFNCZ").unwrap());
+        let recent = history.recent(10).unwrap();
+        assert_eq!((recent.len(), recent[0].output.as_str()), (1, "MFNCZ"));
+        assert_eq!(recent[0].input_digest, input_digest("HELLO"));
+
+        // Texto apagado e outra chave começam mensagens novas
+        assert!(history.record(&config(), "W", "Q").unwrap());
+        let mut other = config();
+        other.reflector = "C".into();
+        assert!(history.record(&other, "WE", "QB").unwrap());
+        assert_eq!(history.recent(10).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_text_after_a_pause_is_a_new_message() {
+        let mut history = History::open_in_memory().unwrap();
+        let start = Instant::now();
+        assert!(history.record_at(start, &config(), "Hello", "MFNCZ").unwrap());
+        assert!(!history.record_at(start + Duration::from_secs(5), &config(), "Hello w", "MFNCZQ").unwrap());
+        let later = start + Duration::from_secs(5) + DRAFT_GAP;
+        assert!(history.record_at(later, &config(), "Hello world", "MFNCZQBLTW").unwrap());
+        let recent = history.recent(10).unwrap();
+        assert_eq!((recent[0].output.as_str(), recent[1].output.as_str()), ("MFNCZQBLTW", "MFNCZQ"));
+    }
+}
@@ -0,0 +1,88 @@
+//! "Livro de códigos capturado": uma folha de chaves cifrada com senha, que o
+//! professor entrega e que só abre quando a aula chega ao ponto de revelar a senha.
+//!
+//! A chave vem da senha pelo Argon2id, com sal aleatório, e a folha (em JSON) é
+//! cifrada com XChaCha20-Poly1305, que também acusa senha errada ou arquivo
+//! alterado. Arquivo: `MAGIC`, sal, nonce e o texto cifrado.
+
+use std::path::Path;
+
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+
+use crate::pdf::KeySheet;
+
+/// Início de todo arquivo de chaves (com a versão do formato).
+const MAGIC: &[u8] = b"ENIGMAKEYS1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key, String> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Erro ao derivar a chave da senha: {}", e))?;
+    Ok(key)
+}
+
+/// Cifra `plaintext` com a senha.
+pub fn seal(passphrase: &str, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    if passphrase.is_empty() {
+        return Err("Escolha uma senha.".to_string());
+    }
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let cipher = XChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let ciphertext = cipher.encrypt(&nonce, plaintext).map_err(|_| "Erro ao cifrar as chaves.".to_string())?;
+    Ok([MAGIC, &salt, &nonce[..], &ciphertext].concat())
+}
+
+/// Decifra o que `seal` gerou.
+pub fn unseal(passphrase: &str, sealed: &[u8]) -> Result<Vec<u8>, String> {
+    let body = sealed.strip_prefix(MAGIC).ok_or("Não é um arquivo de chaves.")?;
+    if body.len() < SALT_LEN + NONCE_LEN {
+        return Err("Arquivo de chaves incompleto.".to_string());
+    }
+    let (salt, rest) = body.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let nonce: [u8; NONCE_LEN] = nonce.try_into().map_err(|_| "Arquivo de chaves incompleto.".to_string())?;
+    let cipher = XChaCha20Poly1305::new(&derive_key(passphrase, salt)?);
+    cipher
+        .decrypt(&XNonce::from(nonce), ciphertext)
+        .map_err(|_| "Senha errada ou arquivo de chaves alterado.".to_string())
+}
+
+/// Grava `sheet` cifrada com a senha em `path`.
+pub fn export(path: &Path, sheet: &KeySheet, passphrase: &str) -> Result<(), String> {
+    let json = serde_json::to_vec(sheet).map_err(|e| format!("Erro ao gerar o arquivo de chaves: {}", e))?;
+    std::fs::write(path, seal(passphrase, &json)?).map_err(|e| format!("Erro ao salvar o arquivo de chaves: {}", e))
+}
+
+/// Abre um arquivo gravado por `export`.
+pub fn import(path: &Path, passphrase: &str) -> Result<KeySheet, String> {
+    let sealed = std::fs::read(path).map_err(|e| format!("Erro ao abrir o arquivo de chaves: {}", e))?;
+    serde_json::from_slice(&unseal(passphrase, &sealed)?).map_err(|e| format!("Arquivo de chaves inválido: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_needs_the_right_passphrase() {
+        let sealed = seal("Kurbel", b"Walzenlage I II III").unwrap();
+        assert!(sealed.starts_with(MAGIC));
+        assert_eq!(unseal("Kurbel", &sealed).unwrap(), b"Walzenlage I II III");
+        assert!(unseal("kurbel", &sealed).unwrap_err().starts_with("Senha errada"));
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(unseal("Kurbel", &tampered).is_err());
+        assert!(unseal("Kurbel", &sealed[..MAGIC.len() + 8]).is_err());
+        assert!(unseal("Kurbel", b"PK\x03\x04").is_err());
+        assert!(seal("", b"x").is_err());
+    }
+}
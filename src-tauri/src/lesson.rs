@@ -0,0 +1,245 @@
+//! Lições guiadas: um roteiro em JSON com passos, a chave em que cada passo começa
+//! e o que o aluno precisa fazer na máquina ao vivo para seguir adiante.
+//!
+//! ```json
+//! {
+//!   "title": "O passo duplo",
+//!   "steps": [
+//!     {
+//!       "instruction": "Tecle uma letra e veja o rotor da direita chegar ao entalhe.",
+//!       "machine": {"rotors": [...], "reflector": "B", "plugboard_pairs": ""},
+//!       "expect": [{"check": "action", "action": "press"}, {"check": "positions", "positions": "ADV"}],
+//!       "hint": "O rotor da direita gira antes de cada letra."
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! O roteiro é interpretado aqui: a cada ação na máquina da janela (abrir, teclar,
+//! digitar um texto, voltar à chave inicial) as verificações do passo atual são
+//! conferidas com a ação e o estado da máquina depois dela, e a lição só avança
+//! quando todas passam.
+
+use serde::{Deserialize, Serialize};
+
+use crate::enigma::EnigmaConfig;
+use crate::session::Session;
+
+/// Ação do aluno na máquina.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Open,
+    Press,
+    Type,
+    Reset,
+}
+
+/// Uma verificação de um passo, no JSON com o tipo em `check`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "check", rename_all = "snake_case")]
+pub enum Check {
+    /// A ação foi esta.
+    Action { action: Action },
+    /// A última letra teclada foi `key`.
+    Key { key: char },
+    /// A última lâmpada acesa foi `lamp`.
+    Lamp { lamp: char },
+    /// As janelas mostram estas letras (L, M, R), ex.: "ADV".
+    Positions { positions: String },
+    /// O que foi teclado termina com `text`.
+    Input { text: String },
+    /// As lâmpadas acesas terminam com `text`.
+    Output { text: String },
+    /// A máquina usa os rotores `rotors`, da esquerda para a direita.
+    Rotors { rotors: Vec<String> },
+    /// A máquina usa o refletor `reflector`.
+    Reflector { reflector: String },
+}
+
+impl Check {
+    fn passes(&self, action: Action, session: &Session) -> bool {
+        let (right, middle, left) = &session.config.rotors;
+        match self {
+            Check::Action { action: expected } => *expected == action,
+            Check::Key { key } => action == Action::Press && session.input.ends_with(key.to_ascii_uppercase()),
+            Check::Lamp { lamp } => action == Action::Press && session.output.ends_with(lamp.to_ascii_uppercase()),
+            Check::Positions { positions } => {
+                let (l, m, r) = session.positions;
+                positions.eq_ignore_ascii_case(&[l, m, r].iter().collect::<String>())
+            }
+            Check::Input { text } => session.input.ends_with(&text.to_ascii_uppercase()),
+            Check::Output { text } => session.output.ends_with(&text.to_ascii_uppercase()),
+            Check::Rotors { rotors } => rotors.iter().eq([&left.name, &middle.name, &right.name]),
+            Check::Reflector { reflector } => *reflector == session.config.reflector,
+        }
+    }
+}
+
+/// Um passo da lição.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LessonStep {
+    pub instruction: String,
+    /// Chave em que a máquina da janela é aberta quando o passo começa. Sem ela, o
+    /// passo continua da máquina como está.
+    #[serde(default)]
+    pub machine: Option<EnigmaConfig>,
+    /// O que precisa ser verdade depois de uma ação para o passo terminar.
+    pub expect: Vec<Check>,
+    /// Dica mostrada quando uma ação não completa o passo.
+    #[serde(default)]
+    pub hint: Option<String>,
+}
+
+/// Roteiro de uma lição.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lesson {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub steps: Vec<LessonStep>,
+}
+
+/// Andamento da lição, para a UI, depois de começar ou de cada ação.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LessonProgress {
+    pub title: String,
+    /// Passo atual (a partir de 0); igual a `total` quando a lição termina.
+    pub step: usize,
+    pub total: usize,
+    /// Instrução do passo atual (vazia quando a lição termina).
+    pub instruction: String,
+    /// A ação completou um passo.
+    pub advanced: bool,
+    pub finished: bool,
+    /// Dica, quando a ação não completou o passo.
+    pub hint: Option<String>,
+    /// Chave em que a máquina deve ser aberta, quando o passo atual acabou de
+    /// começar e tem uma.
+    pub setup: Option<EnigmaConfig>,
+}
+
+/// Uma lição em andamento numa janela.
+#[derive(Debug, Clone)]
+pub struct LessonRun {
+    lesson: Lesson,
+    step: usize,
+}
+
+impl LessonRun {
+    /// Confere o roteiro e começa do primeiro passo.
+    pub fn new(mut lesson: Lesson) -> Result<Self, String> {
+        if lesson.steps.is_empty() {
+            return Err("A lição não tem passos.".to_string());
+        }
+        for (i, step) in lesson.steps.iter_mut().enumerate() {
+            if step.expect.is_empty() {
+                return Err(format!("O passo {} da lição não tem verificações.", i + 1));
+            }
+            if let Some(config) = step.machine.take() {
+                step.machine = Some(config.normalized().map_err(|e| format!("Passo {} da lição: {}", i + 1, e))?);
+            }
+        }
+        Ok(Self { lesson, step: 0 })
+    }
+
+    /// Lê um roteiro em JSON e começa a lição.
+    pub fn from_json(script: &str) -> Result<Self, String> {
+        let lesson = serde_json::from_str(script).map_err(|e| format!("Roteiro de lição inválido: {}", e))?;
+        Self::new(lesson)
+    }
+
+    /// Andamento atual. `entered` indica que o passo atual acabou de começar.
+    fn progress(&self, entered: bool, hint: Option<String>) -> LessonProgress {
+        let current = self.lesson.steps.get(self.step);
+        LessonProgress {
+            title: self.lesson.title.clone(),
+            step: self.step,
+            total: self.lesson.steps.len(),
+            instruction: current.map(|step| step.instruction.clone()).unwrap_or_default(),
+            advanced: entered && self.step > 0,
+            finished: current.is_none(),
+            hint,
+            setup: current.filter(|_| entered).and_then(|step| step.machine.clone()),
+        }
+    }
+
+    /// Andamento ao começar a lição, com a chave do primeiro passo.
+    pub fn start(&self) -> LessonProgress {
+        self.progress(true, None)
+    }
+
+    /// Andamento atual, sem chave a abrir.
+    pub fn current(&self) -> LessonProgress {
+        self.progress(false, None)
+    }
+
+    /// Confere a ação `action`, que deixou a máquina em `session`, com o passo
+    /// atual, avançando se ela o completa.
+    pub fn observe(&mut self, action: Action, session: &Session) -> LessonProgress {
+        let Some(step) = self.lesson.steps.get(self.step) else { return self.current() };
+        if step.expect.iter().all(|check| check.passes(action, session)) {
+            self.step += 1;
+            self.progress(true, None)
+        } else {
+            self.progress(false, step.hint.clone())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::Machine;
+
+    fn step(instruction: &str, expect: Vec<Check>) -> LessonStep {
+        LessonStep { instruction: instruction.to_string(), machine: None, expect, hint: None }
+    }
+
+    fn double_step() -> Lesson {
+        let positions = |positions: &str| Check::Positions { positions: positions.to_string() };
+        let mut first = step("Tecle uma letra.", vec![Check::Action { action: Action::Press }, positions("ADV")]);
+        first.machine = Some(EnigmaConfig::from_share_code("ENIGMA:I.II.III:B:ADU:AAA:").unwrap());
+        let rotors = Check::Rotors { rotors: vec!["I".to_string(), "II".to_string(), "III".to_string()] };
+        let instruction = "Tecle até os rotores da esquerda e do meio girarem juntos.";
+        let mut second = step(instruction, vec![positions("bfx"), rotors]);
+        second.hint = Some("O rotor do meio gira de novo quando chega ao entalhe.".to_string());
+        Lesson { title: "O passo duplo".to_string(), description: String::new(), steps: vec![first, second] }
+    }
+
+    #[test]
+    fn test_lesson_advances_only_on_the_expected_state() {
+        let mut run = LessonRun::new(double_step()).unwrap();
+        let start = run.start();
+        assert_eq!((start.step, start.total, start.advanced), (0, 2, false));
+        let mut machine = Machine::new(start.setup.unwrap()).unwrap();
+
+        machine.reset();
+        assert_eq!(run.observe(Action::Reset, &machine.session()).step, 0);
+        machine.press('A').unwrap();
+        let progress = run.observe(Action::Press, &machine.session());
+        assert!(progress.advanced && progress.setup.is_none());
+        assert_eq!(progress.step, 1);
+
+        machine.press('B').unwrap();
+        let progress = run.observe(Action::Press, &machine.session());
+        assert_eq!(progress.hint.as_deref(), Some("O rotor do meio gira de novo quando chega ao entalhe."));
+        machine.press('C').unwrap();
+        let progress = run.observe(Action::Press, &machine.session());
+        assert!(progress.finished && progress.advanced);
+        assert_eq!(progress.step, 2);
+    }
+
+    #[test]
+    fn test_invalid_scripts_are_rejected() {
+        let mut lesson = double_step();
+        lesson.steps[1].expect.clear();
+        assert!(LessonRun::new(lesson).unwrap_err().contains("passo 2"));
+        let mut lesson = double_step();
+        lesson.steps[0].machine.as_mut().unwrap().reflector = "Z".to_string();
+        assert!(LessonRun::new(lesson).unwrap_err().starts_with("Passo 1"));
+        lesson = Lesson { steps: Vec::new(), ..double_step() };
+        assert!(LessonRun::new(lesson).unwrap_err().contains("não tem passos"));
+        assert!(LessonRun::from_json("{").is_err());
+    }
+}
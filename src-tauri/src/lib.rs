@@ -3,36 +3,2371 @@
     windows_subsystem = "windows"
 )]
 
-// Declara o módulo enigma, que será definido em src/enigma.rs
-pub mod enigma;
+// A simulação da máquina fica no crate enigma-core (sem Tauri), reexportado como
+// `enigma` para o resto do app
+pub use enigma_core as enigma;
+// A criptoanálise fica no crate enigma-cryptanalysis (sem Tauri), que o enigma-cli
+// também usa
+pub use enigma_cryptanalysis as cryptanalysis;
+// Declara o benchmark dos caminhos de cifragem, definido em src/benchmark.rs
+pub mod benchmark;
+// Declara os arquivos de traço compacto, definidos em src/trace.rs
+pub mod trace;
+// Declara a rede de rádio entre duas instâncias, definida em src/radio.rs
+pub mod radio;
+// Declara as configurações salvas com nome, definidas em src/presets.rs
+pub mod presets;
+// Declara o histórico de configurações usadas, definido em src/recent.rs
+pub mod recent;
+// Declara o arquivo de área de trabalho, definido em src/workspace.rs
+pub mod workspace;
+// Declara o histórico de mensagens em SQLite, definido em src/history.rs
+pub mod history;
+// Declara o diagrama SVG do caminho do sinal, definido em src/diagram.rs
+pub mod diagram;
+// Declara a exportação em PDF de folhas de chaves e traços, definida em src/pdf.rs
+pub mod pdf;
+// Declara o QR code das configurações, definido em src/qr.rs
+pub mod qr;
+// Declara os links enigma://, definidos em src/deep_link.rs
+pub mod deep_link;
+// Declara a leitura de configurações de outros simuladores, definida em src/simulators.rs
+pub mod simulators;
+// Declara os arquivos de configuração em TOML e YAML, definidos em src/config_file.rs
+pub mod config_file;
+// Declara os arquivos de chaves cifrados com senha, definidos em src/key_file.rs
+pub mod key_file;
+// Declara o módulo da máquina com estado e do autosave, definido em src/session.rs
+pub mod session;
+// Declara a cifragem de arquivos de texto, definida em src/file_cipher.rs
+pub mod file_cipher;
+// Declara o registro (log) em arquivos, definido em src/logging.rs
+pub mod logging;
+// Declara os pacotes de peças extras, definidos em src/packs.rs
+pub mod packs;
+// Declara o catálogo remoto de pacotes, definido em src/catalog.rs
+pub mod catalog;
+// Declara a cifragem rápida pelo ícone da bandeja, definida em src/tray.rs
+pub mod tray;
+// Declara a versão do formato dos arquivos gravados, definida em src/schema.rs
+pub mod schema;
+// Declara os questionários com correção no backend, definidos em src/quiz.rs
+pub mod quiz;
+// Declara as lições guiadas na máquina ao vivo, definidas em src/lesson.rs
+pub mod lesson;
+// Declara o progresso nas lições, desafios e questionários, definido em src/progress.rs
+pub mod progress;
+// Declara os desafios com chave secreta e dicas, definidos em src/challenge.rs
+pub mod challenge;
+// Declara o modo sala de aula, com o servidor do professor, definido em src/classroom.rs
+pub mod classroom;
+// Declara os arquivos de exercício assinados, definidos em src/exercise.rs
+pub mod exercise;
+// Declara a gravação e a reprodução de demonstrações, definidas em src/replay.rs
+pub mod replay;
 
-use enigma::{EnigmaConfig, EncryptionStep};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use benchmark::BenchmarkReport;
+use catalog::Catalog as PackCatalog;
+use challenge::{ChallengeView, Challenges, ExerciseView, HintReveal, Solution, Verdict};
+use classroom::{Classroom, ClassroomSeat, StudentStatus, ToTeacher};
+use deep_link::DeepLink;
+use exercise::{Exercise, ExerciseDraft};
+use file_cipher::{DroppedFile, FileOptions, FileProgress};
+use history::{History, HistoryEntry};
+use lesson::{Action, LessonProgress, LessonRun};
+use logging::LogEntry;
+use packs::{ComponentHistory, ComponentList, PackStatus};
+use progress::{Progress, QuizScore};
+use quiz::{Correction, Difficulty, QuizView, Quizzes, Topic};
+use radio::{RadioLink, Transmission};
+use replay::{Recorder, ReplayEvent};
+use session::{Machine, Session};
+use cryptanalysis::avalanche::{self, SettingsComparison};
+use cryptanalysis::banburismus::{self, Alignment};
+use cryptanalysis::bruteforce::{self, SearchSpace};
+use cryptanalysis::checkpoint::{AttackCheckpoint, CheckpointSummary};
+use cryptanalysis::cillies::{self, CillieReport, IndicatorMessage};
+use cryptanalysis::cribs::{self, CribContext, CribSuggestion};
+use cryptanalysis::cyclometer::{Catalog, CatalogSummary};
+use cryptanalysis::depth::{self, DepthMessage, DepthReader, DepthView, KappaOffset};
+use cryptanalysis::export::{AttackExport, ExportedReport};
+use cryptanalysis::fitness::{NgramModelInfo, NgramScorer, ScorerRegistry};
+use cryptanalysis::flattening::{self, FlatteningDemo};
+use cryptanalysis::herivel::{self, RingGuess};
+use cryptanalysis::keyspace::{
+    self, Keyspace, KeyspaceExplanation, KeyspaceOptions, Language, MachineModel, UnicityReport,
+};
+use cryptanalysis::known_plaintext::{self, BombeStep, DiagonalBoardComparison, KeyConstraints, StopCheck};
+use cryptanalysis::message_keys;
+use cryptanalysis::partition::{self, ChunkResult, MergedResult, SearchChunk};
+use cryptanalysis::pipeline::{self, PipelineOptions};
+use cryptanalysis::plugboard::{self, Optimizer};
+use cryptanalysis::rejewski::{self, Characteristic, RejewskiCandidate, RejewskiReport};
+use cryptanalysis::report::{self, AttackReport, SearchStats};
+use cryptanalysis::rings;
+use cryptanalysis::self_encryption::{self, SelfEncryptionDemo};
+use cryptanalysis::zygalski::{self, ZygalskiSheet};
+use enigma::classical::{Caesar, ClassicalConfig, ClassicalSteps, Playfair, Vigenere};
+use enigma::lorenz::{LorenzConfig, LorenzMachine, LorenzStep};
+use enigma::sigaba::{SigabaConfig, SigabaMachine, SigabaStep};
+use enigma::typex::{TypexConfig, TypexMachine, TypexStep};
+use enigma::{CipherMachine, EnigmaConfig, EncryptionStep, OutputFormat, VectorResult};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, DragDropEvent, Emitter, Manager, State, Window, WindowEvent};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_deep_link::DeepLinkExt;
+use tauri_plugin_dialog::DialogExt;
+use tracing::Level;
+use tracing_appender::non_blocking::WorkerGuard;
+#[cfg(desktop)]
+use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Shortcut, ShortcutState};
+#[cfg(desktop)]
+use tray::QuickAction;
 
 /// Processa (criptografa/descriptografa) um texto completo e retorna apenas o resultado final.
 /// Esta função é stateless; a configuração da máquina é fornecida a cada chamada.
+/// Roda fora da thread principal (async): a UI chama a cada tecla e o histórico
+/// grava em disco.
 #[tauri::command]
-fn enigma_process_string(config: EnigmaConfig, text: String) -> String {
+async fn enigma_process_string(app: AppHandle, config: EnigmaConfig, text: String) -> Result<String, String> {
+    let config = validate(config)?;
     // Cria uma nova instância da máquina com base na configuração da UI
-    let mut machine = enigma::EnigmaMachine::new(config);
-    machine.process_string(&text)
+    let output = type_text(enigma::EnigmaMachine::new(config.clone()), &text);
+    remember(&app, &config, &text, &output);
+    Ok(output)
 }
 
 /// Processa um texto e retorna uma lista detalhada de cada passo da criptografia
 #[tauri::command]
-fn enigma_process_detailed(config: EnigmaConfig, text: String) -> Vec<EncryptionStep> {
+async fn enigma_process_detailed(
+    app: AppHandle,
+    config: EnigmaConfig,
+    text: String,
+) -> Result<Vec<EncryptionStep>, String> {
+    let config = validate(config)?;
     // Cria uma nova instância da máquina
+    let steps = type_text_detailed(enigma::EnigmaMachine::new(config.clone()), &text);
+    let output: String = steps.iter().map(|step| step.output_char).collect();
+    remember(&app, &config, &text, &output);
+    Ok(steps)
+}
+
+/// Tecla `text` em duas máquinas lado a lado e alinha as saídas, marcando onde elas
+/// começam a diferir.
+#[tauri::command]
+fn diff_traces(config_a: EnigmaConfig, config_b: EnigmaConfig, text: String) -> Result<enigma::TraceDiff, String> {
+    let config_a = validate(config_a).map_err(|e| format!("Máquina A: {}", e))?;
+    let config_b = validate(config_b).map_err(|e| format!("Máquina B: {}", e))?;
+    enigma::diff_traces(config_a, config_b, &text)
+}
+
+/// Fiação de cada rotor, vista da entrada, em cada tecla de `text` (já com os
+/// rotores girados, como no caminho de `enigma_process_detailed`), para a UI
+/// desenhar as colunas de fios.
+#[tauri::command]
+fn enigma_rotor_wirings(config: EnigmaConfig, text: String) -> Result<Vec<enigma::RotorWirings>, String> {
+    let config = validate(config)?;
     let mut machine = enigma::EnigmaMachine::new(config);
-    machine.process_string_detailed(&text)
+    Ok(text
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|_| {
+            machine.step_rotors();
+            machine.rotor_wirings()
+        })
+        .collect())
+}
+
+/// Maior linha do tempo pedida de uma vez: um ciclo completo dos três rotores
+/// (26 × 25 × 26 teclas, por causa do passo duplo).
+const TIMELINE_LIMIT: usize = 16_900;
+
+/// Posições (L, M, R) depois de cada uma das `length` primeiras teclas, sem o
+/// caminho do sinal, para a UI desenhar a linha do tempo dos rotores.
+#[tauri::command]
+fn enigma_position_timeline(config: EnigmaConfig, length: usize) -> Result<Vec<(char, char, char)>, String> {
+    let config = validate(config)?;
+    if length > TIMELINE_LIMIT {
+        return Err(format!("A linha do tempo vai até {} teclas.", TIMELINE_LIMIT));
+    }
+    Ok(enigma::EnigmaMachine::new(config).position_timeline(length))
+}
+
+/// Alfabetos de substituição das próximas `length` teclas (até um ciclo completo
+/// dos rotores), uma linha por tecla, para o mapa de calor da cifra.
+#[tauri::command]
+fn mapping_evolution(config: EnigmaConfig, length: usize) -> Result<enigma::MappingEvolution, String> {
+    let config = validate(config)?;
+    if length > TIMELINE_LIMIT {
+        return Err(format!("O mapa de calor vai até {} teclas.", TIMELINE_LIMIT));
+    }
+    enigma::mapping_evolution(config, length)
+}
+
+/// Aperta `letter` `n` vezes (até dois ciclos completos) e mostra quando a
+/// sequência de lâmpadas começa a se repetir.
+#[tauri::command]
+fn trace_repeated_letter(config: EnigmaConfig, letter: char, n: usize) -> Result<enigma::RepeatedLetter, String> {
+    let config = validate(config)?;
+    if n > 2 * TIMELINE_LIMIT {
+        return Err(format!("A demonstração vai até {} teclas.", 2 * TIMELINE_LIMIT));
+    }
+    enigma::repeated_letter(config, letter, n)
+}
+
+/// Arquivo do histórico de configurações usadas, na pasta de dados do app.
+fn recent_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join("recent.json"))
+}
+
+/// Banco do histórico de mensagens, aberto no primeiro uso e mantido aberto.
+#[derive(Default)]
+struct MessageHistory(Mutex<Option<History>>);
+
+/// Roda `f` no histórico de mensagens, abrindo o banco na pasta de dados do app na
+/// primeira vez.
+fn with_history<T>(app: &AppHandle, f: impl FnOnce(&mut History) -> Result<T, String>) -> Result<T, String> {
+    let state = app.state::<MessageHistory>();
+    let mut history = state.0.lock().map_err(|e| e.to_string())?;
+    let history = match &mut *history {
+        Some(history) => history,
+        closed => {
+            let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+            closed.insert(History::open(&dir.join("history.sqlite"))?)
+        }
+    };
+    f(history)
+}
+
+/// Põe a mensagem no histórico e, quando ela é nova (não só mais letras da que
+/// está sendo digitada), `config` nas configurações recentes. Uma falha ao gravar
+/// não impede a cifragem.
+fn remember(app: &AppHandle, config: &EnigmaConfig, text: &str, output: &str) {
+    let new_message = with_history(app, |history| history.record(config, text, output)).unwrap_or(true);
+    if let (true, Ok(path)) = (new_message, recent_path(app)) {
+        let _ = recent::record(&path, config.clone());
+    }
+}
+
+/// Últimas configurações usadas para cifrar, da mais recente para a mais antiga.
+#[tauri::command]
+fn recent_configs(app: AppHandle) -> Result<Vec<EnigmaConfig>, String> {
+    Ok(recent::list(&recent_path(&app)?))
+}
+
+/// Esquece o histórico de configurações usadas.
+#[tauri::command]
+fn clear_recent(app: AppHandle) -> Result<(), String> {
+    recent::clear(&recent_path(&app)?)
+}
+
+/// Confere `config` e a devolve normalizada, registrando a recusa no log.
+fn validate(config: EnigmaConfig) -> Result<EnigmaConfig, String> {
+    cryptanalysis::validate_config(&config).inspect_err(|e| tracing::warn!(erro = %e, "configuração recusada"))
+}
+
+/// Teclas de um texto em qualquer máquina: os comandos só dependem de `CipherMachine`.
+fn type_text<M: CipherMachine>(mut machine: M, text: &str) -> String {
+    machine.process_string(text)
+}
+
+fn type_text_detailed<M: CipherMachine>(mut machine: M, text: &str) -> Vec<M::Step> {
+    machine.process_string_detailed(text)
+}
+
+/// Cifra (ou decifra) `text` com uma cifra clássica, para comparar com a Enigma.
+#[tauri::command]
+fn classical_process(cipher: ClassicalConfig, text: String) -> Result<String, String> {
+    Ok(match cipher {
+        ClassicalConfig::Caesar(config) => type_text(Caesar::new(config), &text),
+        ClassicalConfig::Vigenere(config) => type_text(Vigenere::new(config)?, &text),
+        ClassicalConfig::Playfair(config) => type_text(Playfair::new(config), &text),
+    })
+}
+
+/// Como `classical_process`, mas retorna o passo detalhado de cada tecla (de cada
+/// par, na Playfair).
+#[tauri::command]
+fn classical_process_detailed(cipher: ClassicalConfig, text: String) -> Result<ClassicalSteps, String> {
+    Ok(match cipher {
+        ClassicalConfig::Caesar(config) => ClassicalSteps::Caesar(type_text_detailed(Caesar::new(config), &text)),
+        ClassicalConfig::Vigenere(config) => {
+            ClassicalSteps::Vigenere(type_text_detailed(Vigenere::new(config)?, &text))
+        }
+        ClassicalConfig::Playfair(config) => {
+            ClassicalSteps::Playfair(type_text_detailed(Playfair::new(config), &text))
+        }
+    })
+}
+
+/// Cifra (ou decifra) `text` na Typex, impresso como na fita: cifrando, o espaço
+/// vira X e o texto sai em grupos de cinco; decifrando, o X sai como espaço.
+#[tauri::command]
+fn typex_process(config: TypexConfig, text: String) -> Result<String, String> {
+    Ok(TypexMachine::new(config)?.print(&text))
+}
+
+/// Como `typex_process`, mas retorna o passo detalhado de cada tecla.
+#[tauri::command]
+fn typex_process_detailed(config: TypexConfig, text: String) -> Result<Vec<TypexStep>, String> {
+    Ok(type_text_detailed(TypexMachine::new(config)?, &text))
+}
+
+/// Cifra (ou decifra) `text` na SIGABA: cifrando, o Z vira X, o espaço vira Z e o
+/// texto sai em grupos de cinco; decifrando, o Z sai como espaço.
+#[tauri::command]
+fn sigaba_process(config: SigabaConfig, text: String) -> Result<String, String> {
+    Ok(SigabaMachine::new(config)?.print(&text))
+}
+
+/// Como `sigaba_process`, mas retorna o passo detalhado de cada tecla, com os
+/// rotores da cifra que o controle e o índice fizeram girar.
+#[tauri::command]
+fn sigaba_process_detailed(config: SigabaConfig, text: String) -> Result<Vec<SigabaStep>, String> {
+    Ok(type_text_detailed(SigabaMachine::new(config)?, &text))
+}
+
+/// Cifra (ou decifra: é a mesma operação) `text` na Lorenz. Entrada e saída vão
+/// na notação de Bletchley; o espaço entra como `9`.
+#[tauri::command]
+fn lorenz_process(config: LorenzConfig, text: String) -> Result<String, String> {
+    Ok(type_text(LorenzMachine::new(config)?, &text))
+}
+
+/// Como `lorenz_process`, mas retorna cada caractere com os impulsos das χ, das ψ
+/// e do motor.
+#[tauri::command]
+fn lorenz_process_detailed(config: LorenzConfig, text: String) -> Result<Vec<LorenzStep>, String> {
+    Ok(type_text_detailed(LorenzMachine::new(config)?, &text))
+}
+
+/// Grava em `path` o traço compacto (MessagePack) da cifragem de `text`. Retorna o
+/// tamanho do arquivo em bytes.
+#[tauri::command]
+fn enigma_export_trace(config: EnigmaConfig, text: String, path: PathBuf) -> Result<usize, String> {
+    trace::save(&enigma::Trace::record(config, &text)?, &path)
+}
+
+/// Abre um traço gravado por `enigma_export_trace`.
+#[tauri::command]
+fn enigma_import_trace(path: PathBuf) -> Result<trace::ImportedTrace, String> {
+    trace::load(&path)
+}
+
+/// Grava em `path` o traço detalhado da cifragem de `text` em CSV, uma linha por
+/// componente, para abrir em planilhas. Retorna o tamanho do arquivo em bytes.
+#[tauri::command]
+fn enigma_export_csv(config: EnigmaConfig, text: String, path: PathBuf) -> Result<usize, String> {
+    let config = validate(config)?;
+    trace::save_csv(&type_text_detailed(enigma::EnigmaMachine::new(config), &text), &path)
+}
+
+/// Diagrama SVG do caminho do sinal na tecla `index` (a partir de 0) de `text`.
+#[tauri::command]
+fn enigma_step_svg(config: EnigmaConfig, text: String, index: usize) -> Result<String, String> {
+    let config = validate(config)?;
+    let steps = type_text_detailed(enigma::EnigmaMachine::new(config), &text);
+    let step = steps.get(index).ok_or_else(|| format!("O texto só tem {} letras.", steps.len()))?;
+    Ok(diagram::step_svg(step))
+}
+
+/// Grava em `path` o diagrama de `enigma_step_svg`.
+#[tauri::command]
+fn enigma_save_step_svg(config: EnigmaConfig, text: String, index: usize, path: PathBuf) -> Result<(), String> {
+    let svg = enigma_step_svg(config, text, index)?;
+    std::fs::write(&path, svg).map_err(|e| format!("Erro ao salvar o diagrama: {}", e))
+}
+
+/// Disposição histórica (QWERTZ) do teclado e do painel de lâmpadas.
+#[tauri::command]
+fn keyboard_layout() -> enigma::KeyboardLayout {
+    enigma::keyboard_layout()
+}
+
+/// A máquina de `config` inteira como grafo (teclas, contatos e fiação) com as
+/// coordenadas do desenho, para todas as interfaces mostrarem o mesmo diagrama.
+#[tauri::command]
+fn enigma_diagram_graph(config: EnigmaConfig) -> Result<diagram::MachineGraph, String> {
+    let config = validate(config)?;
+    Ok(diagram::machine_graph(&config))
+}
+
+/// Gera o PDF de uma folha de chaves ou de um traço e pergunta onde salvá-lo.
+/// Retorna o caminho escolhido, ou `None` se o usuário cancelar.
+#[tauri::command]
+async fn export_pdf(app: AppHandle, export: pdf::PdfExport) -> Result<Option<PathBuf>, String> {
+    let bytes = export.to_pdf()?;
+    let Some(path) = app.dialog().file().add_filter("PDF", &["pdf"]).blocking_save_file() else {
+        return Ok(None);
+    };
+    let path = path.into_path().map_err(|e| e.to_string())?;
+    std::fs::write(&path, bytes).map_err(|e| format!("Erro ao salvar o PDF: {}", e))?;
+    Ok(Some(path))
+}
+
+/// Copia `ciphertext` para a área de transferência já no formato de transmissão
+/// (grupos, linhas e preâmbulo), o mesmo do `enigma-cli`. Retorna o texto copiado.
+#[tauri::command]
+fn copy_ciphertext(app: AppHandle, ciphertext: String, format: Option<OutputFormat>) -> Result<String, String> {
+    let text = format.unwrap_or_default().apply(&ciphertext);
+    app.clipboard().write_text(text.clone()).map_err(|e| format!("Erro ao copiar o texto: {}", e))?;
+    Ok(text)
+}
+
+/// Código curto de `config` (ex.: "ENIGMA:I.II.III:B:ABC:AAA:ABCD"), para compartilhar.
+#[tauri::command]
+fn config_share_code(config: EnigmaConfig) -> Result<String, String> {
+    config.to_share_code()
+}
+
+/// PNG com o QR code do código curto de `config`, para mostrar no projetor.
+#[tauri::command]
+fn config_qr_png(config: EnigmaConfig) -> Result<Vec<u8>, String> {
+    qr::config_png(&config)
+}
+
+/// Lê a configuração de um código curto (digitado ou lido do QR code).
+#[tauri::command]
+fn config_from_share_code(code: String) -> Result<EnigmaConfig, String> {
+    EnigmaConfig::from_share_code(&code)
+}
+
+/// Lê uma configuração copiada de outro simulador (Cryptii ou Universal Enigma).
+#[tauri::command]
+fn import_simulator_settings(text: String) -> Result<EnigmaConfig, String> {
+    simulators::parse(&text)
+}
+
+/// Grava `config` em `path`, em TOML ou YAML conforme a extensão.
+#[tauri::command]
+fn save_config_file(path: PathBuf, config: EnigmaConfig) -> Result<(), String> {
+    config_file::save_config(&path, &config)
+}
+
+/// Abre uma configuração de um arquivo TOML ou YAML.
+#[tauri::command]
+fn open_config_file(path: PathBuf) -> Result<EnigmaConfig, String> {
+    config_file::open_config(&path)
+}
+
+/// Grava uma folha de chaves em `path`, em TOML ou YAML conforme a extensão.
+#[tauri::command]
+fn save_key_sheet_file(path: PathBuf, sheet: pdf::KeySheet) -> Result<(), String> {
+    config_file::save_key_sheet(&path, &sheet)
+}
+
+/// Abre uma folha de chaves de um arquivo TOML ou YAML.
+#[tauri::command]
+fn open_key_sheet_file(path: PathBuf) -> Result<pdf::KeySheet, String> {
+    config_file::open_key_sheet(&path)
+}
+
+/// Grava uma folha de chaves cifrada com `passphrase` em `path`.
+#[tauri::command]
+async fn export_key_file(path: PathBuf, sheet: pdf::KeySheet, passphrase: String) -> Result<(), String> {
+    key_file::export(&path, &sheet, &passphrase)
+}
+
+/// Abre uma folha de chaves gravada por `export_key_file`, se a senha estiver certa.
+#[tauri::command]
+async fn import_key_file(path: PathBuf, passphrase: String) -> Result<pdf::KeySheet, String> {
+    key_file::import(&path, &passphrase)
+}
+
+/// Arquivo das configurações salvas, na pasta de dados do app.
+fn presets_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join("presets.json"))
+}
+
+/// Salva `config` como `name`, substituindo uma configuração de mesmo nome.
+#[tauri::command]
+fn preset_save(app: AppHandle, name: String, config: EnigmaConfig) -> Result<(), String> {
+    presets::save(&presets_path(&app)?, &name, config)?;
+    #[cfg(desktop)]
+    refresh_tray(&app);
+    Ok(())
+}
+
+/// Nomes das configurações salvas, em ordem alfabética.
+#[tauri::command]
+fn preset_list(app: AppHandle) -> Result<Vec<String>, String> {
+    presets::list(&presets_path(&app)?)
+}
+
+/// Abre a configuração salva como `name`.
+#[tauri::command]
+fn preset_load(app: AppHandle, name: String) -> Result<EnigmaConfig, String> {
+    presets::load(&presets_path(&app)?, &name)
+}
+
+/// Apaga a configuração salva como `name`.
+#[tauri::command]
+fn preset_delete(app: AppHandle, name: String) -> Result<(), String> {
+    presets::remove(&presets_path(&app)?, &name)?;
+    #[cfg(desktop)]
+    refresh_tray(&app);
+    Ok(())
+}
+
+/// Últimas `limit` mensagens cifradas, da mais recente para a mais antiga.
+#[tauri::command]
+fn history_recent(app: AppHandle, limit: usize) -> Result<Vec<HistoryEntry>, String> {
+    with_history(&app, |history| history.recent(limit))
+}
+
+/// Mensagens do histórico cujo texto de entrada é `term`, cuja saída contém `term`
+/// ou cuja configuração tem a impressão digital `term`.
+#[tauri::command]
+fn history_search(app: AppHandle, term: String, limit: usize) -> Result<Vec<HistoryEntry>, String> {
+    with_history(&app, |history| history.search(&term, limit))
+}
+
+/// Apaga as mensagens anteriores a `before` (segundos desde 1970), ou todas.
+/// Retorna quantas foram apagadas.
+#[tauri::command]
+fn history_purge(app: AppHandle, before: Option<u64>) -> Result<usize, String> {
+    with_history(&app, |history| history.purge(before))
+}
+
+/// Grava a área de trabalho (configuração, textos, notas e marcadores) em `path`.
+#[tauri::command]
+fn save_workspace(workspace: workspace::Workspace, path: PathBuf) -> Result<(), String> {
+    workspace.save(&path)
+}
+
+/// Abre uma área de trabalho gravada por `save_workspace`.
+#[tauri::command]
+fn open_workspace(path: PathBuf) -> Result<workspace::Workspace, String> {
+    workspace::Workspace::open(&path)
+}
+
+/// Mede, no computador atual, quantas letras por segundo cada caminho de cifragem
+/// processa (didático, simples, rápido e paralelo).
+#[tauri::command]
+async fn enigma_benchmark() -> BenchmarkReport {
+    benchmark::run()
+}
+
+/// Uma mensagem de `enigma_process_batch`, com a sua própria configuração.
+#[derive(Debug, Deserialize, Clone)]
+struct BatchMessage {
+    config: EnigmaConfig,
+    text: String,
+}
+
+/// Processa várias mensagens numa única chamada, cada uma com a sua configuração,
+/// e retorna os resultados na mesma ordem. Uma configuração inválida recusa o lote
+/// inteiro, indicando a mensagem.
+#[tauri::command]
+async fn enigma_process_batch(messages: Vec<BatchMessage>) -> Result<Vec<String>, String> {
+    let configs = messages
+        .iter()
+        .enumerate()
+        .map(|(i, message)| validate(message.config.clone()).map_err(|e| format!("Mensagem {}: {}", i + 1, e)))
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(configs
+        .into_iter()
+        .zip(messages)
+        .map(|(config, message)| enigma::EnigmaMachine::new(config).process_string(&message.text))
+        .collect())
+}
+
+/// Gera os indicadores duplicados (chave da mensagem cifrada duas vezes na posição
+/// básica) de um dia, para demonstrar o ataque de Rejewski.
+#[tauri::command]
+fn enigma_rejewski_indicators(config: EnigmaConfig, message_keys: Vec<String>) -> Result<Vec<String>, String> {
+    let config = validate(config)?;
+    rejewski::encipher_indicators(&config, &message_keys)
+}
+
+/// Recupera a ordem dos rotores e a Grundstellung a partir dos indicadores de um dia.
+/// Usa o catálogo do ciclômetro salvo em disco, se existir; senão percorre todas as
+/// posições, por isso roda numa thread separada, fora do runtime assíncrono.
+#[tauri::command]
+async fn enigma_rejewski_attack(app: AppHandle, indicators: Vec<String>, reflector: String) -> Result<RejewskiReport, String> {
+    let path = cyclometer_path(&app, &reflector)?;
+    let attack = move || {
+        if path.exists() {
+            rejewski::attack_with_catalog(&indicators, &Catalog::load(&path)?)
+        } else {
+            rejewski::attack(&indicators, &reflector)
+        }
+    };
+    tauri::async_runtime::spawn_blocking(attack).await.map_err(|e| e.to_string())?
+}
+
+/// Progresso de uma tarefa longa, emitido em eventos como "cyclometer-progress".
+#[derive(Debug, Serialize, Clone)]
+struct JobProgress {
+    done: usize,
+    total: usize,
+}
+
+/// Caminho do catálogo do ciclômetro de um refletor, na pasta de dados do app. O
+/// refletor é conferido antes, e o nome do arquivo só leva letras, dígitos, `-` e
+/// `_`.
+fn cyclometer_path(app: &AppHandle, reflector: &str) -> Result<PathBuf, String> {
+    let reflector = cryptanalysis::parse_reflector(reflector)?;
+    let name: String = reflector
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c.to_ascii_uppercase() } else { '_' })
+        .collect();
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join(format!("cyclometer_{}.json", name)))
+}
+
+/// Gera o catálogo do ciclômetro (todas as ordens e posições) e o salva em disco.
+/// Tarefa longa: o progresso é emitido no evento "cyclometer-progress".
+#[tauri::command]
+async fn enigma_cyclometer_build(app: AppHandle, reflector: String) -> Result<CatalogSummary, String> {
+    let path = cyclometer_path(&app, &reflector)?;
+    let build = move || {
+        let catalog = Catalog::generate(&reflector, |done, total| {
+            let _ = app.emit("cyclometer-progress", JobProgress { done, total });
+        })?;
+        catalog.save(&path)?;
+        Ok(catalog.summary())
+    };
+    tauri::async_runtime::spawn_blocking(build).await.map_err(|e| e.to_string())?
+}
+
+/// Consulta o catálogo salvo: configurações que produzem a característica informada.
+#[tauri::command]
+async fn enigma_cyclometer_lookup(
+    app: AppHandle,
+    reflector: String,
+    characteristic: Characteristic,
+) -> Result<Vec<RejewskiCandidate>, String> {
+    let catalog = Catalog::load(&cyclometer_path(&app, &reflector)?)?;
+    Ok(catalog.lookup(&characteristic).to_vec())
+}
+
+/// Cifra o mesmo texto com duas configurações (em geral diferindo numa só) e
+/// compara as saídas posição a posição, para mostrar a sensibilidade a cada ajuste.
+#[tauri::command]
+fn enigma_compare_settings(
+    config_a: EnigmaConfig,
+    config_b: EnigmaConfig,
+    text: String,
+) -> Result<SettingsComparison, String> {
+    avalanche::compare_settings(&config_a, &config_b, &text)
+}
+
+/// Lição "por que a análise de frequências falha": histogramas e índice de
+/// coincidência de um texto longo (o de exemplo, se `text` vier vazio), da mesma
+/// mensagem numa cifra de César e na Enigma com `config`.
+#[tauri::command]
+fn enigma_frequency_demo(config: EnigmaConfig, text: Option<String>) -> Result<FlatteningDemo, String> {
+    flattening::flattening_demo(&config, text.as_deref())
+}
+
+/// Lição sobre a falta de auto-cifragem: a saída de cada letra de A a Z nas
+/// `steps` primeiras posições a partir de `config`.
+#[tauri::command]
+fn enigma_self_encryption_demo(config: EnigmaConfig, steps: usize) -> Result<SelfEncryptionDemo, String> {
+    self_encryption::self_encryption_demo(&config, steps)
+}
+
+/// Gera as 26 folhas de Zygalski de uma ordem de rotores (Direita, Meio, Esquerda).
+#[tauri::command]
+async fn enigma_zygalski_sheets(rotors: (String, String, String), reflector: String) -> Result<Vec<ZygalskiSheet>, String> {
+    let order = cryptanalysis::parse_rotor_order(&rotors)?;
+    let reflector = cryptanalysis::parse_reflector(&reflector)?;
+    Ok(zygalski::sheets(order, reflector))
+}
+
+/// Banburismus: desliza duas mensagens cifradas uma sobre a outra e ordena as
+/// distâncias do rotor da direita pela evidência (em decibans) de estarem em profundidade.
+#[tauri::command]
+fn enigma_banburismus(first: String, second: String, max_offset: usize) -> Vec<Alignment> {
+    banburismus::rank_distances(&first, &second, max_offset, banburismus::NAVAL_GERMAN_REPEAT_RATE)
+}
+
+/// Teste kappa entre dois textos cifrados: coincidências em cada deslocamento, com
+/// os alinhamentos prováveis "em profundidade" marcados.
+#[tauri::command]
+fn enigma_depth_test(first: String, second: String, max_offset: usize) -> Vec<KappaOffset> {
+    depth::kappa_test(&first, &second, max_offset)
+}
+
+/// Tamanho exato do espaço de chaves de um modelo, com o detalhamento por fator.
+#[tauri::command]
+fn enigma_keyspace(model: MachineModel, options: Option<KeyspaceOptions>) -> Keyspace {
+    keyspace::keyspace(model, &options.unwrap_or_default())
+}
+
+/// Espaço de chaves de uma máquina como a de `config`, fator a fator, com os
+/// produtos parciais para a UI animar a conta.
+#[tauri::command]
+fn explain_keyspace(config: EnigmaConfig) -> Result<KeyspaceExplanation, String> {
+    keyspace::explain_keyspace(&config)
+}
+
+/// Entropia da chave do modelo e distância de unicidade para a língua escolhida:
+/// quantas letras cifradas, em teoria, bastam para determinar a chave.
+#[tauri::command]
+fn enigma_unicity(model: MachineModel, options: Option<KeyspaceOptions>, language: Language) -> Result<UnicityReport, String> {
+    let space = keyspace::keyspace(model, &options.unwrap_or_default());
+    keyspace::unicity(space.bits, language)
+}
+
+/// Sessão de leitura de mensagens em profundidade aberta na UI.
+#[derive(Default)]
+struct DepthSession(Mutex<Option<DepthReader>>);
+
+/// Abre a leitura de duas mensagens alinhadas em profundidade no deslocamento dado.
+#[tauri::command]
+fn enigma_depth_open(session: State<'_, DepthSession>, first: String, second: String, offset: i32) -> Result<DepthView, String> {
+    let reader = DepthReader::new(&first, &second, offset)?;
+    let view = reader.view();
+    *session.0.lock().unwrap() = Some(reader);
+    Ok(view)
+}
+
+/// Supõe uma palavra numa das mensagens e devolve o que passa a ser lido nas duas.
+#[tauri::command]
+fn enigma_depth_guess(
+    session: State<'_, DepthSession>,
+    message: DepthMessage,
+    position: usize,
+    word: String,
+) -> Result<DepthView, String> {
+    let mut session = session.0.lock().unwrap();
+    let reader = session.as_mut().ok_or("Nenhuma leitura em profundidade aberta.")?;
+    reader.guess(message, position, &word)
+}
+
+/// Desfaz a última suposição da leitura em profundidade.
+#[tauri::command]
+fn enigma_depth_undo(session: State<'_, DepthSession>) -> Result<DepthView, String> {
+    let mut session = session.0.lock().unwrap();
+    let reader = session.as_mut().ok_or("Nenhuma leitura em profundidade aberta.")?;
+    Ok(reader.undo())
+}
+
+/// Sugere palavras prováveis (cribs) que cabem num texto cifrado do tamanho dado,
+/// opcionalmente só as de um tipo de mensagem.
+#[tauri::command]
+fn enigma_suggest_cribs(ciphertext_len: usize, context: Option<CribContext>) -> Vec<CribSuggestion> {
+    cribs::suggest_cribs(ciphertext_len, context)
+}
+
+/// Dica de Herivel: a partir das Grundstellungen das primeiras mensagens do dia,
+/// sugere as Ringstellungen mais prováveis (centro do agrupamento).
+#[tauri::command]
+fn enigma_herivel_tip(grundstellungen: Vec<String>, radius: u8, top: usize) -> Vec<RingGuess> {
+    herivel::rank_ring_settings(&grundstellungen, radius, top)
+}
+
+/// Decifra os indicadores de um lote de mensagens com a chave do dia e aponta as
+/// chaves de mensagem descuidadas ("cillies").
+#[tauri::command]
+fn enigma_detect_cillies(config: EnigmaConfig, messages: Vec<IndicatorMessage>) -> Result<Vec<CillieReport>, String> {
+    cillies::detect(&config, &messages)
+}
+
+/// Ataques longos em andamento, cada um com o seu pedido de cancelamento, para a UI
+/// interromper um ataque sem afetar os outros.
+#[derive(Default)]
+struct AttackControl {
+    next: AtomicU64,
+    runs: Mutex<HashMap<u64, Arc<AtomicBool>>>,
+}
+
+impl AttackControl {
+    /// Registra um novo ataque e retorna o seu identificador.
+    fn start(&self) -> u64 {
+        let id = self.next.fetch_add(1, Ordering::SeqCst) + 1;
+        self.runs.lock().unwrap().insert(id, Arc::default());
+        id
+    }
+
+    /// Cancelamento do ataque `id`; o registro é apagado quando o ataque termina.
+    fn run(&self, id: u64) -> Result<AttackRun<'_>, String> {
+        let cancel = self.runs.lock().unwrap().get(&id).cloned();
+        let cancel = cancel.ok_or_else(|| format!("Ataque desconhecido: {}", id))?;
+        Ok(AttackRun { control: self, id, cancel })
+    }
+
+    /// Pede a interrupção do ataque `id`, se ele ainda estiver registrado.
+    fn cancel(&self, id: u64) {
+        if let Some(cancel) = self.runs.lock().unwrap().get(&id) {
+            cancel.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Um ataque em andamento; ao sair de escopo, apaga o seu registro.
+struct AttackRun<'a> {
+    control: &'a AttackControl,
+    id: u64,
+    cancel: Arc<AtomicBool>,
+}
+
+impl Drop for AttackRun<'_> {
+    fn drop(&mut self) {
+        self.control.runs.lock().unwrap().remove(&self.id);
+    }
+}
+
+/// Registra um ataque antes de começá-lo. O identificador retornado é passado ao
+/// comando do ataque (`run`) e a `cancel_attack`.
+#[tauri::command]
+fn attack_start(control: State<'_, AttackControl>) -> u64 {
+    control.start()
+}
+
+/// Lista os pontuadores disponíveis para os ataques.
+#[tauri::command]
+fn attack_scorers(scorers: State<'_, ScorerRegistry>) -> Vec<String> {
+    scorers.names()
+}
+
+/// Carrega um arquivo de frequências de n-gramas (ex.: quadrigramas de um corpus ou
+/// de uma língua sem modelo embutido) e o registra como pontuador. O nome padrão é o
+/// do arquivo sem extensão; um modelo com o mesmo nome é substituído.
+#[tauri::command]
+async fn attack_load_ngrams(
+    scorers: State<'_, ScorerRegistry>,
+    path: PathBuf,
+    name: Option<String>,
+) -> Result<NgramModelInfo, String> {
+    let name = match name {
+        Some(name) if !name.trim().is_empty() => name.trim().to_string(),
+        _ => path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .ok_or("Caminho do modelo inválido.")?,
+    };
+    let model = NgramScorer::load(&name, &path)?;
+    let info = model.info();
+    scorers.register(Arc::new(model));
+    Ok(info)
+}
+
+/// Intervalo mínimo entre dois salvamentos do ponto de retomada da busca exaustiva.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Pasta das buscas exaustivas salvas, na pasta de dados do app.
+fn checkpoint_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join("checkpoints"))
+}
+
+/// Executa (ou continua) a busca exaustiva de `checkpoint`, salvando o ponto de
+/// retomada a cada `CHECKPOINT_INTERVAL` e ao ser cancelada. O identificador da busca
+/// é emitido em "attack-checkpoint" a cada salvamento; ao terminar, o arquivo é apagado.
+/// A cada bloco testado, o andamento sai em "attack-progress" e o bloco e o melhor
+/// candidato até ali em "attack-live", para a UI animar a busca.
+fn run_bruteforce(
+    app: &AppHandle,
+    cancel: &AtomicBool,
+    scorers: &ScorerRegistry,
+    mut checkpoint: AttackCheckpoint,
+) -> Result<AttackReport, String> {
+    let scorer = scorers.resolve(checkpoint.crib.as_deref(), checkpoint.scorer.as_deref())?;
+    let dir = checkpoint_dir(app)?;
+    tracing::info!(id = %checkpoint.id, "busca exaustiva iniciada");
+    let started = Instant::now();
+    let mut last_save = Instant::now();
+    let mut pending = false;
+    let (ciphertext, search_space, cursor) =
+        (checkpoint.ciphertext.clone(), checkpoint.search_space.clone(), checkpoint.cursor.clone());
+    let result = bruteforce::resume_bruteforce(
+        &ciphertext,
+        scorer.as_ref(),
+        &search_space,
+        &cursor,
+        |event| {
+            let _ = app.emit("attack-progress", JobProgress { done: event.done, total: event.total });
+            let _ = app.emit("attack-live", event);
+        },
+        |cursor| {
+            checkpoint.advance(cursor);
+            pending = true;
+            if last_save.elapsed() >= CHECKPOINT_INTERVAL && checkpoint.save(&dir).is_ok() {
+                tracing::info!(id = %checkpoint.id, "ponto de retomada salvo");
+                let _ = app.emit("attack-checkpoint", checkpoint.summary());
+                last_save = Instant::now();
+                pending = false;
+            }
+        },
+        cancel,
+    )?;
+    if !result.cancelled {
+        AttackCheckpoint::remove(&dir, &checkpoint.id)?;
+    } else if pending {
+        checkpoint.save(&dir)?;
+        let _ = app.emit("attack-checkpoint", checkpoint.summary());
+    }
+    let stats = SearchStats::since(started, result.keys_tried - cursor.keys_tried, result.cancelled);
+    let (keys, cancelled) = (result.keys_tried, result.cancelled);
+    tracing::info!(id = %checkpoint.id, chaves = keys, cancelada = cancelled, "busca exaustiva terminada");
+    Ok(AttackReport::new(result.candidates.into_iter().map(Into::into), stats))
+}
+
+/// O que `attack_bruteforce` deve procurar.
+#[derive(Debug, Deserialize, Clone)]
+struct BruteforceRequest {
+    ciphertext: String,
+    crib: Option<String>,
+    scorer: Option<String>,
+    search_space: SearchSpace,
+}
+
+/// Busca exaustiva em paralelo de ordem dos rotores, refletor e posições iniciais.
+/// O progresso é emitido no evento "attack-progress"; `cancel_attack` com o mesmo `run`
+/// (obtido de `attack_start`) interrompe a busca.
+/// Com `crib`, pontua pelas coincidências com ele; senão usa o pontuador `scorer`
+/// (índice de coincidência por padrão). O mesmo vale para os ataques seguintes.
+/// A busca é salva periodicamente e pode ser continuada com `resume_attack`.
+#[tauri::command]
+async fn attack_bruteforce(
+    app: AppHandle,
+    control: State<'_, AttackControl>,
+    scorers: State<'_, ScorerRegistry>,
+    request: BruteforceRequest,
+    run: u64,
+) -> Result<AttackReport, String> {
+    let run = control.run(run)?;
+    let BruteforceRequest { ciphertext, crib, scorer, search_space } = request;
+    let checkpoint = AttackCheckpoint::new(&ciphertext, crib, scorer, search_space)?;
+    run_bruteforce(&app, &run.cancel, &scorers, checkpoint)
+}
+
+/// Lista as buscas exaustivas salvas que ainda não terminaram.
+#[tauri::command]
+fn attack_checkpoints(app: AppHandle) -> Result<Vec<CheckpointSummary>, String> {
+    Ok(AttackCheckpoint::list(&checkpoint_dir(&app)?))
+}
+
+/// Continua uma busca exaustiva salva do último ponto de retomada.
+#[tauri::command]
+async fn resume_attack(
+    app: AppHandle,
+    control: State<'_, AttackControl>,
+    scorers: State<'_, ScorerRegistry>,
+    id: String,
+    run: u64,
+) -> Result<AttackReport, String> {
+    let run = control.run(run)?;
+    let checkpoint = AttackCheckpoint::load(&checkpoint_dir(&app)?, &id)?;
+    run_bruteforce(&app, &run.cancel, &scorers, checkpoint)
+}
+
+/// Descarta uma busca exaustiva salva.
+#[tauri::command]
+fn discard_checkpoint(app: AppHandle, id: String) -> Result<(), String> {
+    AttackCheckpoint::remove(&checkpoint_dir(&app)?, &id)
+}
+
+/// Divide uma busca exaustiva em `n_chunks` pedaços para rodar em várias máquinas.
+#[tauri::command]
+fn partition_search(
+    ciphertext: String,
+    crib: Option<String>,
+    scorer: Option<String>,
+    search_space: SearchSpace,
+    n_chunks: usize,
+) -> Result<Vec<SearchChunk>, String> {
+    partition::partition_search(&ciphertext, crib, scorer, &search_space, n_chunks)
+}
+
+/// Roda um pedaço de uma busca dividida. Usa o mesmo evento de progresso e o mesmo
+/// cancelamento da busca exaustiva.
+#[tauri::command]
+async fn run_chunk(
+    app: AppHandle,
+    control: State<'_, AttackControl>,
+    scorers: State<'_, ScorerRegistry>,
+    chunk: SearchChunk,
+    run: u64,
+) -> Result<ChunkResult, String> {
+    let run = control.run(run)?;
+    let scorer = scorers.resolve(chunk.crib.as_deref(), chunk.scorer.as_deref())?;
+    partition::run_chunk(
+        &chunk,
+        scorer.as_ref(),
+        |done, total| {
+            let _ = app.emit("attack-progress", JobProgress { done, total });
+        },
+        &run.cancel,
+    )
+}
+
+/// Junta os resultados dos pedaços de uma busca dividida.
+#[tauri::command]
+fn merge_results(results: Vec<ChunkResult>) -> Result<MergedResult, String> {
+    partition::merge_results(&results)
+}
+
+/// Número de chaves que a busca exaustiva testaria com as restrições dadas, para a
+/// UI estimar o tempo antes de começar.
+#[tauri::command]
+fn attack_search_space_size(search_space: SearchSpace) -> Result<usize, String> {
+    search_space.size()
+}
+
+/// Refina os anéis de uma chave encontrada com anéis em 'A' (por exemplo, pela busca
+/// exaustiva), transformando-a numa chave completa e utilizável.
+#[tauri::command]
+async fn attack_refine_rings(
+    scorers: State<'_, ScorerRegistry>,
+    ciphertext: String,
+    crib: Option<String>,
+    scorer: Option<String>,
+    config: EnigmaConfig,
+) -> Result<AttackReport, String> {
+    let scorer = scorers.resolve(crib.as_deref(), scorer.as_deref())?;
+    let started = Instant::now();
+    let key = rings::refine_rings(&ciphertext, scorer.as_ref(), &config)?;
+    let stats = SearchStats::since(started, key.keys_tried, false);
+    Ok(AttackReport::new([key.into()], stats))
+}
+
+/// Recupera o plugboard com os rotores de `config` já conhecidos.
+/// `max_pairs` limita o número de pares (10 por padrão, como na prática alemã) e
+/// `optimizer` escolhe entre subida de encosta (padrão) e recozimento simulado.
+#[tauri::command]
+async fn attack_plugboard(
+    scorers: State<'_, ScorerRegistry>,
+    ciphertext: String,
+    crib: Option<String>,
+    scorer: Option<String>,
+    config: EnigmaConfig,
+    max_pairs: Option<usize>,
+    optimizer: Option<Optimizer>,
+) -> Result<AttackReport, String> {
+    let scorer = scorers.resolve(crib.as_deref(), scorer.as_deref())?;
+    let started = Instant::now();
+    let solution = plugboard::recover_plugboard(
+        &ciphertext,
+        scorer.as_ref(),
+        &config,
+        max_pairs.unwrap_or(plugboard::MAX_PAIRS),
+        &optimizer.unwrap_or_default(),
+    )?;
+    let stats = SearchStats::since(started, solution.keys_tried, false);
+    Ok(AttackReport::new([solution.into()], stats))
+}
+
+/// Ataque completo só com o texto cifrado: rotores e posições, anéis e plugboard.
+/// O andamento da busca exaustiva sai em "attack-progress" e o melhor candidato de
+/// cada etapa em "attack-pipeline"; `scorer` pontua a etapa do plugboard.
+#[tauri::command]
+async fn attack_pipeline(
+    app: AppHandle,
+    control: State<'_, AttackControl>,
+    scorers: State<'_, ScorerRegistry>,
+    ciphertext: String,
+    scorer: Option<String>,
+    options: Option<PipelineOptions>,
+    run: u64,
+) -> Result<AttackReport, String> {
+    let run = control.run(run)?;
+    let scorer = scorers.resolve(None, scorer.as_deref())?;
+    tracing::info!(letras = ciphertext.len(), "ataque completo iniciado");
+    let started = Instant::now();
+    let result = pipeline::break_ciphertext(
+        &ciphertext,
+        scorer.as_ref(),
+        &options.unwrap_or_default(),
+        |done, total| {
+            let _ = app.emit("attack-progress", JobProgress { done, total });
+        },
+        |update| {
+            let _ = app.emit("attack-pipeline", update);
+        },
+        &run.cancel,
+    )?;
+    let stats = SearchStats::since(started, result.keys_tried, result.cancelled);
+    tracing::info!(chaves = result.keys_tried, cancelado = result.cancelled, "ataque completo terminado");
+    Ok(AttackReport::new(result.solutions.into_iter().map(Into::into), stats))
+}
+
+/// Ataque de dicionário à chave da mensagem: com a chave do dia em `config`, testa
+/// as chaves previsíveis (padrões de descuido, nomes, `extra_keys`) antes de partir
+/// para a busca exaustiva.
+#[tauri::command]
+async fn attack_message_keys(
+    scorers: State<'_, ScorerRegistry>,
+    ciphertext: String,
+    crib: Option<String>,
+    scorer: Option<String>,
+    config: EnigmaConfig,
+    extra_keys: Option<Vec<String>>,
+    top_n: Option<usize>,
+) -> Result<AttackReport, String> {
+    let scorer = scorers.resolve(crib.as_deref(), scorer.as_deref())?;
+    let started = Instant::now();
+    let result = message_keys::attack_message_keys(
+        &ciphertext,
+        scorer.as_ref(),
+        &config,
+        &extra_keys.unwrap_or_default(),
+        top_n.unwrap_or(10),
+    )?;
+    let stats = SearchStats::since(started, result.keys_tried, false);
+    Ok(AttackReport::new(result.candidates.into_iter().map(Into::into), stats))
+}
+
+/// Busca as chaves consistentes com um par texto claro/texto cifrado, percorrendo
+/// só as partes da chave que o usuário não conhece. Usa o mesmo evento de progresso
+/// e o mesmo cancelamento da busca exaustiva.
+#[tauri::command]
+async fn attack_known_plaintext(
+    app: AppHandle,
+    control: State<'_, AttackControl>,
+    plaintext: String,
+    ciphertext: String,
+    constraints: KeyConstraints,
+    run: u64,
+) -> Result<AttackReport, String> {
+    let run = control.run(run)?;
+    let started = Instant::now();
+    let result = known_plaintext::solve(
+        &plaintext,
+        &ciphertext,
+        &constraints,
+        |done, total| {
+            let _ = app.emit("attack-progress", JobProgress { done, total });
+        },
+        &run.cancel,
+    )?;
+    let stats = SearchStats::since(started, result.keys_tried, result.cancelled);
+    Ok(AttackReport::new(report::known_keys(&ciphertext, result.keys), stats))
+}
+
+/// Conta as paradas da Bombe com e sem o quadro diagonal de Welchman nas chaves
+/// permitidas por `constraints`. Usa o progresso e o cancelamento das buscas.
+#[tauri::command]
+async fn bombe_diagonal_board(
+    app: AppHandle,
+    control: State<'_, AttackControl>,
+    plaintext: String,
+    ciphertext: String,
+    constraints: KeyConstraints,
+    run: u64,
+) -> Result<DiagonalBoardComparison, String> {
+    let run = control.run(run)?;
+    known_plaintext::compare_diagonal_board(
+        &plaintext,
+        &ciphertext,
+        &constraints,
+        |done, total| {
+            let _ = app.emit("attack-progress", JobProgress { done, total });
+        },
+        &run.cancel,
+    )
+}
+
+/// Máquina de verificação: classifica as paradas da Bombe em verdadeiras e falsas,
+/// decifrando o crib com os pares deduzidos em cada uma.
+#[tauri::command]
+async fn bombe_check_stops(
+    plaintext: String,
+    ciphertext: String,
+    stops: Vec<EnigmaConfig>,
+) -> Result<Vec<StopCheck>, String> {
+    tauri::async_runtime::spawn_blocking(move || known_plaintext::check_stops(&plaintext, &ciphertext, &stops))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Refaz em câmera lenta a dedução do plugboard de uma chave, ao estilo da Bombe:
+/// cada passo sai no evento "bombe-step" para esta janela, com `delay_ms` de pausa
+/// entre eles (250 ms se omitido). `bombe_walkthrough_cancel` com o mesmo `run`
+/// (obtido de `bombe_walkthrough_start`) interrompe a demonstração, sem mexer no
+/// cancelamento dos ataques. Retorna o plugboard deduzido, ou `None` se a chave não
+/// é consistente com o par.
+#[tauri::command]
+async fn bombe_walkthrough(
+    window: Window,
+    walkthroughs: State<'_, BombeWalkthrough>,
+    config: EnigmaConfig,
+    plaintext: String,
+    ciphertext: String,
+    delay_ms: Option<u64>,
+    run: u64,
+) -> Result<Option<String>, String> {
+    let config = validate(config)?;
+    let run = walkthroughs.0.run(run)?;
+    let cancel = run.cancel.clone();
+    let app = window.app_handle().clone();
+    let label = window.label().to_string();
+    let delay = Duration::from_millis(delay_ms.unwrap_or(250));
+    let walk = move || {
+        known_plaintext::walkthrough(&config, &plaintext, &ciphertext, |step: BombeStep| {
+            if cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            let _ = app.emit_to(label.as_str(), "bombe-step", step);
+            std::thread::sleep(delay);
+        })
+    };
+    tauri::async_runtime::spawn_blocking(walk).await.map_err(|e| e.to_string())?
+}
+
+/// Demonstrações da Bombe em andamento, com registro próprio, separado dos ataques
+/// do `AttackControl`.
+#[derive(Default)]
+struct BombeWalkthrough(AttackControl);
+
+/// Registra uma demonstração da Bombe antes de começá-la. O identificador retornado
+/// é passado a `bombe_walkthrough` e a `bombe_walkthrough_cancel`.
+#[tauri::command]
+fn bombe_walkthrough_start(walkthroughs: State<'_, BombeWalkthrough>) -> u64 {
+    walkthroughs.0.start()
+}
+
+/// Interrompe a demonstração da Bombe `run`.
+#[tauri::command]
+fn bombe_walkthrough_cancel(walkthroughs: State<'_, BombeWalkthrough>, run: u64) {
+    walkthroughs.0.cancel(run);
+}
+
+/// Exporta o relatório completo de um ataque em JSON e Markdown, no caminho
+/// escolhido pelo usuário. Retorna os arquivos gravados.
+#[tauri::command]
+fn attack_export_report(export: AttackExport, path: PathBuf) -> Result<Vec<PathBuf>, String> {
+    ExportedReport::new(export).write(&path)
+}
+
+/// Pede a interrupção do ataque `run`, sem afetar os outros em andamento.
+#[tauri::command]
+fn cancel_attack(control: State<'_, AttackControl>, run: u64) {
+    control.cancel(run);
+}
+
+/// Ligação da rede de rádio aberta na UI (uma por vez).
+#[derive(Default)]
+struct RadioNet(Mutex<Option<RadioLink>>);
+
+/// Abre uma estação de rádio em `port` e espera o parceiro. Os acontecimentos da
+/// rede (parceiro conectado, interceptações, queda) saem no evento "radio".
+#[tauri::command]
+async fn radio_host(app: AppHandle, net: State<'_, RadioNet>, port: u16) -> Result<(), String> {
+    let link = RadioLink::host(port, move |event| {
+        let _ = app.emit("radio", event);
+    })
+    .await?;
+    *net.0.lock().unwrap() = Some(link);
+    Ok(())
+}
+
+/// Entra na estação de outra instância (ex.: "ws://192.168.0.10:9000").
+#[tauri::command]
+async fn radio_join(app: AppHandle, net: State<'_, RadioNet>, url: String) -> Result<(), String> {
+    let link = RadioLink::join(&url, move |event| {
+        let _ = app.emit("radio", event);
+    })
+    .await?;
+    *net.0.lock().unwrap() = Some(link);
+    Ok(())
+}
+
+/// Transmite um texto cifrado ao parceiro e retorna a transmissão como foi enviada.
+#[tauri::command]
+fn radio_send(net: State<'_, RadioNet>, callsign: String, ciphertext: String) -> Result<Transmission, String> {
+    let transmission = Transmission::new(&callsign, &ciphertext);
+    let net = net.0.lock().unwrap();
+    net.as_ref().ok_or("Nenhuma estação de rádio aberta.")?.send(transmission.clone())?;
+    Ok(transmission)
+}
+
+/// Sai da rede de rádio.
+#[tauri::command]
+fn radio_leave(net: State<'_, RadioNet>) {
+    *net.0.lock().unwrap() = None;
+}
+
+/// Turma aberta pelo professor neste app (uma por vez).
+#[derive(Default)]
+struct ClassroomHost(Mutex<Option<Classroom>>);
+
+/// Lugar deste app, como aluno, na turma de um professor.
+#[derive(Default)]
+struct ClassroomDesk(Mutex<Option<ClassroomSeat>>);
+
+/// Abre a turma em `port` para os alunos entrarem. A visão geral da turma sai no
+/// evento "classroom" sempre que algo muda.
+#[tauri::command]
+async fn classroom_host(app: AppHandle, host: State<'_, ClassroomHost>, port: u16) -> Result<(), String> {
+    let classroom = Classroom::host(port, move |event| {
+        let _ = app.emit("classroom", event);
+    })
+    .await?;
+    *host.0.lock().unwrap() = Some(classroom);
+    Ok(())
+}
+
+/// Passa um desafio novo de nível `difficulty`, um diferente para cada aluno.
+#[tauri::command]
+fn classroom_assign(host: State<'_, ClassroomHost>, difficulty: Difficulty) -> Result<Vec<StudentStatus>, String> {
+    let host = host.0.lock().unwrap();
+    Ok(host.as_ref().ok_or("Nenhuma turma aberta.")?.assign(difficulty))
+}
+
+/// Visão geral da turma aberta.
+#[tauri::command]
+fn classroom_overview(host: State<'_, ClassroomHost>) -> Result<Vec<StudentStatus>, String> {
+    let host = host.0.lock().unwrap();
+    Ok(host.as_ref().ok_or("Nenhuma turma aberta.")?.overview())
+}
+
+/// Fecha a turma, desconectando os alunos.
+#[tauri::command]
+fn classroom_close(host: State<'_, ClassroomHost>) {
+    *host.0.lock().unwrap() = None;
+}
+
+/// Entra como aluno na turma em `url` (ex.: "ws://192.168.0.10:9100"). Os desafios,
+/// dicas e correções chegam no evento "classroom-seat".
+#[tauri::command]
+async fn classroom_join(
+    app: AppHandle,
+    desk: State<'_, ClassroomDesk>,
+    url: String,
+    name: String,
+) -> Result<(), String> {
+    let seat = ClassroomSeat::join(&url, &name, move |event| {
+        let _ = app.emit("classroom-seat", event);
+    })
+    .await?;
+    *desk.0.lock().unwrap() = Some(seat);
+    Ok(())
+}
+
+/// Pede ao professor a próxima dica do desafio atual.
+#[tauri::command]
+fn classroom_request_hint(desk: State<'_, ClassroomDesk>) -> Result<(), String> {
+    let desk = desk.0.lock().unwrap();
+    desk.as_ref().ok_or("Você não está em nenhuma turma.")?.send(ToTeacher::Hint)
+}
+
+/// Manda ao professor a solução do desafio atual; a correção chega pelo evento.
+#[tauri::command]
+fn classroom_submit(desk: State<'_, ClassroomDesk>, solution: Solution) -> Result<(), String> {
+    let desk = desk.0.lock().unwrap();
+    desk.as_ref().ok_or("Você não está em nenhuma turma.")?.send(ToTeacher::Submit { solution })
+}
+
+/// Sai da turma.
+#[tauri::command]
+fn classroom_leave(desk: State<'_, ClassroomDesk>) {
+    *desk.0.lock().unwrap() = None;
+}
+
+/// Último link `enigma://` aberto, até a UI buscá-lo: quando o app é aberto pelo
+/// link, o evento sai antes de a UI carregar.
+#[derive(Default)]
+struct PendingLink(Mutex<Option<DeepLink>>);
+
+/// Lê os links abertos e os repassa à UI no evento "deep-link" (ou o erro em
+/// "deep-link-error").
+fn open_links(app: &AppHandle, urls: impl IntoIterator<Item = String>) {
+    for url in urls {
+        match deep_link::parse(&url) {
+            Ok(link) => {
+                *app.state::<PendingLink>().0.lock().unwrap() = Some(link.clone());
+                let _ = app.emit("deep-link", link);
+            }
+            Err(e) => {
+                let _ = app.emit("deep-link-error", e);
+            }
+        }
+    }
+}
+
+/// Retorna (e esquece) o último link aberto ainda não tratado pela UI.
+#[tauri::command]
+fn take_deep_link(pending: State<'_, PendingLink>) -> Option<DeepLink> {
+    pending.0.lock().unwrap().take()
+}
+
+/// Máquinas com estado abertas na UI, uma por janela (pelo rótulo da janela).
+#[derive(Default)]
+struct MachineState(Mutex<BTreeMap<String, Machine>>);
+
+/// Arquivo da sessão salva ao fechar o app, na pasta de dados do app.
+fn session_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join("session.json"))
+}
+
+/// Salva as sessões abertas (ou apaga as antigas, se não houver nenhuma).
+fn autosave(app: &AppHandle) -> Result<(), String> {
+    let machines = app.state::<MachineState>();
+    let machines = machines.0.lock().unwrap();
+    let sessions = machines.iter().map(|(label, machine)| (label.clone(), machine.session())).collect();
+    session::save(&session_path(app)?, &sessions)
+}
+
+/// Recusa mexer na máquina da janela `label` enquanto uma gravação é reproduzida nela.
+fn not_replaying(app: &AppHandle, label: &str) -> Result<(), String> {
+    if app.state::<ReplayState>().playing.lock().unwrap().contains_key(label) {
+        return Err("A máquina desta janela está reproduzindo uma gravação.".to_string());
+    }
+    Ok(())
+}
+
+/// Abre uma sessão nova na chave `config` para a janela que chamou, no lugar da
+/// anterior dela.
+#[tauri::command]
+fn machine_open(window: Window, state: State<'_, MachineState>, config: EnigmaConfig) -> Result<Session, String> {
+    not_replaying(window.app_handle(), window.label())?;
+    let machine = Machine::new(config).inspect_err(|e| tracing::warn!(erro = %e, "configuração recusada"))?;
+    let session = machine.session();
+    state.0.lock().unwrap().insert(window.label().to_string(), machine);
+    lesson_observe(window.app_handle(), window.label(), Action::Open);
+    replay_observe(window.app_handle(), window.label(), ReplayEvent::Open { config: session.config.clone() });
+    Ok(session)
+}
+
+/// Tecla uma letra na máquina da janela e retorna o passo detalhado.
+#[tauri::command]
+fn machine_press(window: Window, state: State<'_, MachineState>, key: char) -> Result<EncryptionStep, String> {
+    not_replaying(window.app_handle(), window.label())?;
+    let step = {
+        let mut state = state.0.lock().unwrap();
+        state.get_mut(window.label()).ok_or("Nenhuma máquina aberta nesta janela.")?.press(key)?
+    };
+    lesson_observe(window.app_handle(), window.label(), Action::Press);
+    replay_observe(window.app_handle(), window.label(), ReplayEvent::Press { key: step.input_char });
+    Ok(step)
+}
+
+/// Tecla um texto na máquina da janela e retorna as lâmpadas acesas.
+#[tauri::command]
+fn machine_type(window: Window, state: State<'_, MachineState>, text: String) -> Result<String, String> {
+    not_replaying(window.app_handle(), window.label())?;
+    let lamps = {
+        let mut state = state.0.lock().unwrap();
+        state.get_mut(window.label()).ok_or("Nenhuma máquina aberta nesta janela.")?.type_text(&text)
+    };
+    lesson_observe(window.app_handle(), window.label(), Action::Type);
+    replay_observe(window.app_handle(), window.label(), ReplayEvent::Type { text });
+    Ok(lamps)
+}
+
+/// Alfabeto de substituição que a máquina da janela aplica na próxima tecla, sem
+/// girar os rotores: para mostrar a cifra monoalfabética do momento mudando a
+/// cada tecla.
+#[tauri::command]
+fn machine_substitution(
+    window: Window,
+    state: State<'_, MachineState>,
+) -> Result<enigma::SubstitutionAlphabet, String> {
+    let state = state.0.lock().unwrap();
+    Ok(state.get(window.label()).ok_or("Nenhuma máquina aberta nesta janela.")?.next_substitution())
+}
+
+/// Volta a máquina da janela à chave inicial, apagando o transcrito.
+#[tauri::command]
+fn machine_reset(window: Window, state: State<'_, MachineState>) -> Result<Session, String> {
+    not_replaying(window.app_handle(), window.label())?;
+    let session = {
+        let mut state = state.0.lock().unwrap();
+        let machine = state.get_mut(window.label()).ok_or("Nenhuma máquina aberta nesta janela.")?;
+        machine.reset();
+        machine.session()
+    };
+    lesson_observe(window.app_handle(), window.label(), Action::Reset);
+    replay_observe(window.app_handle(), window.label(), ReplayEvent::Reset);
+    Ok(session)
+}
+
+/// Sessão da janela (a restaurada do último uso, logo ao abrir o app), se houver.
+#[tauri::command]
+fn machine_session(window: Window, state: State<'_, MachineState>) -> Option<Session> {
+    state.0.lock().unwrap().get(window.label()).map(Machine::session)
+}
+
+/// Abre a janela de máquina `label`, além da principal.
+fn open_machine_window(app: &AppHandle, label: &str) -> Result<(), String> {
+    tauri::WebviewWindowBuilder::new(app, label, tauri::WebviewUrl::default())
+        .title("enigma")
+        .inner_size(800.0, 600.0)
+        .build()
+        .map(|_| ())
+        .map_err(|e| format!("Erro ao abrir a janela: {}", e))
+}
+
+/// Abre outra janela com a sua própria máquina (ex.: emissor e receptor lado a
+/// lado), com rótulo `machine-2`, `machine-3`... Retorna o rótulo da janela nova.
+#[tauri::command]
+async fn machine_new_window(app: AppHandle) -> Result<String, String> {
+    let mut n = 2;
+    while app.get_webview_window(&format!("machine-{}", n)).is_some() {
+        n += 1;
+    }
+    let label = format!("machine-{}", n);
+    open_machine_window(&app, &label)?;
+    Ok(label)
+}
+
+/// Fecha a máquina da janela; ela não volta na próxima vez que o app abrir.
+#[tauri::command]
+fn machine_close(window: Window, state: State<'_, MachineState>) {
+    state.0.lock().unwrap().remove(window.label());
+}
+
+/// Gravações em andamento, uma por janela, e o pedido de parar a reprodução de
+/// cada janela que está reproduzindo.
+#[derive(Default)]
+struct ReplayState {
+    recording: Mutex<BTreeMap<String, Recorder>>,
+    playing: Mutex<BTreeMap<String, Arc<AtomicBool>>>,
+}
+
+/// Grava a ação na gravação da janela, se houver uma em andamento.
+fn replay_observe(app: &AppHandle, label: &str, event: ReplayEvent) {
+    if let Some(recorder) = app.state::<ReplayState>().recording.lock().unwrap().get_mut(label) {
+        recorder.record(event);
+    }
+}
+
+/// Começa a gravar o que é feito na máquina desta janela, a partir do estado atual
+/// dela, no lugar de uma gravação anterior ainda aberta.
+#[tauri::command]
+fn replay_start(window: Window, replays: State<'_, ReplayState>, machines: State<'_, MachineState>) {
+    let session = machines.0.lock().unwrap().get(window.label()).map(Machine::session);
+    let recorder = Recorder::start(session.as_ref());
+    replays.recording.lock().unwrap().insert(window.label().to_string(), recorder);
+}
+
+/// Termina a gravação desta janela e a salva em `path`. Retorna quantas ações
+/// foram gravadas.
+#[tauri::command]
+fn replay_stop(window: Window, replays: State<'_, ReplayState>, path: PathBuf) -> Result<usize, String> {
+    let recorder = replays.recording.lock().unwrap().remove(window.label());
+    let replay = recorder.ok_or("Nenhuma gravação em andamento nesta janela.")?.finish();
+    replay::save(&path, &replay)?;
+    Ok(replay.entries.len())
+}
+
+/// Reproduz a gravação `path` na máquina desta janela, no ritmo original (ou
+/// `speed` vezes mais rápido). Cada ação sai no evento "replay-event" para a
+/// janela; `replay_cancel` nesta janela interrompe. Retorna quantas ações foram
+/// reproduzidas.
+#[tauri::command]
+async fn replay_play(window: Window, path: PathBuf, speed: Option<f64>) -> Result<usize, String> {
+    let replay = replay::load(&path)?;
+    let app = window.app_handle().clone();
+    let label = window.label().to_string();
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let replays = app.state::<ReplayState>();
+        let mut playing = replays.playing.lock().unwrap();
+        if playing.contains_key(&label) {
+            return Err("Já há uma reprodução em andamento nesta janela.".to_string());
+        }
+        playing.insert(label.clone(), cancel.clone());
+    }
+    let play = move || {
+        let cancelled = || cancel.load(Ordering::Relaxed);
+        // Espera em fatias curtas, para atender a um cancelamento no meio de uma pausa longa
+        let wait = |delay: Duration| {
+            let until = Instant::now() + delay;
+            while !cancelled() && Instant::now() < until {
+                std::thread::sleep((until - Instant::now()).min(Duration::from_millis(50)));
+            }
+            !cancelled()
+        };
+        // A máquina fica no estado a cada ação, para o autosave não perdê-la se o app
+        // fechar no meio; as teclas da UI são recusadas até a reprodução terminar
+        let apply_event = |event: &ReplayEvent| {
+            let machines = app.state::<MachineState>();
+            let mut machines = machines.0.lock().unwrap();
+            let mut machine = machines.remove(&label);
+            let applied = replay::step(&mut machine, event);
+            if let Some(machine) = machine {
+                machines.insert(label.clone(), machine);
+            }
+            applied
+        };
+        let result = replay.play(apply_event, speed.unwrap_or(1.0), wait, |frame| {
+            let _ = app.emit_to(label.as_str(), "replay-event", frame);
+        });
+        app.state::<ReplayState>().playing.lock().unwrap().remove(&label);
+        result
+    };
+    tauri::async_runtime::spawn_blocking(play).await.map_err(|e| e.to_string())?
+}
+
+/// Interrompe a reprodução em andamento nesta janela.
+#[tauri::command]
+fn replay_cancel(window: Window, replays: State<'_, ReplayState>) {
+    if let Some(cancel) = replays.playing.lock().unwrap().get(window.label()) {
+        cancel.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Lições em andamento, uma por janela (pelo rótulo da janela).
+#[derive(Default)]
+struct LessonState(Mutex<BTreeMap<String, LessonRun>>);
+
+/// Abre a máquina da janela na chave do passo que acabou de começar, se ele tiver
+/// uma. Chamado com a trava das máquinas já segura.
+fn lesson_setup(machines: &mut BTreeMap<String, Machine>, label: &str, progress: &LessonProgress) {
+    let Some(config) = &progress.setup else { return };
+    match Machine::new(config.clone()) {
+        Ok(machine) => {
+            machines.insert(label.to_string(), machine);
+        }
+        Err(e) => tracing::warn!(erro = %e, "chave da lição recusada"),
+    }
+}
+
+/// Confere a ação `action` na máquina da janela `label` com a lição dela, se
+/// houver, e manda o andamento à janela no evento "lesson-progress".
+fn lesson_observe(app: &AppHandle, label: &str, action: Action) {
+    let lessons = app.state::<LessonState>();
+    let mut lessons = lessons.0.lock().unwrap();
+    let Some(run) = lessons.get_mut(label) else { return };
+    let machines = app.state::<MachineState>();
+    let mut machines = machines.0.lock().unwrap();
+    let Some(session) = machines.get(label).map(Machine::session) else { return };
+    let progress = run.observe(action, &session);
+    lesson_setup(&mut machines, label, &progress);
+    if progress.finished {
+        tracing::info!(titulo = %progress.title, "lição concluída");
+        lessons.remove(label);
+        record_progress(app, |done| done.complete_lesson(&progress.title, progress::now()));
+    }
+    let _ = app.emit_to(label, "lesson-progress", progress);
+}
+
+/// Começa a lição do roteiro `script` (JSON) na janela, no lugar da anterior
+/// dela. Se o primeiro passo tiver uma chave, a máquina da janela é aberta nela.
+/// O andamento depois de cada ação na máquina sai no evento "lesson-progress".
+#[tauri::command]
+fn lesson_start(
+    window: Window,
+    lessons: State<'_, LessonState>,
+    machines: State<'_, MachineState>,
+    script: String,
+) -> Result<LessonProgress, String> {
+    let run = LessonRun::from_json(&script).inspect_err(|e| tracing::warn!(erro = %e, "lição recusada"))?;
+    let progress = run.start();
+    let mut lessons = lessons.0.lock().unwrap();
+    lesson_setup(&mut machines.0.lock().unwrap(), window.label(), &progress);
+    lessons.insert(window.label().to_string(), run);
+    Ok(progress)
+}
+
+/// Andamento da lição da janela, se houver uma.
+#[tauri::command]
+fn lesson_progress(window: Window, lessons: State<'_, LessonState>) -> Option<LessonProgress> {
+    lessons.0.lock().unwrap().get(window.label()).map(LessonRun::current)
+}
+
+/// Abandona a lição da janela; a máquina continua como está.
+#[tauri::command]
+fn lesson_stop(window: Window, lessons: State<'_, LessonState>) {
+    lessons.0.lock().unwrap().remove(window.label());
+}
+
+/// Cifra os arquivos soltos na janela com a chave da máquina dela, numa thread à
+/// parte. O andamento sai no evento "file-progress", cada resultado em
+/// "file-encrypted" e os erros em "file-drop-error", todos só para a janela.
+fn encrypt_dropped(window: &Window, paths: Vec<PathBuf>) {
+    let app = window.app_handle().clone();
+    let label = window.label().to_string();
+    let config = app.state::<MachineState>().0.lock().unwrap().get(&label).map(|machine| machine.config().clone());
+    let Some(config) = config else {
+        let error = "Abra uma máquina nesta janela antes de soltar arquivos.";
+        let _ = app.emit_to(label.as_str(), "file-drop-error", error);
+        return;
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        for path in paths {
+            let result = file_cipher::encrypt_txt(&path, config.clone(), |done, total| {
+                let progress = FileProgress { path: path.clone(), done, total };
+                let _ = app.emit_to(label.as_str(), "file-progress", progress);
+            });
+            let _ = match result {
+                Ok(output) => app.emit_to(label.as_str(), "file-encrypted", DroppedFile { path, output }),
+                Err(e) => app.emit_to(label.as_str(), "file-drop-error", e),
+            };
+        }
+    });
+}
+
+/// Cifra o arquivo `in_path` e grava o resultado em `out_path`, aos poucos, sem
+/// carregá-lo todo na memória. O andamento sai no evento "file-progress" para a
+/// janela que chamou. Retorna quantas letras foram cifradas.
+#[tauri::command]
+async fn encrypt_file(
+    window: Window,
+    config: EnigmaConfig,
+    in_path: PathBuf,
+    out_path: PathBuf,
+    options: Option<FileOptions>,
+) -> Result<usize, String> {
+    let options = options.unwrap_or_default();
+    let encrypt = move || {
+        file_cipher::encrypt_file(config, &in_path, &out_path, &options, |done, total| {
+            let progress = FileProgress { path: in_path.clone(), done, total };
+            let _ = window.emit_to(window.label(), "file-progress", progress);
+        })
+    };
+    tauri::async_runtime::spawn_blocking(encrypt).await.map_err(|e| e.to_string())?
+}
+
+/// Teclas físicas capturadas no modo teclado, com a letra de cada uma.
+#[cfg(desktop)]
+const KEYS: [(Code, char); 26] = [
+    (Code::KeyA, 'A'), (Code::KeyB, 'B'), (Code::KeyC, 'C'), (Code::KeyD, 'D'), (Code::KeyE, 'E'), (Code::KeyF, 'F'),
+    (Code::KeyG, 'G'), (Code::KeyH, 'H'), (Code::KeyI, 'I'), (Code::KeyJ, 'J'), (Code::KeyK, 'K'), (Code::KeyL, 'L'),
+    (Code::KeyM, 'M'), (Code::KeyN, 'N'), (Code::KeyO, 'O'), (Code::KeyP, 'P'), (Code::KeyQ, 'Q'), (Code::KeyR, 'R'),
+    (Code::KeyS, 'S'), (Code::KeyT, 'T'), (Code::KeyU, 'U'), (Code::KeyV, 'V'), (Code::KeyW, 'W'), (Code::KeyX, 'X'),
+    (Code::KeyY, 'Y'), (Code::KeyZ, 'Z'),
+];
+
+/// Janela que recebe as teclas físicas, com o modo teclado ligado.
+#[derive(Default)]
+struct KeyboardCapture(Mutex<Option<String>>);
+
+/// Tecla física capturada: cifra na máquina da janela que ligou o modo teclado e
+/// manda o passo a ela no evento "machine-key" (ou o erro em "machine-key-error").
+#[cfg(desktop)]
+fn captured_key(app: &AppHandle, shortcut: &Shortcut) {
+    let Some(&(_, key)) = KEYS.iter().find(|(code, _)| *code == shortcut.key) else { return };
+    let Some(label) = app.state::<KeyboardCapture>().0.lock().unwrap().clone() else { return };
+    let machines = app.state::<MachineState>();
+    let step = not_replaying(app, &label).and_then(|()| match machines.0.lock().unwrap().get_mut(&label) {
+        Some(machine) => machine.press(key),
+        None => Err("Nenhuma máquina aberta nesta janela.".to_string()),
+    });
+    if let Ok(step) = &step {
+        lesson_observe(app, &label, Action::Press);
+        replay_observe(app, &label, ReplayEvent::Press { key: step.input_char });
+    }
+    let _ = match step {
+        Ok(step) => app.emit_to(label.as_str(), "machine-key", step),
+        Err(e) => app.emit_to(label.as_str(), "machine-key-error", e),
+    };
+}
+
+/// Liga ou desliga o modo teclado físico: ligado, cada letra apertada no teclado
+/// gira os rotores da máquina desta janela, como numa Enigma de verdade, mesmo com
+/// o foco em outro lugar. Só uma janela recebe as teclas por vez.
+#[tauri::command]
+fn machine_capture_keyboard(
+    app: AppHandle,
+    window: Window,
+    capture: State<'_, KeyboardCapture>,
+    enabled: bool,
+) -> Result<(), String> {
+    #[cfg(desktop)]
+    {
+        let shortcuts: Vec<Shortcut> = KEYS.iter().map(|&(code, _)| Shortcut::new(None, code)).collect();
+        // O registro roda na thread principal, onde também roda `captured_key`: não
+        // segura a trava de `capture` enquanto espera por ele
+        if !enabled {
+            let previous = capture.0.lock().unwrap().take();
+            if previous.is_some() {
+                app.global_shortcut().unregister_multiple(shortcuts).map_err(|e| e.to_string())?;
+            }
+            return Ok(());
+        }
+        if !app.state::<MachineState>().0.lock().unwrap().contains_key(window.label()) {
+            return Err("Nenhuma máquina aberta nesta janela.".to_string());
+        }
+        let previous = capture.0.lock().unwrap().replace(window.label().to_string());
+        if previous.is_none() {
+            if let Err(e) = app.global_shortcut().register_multiple(shortcuts) {
+                *capture.0.lock().unwrap() = None;
+                return Err(format!("Erro ao capturar o teclado: {}", e));
+            }
+        }
+        Ok(())
+    }
+    #[cfg(mobile)]
+    {
+        let _ = (app, window, capture, enabled);
+        Err("O modo teclado só existe no desktop.".to_string())
+    }
+}
+
+/// Configuração salva escolhida no menu da bandeja para a cifragem rápida.
+#[cfg(desktop)]
+#[derive(Default)]
+struct TrayPreset(Mutex<Option<String>>);
+
+/// Cifra (ou decifra) o texto da área de transferência na configuração salva
+/// escolhida no menu da bandeja e põe o resultado no lugar. Um erro vai para a UI
+/// no evento "tray-error".
+#[cfg(desktop)]
+fn quick_cipher(app: &AppHandle, action: QuickAction) {
+    let result = (|| {
+        let name = app.state::<TrayPreset>().0.lock().unwrap().clone();
+        let name = name.ok_or("Escolha uma configuração salva no menu da bandeja.")?;
+        let config = presets::load(&presets_path(app)?, &name)?;
+        let text = app.clipboard().read_text().map_err(|e| format!("Erro ao ler a área de transferência: {}", e))?;
+        let output = tray::apply(config.clone(), action, &text)?;
+        app.clipboard().write_text(output.clone()).map_err(|e| format!("Erro ao copiar o texto: {}", e))?;
+        remember(app, &config, &text, &output);
+        Ok::<_, String>(())
+    })();
+    if let Err(e) = result {
+        tracing::warn!(erro = %e, "cifragem pela bandeja falhou");
+        let _ = app.emit("tray-error", e);
+    }
+}
+
+/// Menu da bandeja, com as configurações salvas num submenu e a escolhida marcada.
+#[cfg(desktop)]
+fn tray_menu(app: &AppHandle) -> tauri::Result<tauri::menu::Menu<tauri::Wry>> {
+    use tauri::menu::{CheckMenuItem, Menu, MenuItem, Submenu};
+
+    let selected = app.state::<TrayPreset>().0.lock().unwrap().clone();
+    let names = presets_path(app).and_then(|path| presets::list(&path)).unwrap_or_default();
+    let presets = Submenu::with_id(app, "tray-presets", "Configuração", true)?;
+    if names.is_empty() {
+        presets.append(&MenuItem::with_id(app, "tray-no-presets", "Nenhuma configuração salva", false, None::<&str>)?)?;
+    }
+    for name in names {
+        let checked = selected.as_deref() == Some(name.as_str());
+        let id = format!("{}{}", TRAY_PRESET, name);
+        presets.append(&CheckMenuItem::with_id(app, id, &name, true, checked, None::<&str>)?)?;
+    }
+    let encrypt = MenuItem::with_id(app, "tray-encrypt", "Cifrar a área de transferência", true, None::<&str>)?;
+    let decrypt = MenuItem::with_id(app, "tray-decrypt", "Decifrar a área de transferência", true, None::<&str>)?;
+    let open = MenuItem::with_id(app, "tray-open", "Abrir o Enigma", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "tray-quit", "Sair", true, None::<&str>)?;
+    Menu::with_items(app, &[&presets, &encrypt, &decrypt, &open, &quit])
+}
+
+/// Início do id dos itens do submenu de configurações; o resto é o nome.
+#[cfg(desktop)]
+const TRAY_PRESET: &str = "tray-preset:";
+
+/// Refaz o menu da bandeja, depois de mudar as configurações salvas ou a escolhida.
+#[cfg(desktop)]
+fn refresh_tray(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id("enigma") else { return };
+    match tray_menu(app) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => tracing::warn!(erro = %e, "menu da bandeja não foi atualizado"),
+    }
+}
+
+/// Ícone na bandeja com a cifragem rápida da área de transferência.
+#[cfg(desktop)]
+fn build_tray(app: &tauri::App) -> tauri::Result<()> {
+    use tauri::tray::TrayIconBuilder;
+
+    let menu = tray_menu(app.handle())?;
+    let mut tray = TrayIconBuilder::with_id("enigma").tooltip("Enigma").menu(&menu).show_menu_on_left_click(true);
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.on_menu_event(|app, event| match event.id().as_ref() {
+        "tray-encrypt" => quick_cipher(app, QuickAction::Encrypt),
+        "tray-decrypt" => quick_cipher(app, QuickAction::Decrypt),
+        "tray-open" => {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }
+        "tray-quit" => app.exit(0),
+        id => {
+            if let Some(name) = id.strip_prefix(TRAY_PRESET) {
+                *app.state::<TrayPreset>().0.lock().unwrap() = Some(name.to_string());
+                refresh_tray(app);
+            }
+        }
+    })
+    .build(app)?;
+    Ok(())
+}
+
+/// Mantém a thread do registro viva enquanto o app roda.
+struct LogGuard {
+    _guard: WorkerGuard,
+}
+
+/// Pasta do registro, na pasta de dados do app.
+fn log_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join("logs"))
+}
+
+/// Até `limit` linhas recentes do registro de nível `level` ("error", "warn",
+/// "info") ou mais grave, da mais recente para a mais antiga, para anexar a um
+/// relato de problema.
+#[tauri::command]
+fn get_recent_logs(app: AppHandle, level: String, limit: usize) -> Result<Vec<LogEntry>, String> {
+    let level: Level = level.parse().map_err(|_| format!("Nível de registro desconhecido: {}", level))?;
+    logging::recent(&log_dir(&app)?, level, limit)
+}
+
+/// Resultado do autoteste feito ao abrir o app.
+struct SelfTest(Vec<VectorResult>);
+
+/// Confere o motor contra os vetores de teste publicados. O teste roda ao abrir o
+/// app; a UI mostra o resultado guardado e avisa se algum vetor falhou.
+#[tauri::command]
+fn self_test(state: State<'_, SelfTest>) -> Vec<VectorResult> {
+    state.0.clone()
+}
+
+/// Pacotes de peças lidos ao abrir o app e os instalados depois, do catálogo.
+struct ComponentPacks(Mutex<Vec<PackStatus>>);
+
+/// Pasta dos pacotes de peças, na pasta de dados do app.
+fn packs_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join("components"))
+}
+
+/// Rotores, refletores e modelos disponíveis: os embutidos e os dos pacotes.
+#[tauri::command]
+fn component_list() -> ComponentList {
+    packs::list()
+}
+
+/// Ano, forças, ranhuras e curiosidades de cada peça e modelo, para os painéis de
+/// informação.
+#[tauri::command]
+fn component_history() -> ComponentHistory {
+    packs::history()
+}
+
+/// Os pacotes de peças instalados, com o erro dos que ficaram de fora.
+#[tauri::command]
+fn component_packs(state: State<'_, ComponentPacks>) -> Vec<PackStatus> {
+    state.0.lock().unwrap().clone()
+}
+
+/// Pacotes oferecidos pelo catálogo publicado em `url` (configurado na UI).
+#[tauri::command]
+async fn catalog_list(url: String) -> Result<PackCatalog, String> {
+    catalog::fetch_catalog(&url).await
+}
+
+/// Baixa do catálogo em `url` os pacotes chamados `names`, confere o SHA-256 de
+/// cada um e os instala na pasta de pacotes, já valendo nesta sessão.
+#[tauri::command]
+async fn catalog_install(app: AppHandle, url: String, names: Vec<String>) -> Result<Vec<PackStatus>, String> {
+    let catalog = catalog::fetch_catalog(&url).await?;
+    let dir = packs_dir(&app)?;
+    let mut statuses = Vec::new();
+    for name in &names {
+        let entry = catalog
+            .packs
+            .iter()
+            .find(|entry| &entry.name == name)
+            .ok_or_else(|| format!("O catálogo não tem o pacote {}", name))?;
+        let status = catalog::install(&dir, entry).await;
+        match &status.error {
+            Some(e) => tracing::warn!(pacote = %entry.name, erro = %e, "pacote do catálogo recusado"),
+            None => tracing::info!(pacote = %entry.name, arquivo = %entry.file, "pacote do catálogo instalado"),
+        }
+        statuses.push(status);
+    }
+    let state = app.state::<ComponentPacks>();
+    let mut installed = state.0.lock().unwrap();
+    for status in statuses.iter().filter(|status| status.error.is_none()) {
+        installed.retain(|known| known.file != status.file);
+        installed.push(status.clone());
+    }
+    Ok(statuses)
+}
+
+/// Questionários em andamento, com as respostas que a UI não vê.
+#[derive(Default)]
+struct QuizState(Mutex<Quizzes>);
+
+/// Gera um questionário sobre `topic`. Vão para a UI só os enunciados e as chaves;
+/// as respostas ficam guardadas para `check_answer`.
+#[tauri::command]
+fn generate_quiz(state: State<'_, QuizState>, topic: Topic, difficulty: Difficulty) -> QuizView {
+    state.0.lock().unwrap().generate(&mut StdRng::from_entropy(), topic, difficulty)
+}
+
+/// Corrige a resposta da próxima pergunta do questionário `quiz_id`. A nota de
+/// um questionário terminado vai para o progresso.
+#[tauri::command]
+fn check_answer(
+    app: AppHandle,
+    state: State<'_, QuizState>,
+    quiz_id: u64,
+    answer: String,
+) -> Result<Correction, String> {
+    let correction = state.0.lock().unwrap().check(quiz_id, &answer)?;
+    if correction.finished {
+        let score = QuizScore {
+            topic: correction.topic,
+            difficulty: correction.difficulty,
+            correct: correction.score.correct,
+            total: correction.score.total,
+            finished_at: progress::now(),
+        };
+        record_progress(&app, |progress| {
+            progress.add_quiz(score);
+            true
+        });
+    }
+    Ok(correction)
+}
+
+/// Desafios em andamento, com as chaves que a UI não vê.
+#[derive(Default)]
+struct ChallengeState(Mutex<Challenges>);
+
+/// Gera um desafio: um texto cifrado com uma chave sorteada, guardada para
+/// `request_hint` e `submit_solution`.
+#[tauri::command]
+fn generate_challenge(state: State<'_, ChallengeState>, difficulty: Difficulty) -> ChallengeView {
+    state.0.lock().unwrap().generate(&mut StdRng::from_entropy(), difficulty)
+}
+
+/// Revela a próxima parte da chave do desafio `challenge_id`.
+#[tauri::command]
+fn request_hint(state: State<'_, ChallengeState>, challenge_id: u64) -> Result<HintReveal, String> {
+    state.0.lock().unwrap().hint(challenge_id)
+}
+
+/// Confere a solução (texto claro ou chave) do desafio `challenge_id`. Um desafio
+/// resolvido vai para o progresso.
+#[tauri::command]
+fn submit_solution(
+    app: AppHandle,
+    state: State<'_, ChallengeState>,
+    challenge_id: u64,
+    solution: Solution,
+) -> Result<Verdict, String> {
+    let verdict = state.0.lock().unwrap().submit(challenge_id, &solution)?;
+    if verdict.solved {
+        record_progress(&app, |done| done.solve_challenge(&verdict.ciphertext, progress::now()));
+    }
+    Ok(verdict)
+}
+
+/// Monta o exercício do rascunho (cifrando o texto claro com a chave dele) e o
+/// grava em `path`, assinado com o segredo do curso. A chave fica legível no
+/// arquivo: ver `exercise::NOTICE`.
+#[tauri::command]
+fn exercise_export(path: PathBuf, draft: ExerciseDraft, secret: String) -> Result<(), String> {
+    exercise::export(&path, &Exercise::from_draft(draft)?, &secret)
+}
+
+/// Abre um exercício, conferindo a assinatura com o segredo do curso, e o guarda
+/// como desafio: as dicas e a correção seguem por `request_hint` e
+/// `submit_solution`, com as regras do arquivo.
+#[tauri::command]
+fn exercise_import(state: State<'_, ChallengeState>, path: PathBuf, secret: String) -> Result<ExerciseView, String> {
+    let exercise = exercise::import(&path, &secret)?;
+    Ok(state.0.lock().unwrap().open_exercise(exercise))
+}
+
+/// Arquivo do progresso nas atividades de aula, na pasta de dados do app.
+fn progress_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join("progress.json"))
+}
+
+/// Grava uma mudança no progresso. Uma falha fica só no log: não atrapalha a aula.
+fn record_progress(app: &AppHandle, change: impl FnOnce(&mut Progress) -> bool) {
+    if let Err(e) = progress_path(app).and_then(|path| progress::update(&path, change)) {
+        tracing::warn!(erro = %e, "progresso não gravado");
+    }
+}
+
+/// Lições concluídas, desafios resolvidos e notas dos questionários.
+#[tauri::command]
+fn progress_get(app: AppHandle) -> Result<Progress, String> {
+    Ok(progress::load(&progress_path(&app)?))
+}
+
+/// Registra o desafio `challenge` (ex.: o texto cifrado de um link `enigma://`)
+/// como resolvido.
+#[tauri::command]
+fn progress_solve_challenge(app: AppHandle, challenge: String) -> Result<(), String> {
+    progress::update(&progress_path(&app)?, |progress| progress.solve_challenge(&challenge, progress::now()))
+}
+
+/// Apaga todo o progresso, para começar o curso de novo.
+#[tauri::command]
+fn progress_reset(app: AppHandle) -> Result<(), String> {
+    progress::reset(&progress_path(&app)?)
+}
+
+/// Fixa o tipo do handler gerado, que sozinho num `let` não é inferido.
+fn logged<F: Fn(tauri::ipc::Invoke<tauri::Wry>) -> bool>(handler: F) -> F {
+    handler
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    // Todo comando chamado pela UI passa pelo registro antes de rodar
+    let handler = logged(tauri::generate_handler![
+        enigma_process_string,
+        enigma_process_detailed,
+        classical_process,
+        classical_process_detailed,
+        typex_process,
+        typex_process_detailed,
+        sigaba_process,
+        sigaba_process_detailed,
+        lorenz_process,
+        lorenz_process_detailed,
+        diff_traces,
+        enigma_position_timeline,
+        enigma_rotor_wirings,
+        trace_repeated_letter,
+        mapping_evolution,
+        enigma_export_trace,
+        enigma_import_trace,
+        enigma_export_csv,
+        enigma_step_svg,
+        enigma_save_step_svg,
+        enigma_diagram_graph,
+        keyboard_layout,
+        export_pdf,
+        copy_ciphertext,
+        encrypt_file,
+        config_share_code,
+        config_qr_png,
+        config_from_share_code,
+        import_simulator_settings,
+        save_config_file,
+        open_config_file,
+        save_key_sheet_file,
+        open_key_sheet_file,
+        export_key_file,
+        import_key_file,
+        take_deep_link,
+        get_recent_logs,
+        self_test,
+        component_list,
+        component_history,
+        component_packs,
+        catalog_list,
+        catalog_install,
+        generate_quiz,
+        check_answer,
+        generate_challenge,
+        request_hint,
+        submit_solution,
+        exercise_export,
+        exercise_import,
+        progress_get,
+        progress_solve_challenge,
+        progress_reset,
+        machine_open,
+        machine_press,
+        machine_type,
+        machine_substitution,
+        replay_start,
+        replay_stop,
+        replay_play,
+        replay_cancel,
+        machine_reset,
+        machine_session,
+        machine_new_window,
+        machine_close,
+        machine_capture_keyboard,
+        lesson_start,
+        lesson_progress,
+        lesson_stop,
+        preset_save,
+        preset_list,
+        preset_load,
+        preset_delete,
+        recent_configs,
+        clear_recent,
+        history_recent,
+        history_search,
+        history_purge,
+        save_workspace,
+        open_workspace,
+        enigma_process_batch,
+        enigma_benchmark,
+        enigma_compare_settings,
+        enigma_frequency_demo,
+        enigma_self_encryption_demo,
+        enigma_rejewski_indicators,
+        enigma_rejewski_attack,
+        enigma_cyclometer_build,
+        enigma_cyclometer_lookup,
+        enigma_zygalski_sheets,
+        enigma_banburismus,
+        enigma_suggest_cribs,
+        enigma_depth_test,
+        enigma_depth_open,
+        enigma_depth_guess,
+        enigma_depth_undo,
+        enigma_keyspace,
+        explain_keyspace,
+        enigma_unicity,
+        enigma_herivel_tip,
+        enigma_detect_cillies,
+        attack_scorers,
+        attack_start,
+        attack_load_ngrams,
+        attack_bruteforce,
+        attack_checkpoints,
+        resume_attack,
+        discard_checkpoint,
+        partition_search,
+        run_chunk,
+        merge_results,
+        attack_export_report,
+        attack_search_space_size,
+        attack_refine_rings,
+        attack_plugboard,
+        attack_known_plaintext,
+        bombe_check_stops,
+        bombe_diagonal_board,
+        bombe_walkthrough_start,
+        bombe_walkthrough,
+        bombe_walkthrough_cancel,
+        attack_message_keys,
+        attack_pipeline,
+        cancel_attack,
+        radio_host,
+        radio_join,
+        radio_send,
+        radio_leave,
+        classroom_host,
+        classroom_assign,
+        classroom_overview,
+        classroom_close,
+        classroom_join,
+        classroom_request_hint,
+        classroom_submit,
+        classroom_leave
+    ]);
+    let builder = tauri::Builder::default();
+    // Tem de vir antes dos outros plugins: um link enigma:// com o app aberto chega
+    // à instância que já roda (pelo `on_open_url`), em vez de abrir uma segunda que
+    // sobrescreveria a sessão salva ao sair
+    #[cfg(desktop)]
+    let builder = builder.plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }));
+    builder
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![
-            enigma_process_string,
-            enigma_process_detailed
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .setup(|app| {
+            // Sem o registro o app funciona do mesmo jeito
+            if let Ok(guard) = log_dir(app.handle()).and_then(|dir| logging::init(&dir)) {
+                app.manage(LogGuard { _guard: guard });
+            }
+            let results = enigma::self_test();
+            for result in results.iter().filter(|result| !result.passed) {
+                tracing::error!(vetor = %result.name, saida = %result.output, "autoteste falhou");
+            }
+            app.manage(SelfTest(results));
+            // Antes de retomar as sessões, que podem usar peças dos pacotes
+            let statuses = packs_dir(app.handle()).map(|dir| packs::load_dir(&dir)).unwrap_or_default();
+            for status in &statuses {
+                match &status.error {
+                    Some(e) => tracing::warn!(arquivo = %status.file, erro = %e, "pacote de peças recusado"),
+                    None => tracing::info!(arquivo = %status.file, pacote = %status.name, "pacote de peças carregado"),
+                }
+            }
+            app.manage(ComponentPacks(Mutex::new(statuses)));
+            #[cfg(desktop)]
+            app.handle().plugin(
+                tauri_plugin_global_shortcut::Builder::new()
+                    .with_handler(|app, shortcut, event| {
+                        if event.state() == ShortcutState::Pressed {
+                            captured_key(app, shortcut);
+                        }
+                    })
+                    .build(),
+            )?;
+            #[cfg(desktop)]
+            {
+                app.manage(TrayPreset::default());
+                build_tray(app)?;
+            }
+            // No Linux e no Windows o esquema só fica registrado depois de instalado;
+            // sem ele os links não abrem o app, mas o resto funciona
+            #[cfg(any(windows, target_os = "linux"))]
+            if let Err(e) = app.deep_link().register_all() {
+                tracing::warn!(erro = %e, "links enigma:// não registrados");
+            }
+            if let Some(urls) = app.deep_link().get_current()? {
+                open_links(app.handle(), urls.iter().map(|url| url.to_string()));
+            }
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                open_links(&handle, event.urls().iter().map(|url| url.to_string()));
+            });
+            // Retoma as demonstrações deixadas abertas da última vez
+            if let Ok(path) = session_path(app.handle()) {
+                match session::load(&path) {
+                    Ok(machines) => {
+                        // As janelas extras voltam junto com as suas máquinas
+                        for label in machines.keys().filter(|label| *label != "main") {
+                            if let Err(e) = open_machine_window(app.handle(), label) {
+                                tracing::warn!(janela = %label, erro = %e, "janela da sessão não reaberta");
+                            }
+                        }
+                        *app.state::<MachineState>().0.lock().unwrap() = machines;
+                    }
+                    Err(e) => {
+                        // Guarda o arquivo de lado, para o próximo autosave não apagá-lo
+                        tracing::warn!(erro = %e, "sessão salva não restaurada");
+                        let _ = std::fs::rename(&path, path.with_extension("invalid.json"));
+                    }
+                }
+            }
+            Ok(())
+        })
+        .on_window_event(|window, event| {
+            if let WindowEvent::DragDrop(DragDropEvent::Drop { paths, .. }) = event {
+                encrypt_dropped(window, paths.clone());
+            }
+        })
+        .manage(AttackControl::default())
+        .manage(BombeWalkthrough::default())
+        .manage(ScorerRegistry::default())
+        .manage(DepthSession::default())
+        .manage(RadioNet::default())
+        .manage(ClassroomHost::default())
+        .manage(ClassroomDesk::default())
+        .manage(PendingLink::default())
+        .manage(MachineState::default())
+        .manage(KeyboardCapture::default())
+        .manage(QuizState::default())
+        .manage(ChallengeState::default())
+        .manage(ReplayState::default())
+        .manage(MessageHistory::default())
+        .manage(LessonState::default())
+        .invoke_handler(move |invoke| {
+            tracing::info!(command = invoke.message.command(), "comando");
+            handler(invoke)
+        })
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                let _ = autosave(app);
+            }
+        });
 }
@@ -0,0 +1,125 @@
+//! Registro (log) do app em arquivos na pasta de dados: comandos chamados, chaves
+//! recusadas e marcos dos ataques. `recent` lê as últimas linhas de volta, para o
+//! usuário anexar a um relato de problema sem ter de procurar os arquivos.
+//!
+//! Um arquivo por dia (`enigma.AAAA-MM-DD.log`), guardando os `LOG_FILES` mais
+//! recentes. Cada linha é "instante NÍVEL alvo: mensagem campo=valor".
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::Serialize;
+use tracing::Level;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{Builder, Rotation};
+
+const LOG_PREFIX: &str = "enigma";
+const LOG_SUFFIX: &str = "log";
+
+/// Quantos arquivos diários o registro guarda.
+pub const LOG_FILES: usize = 7;
+
+/// Liga o registro em `dir`. As linhas são gravadas por uma thread à parte, que
+/// termina de gravar quando o `WorkerGuard` é descartado: guarde-o até o app fechar.
+pub fn init(dir: &Path) -> Result<WorkerGuard, String> {
+    let appender = Builder::new()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_PREFIX)
+        .filename_suffix(LOG_SUFFIX)
+        .max_log_files(LOG_FILES)
+        .build(dir)
+        .map_err(|e| format!("Erro ao abrir o registro: {}", e))?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    tracing_subscriber::fmt()
+        .with_writer(writer)
+        .with_ansi(false)
+        .with_max_level(Level::INFO)
+        .try_init()
+        .map_err(|e| format!("Erro ao ligar o registro: {}", e))?;
+    Ok(guard)
+}
+
+/// Uma linha do registro.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    /// Mensagem e campos ("comando command=\"machine_open\"").
+    pub message: String,
+}
+
+/// Lê uma linha do registro. Linhas de continuação (mensagens com quebra de linha)
+/// não têm cabeçalho e ficam de fora.
+fn parse_line(line: &str) -> Option<(Level, LogEntry)> {
+    let (timestamp, rest) = line.trim_start().split_once(' ')?;
+    let (level, rest) = rest.trim_start().split_once(' ')?;
+    let (target, message) = rest.split_once(": ")?;
+    let entry = LogEntry {
+        timestamp: timestamp.to_string(),
+        level: level.to_string(),
+        target: target.to_string(),
+        message: message.to_string(),
+    };
+    Some((Level::from_str(level).ok()?, entry))
+}
+
+/// Arquivos do registro em `dir`, do mais antigo para o mais recente (o nome leva a
+/// data). Uma pasta ausente conta como vazia.
+fn log_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+            name.starts_with(&format!("{}.", LOG_PREFIX)) && name.ends_with(&format!(".{}", LOG_SUFFIX))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Até `limit` linhas do registro de nível `level` ou mais grave, da mais recente
+/// para a mais antiga.
+pub fn recent(dir: &Path, level: Level, limit: usize) -> Result<Vec<LogEntry>, String> {
+    let mut entries = Vec::new();
+    for path in log_files(dir).iter().rev() {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("Erro ao ler o registro: {}", e))?;
+        // `Level` cresce com o detalhe: TRACE > DEBUG > INFO > WARN > ERROR
+        let lines = text.lines().rev().filter_map(parse_line).filter(|(line_level, _)| *line_level <= level);
+        entries.extend(lines.map(|(_, entry)| entry).take(limit - entries.len()));
+        if entries.len() == limit {
+            break;
+        }
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_filters_by_level_newest_first() {
+        let dir = std::env::temp_dir().join(format!("enigma-logs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let older = "2026-10-15T09:00:00.000000Z  INFO enigma_lib: comando command=\"machine_open\"\n\
+            2026-10-15T09:00:01.000000Z  WARN enigma_lib: chave recusada erro=\"Rotor desconhecido: IX\"\n";
+        let newer = "2026-10-16T10:00:00.000000Z ERROR enigma_lib: falha\n  continuação\n\
+            2026-10-16T10:00:02.000000Z  INFO enigma_lib: ataque terminado cancelado=false\n";
+        std::fs::write(dir.join("enigma.2026-10-15.log"), older).unwrap();
+        std::fs::write(dir.join("enigma.2026-10-16.log"), newer).unwrap();
+        std::fs::write(dir.join("outro.txt"), "2026-10-16T11:00:00Z ERROR x: y\n").unwrap();
+
+        let all = recent(&dir, Level::INFO, 10).unwrap();
+        let messages: Vec<&str> = all.iter().map(|entry| entry.message.as_str()).collect();
+        assert_eq!(messages[..2], ["ataque terminado cancelado=false", "falha"]);
+        assert_eq!(messages[2], "chave recusada erro=\"Rotor desconhecido: IX\"");
+        assert_eq!(messages[3], "comando command=\"machine_open\"");
+        let warnings = recent(&dir, Level::WARN, 10).unwrap();
+        assert_eq!(warnings.iter().map(|entry| entry.level.as_str()).collect::<Vec<_>>(), ["ERROR", "WARN"]);
+        assert_eq!(recent(&dir, Level::INFO, 1).unwrap().len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(recent(&dir, Level::INFO, 10).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+//! Pacotes de peças (rotores, refletores e modelos extras) lidos da pasta
+//! `components` nos dados do app ao abrir, um arquivo JSON por pacote.
+//!
+//! Os arquivos são lidos em ordem alfabética, então um pacote pode montar modelos
+//! com peças de um pacote anterior. Um pacote com erro fica de fora, sem impedir
+//! os outros; o erro aparece na lista de pacotes.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::enigma::components::{self, ComponentDetails, ComponentPack, ModelDefinition};
+
+/// Resultado da carga de um arquivo de pacote.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PackStatus {
+    /// Nome do arquivo na pasta `components`.
+    pub file: String,
+    /// Nome do pacote (vazio se o arquivo nem pôde ser lido).
+    pub name: String,
+    /// Conteúdo, ex.: "2 rotores, 1 refletor, 0 modelos".
+    pub summary: String,
+    pub error: Option<String>,
+}
+
+/// Peças que a máquina sabe montar, para as listas da UI.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ComponentList {
+    pub rotors: Vec<String>,
+    pub reflectors: Vec<String>,
+    pub models: Vec<ModelDefinition>,
+}
+
+/// Peças e modelos com os dados históricos, para os painéis de informação.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ComponentHistory {
+    pub rotors: Vec<ComponentDetails>,
+    pub reflectors: Vec<ComponentDetails>,
+    pub models: Vec<ModelDefinition>,
+}
+
+/// Lê um arquivo de pacote.
+fn read(path: &Path) -> Result<ComponentPack, String> {
+    let file = File::open(path).map_err(|e| format!("Erro ao abrir o pacote: {}", e))?;
+    serde_json::from_reader(BufReader::new(file)).map_err(|e| format!("Pacote de peças inválido: {}", e))
+}
+
+/// Arquivos `.json` de `dir`, em ordem alfabética. Uma pasta ausente conta como vazia.
+fn pack_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json")))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Lê e registra todos os pacotes de `dir`.
+pub fn load_dir(dir: &Path) -> Vec<PackStatus> {
+    pack_files(dir)
+        .iter()
+        .map(|path| {
+            let file = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            match read(path) {
+                Ok(pack) => PackStatus {
+                    file,
+                    name: pack.name.clone(),
+                    summary: pack.summary(),
+                    error: components::register_pack(&pack).err(),
+                },
+                Err(e) => PackStatus { file, name: String::new(), summary: String::new(), error: Some(e) },
+            }
+        })
+        .collect()
+}
+
+/// Rotores, refletores e modelos registrados, embutidos e de pacotes.
+pub fn list() -> ComponentList {
+    ComponentList {
+        rotors: components::rotor_names().into_iter().map(str::to_string).collect(),
+        reflectors: components::reflector_names().into_iter().map(str::to_string).collect(),
+        models: components::models(),
+    }
+}
+
+/// Os dados históricos de tudo o que `list` mostra, na mesma ordem.
+pub fn history() -> ComponentHistory {
+    ComponentHistory {
+        rotors: components::rotor_names().into_iter().filter_map(components::rotor_details).collect(),
+        reflectors: components::reflector_names().into_iter().filter_map(components::reflector_details).collect(),
+        models: components::models(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_follows_the_list() {
+        let (list, history) = (list(), history());
+        assert!(history.rotors.iter().map(|rotor| &rotor.name).eq(list.rotors.iter()));
+        assert!(history.reflectors.iter().map(|reflector| &reflector.name).eq(list.reflectors.iter()));
+        assert_eq!(history.rotors[0].notches, "Q");
+        assert_eq!(history.rotors[0].info.introduced, Some(1930));
+    }
+
+    #[test]
+    fn test_load_dir_registers_packs_in_order() {
+        let dir = std::env::temp_dir().join(format!("enigma-packs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let rotors = r#"{"name": "Rotores extras", "rotors": [
+            {"name": "X1", "wiring": "JPGVOUMFYQBENHZRDKASXLICTW", "notch": "Z"}]}"#;
+        let models = r#"{"name": "Modelo", "models": [{"name": "Didático", "rotors": ["I", "II", "X1"],
+            "reflectors": ["B"]}]}"#;
+        std::fs::write(dir.join("1-rotores.json"), rotors).unwrap();
+        std::fs::write(dir.join("2-modelo.json"), models).unwrap();
+        std::fs::write(dir.join("3-quebrado.json"), "{").unwrap();
+        std::fs::write(dir.join("leia-me.txt"), "não é um pacote").unwrap();
+
+        let statuses = load_dir(&dir);
+        let files: Vec<&str> = statuses.iter().map(|status| status.file.as_str()).collect();
+        assert_eq!(files, ["1-rotores.json", "2-modelo.json", "3-quebrado.json"]);
+        assert_eq!(statuses[0].error, None);
+        assert_eq!(statuses[1].summary, "0 rotores, 0 refletores, 1 modelo");
+        assert_eq!(statuses[1].error, None);
+        assert!(statuses[2].error.as_ref().unwrap().contains("inválido"));
+        let components = list();
+        assert!(components.rotors.contains(&"X1".to_string()));
+        assert!(components.models.iter().any(|model| model.name == "Didático"));
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(load_dir(&dir).is_empty());
+    }
+}
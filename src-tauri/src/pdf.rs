@@ -0,0 +1,196 @@
+//! Exportação em PDF de folhas de chaves e de traços de mensagens, no leiaute das
+//! folhas datilografadas da época (A4, Courier, títulos em alemão).
+//!
+//! O conteúdo é montado antes como linhas de texto de largura fixa; `render` só
+//! distribui as linhas pelas páginas.
+
+use std::cmp::Reverse;
+
+use printpdf::{BuiltinFont, Mm, PdfDocument};
+use serde::{Deserialize, Serialize};
+
+use crate::enigma::{Component, EncryptionStep, EnigmaConfig, EnigmaMachine, RotorSlot};
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+const FONT_SIZE: f32 = 9.0;
+/// Espaço entre linhas, em milímetros.
+const LINE_HEIGHT: f32 = 4.6;
+
+/// Um dia da folha de chaves.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct KeySheetDay {
+    pub day: u8,
+    pub config: EnigmaConfig,
+}
+
+/// Folha de chaves de um período (ex.: "Heeres-Maschinenschluessel Nr. 7").
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct KeySheet {
+    pub title: String,
+    pub days: Vec<KeySheetDay>,
+}
+
+/// O que exportar, vindo da UI com a variante em `type`.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "type")]
+pub enum PdfExport {
+    KeySheet(KeySheet),
+    /// Traço da cifragem de `text`, letra por letra.
+    Trace { config: EnigmaConfig, text: String },
+}
+
+impl PdfExport {
+    /// Título do documento e as suas linhas.
+    fn lines(&self) -> Result<(String, Vec<String>), String> {
+        match self {
+            Self::KeySheet(sheet) => Ok((sheet.title.clone(), key_sheet_lines(&sheet.title, &sheet.days))),
+            Self::Trace { config, text } => {
+                // A máquina entra em pânico com nomes desconhecidos
+                let config = config.clone().normalized()?;
+                let steps = EnigmaMachine::new(config.clone()).process_string_detailed(text);
+                Ok(("Spruchverlauf".to_string(), trace_lines(&config, &steps)))
+            }
+        }
+    }
+
+    /// O PDF pronto.
+    pub fn to_pdf(&self) -> Result<Vec<u8>, String> {
+        let (title, lines) = self.lines()?;
+        render(&title, &lines)
+    }
+}
+
+/// Walzenlage, Ringstellung (em números, como nas folhas) e Grundstellung, da
+/// esquerda para a direita.
+fn settings(config: &EnigmaConfig) -> (String, String, String) {
+    let (r, m, l) = &config.rotors;
+    let order = format!("{} {} {}", l.name, m.name, r.name);
+    let rings: Vec<String> = [l.ring, m.ring, r.ring].iter().map(|&c| format!("{:02}", c as u8 - b'A' + 1)).collect();
+    let start = format!("{} {} {}", l.position, m.position, r.position);
+    (order, rings.join(" "), start)
+}
+
+/// Linha da tabela da folha de chaves, em colunas de largura fixa.
+fn row(day: &str, order: &str, rings: &str, plugboard: &str, start: &str) -> String {
+    format!("{:<6}| {:<11}| {:<13}| {:<30}| {}", day, order, rings, plugboard, start)
+}
+
+/// Tabela da folha de chaves, do último dia para o primeiro, como nas originais
+/// (a linha do dia usado era cortada e destruída).
+pub fn key_sheet_lines(title: &str, days: &[KeySheetDay]) -> Vec<String> {
+    let mut days: Vec<&KeySheetDay> = days.iter().collect();
+    days.sort_by_key(|day| Reverse(day.day));
+    let mut lines = vec![
+        "GEHEIM!".to_string(),
+        title.to_string(),
+        String::new(),
+        row("Datum", "Walzenlage", "Ringstellung", "Steckerverbindungen", "Grund"),
+        "-".repeat(76),
+    ];
+    for day in days {
+        let (order, rings, start) = settings(&day.config);
+        lines.push(row(&day.day.to_string(), &order, &rings, &day.config.plugboard_pairs.to_uppercase(), &start));
+    }
+    lines
+}
+
+/// Cabeçalho com a chave e uma linha por letra: janelas depois do passo, caminho
+/// do sinal e lâmpada.
+pub fn trace_lines(config: &EnigmaConfig, steps: &[EncryptionStep]) -> Vec<String> {
+    let (order, rings, start) = settings(config);
+    let mut lines = vec![
+        format!("Walzenlage {}   UKW {}   Ringstellung {}", order, config.reflector.to_uppercase(), rings),
+        format!("Grundstellung {}   Stecker {}", start, config.plugboard_pairs.to_uppercase()),
+        String::new(),
+    ];
+    for (i, step) in steps.iter().enumerate() {
+        let (l, m, r) = step.positions_after_step;
+        let path: Vec<String> = step
+            .path
+            .iter()
+            .map(|entry| {
+                let label = match &entry.component {
+                    Component::Plugboard => "S",
+                    Component::EntryWheel => "E",
+                    Component::Rotor { slot: RotorSlot::Left, .. } => "L",
+                    Component::Rotor { slot: RotorSlot::Middle, .. } => "M",
+                    Component::Rotor { slot: RotorSlot::Right, .. } => "R",
+                    Component::Reflector { .. } => "U",
+                };
+                format!("{}{}{}", label, entry.input_char, entry.output_char)
+            })
+            .collect();
+        lines.push(format!(
+            "{:03}  {}  [{}{}{}]  {}  = {}",
+            i + 1,
+            step.input_char,
+            l,
+            m,
+            r,
+            path.join(" "),
+            step.output_char
+        ));
+    }
+    lines
+}
+
+/// Distribui `lines` em páginas A4 e gera o PDF.
+fn render(title: &str, lines: &[String]) -> Result<Vec<u8>, String> {
+    let error = |e: printpdf::Error| format!("Erro ao gerar o PDF: {}", e);
+    let (document, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Texto");
+    let font = document.add_builtin_font(BuiltinFont::Courier).map_err(error)?;
+    let per_page = ((PAGE_HEIGHT - 2.0 * MARGIN) / LINE_HEIGHT) as usize;
+    let mut current = document.get_page(page).get_layer(layer);
+    for (i, chunk) in lines.chunks(per_page).enumerate() {
+        if i > 0 {
+            let (page, layer) = document.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Texto");
+            current = document.get_page(page).get_layer(layer);
+        }
+        for (row, line) in chunk.iter().enumerate() {
+            let y = PAGE_HEIGHT - MARGIN - row as f32 * LINE_HEIGHT;
+            current.use_text(line.as_str(), FONT_SIZE, Mm(MARGIN), Mm(y), &font);
+        }
+    }
+    document.save_to_bytes().map_err(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enigma::RotorConfig;
+
+    fn config(start: char) -> EnigmaConfig {
+        let rotor = |name: &str, ring| RotorConfig { name: name.to_string(), position: start, ring };
+        EnigmaConfig {
+            rotors: (rotor("III", 'X'), rotor("II", 'L'), rotor("I", 'A')),
+            reflector: "B".into(),
+            plugboard_pairs: "ab cd".into(),
+        }
+    }
+
+    #[test]
+    fn test_key_sheet_lists_last_day_first() {
+        let days = vec![KeySheetDay { day: 1, config: config('A') }, KeySheetDay { day: 2, config: config('B') }];
+        let lines = key_sheet_lines("Nr. 7", &days);
+        assert_eq!(lines[1], "Nr. 7");
+        assert!(lines[5].starts_with("2     | I II III   | 01 12 24     | AB CD"));
+        assert!(lines[5].ends_with("| B B B"));
+        assert!(lines[6].starts_with("1 "));
+    }
+
+    #[test]
+    fn test_trace_has_a_line_per_letter() {
+        let export = PdfExport::Trace { config: config('A'), text: "AB".into() };
+        let (_, lines) = export.lines().unwrap();
+        let steps = EnigmaMachine::new(config('A').normalized().unwrap()).process_string_detailed("AB");
+        assert_eq!(lines.len(), 3 + 2);
+        assert!(lines[0].ends_with("Ringstellung 01 12 24"));
+        assert!(lines[3].starts_with("001  A  [AAB]  SAB R"));
+        assert!(lines[3].ends_with(&format!("= {}", steps[0].output_char)));
+        assert!(PdfExport::Trace { config: EnigmaConfig { reflector: "Z".into(), ..config('A') }, text: "A".into() }
+            .lines()
+            .is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! Configurações salvas com um nome ("presets"), para não redigitar os pares do
+//! plugboard a cada sessão.
+//!
+//! Todas ficam num único arquivo JSON na pasta de dados do app, um objeto com o nome
+//! de cada configuração como chave (dentro do formato versionado de `schema`).
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind};
+use std::path::Path;
+
+use crate::enigma::EnigmaConfig;
+use crate::schema::{self, Kind};
+
+type Presets = BTreeMap<String, EnigmaConfig>;
+
+/// Lê o arquivo de configurações salvas. Se ainda não existir, não há nenhuma.
+fn read(path: &Path) -> Result<Presets, String> {
+    match File::open(path) {
+        Ok(file) => serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| e.to_string())
+            .and_then(|value| schema::from_value(value, Kind::Presets))
+            .map_err(|e| format!("Arquivo de configurações salvas inválido: {}", e)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Presets::new()),
+        Err(e) => Err(format!("Erro ao abrir as configurações salvas: {}", e)),
+    }
+}
+
+/// Grava num arquivo temporário e o renomeia, para não perder as configurações se
+/// o app fechar no meio da gravação.
+fn write(path: &Path, presets: &Presets) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Erro ao criar a pasta de dados: {}", e))?;
+    }
+    let temporary = path.with_extension("tmp");
+    let file = File::create(&temporary).map_err(|e| format!("Erro ao salvar as configurações: {}", e))?;
+    serde_json::to_writer_pretty(BufWriter::new(file), &schema::to_value(presets, Kind::Presets)?)
+        .map_err(|e| format!("Erro ao salvar as configurações: {}", e))?;
+    std::fs::rename(&temporary, path).map_err(|e| format!("Erro ao salvar as configurações: {}", e))
+}
+
+/// Nome sem espaços nas pontas; não pode ficar vazio.
+fn preset_name(name: &str) -> Result<&str, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Dê um nome à configuração.".to_string());
+    }
+    Ok(name)
+}
+
+/// Nomes das configurações salvas, em ordem alfabética.
+pub fn list(path: &Path) -> Result<Vec<String>, String> {
+    Ok(read(path)?.into_keys().collect())
+}
+
+/// Salva `config` como `name`, substituindo uma configuração de mesmo nome.
+pub fn save(path: &Path, name: &str, config: EnigmaConfig) -> Result<(), String> {
+    let name = preset_name(name)?;
+    let config = config.normalized()?;
+    let mut presets = read(path)?;
+    presets.insert(name.to_string(), config);
+    write(path, &presets)
+}
+
+/// Abre a configuração salva como `name`.
+pub fn load(path: &Path, name: &str) -> Result<EnigmaConfig, String> {
+    let name = preset_name(name)?;
+    read(path)?.remove(name).ok_or_else(|| format!("Configuração não encontrada: {}", name))
+}
+
+/// Apaga a configuração salva como `name` (não é erro se ela não existir).
+pub fn remove(path: &Path, name: &str) -> Result<(), String> {
+    let name = preset_name(name)?;
+    let mut presets = read(path)?;
+    if presets.remove(name).is_some() {
+        write(path, &presets)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_file_has_no_presets() {
+        let path = std::env::temp_dir().join("enigma_presets_inexistente.json");
+        assert_eq!(list(&path).unwrap(), Vec::<String>::new());
+        assert_eq!(load(&path, " Aula 1 ").unwrap_err(), "Configuração não encontrada: Aula 1");
+        assert!(remove(&path, "Aula 1").is_ok());
+        assert!(load(&path, "  ").is_err());
+    }
+}
@@ -0,0 +1,141 @@
+//! Progresso nas atividades de aula: lições concluídas, desafios resolvidos e as
+//! notas dos questionários, guardados entre uma sessão e outra.
+//!
+//! Fica num arquivo JSON na pasta de dados do app. Um arquivo ausente ou ilegível
+//! conta como progresso vazio.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::quiz::{Difficulty, Topic};
+use crate::schema::{self, Kind};
+
+/// Quantas notas de questionário o progresso guarda; as mais antigas saem.
+pub const QUIZ_LIMIT: usize = 100;
+
+/// Nota de um questionário terminado.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuizScore {
+    pub topic: Topic,
+    pub difficulty: Difficulty,
+    pub correct: usize,
+    pub total: usize,
+    /// Segundos desde 1970 (UTC).
+    pub finished_at: u64,
+}
+
+/// Tudo o que o aluno já fez.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Progress {
+    /// Lições concluídas, pelo título, com a data da primeira conclusão.
+    #[serde(default)]
+    pub lessons: BTreeMap<String, u64>,
+    /// Desafios resolvidos, pelo identificador, com a data da primeira solução.
+    #[serde(default)]
+    pub challenges: BTreeMap<String, u64>,
+    /// Notas dos questionários, da mais antiga para a mais recente.
+    #[serde(default)]
+    pub quizzes: Vec<QuizScore>,
+}
+
+impl Progress {
+    /// Registra a lição `title` como concluída. Retorna se é a primeira vez.
+    pub fn complete_lesson(&mut self, title: &str, now: u64) -> bool {
+        if self.lessons.contains_key(title) {
+            return false;
+        }
+        self.lessons.insert(title.to_string(), now);
+        true
+    }
+
+    /// Registra o desafio `id` como resolvido. Retorna se é a primeira vez.
+    pub fn solve_challenge(&mut self, id: &str, now: u64) -> bool {
+        if self.challenges.contains_key(id) {
+            return false;
+        }
+        self.challenges.insert(id.to_string(), now);
+        true
+    }
+
+    /// Acrescenta a nota de um questionário, descartando as que passarem de
+    /// `QUIZ_LIMIT`.
+    pub fn add_quiz(&mut self, score: QuizScore) {
+        self.quizzes.push(score);
+        let excess = self.quizzes.len().saturating_sub(QUIZ_LIMIT);
+        self.quizzes.drain(..excess);
+    }
+}
+
+/// Segundos desde 1970 (UTC).
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// Progresso gravado em `path`.
+pub fn load(path: &Path) -> Progress {
+    File::open(path)
+        .ok()
+        .and_then(|file| serde_json::from_reader(BufReader::new(file)).ok())
+        .and_then(|value| schema::from_value(value, Kind::Progress).ok())
+        .unwrap_or_default()
+}
+
+/// Aplica `change` ao progresso gravado em `path` e grava de novo se ele mudou.
+pub fn update(path: &Path, change: impl FnOnce(&mut Progress) -> bool) -> Result<(), String> {
+    let mut progress = load(path);
+    if !change(&mut progress) {
+        return Ok(());
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Erro ao criar a pasta de dados: {}", e))?;
+    }
+    let temporary = path.with_extension("tmp");
+    let file = File::create(&temporary).map_err(|e| format!("Erro ao salvar o progresso: {}", e))?;
+    serde_json::to_writer(BufWriter::new(file), &schema::to_value(&progress, Kind::Progress)?)
+        .map_err(|e| format!("Erro ao salvar o progresso: {}", e))?;
+    std::fs::rename(&temporary, path).map_err(|e| format!("Erro ao salvar o progresso: {}", e))
+}
+
+/// Apaga todo o progresso.
+pub fn reset(path: &Path) -> Result<(), String> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(format!("Erro ao apagar o progresso: {}", e)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(correct: usize) -> QuizScore {
+        QuizScore { topic: Topic::Stepping, difficulty: Difficulty::Hard, correct, total: 5, finished_at: 0 }
+    }
+
+    #[test]
+    fn test_first_completion_is_kept() {
+        let mut progress = Progress::default();
+        assert!(progress.complete_lesson("O passo duplo", 10));
+        assert!(!progress.complete_lesson("O passo duplo", 20));
+        assert!(progress.solve_challenge("QBLTW LDPFS", 30));
+        assert!(!progress.solve_challenge("QBLTW LDPFS", 40));
+        assert_eq!(progress.lessons["O passo duplo"], 10);
+        assert_eq!(progress.challenges["QBLTW LDPFS"], 30);
+    }
+
+    #[test]
+    fn test_quiz_scores_keep_the_most_recent() {
+        let mut progress = Progress::default();
+        for correct in 0..QUIZ_LIMIT + 3 {
+            progress.add_quiz(score(correct));
+        }
+        assert_eq!(progress.quizzes.len(), QUIZ_LIMIT);
+        assert_eq!(progress.quizzes[0], score(3));
+        assert_eq!(progress.quizzes.last(), Some(&score(QUIZ_LIMIT + 2)));
+    }
+}
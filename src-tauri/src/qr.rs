@@ -0,0 +1,24 @@
+//! QR code da configuração, para o projetor: os alunos leem com o celular em vez de
+//! digitar a chave.
+
+use std::io::Cursor;
+
+use image::{DynamicImage, ImageFormat, Luma};
+use qrcode::QrCode;
+
+use crate::enigma::EnigmaConfig;
+
+/// Lado mínimo da imagem, em pixels, para ser lida de longe numa projeção.
+const MIN_SIZE: u32 = 480;
+
+/// PNG com o QR code do código curto de `config` (`EnigmaConfig::to_share_code`).
+pub fn config_png(config: &EnigmaConfig) -> Result<Vec<u8>, String> {
+    let code = config.to_share_code()?;
+    let qr = QrCode::new(code.as_bytes()).map_err(|e| format!("Erro ao gerar o QR code: {}", e))?;
+    let image = qr.render::<Luma<u8>>().min_dimensions(MIN_SIZE, MIN_SIZE).build();
+    let mut png = Vec::new();
+    DynamicImage::ImageLuma8(image)
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| format!("Erro ao gerar o PNG: {}", e))?;
+    Ok(png)
+}
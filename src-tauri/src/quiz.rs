@@ -0,0 +1,300 @@
+//! Questionários para a aula: perguntas geradas com chaves sorteadas e respostas
+//! que ficam só no backend. A UI recebe o enunciado e a chave da máquina, manda
+//! a resposta e recebe a correção; o gabarito não passa pelo código da página.
+//!
+//! Um questionário é respondido em ordem: cada resposta corrige a próxima
+//! pergunta ainda não respondida.
+
+use std::collections::BTreeMap;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::enigma::{u8_to_char, EnigmaConfig, EnigmaMachine, RotorConfig, REFLECTOR_NAMES, ROTOR_NAMES};
+
+/// Perguntas por questionário.
+pub const QUESTIONS: usize = 5;
+
+/// Questionários em andamento guardados ao mesmo tempo; o mais antigo sai primeiro.
+const OPEN_QUIZZES: usize = 32;
+
+/// Assunto das perguntas.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Topic {
+    /// Em que letra uma tecla é cifrada.
+    Encryption,
+    /// Que posições os rotores mostram depois da próxima tecla.
+    Stepping,
+    /// A cifra é recíproca: se K vira X, X vira K.
+    Reciprocity,
+    /// Um pouco de cada.
+    Mixed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+/// Uma pergunta, como vai para a UI (sem a resposta).
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Question {
+    pub topic: Topic,
+    pub prompt: String,
+    /// Chave da máquina para a pergunta.
+    pub config: EnigmaConfig,
+}
+
+/// Questionário novo, como vai para a UI.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct QuizView {
+    pub id: u64,
+    pub questions: Vec<Question>,
+}
+
+/// Acertos até agora.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct Score {
+    pub correct: usize,
+    pub answered: usize,
+    pub total: usize,
+}
+
+/// Correção de uma resposta.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Correction {
+    pub topic: Topic,
+    pub difficulty: Difficulty,
+    /// Número da pergunta corrigida (a partir de 0).
+    pub question: usize,
+    pub correct: bool,
+    pub expected: String,
+    pub explanation: String,
+    pub score: Score,
+    /// Não há mais perguntas: o questionário foi encerrado.
+    pub finished: bool,
+}
+
+/// Resposta e explicação de uma pergunta, guardadas no backend.
+#[derive(Debug, Clone)]
+struct Answer {
+    expected: String,
+    explanation: String,
+}
+
+#[derive(Debug, Clone)]
+struct Quiz {
+    topic: Topic,
+    difficulty: Difficulty,
+    answers: Vec<Answer>,
+    correct: usize,
+    answered: usize,
+}
+
+/// Questionários em andamento, pelo número.
+#[derive(Debug, Default)]
+pub struct Quizzes {
+    next_id: u64,
+    open: BTreeMap<u64, Quiz>,
+}
+
+/// Chave sorteada: rotores e refletor dos que os ataques conhecem; anéis e
+/// plugboard só a partir do nível médio.
+pub(crate) fn random_config(rng: &mut impl Rng, difficulty: Difficulty) -> EnigmaConfig {
+    let mut names = ROTOR_NAMES.to_vec();
+    names.shuffle(rng);
+    let mut letter = |varies: bool| if varies { u8_to_char(rng.gen_range(0..26)) } else { 'A' };
+    let ring = difficulty != Difficulty::Easy;
+    let rotor = |name: &str, position: char, ring: char| RotorConfig { name: name.to_string(), position, ring };
+    let rotors = (
+        rotor(names[0], letter(true), letter(ring)),
+        rotor(names[1], letter(true), letter(ring)),
+        rotor(names[2], letter(true), letter(ring)),
+    );
+    let pairs = match difficulty {
+        Difficulty::Easy => 0,
+        Difficulty::Medium => 3,
+        Difficulty::Hard => 10,
+    };
+    let mut letters: Vec<u8> = (0..26).collect();
+    letters.shuffle(rng);
+    let plugboard_pairs = letters[..pairs * 2]
+        .chunks(2)
+        .map(|pair| format!("{}{}", u8_to_char(pair[0]), u8_to_char(pair[1])))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let reflector = REFLECTOR_NAMES.choose(rng).copied().unwrap_or("B").to_string();
+    EnigmaConfig { rotors, reflector, plugboard_pairs }
+}
+
+fn encryption(rng: &mut impl Rng, config: EnigmaConfig) -> (Question, Answer) {
+    let key = u8_to_char(rng.gen_range(0..26));
+    let (output, step) = EnigmaMachine::new(config.clone()).process_char_detailed(key);
+    let path: Vec<String> = step.path.iter().map(|entry| entry.output_char.to_string()).collect();
+    let question = Question {
+        topic: Topic::Encryption,
+        prompt: format!("Com esta chave, em que letra o {} é cifrado na primeira tecla?", key),
+        config,
+    };
+    let explanation = format!(
+        "Os rotores giram antes do sinal passar ({}). Caminho: {} → {}.",
+        positions(step.positions_after_step),
+        key,
+        path.join(" → ")
+    );
+    (question, Answer { expected: output.to_string(), explanation })
+}
+
+fn positions((left, middle, right): (char, char, char)) -> String {
+    [left, middle, right].iter().collect()
+}
+
+/// Nos níveis médio e difícil o rotor da direita (e no difícil também o do meio)
+/// começa no entalhe, para a pergunta cair no passo do vizinho e no passo duplo.
+fn stepping(config: EnigmaConfig, difficulty: Difficulty) -> (Question, Answer) {
+    let mut config = config;
+    let (middle_notch, right_notch) = EnigmaMachine::new(config.clone()).notches();
+    if difficulty != Difficulty::Easy {
+        config.rotors.0.position = u8_to_char(right_notch);
+    }
+    if difficulty == Difficulty::Hard {
+        config.rotors.1.position = u8_to_char(middle_notch);
+    }
+    let mut machine = EnigmaMachine::new(config.clone());
+    let before = machine.get_positions();
+    machine.step_rotors();
+    let after = machine.get_positions();
+    let explanation = match (before.0 != after.0, before.1 != after.1) {
+        (true, _) => "O rotor do meio estava no entalhe: girou ele mesmo e levou o da esquerda (passo duplo).",
+        (false, true) => "O rotor da direita estava no entalhe e levou o do meio junto.",
+        (false, false) => "Nenhum entalhe: só o rotor da direita girou.",
+    };
+    let question = Question {
+        topic: Topic::Stepping,
+        prompt: format!("As janelas mostram {}. O que mostram depois da próxima tecla?", positions(before)),
+        config,
+    };
+    (question, Answer { expected: positions(after), explanation: explanation.to_string() })
+}
+
+fn reciprocity(rng: &mut impl Rng, config: EnigmaConfig) -> (Question, Answer) {
+    let key = u8_to_char(rng.gen_range(0..26));
+    let output = EnigmaMachine::new(config.clone()).process_char(key);
+    let question = Question {
+        topic: Topic::Reciprocity,
+        prompt: format!("Com esta chave, na primeira tecla o {} vira {}. Em que letra o {} vira?", key, output, output),
+        config,
+    };
+    let explanation = "Na mesma posição dos rotores a cifra é recíproca: o refletor devolve o sinal pelo mesmo \
+        caminho, então se uma letra vira outra, a outra vira a primeira.";
+    (question, Answer { expected: key.to_string(), explanation: explanation.to_string() })
+}
+
+/// Letras da resposta, em maiúsculas (espaços e pontuação não contam).
+fn normalized(answer: &str) -> String {
+    answer.chars().filter(|c| c.is_ascii_alphabetic()).map(|c| c.to_ascii_uppercase()).collect()
+}
+
+impl Quizzes {
+    /// Gera um questionário de `QUESTIONS` perguntas e guarda as respostas.
+    pub fn generate(&mut self, rng: &mut impl Rng, topic: Topic, difficulty: Difficulty) -> QuizView {
+        let mut questions = Vec::with_capacity(QUESTIONS);
+        let mut answers = Vec::with_capacity(QUESTIONS);
+        for _ in 0..QUESTIONS {
+            let topic = match topic {
+                Topic::Mixed => *[Topic::Encryption, Topic::Stepping, Topic::Reciprocity].choose(rng).unwrap(),
+                topic => topic,
+            };
+            let config = random_config(rng, difficulty);
+            let (question, answer) = match topic {
+                Topic::Stepping => stepping(config, difficulty),
+                Topic::Reciprocity => reciprocity(rng, config),
+                Topic::Encryption | Topic::Mixed => encryption(rng, config),
+            };
+            questions.push(question);
+            answers.push(answer);
+        }
+
+        self.next_id += 1;
+        if self.open.len() == OPEN_QUIZZES {
+            self.open.pop_first();
+        }
+        self.open.insert(self.next_id, Quiz { topic, difficulty, answers, correct: 0, answered: 0 });
+        QuizView { id: self.next_id, questions }
+    }
+
+    /// Corrige `answer` como resposta da próxima pergunta do questionário `id`.
+    /// Depois da última, o questionário é encerrado.
+    pub fn check(&mut self, id: u64, answer: &str) -> Result<Correction, String> {
+        let quiz = self.open.get_mut(&id).ok_or_else(|| format!("Questionário não encontrado: {}", id))?;
+        let question = quiz.answered;
+        let expected = &quiz.answers[question];
+        let correct = normalized(answer) == expected.expected;
+        quiz.answered += 1;
+        quiz.correct += usize::from(correct);
+        let correction = Correction {
+            topic: quiz.topic,
+            difficulty: quiz.difficulty,
+            question,
+            correct,
+            expected: expected.expected.clone(),
+            explanation: expected.explanation.clone(),
+            score: Score { correct: quiz.correct, answered: quiz.answered, total: quiz.answers.len() },
+            finished: quiz.answered == quiz.answers.len(),
+        };
+        if correction.finished {
+            self.open.remove(&id);
+        }
+        Ok(correction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_answers_are_checked_in_order() {
+        let mut rng = StdRng::seed_from_u64(1932);
+        let mut quizzes = Quizzes::default();
+        let quiz = quizzes.generate(&mut rng, Topic::Encryption, Difficulty::Medium);
+        assert_eq!(quiz.questions.len(), QUESTIONS);
+
+        let mut score = None;
+        for (i, question) in quiz.questions.iter().enumerate() {
+            let key = question.prompt.split_whitespace().nth(7).unwrap().chars().next().unwrap();
+            let output = EnigmaMachine::new(question.config.clone()).process_char(key);
+            // Acerta as pares e erra as ímpares
+            let answer = if i % 2 == 0 { output.to_ascii_lowercase().to_string() } else { "1".to_string() };
+            let correction = quizzes.check(quiz.id, &answer).unwrap();
+            assert_eq!(correction.question, i);
+            assert_eq!(correction.correct, i % 2 == 0);
+            score = Some(correction.score);
+        }
+        assert_eq!(score, Some(Score { correct: 3, answered: 5, total: 5 }));
+        assert!(quizzes.check(quiz.id, "A").is_err());
+    }
+
+    #[test]
+    fn test_hard_stepping_asks_for_the_double_step() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut quizzes = Quizzes::default();
+        let quiz = quizzes.generate(&mut rng, Topic::Stepping, Difficulty::Hard);
+        let config = &quiz.questions[0].config;
+        let mut machine = EnigmaMachine::new(config.clone());
+        let before = machine.get_positions();
+        machine.step_rotors();
+        let after = machine.get_positions();
+        assert!(before.0 != after.0 && before.1 != after.1 && before.2 != after.2);
+        let correction = quizzes.check(quiz.id, &positions(after)).unwrap();
+        assert!(correction.correct && !correction.finished);
+        assert!(correction.explanation.contains("passo duplo"));
+    }
+}
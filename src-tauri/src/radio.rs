@@ -0,0 +1,169 @@
+//! "Rede de rádio": duas instâncias do app trocam mensagens cifradas por WebSocket.
+//!
+//! Uma instância abre a estação (`RadioLink::host`) numa porta e a outra entra nela
+//! (`RadioLink::join`). Cada mensagem transmitida chega ao outro lado como uma
+//! interceptação, em grupos de cinco letras como no tráfego real, e o aluno decifra
+//! com a chave combinada. A estação aceita um só parceiro.
+
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use futures_util::{SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{accept_async, connect_async, WebSocketStream};
+
+use crate::enigma::OutputFormat;
+
+/// Uma mensagem transmitida.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Transmission {
+    /// Indicativo de quem transmitiu (ex.: "P7K").
+    pub callsign: String,
+    /// Texto cifrado em grupos de cinco letras.
+    pub ciphertext: String,
+    /// Momento do envio (segundos desde 1970).
+    pub sent_at: u64,
+}
+
+impl Transmission {
+    /// Monta a transmissão de `ciphertext`, só com as letras, em grupos de cinco.
+    pub fn new(callsign: &str, ciphertext: &str) -> Self {
+        Self {
+            callsign: callsign.trim().to_uppercase(),
+            ciphertext: OutputFormat::default().apply(ciphertext),
+            sent_at: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+        }
+    }
+}
+
+/// O que acontece na rede, repassado à UI.
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "type")]
+pub enum RadioEvent {
+    /// A estação está esperando o parceiro.
+    Listening { port: u16 },
+    Connected { peer: String },
+    /// Mensagem do parceiro.
+    Intercept { transmission: Transmission },
+    Disconnected,
+}
+
+type EventHandler = Arc<dyn Fn(RadioEvent) + Send + Sync>;
+
+/// Ligação com o parceiro. Fechada ao ser descartada.
+pub struct RadioLink {
+    outgoing: UnboundedSender<Transmission>,
+    task: JoinHandle<()>,
+}
+
+impl RadioLink {
+    /// Abre a estação em `port` (em todas as interfaces) e espera um parceiro.
+    pub async fn host(port: u16, on_event: impl Fn(RadioEvent) + Send + Sync + 'static) -> Result<Self, String> {
+        let on_event: EventHandler = Arc::new(on_event);
+        let listener = TcpListener::bind(("0.0.0.0", port))
+            .await
+            .map_err(|e| format!("Erro ao abrir a estação na porta {}: {}", port, e))?;
+        on_event(RadioEvent::Listening { port });
+        let (outgoing, receiver) = mpsc::unbounded_channel();
+        let task = tokio::spawn(async move {
+            // Só o primeiro parceiro: depois dele a porta é fechada
+            let accepted = listener.accept().await;
+            drop(listener);
+            let Ok((socket, peer)) = accepted else {
+                return on_event(RadioEvent::Disconnected);
+            };
+            match accept_async(socket).await {
+                Ok(ws) => {
+                    on_event(RadioEvent::Connected { peer: peer.to_string() });
+                    relay(ws, receiver, on_event).await;
+                }
+                Err(_) => on_event(RadioEvent::Disconnected),
+            }
+        });
+        Ok(Self { outgoing, task })
+    }
+
+    /// Entra na estação em `url` (ex.: "ws://192.168.0.10:9000").
+    pub async fn join(url: &str, on_event: impl Fn(RadioEvent) + Send + Sync + 'static) -> Result<Self, String> {
+        let on_event: EventHandler = Arc::new(on_event);
+        let (ws, _) = connect_async(url).await.map_err(|e| format!("Erro ao entrar na estação {}: {}", url, e))?;
+        on_event(RadioEvent::Connected { peer: url.to_string() });
+        let (outgoing, receiver) = mpsc::unbounded_channel();
+        let task = tokio::spawn(relay(ws, receiver, on_event));
+        Ok(Self { outgoing, task })
+    }
+
+    /// Transmite para o parceiro. Antes de ele entrar, a mensagem espera na fila.
+    pub fn send(&self, transmission: Transmission) -> Result<(), String> {
+        self.outgoing.send(transmission).map_err(|_| "A ligação com o parceiro caiu.".to_string())
+    }
+}
+
+impl Drop for RadioLink {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Repassa as transmissões da fila para o parceiro e as dele para `on_event`, até
+/// um dos lados fechar.
+async fn relay<S>(ws: WebSocketStream<S>, outgoing: UnboundedReceiver<Transmission>, on_event: EventHandler)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    pump(ws, outgoing, |transmission| on_event(RadioEvent::Intercept { transmission })).await;
+    on_event(RadioEvent::Disconnected);
+}
+
+/// Manda as mensagens da fila pela ligação e entrega as que chegam, no formato
+/// `I`, a `on_message`, até um dos lados fechar. Mensagens fora do formato são
+/// ignoradas.
+pub(crate) async fn pump<S, O, I>(ws: WebSocketStream<S>, mut outgoing: UnboundedReceiver<O>, mut on_message: impl FnMut(I))
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    O: Serialize + Send + 'static,
+    I: DeserializeOwned,
+{
+    let (mut sink, mut stream) = ws.split();
+    let writer = tokio::spawn(async move {
+        while let Some(message) = outgoing.recv().await {
+            let Ok(json) = serde_json::to_string(&message) else { continue };
+            if sink.send(Message::Text(json)).await.is_err() {
+                break;
+            }
+        }
+        let _ = sink.close().await;
+    });
+    while let Some(Ok(message)) = stream.next().await {
+        match message {
+            Message::Text(text) => {
+                if let Ok(message) = serde_json::from_str(&text) {
+                    on_message(message);
+                }
+            }
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+    writer.abort();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transmission_groups_letters() {
+        let transmission = Transmission::new(" p7k ", "qblt wldp-fs\nxq");
+        assert_eq!(transmission.callsign, "P7K");
+        assert_eq!(transmission.ciphertext, "QBLTW LDPFS XQ");
+        assert!(transmission.sent_at > 0);
+        assert_eq!(Transmission::new("A", "").ciphertext, "");
+    }
+}
@@ -0,0 +1,92 @@
+//! Histórico das últimas configurações usadas para cifrar, para voltar depressa
+//! "à chave de ontem".
+//!
+//! Fica num arquivo JSON na pasta de dados do app, da mais recente para a mais
+//! antiga, sem repetições.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind};
+use std::path::Path;
+
+use crate::enigma::EnigmaConfig;
+use crate::schema::{self, Kind};
+
+/// Quantas configurações o histórico guarda.
+pub const RECENT_LIMIT: usize = 10;
+
+/// Coloca `config` no início de `recent`, tirando a cópia anterior dela e as que
+/// passarem de `RECENT_LIMIT`. Retorna se a lista mudou.
+fn push(recent: &mut Vec<EnigmaConfig>, config: EnigmaConfig) -> bool {
+    if recent.first() == Some(&config) {
+        return false;
+    }
+    recent.retain(|c| *c != config);
+    recent.insert(0, config);
+    recent.truncate(RECENT_LIMIT);
+    true
+}
+
+/// Configurações usadas, da mais recente para a mais antiga. Um histórico ausente ou
+/// ilegível conta como vazio.
+pub fn list(path: &Path) -> Vec<EnigmaConfig> {
+    File::open(path)
+        .ok()
+        .and_then(|file| serde_json::from_reader(BufReader::new(file)).ok())
+        .and_then(|value| schema::from_value(value, Kind::Recent).ok())
+        .unwrap_or_default()
+}
+
+/// Registra o uso de `config`. Configurações inválidas não entram no histórico.
+pub fn record(path: &Path, config: EnigmaConfig) -> Result<(), String> {
+    let mut recent = list(path);
+    if !push(&mut recent, config.normalized()?) {
+        return Ok(());
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Erro ao criar a pasta de dados: {}", e))?;
+    }
+    let temporary = path.with_extension("tmp");
+    let file = File::create(&temporary).map_err(|e| format!("Erro ao salvar o histórico: {}", e))?;
+    serde_json::to_writer(BufWriter::new(file), &schema::to_value(&recent, Kind::Recent)?)
+        .map_err(|e| format!("Erro ao salvar o histórico: {}", e))?;
+    std::fs::rename(&temporary, path).map_err(|e| format!("Erro ao salvar o histórico: {}", e))
+}
+
+/// Esquece todas as configurações usadas.
+pub fn clear(path: &Path) -> Result<(), String> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(format!("Erro ao apagar o histórico: {}", e)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enigma::RotorConfig;
+
+    fn config(position: char) -> EnigmaConfig {
+        let rotor = |name: &str| RotorConfig { name: name.to_string(), position, ring: 'A' };
+        EnigmaConfig {
+            rotors: (rotor("III"), rotor("II"), rotor("I")),
+            reflector: "B".into(),
+            plugboard_pairs: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_push_keeps_distinct_most_recent_first() {
+        let mut recent = Vec::new();
+        assert!(push(&mut recent, config('A')));
+        assert!(push(&mut recent, config('B')));
+        assert!(!push(&mut recent, config('B')));
+        assert!(push(&mut recent, config('A')));
+        assert_eq!(recent, vec![config('A'), config('B')]);
+
+        for c in 'C'..='Z' {
+            push(&mut recent, config(c));
+        }
+        assert_eq!(recent.len(), RECENT_LIMIT);
+        assert_eq!(recent[0], config('Z'));
+    }
+}
@@ -0,0 +1,214 @@
+//! Gravação de demonstrações: o que foi feito na máquina de uma janela (chaves
+//! abertas, teclas, textos, reinícios), com o instante de cada ação, num arquivo
+//! que depois é reproduzido no mesmo ritmo. O professor ensaia uma vez e repete a
+//! demonstração na aula.
+//!
+//! A reprodução aplica as ações de novo numa máquina da janela: como a máquina é
+//! determinística, as lâmpadas acendem iguais às da gravação.
+
+use std::io::BufWriter;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::enigma::{EncryptionStep, EnigmaConfig};
+use crate::schema::{self, Kind};
+use crate::session::{Machine, Session};
+
+/// Uma ação na máquina, no JSON com o tipo em `event`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ReplayEvent {
+    /// Abriu a máquina numa chave (também ao mudar a chave).
+    Open { config: EnigmaConfig },
+    Press { key: char },
+    Type { text: String },
+    Reset,
+}
+
+/// Uma ação e quando ela aconteceu, em milissegundos desde o começo da gravação.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayEntry {
+    pub at_ms: u64,
+    pub event: ReplayEvent,
+}
+
+/// Uma gravação, como vai no arquivo.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct Replay {
+    pub entries: Vec<ReplayEntry>,
+}
+
+/// Uma ação reproduzida, como vai para a UI.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayFrame {
+    /// Posição da ação na gravação (a partir de 0).
+    pub index: usize,
+    pub entry: ReplayEntry,
+    /// Passo detalhado, quando a ação é uma tecla.
+    pub step: Option<EncryptionStep>,
+    /// Estado da máquina depois da ação.
+    pub session: Option<Session>,
+}
+
+/// Gravação em andamento.
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    started: Instant,
+    replay: Replay,
+}
+
+impl Recorder {
+    /// Começa a gravar. Se a janela já tem uma máquina, a gravação começa por ela:
+    /// a chave e o que já foi teclado.
+    pub fn start(session: Option<&Session>) -> Self {
+        let mut recorder = Recorder { started: Instant::now(), replay: Replay::default() };
+        if let Some(session) = session {
+            recorder.record_at(Duration::ZERO, ReplayEvent::Open { config: session.config.clone() });
+            if !session.input.is_empty() {
+                recorder.record_at(Duration::ZERO, ReplayEvent::Type { text: session.input.clone() });
+            }
+        }
+        recorder
+    }
+
+    /// Grava `event` agora.
+    pub fn record(&mut self, event: ReplayEvent) {
+        self.record_at(self.started.elapsed(), event);
+    }
+
+    fn record_at(&mut self, at: Duration, event: ReplayEvent) {
+        self.replay.entries.push(ReplayEntry { at_ms: at.as_millis() as u64, event });
+    }
+
+    /// Termina a gravação.
+    pub fn finish(self) -> Replay {
+        self.replay
+    }
+}
+
+impl Replay {
+    /// Reproduz a gravação, `speed` vezes mais rápido que o original, aplicando
+    /// cada ação com `apply_event` (em geral `step` numa máquina). Antes de cada
+    /// ação chama `wait` com a pausa até ela; se `wait` retornar `false` a
+    /// reprodução para. Cada ação aplicada vai para `show`. Retorna quantas ações
+    /// foram reproduzidas.
+    pub fn play(
+        &self,
+        mut apply_event: impl FnMut(&ReplayEvent) -> Result<Applied, String>,
+        speed: f64,
+        mut wait: impl FnMut(Duration) -> bool,
+        mut show: impl FnMut(ReplayFrame),
+    ) -> Result<usize, String> {
+        if !(speed.is_finite() && speed > 0.0) {
+            return Err(format!("Velocidade de reprodução inválida: {}", speed));
+        }
+        let mut last = 0;
+        for (index, entry) in self.entries.iter().enumerate() {
+            let delay = Duration::from_millis(entry.at_ms.saturating_sub(last)).div_f64(speed);
+            last = entry.at_ms;
+            if !wait(delay) {
+                return Ok(index);
+            }
+            let (step, session) = apply_event(&entry.event).map_err(|e| format!("Ação {}: {}", index + 1, e))?;
+            show(ReplayFrame { index, entry: entry.clone(), step, session });
+        }
+        Ok(self.entries.len())
+    }
+}
+
+/// Passo detalhado (numa tecla) e estado da máquina depois de uma ação.
+pub type Applied = (Option<EncryptionStep>, Option<Session>);
+
+/// Aplica `event` em `machine`, como `Replay::play` espera.
+pub fn step(machine: &mut Option<Machine>, event: &ReplayEvent) -> Result<Applied, String> {
+    let step = apply(machine, event)?;
+    Ok((step, machine.as_ref().map(Machine::session)))
+}
+
+/// Aplica `event` em `machine`. Retorna o passo detalhado de uma tecla.
+pub fn apply(machine: &mut Option<Machine>, event: &ReplayEvent) -> Result<Option<EncryptionStep>, String> {
+    match event {
+        ReplayEvent::Open { config } => *machine = Some(Machine::new(config.clone())?),
+        ReplayEvent::Press { key } => return opened(machine)?.press(*key).map(Some),
+        ReplayEvent::Type { text } => {
+            opened(machine)?.type_text(text);
+        }
+        ReplayEvent::Reset => opened(machine)?.reset(),
+    }
+    Ok(None)
+}
+
+fn opened(machine: &mut Option<Machine>) -> Result<&mut Machine, String> {
+    machine.as_mut().ok_or_else(|| "A gravação usa a máquina antes de abri-la.".to_string())
+}
+
+/// Grava `replay` em `path`.
+pub fn save(path: &Path, replay: &Replay) -> Result<(), String> {
+    let file = std::fs::File::create(path).map_err(|e| format!("Erro ao salvar a gravação: {}", e))?;
+    serde_json::to_writer(BufWriter::new(file), &schema::to_value(replay, Kind::Replay)?)
+        .map_err(|e| format!("Erro ao salvar a gravação: {}", e))
+}
+
+/// Abre uma gravação feita por `save`.
+pub fn load(path: &Path) -> Result<Replay, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Erro ao abrir a gravação: {}", e))?;
+    let value = serde_json::from_str(&text).map_err(|e| format!("Gravação inválida: {}", e))?;
+    schema::from_value(value, Kind::Replay)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enigma::EnigmaMachine;
+
+    fn config() -> EnigmaConfig {
+        EnigmaConfig::from_share_code("ENIGMA:I.II.III:B:AAA:AAA:").unwrap()
+    }
+
+    #[test]
+    fn test_playback_repeats_the_recording_at_its_pace() {
+        let mut live = Machine::new(config()).unwrap();
+        live.type_text("AB");
+        let mut recorder = Recorder::start(Some(&live.session()));
+        recorder.record_at(Duration::from_millis(400), ReplayEvent::Press { key: 'C' });
+        recorder.record_at(Duration::from_millis(1000), ReplayEvent::Type { text: "de".to_string() });
+        recorder.record_at(Duration::from_millis(1000), ReplayEvent::Reset);
+        recorder.record_at(Duration::from_millis(1500), ReplayEvent::Press { key: 'A' });
+        let replay = recorder.finish();
+        assert_eq!(replay.entries.len(), 6);
+
+        let (mut delays, mut frames) = (Vec::new(), Vec::new());
+        let mut machine = None;
+        let wait = |delay| {
+            delays.push(delay);
+            true
+        };
+        let played = replay.play(|event| step(&mut machine, event), 2.0, wait, |frame| frames.push(frame));
+        assert_eq!(played, Ok(6));
+        let millis: Vec<u128> = delays.iter().map(Duration::as_millis).collect();
+        assert_eq!(millis, [0, 0, 200, 300, 0, 250]);
+        assert_eq!(frames[2].session.as_ref().unwrap().output, EnigmaMachine::new(config()).process_string("ABC"));
+        assert_eq!(frames[2].step.as_ref().unwrap().input_char, 'C');
+        assert_eq!(frames[5].session.as_ref().unwrap().output, "B");
+    }
+
+    #[test]
+    fn test_playback_stops_and_rejects_bad_recordings() {
+        let replay = Replay {
+            entries: vec![
+                ReplayEntry { at_ms: 0, event: ReplayEvent::Open { config: config() } },
+                ReplayEntry { at_ms: 10, event: ReplayEvent::Press { key: 'A' } },
+            ],
+        };
+        let mut machine = None;
+        assert_eq!(replay.play(|event| step(&mut machine, event), 1.0, |delay| delay.is_zero(), |_| {}), Ok(1));
+        assert!(replay.play(|event| step(&mut None, event), 0.0, |_| true, |_| {}).is_err());
+
+        let broken = Replay { entries: replay.entries[1..].to_vec() };
+        let mut machine = None;
+        let played = broken.play(|event| step(&mut machine, event), 1.0, |_| true, |_| {});
+        assert!(played.unwrap_err().starts_with("Ação 1"));
+    }
+}
@@ -0,0 +1,155 @@
+//! Versão do formato dos arquivos gravados (configurações, folhas de chaves, áreas
+//! de trabalho, configurações salvas e recentes, progresso nas aulas, exercícios,
+//! demonstrações gravadas, sessões abertas) e a migração dos antigos.
+//!
+//! Todo arquivo leva o campo `schema_version`. Ao abrir, o arquivo é lido como
+//! JSON genérico e passa pelas migrações da versão dele até a atual, antes de
+//! virar struct: quando as structs mudarem (ex.: a Enigma de quatro rotores), um
+//! arquivo antigo é convertido em vez de falhar na leitura.
+//!
+//! Versões:
+//! - 1: arquivos sem `schema_version`, gravados antes do versionamento (a área de
+//!   trabalho tinha o próprio campo `version`).
+//! - 2: `schema_version` em todos; listas e mapas vão dentro de um objeto.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Versão atual do formato.
+pub const SCHEMA_VERSION: u64 = 2;
+
+const VERSION_FIELD: &str = "schema_version";
+
+/// O que o arquivo guarda.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Config,
+    KeySheet,
+    Workspace,
+    /// Configurações salvas com um nome.
+    Presets,
+    /// Configurações usadas por último.
+    Recent,
+    /// Lições, desafios e questionários concluídos.
+    Progress,
+    /// Exercício assinado, para distribuir aos alunos.
+    Exercise,
+    /// Demonstração gravada na máquina.
+    Replay,
+    /// Sessões abertas ao fechar o app, por janela.
+    Session,
+}
+
+impl Kind {
+    /// Campo com o conteúdo, nos arquivos que são uma lista ou um mapa.
+    fn field(self) -> Option<&'static str> {
+        match self {
+            Kind::Presets => Some("presets"),
+            Kind::Recent => Some("configs"),
+            Kind::Session => Some("sessions"),
+            Kind::Config | Kind::KeySheet | Kind::Workspace | Kind::Progress | Kind::Exercise | Kind::Replay => None,
+        }
+    }
+}
+
+/// Migra um arquivo da versão `n` para a `n + 1` (a posição `n - 1` da lista).
+type Migration = fn(Value, Kind) -> Result<Value, String>;
+
+const MIGRATIONS: [Migration; (SCHEMA_VERSION - 1) as usize] = [v1_to_v2];
+
+/// 1 → 2: listas e mapas passam a ir dentro de um objeto, ao lado da versão; a
+/// área de trabalho perde o campo `version`.
+fn v1_to_v2(value: Value, kind: Kind) -> Result<Value, String> {
+    if let Some(field) = kind.field() {
+        let mut object = Map::new();
+        object.insert(field.to_string(), value);
+        return Ok(Value::Object(object));
+    }
+    let Value::Object(mut object) = value else { return Err("O arquivo não contém um objeto.".to_string()) };
+    if kind == Kind::Workspace {
+        object.remove("version");
+    }
+    Ok(Value::Object(object))
+}
+
+/// Versão gravada em `value` (1 se não houver).
+fn version(value: &Value) -> Result<u64, String> {
+    match value.get(VERSION_FIELD) {
+        None => Ok(1),
+        Some(version) => version.as_u64().ok_or_else(|| format!("Versão do arquivo inválida: {}", version)),
+    }
+}
+
+/// Traz `value`, gravado em qualquer versão, para a atual.
+pub fn upgrade(mut value: Value, kind: Kind) -> Result<Value, String> {
+    let from = version(&value)?;
+    if from == 0 || from > SCHEMA_VERSION {
+        return Err(format!("Arquivo gravado por uma versão mais nova do app (formato {}).", from));
+    }
+    for migration in &MIGRATIONS[(from - 1) as usize..] {
+        value = migration(value, kind)?;
+    }
+    if let Value::Object(object) = &mut value {
+        object.insert(VERSION_FIELD.to_string(), Value::from(SCHEMA_VERSION));
+    }
+    Ok(value)
+}
+
+/// Lê um arquivo já convertido em JSON genérico, migrando-o se for antigo.
+pub fn from_value<T: DeserializeOwned>(value: Value, kind: Kind) -> Result<T, String> {
+    let Value::Object(mut object) = upgrade(value, kind)? else {
+        return Err("O arquivo não contém um objeto.".to_string());
+    };
+    object.remove(VERSION_FIELD);
+    let data = match kind.field() {
+        Some(field) => object.remove(field).unwrap_or(Value::Null),
+        None => Value::Object(object),
+    };
+    serde_json::from_value(data).map_err(|e| format!("Arquivo inválido: {}", e))
+}
+
+/// `data` pronto para gravar, com a versão atual.
+pub fn to_value<T: Serialize>(data: &T, kind: Kind) -> Result<Value, String> {
+    let data = serde_json::to_value(data).map_err(|e| format!("Erro ao gerar o arquivo: {}", e))?;
+    let mut object = match (kind.field(), data) {
+        (Some(field), data) => {
+            let mut object = Map::new();
+            object.insert(field.to_string(), data);
+            object
+        }
+        (None, Value::Object(object)) => object,
+        (None, _) => return Err("Só objetos podem ser gravados.".to_string()),
+    };
+    object.insert(VERSION_FIELD.to_string(), Value::from(SCHEMA_VERSION));
+    Ok(Value::Object(object))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_v1_files_are_upgraded() {
+        let presets = json!({"Aula 1": {"reflector": "B"}});
+        assert_eq!(
+            upgrade(presets, Kind::Presets).unwrap(),
+            json!({"schema_version": 2, "presets": {"Aula 1": {"reflector": "B"}}})
+        );
+        let workspace = json!({"version": 1, "notes": "crib"});
+        assert_eq!(upgrade(workspace, Kind::Workspace).unwrap(), json!({"schema_version": 2, "notes": "crib"}));
+        let recent: Vec<String> = from_value(json!(["a", "b"]), Kind::Recent).unwrap();
+        assert_eq!(recent, ["a", "b"]);
+    }
+
+    #[test]
+    fn test_current_files_round_trip_and_newer_are_rejected() {
+        let value = to_value(&vec!["a".to_string()], Kind::Recent).unwrap();
+        assert_eq!(value, json!({"schema_version": 2, "configs": ["a"]}));
+        assert_eq!(from_value::<Vec<String>>(value, Kind::Recent).unwrap(), ["a"]);
+        let newer = json!({"schema_version": 3, "configs": []});
+        assert!(from_value::<Vec<String>>(newer, Kind::Recent).unwrap_err().contains("mais nova"));
+        assert!(to_value(&"texto", Kind::Config).is_err());
+    }
+}
@@ -0,0 +1,209 @@
+//! Máquina com estado no backend: a UI manda tecla por tecla e os rotores giram
+//! aqui, como numa demonstração ao vivo.
+//!
+//! Cada janela tem a sua máquina, identificada pelo rótulo da janela: o remetente
+//! numa janela e o destinatário na outra, com estados de fato separados.
+//!
+//! Ao fechar o app as sessões abertas (chave inicial e o que foi teclado) vão para
+//! um JSON na pasta de dados (no formato versionado de `schema`), e voltam ao abrir:
+//! um fechamento sem querer não apaga uma demonstração pela metade. As posições dos
+//! rotores são refeitas teclando de novo o transcrito a partir da chave inicial.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::enigma::{EncryptionStep, EnigmaConfig, EnigmaMachine, SubstitutionAlphabet};
+use crate::schema::{self, Kind};
+
+/// Retrato de uma sessão, para a UI e para o arquivo de autosave.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Session {
+    /// Chave com que a sessão começou.
+    pub config: EnigmaConfig,
+    /// Posições atuais dos rotores (L, M, R).
+    pub positions: (char, char, char),
+    /// Letras tecladas.
+    pub input: String,
+    /// Letras acesas no painel de lâmpadas.
+    pub output: String,
+}
+
+/// Uma máquina em uso e o transcrito do que já passou por ela.
+#[derive(Debug, Clone)]
+pub struct Machine {
+    config: EnigmaConfig,
+    machine: EnigmaMachine,
+    input: String,
+    output: String,
+}
+
+impl Machine {
+    /// Máquina nova na chave `config`.
+    pub fn new(config: EnigmaConfig) -> Result<Self, String> {
+        let config = config.normalized()?;
+        let machine = EnigmaMachine::new(config.clone());
+        Ok(Self { config, machine, input: String::new(), output: String::new() })
+    }
+
+    /// Retoma uma sessão salva, teclando de novo o que já tinha sido teclado.
+    pub fn restore(session: Session) -> Result<Self, String> {
+        let mut machine = Self::new(session.config)?;
+        machine.type_text(&session.input);
+        if machine.output != session.output {
+            return Err("A sessão salva não confere com a chave.".to_string());
+        }
+        Ok(machine)
+    }
+
+    /// Tecla `key`: os rotores giram e a lâmpada acende.
+    pub fn press(&mut self, key: char) -> Result<EncryptionStep, String> {
+        if !key.is_ascii_alphabetic() {
+            return Err(format!("Só as letras de A a Z cifram: {}", key));
+        }
+        let key = key.to_ascii_uppercase();
+        let (lamp, step) = self.machine.process_char_detailed(key);
+        self.input.push(key);
+        self.output.push(lamp);
+        Ok(step)
+    }
+
+    /// Tecla as letras de `text` e retorna as lâmpadas acesas.
+    pub fn type_text(&mut self, text: &str) -> String {
+        let letters: String = text.chars().filter(|c| c.is_ascii_alphabetic()).collect();
+        let lamps = self.machine.process_string(&letters);
+        self.input.push_str(&letters.to_ascii_uppercase());
+        self.output.push_str(&lamps);
+        lamps
+    }
+
+    /// Alfabeto de substituição que a próxima tecla vai aplicar, sem teclá-la.
+    pub fn next_substitution(&self) -> SubstitutionAlphabet {
+        self.machine.next_substitution()
+    }
+
+    /// Volta à chave inicial e apaga o transcrito.
+    pub fn reset(&mut self) {
+        self.machine = EnigmaMachine::new(self.config.clone());
+        self.input.clear();
+        self.output.clear();
+    }
+
+    /// Chave com que a sessão começou.
+    pub fn config(&self) -> &EnigmaConfig {
+        &self.config
+    }
+
+    /// Retrato do estado atual.
+    pub fn session(&self) -> Session {
+        Session {
+            config: self.config.clone(),
+            positions: self.machine.get_positions(),
+            input: self.input.clone(),
+            output: self.output.clone(),
+        }
+    }
+}
+
+/// Grava as sessões abertas, por rótulo de janela, em `path`, ou apaga o arquivo
+/// se não houver nenhuma.
+pub fn save(path: &Path, sessions: &BTreeMap<String, Session>) -> Result<(), String> {
+    if sessions.is_empty() {
+        return match std::fs::remove_file(path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(format!("Erro ao apagar a sessão salva: {}", e)),
+            _ => Ok(()),
+        };
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Erro ao criar a pasta de dados: {}", e))?;
+    }
+    let temporary = path.with_extension("tmp");
+    let file = File::create(&temporary).map_err(|e| format!("Erro ao salvar a sessão: {}", e))?;
+    serde_json::to_writer(BufWriter::new(file), &schema::to_value(sessions, Kind::Session)?)
+        .map_err(|e| format!("Erro ao salvar a sessão: {}", e))?;
+    std::fs::rename(&temporary, path).map_err(|e| format!("Erro ao salvar a sessão: {}", e))
+}
+
+/// Sessões salvas em `path`, por rótulo de janela. Um arquivo ausente conta como
+/// vazio; um arquivo ilegível é um erro. Uma sessão que não confere com a chave
+/// fica de fora.
+pub fn load(path: &Path) -> Result<BTreeMap<String, Machine>, String> {
+    let sessions: BTreeMap<String, Session> = match File::open(path) {
+        Ok(file) => serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| e.to_string())
+            .and_then(|value| schema::from_value(value, Kind::Session))
+            .map_err(|e| format!("Sessão salva inválida: {}", e))?,
+        Err(e) if e.kind() == ErrorKind::NotFound => BTreeMap::new(),
+        Err(e) => return Err(format!("Erro ao abrir a sessão salva: {}", e)),
+    };
+    Ok(sessions.into_iter().filter_map(|(label, session)| Some((label, Machine::restore(session).ok()?))).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> EnigmaConfig {
+        EnigmaConfig::from_share_code("ENIGMA:I.II.III:B:AAZ:AAA:").unwrap()
+    }
+
+    #[test]
+    fn test_press_and_type_keep_the_transcript() {
+        let mut machine = Machine::new(config()).unwrap();
+        machine.press('h').unwrap();
+        assert_eq!(machine.type_text("el lo"), EnigmaMachine::new(config()).process_string("HELLO")[1..]);
+        assert!(machine.press('1').is_err());
+        let session = machine.session();
+        assert_eq!(session.input, "HELLO");
+        assert_eq!(session.positions, ('A', 'A', 'E'));
+        machine.reset();
+        assert_eq!(machine.session().positions, ('A', 'A', 'Z'));
+        assert!(machine.session().input.is_empty());
+
+        let next = machine.next_substitution();
+        assert_eq!(next.positions, ('A', 'A', 'A'));
+        assert_eq!(machine.session().positions, ('A', 'A', 'Z'));
+        assert_eq!(Some(machine.press('H').unwrap().output_char), next.alphabet.chars().nth(7));
+    }
+
+    #[test]
+    fn test_restore_replays_the_transcript() {
+        let mut machine = Machine::new(config()).unwrap();
+        machine.type_text("DEMONSTRACAO");
+        let session = machine.session();
+        assert_eq!(Machine::restore(session.clone()).unwrap().session(), session);
+        let tampered = Session { output: "X".repeat(12), ..session };
+        assert!(Machine::restore(tampered).is_err());
+        assert!(load(Path::new("/nao/existe/session.json")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sessions_are_saved_with_the_schema_version() {
+        let dir = std::env::temp_dir().join(format!("enigma-session-{}", std::process::id()));
+        let path = dir.join("session.json");
+        let mut machine = Machine::new(config()).unwrap();
+        machine.type_text("DEMO");
+        let sessions = BTreeMap::from([("main".to_string(), machine.session())]);
+
+        save(&path, &sessions).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(value.get("schema_version").and_then(|v| v.as_u64()), Some(schema::SCHEMA_VERSION));
+        assert_eq!(load(&path).unwrap()["main"].session(), machine.session());
+
+        // Arquivos de antes do versionamento eram o mapa puro
+        std::fs::write(&path, serde_json::to_string(&sessions).unwrap()).unwrap();
+        assert_eq!(load(&path).unwrap()["main"].session(), machine.session());
+
+        std::fs::write(&path, "{\"schema_version\": 99}").unwrap();
+        assert!(load(&path).unwrap_err().contains("mais nova"));
+        std::fs::write(&path, "lixo").unwrap();
+        assert!(load(&path).is_err());
+
+        save(&path, &BTreeMap::new()).unwrap();
+        assert!(!path.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
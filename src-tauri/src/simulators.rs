@@ -0,0 +1,178 @@
+//! Configurações copiadas de outros simuladores, para conferir resultados sem
+//! redigitar a chave.
+//!
+//! - Cryptii: os ajustes do bloco "Enigma" (`rotor1`, `position1`, `ring1`... da
+//!   esquerda para a direita, `reflector` e `plugboard`), em JSON ou em pares
+//!   `chave=valor`;
+//! - Universal Enigma e folhas de chaves: texto com um ajuste por linha, com os
+//!   rótulos em alemão ou inglês ("Walzenlage: I II III", "Ringstellung: 01 12 24",
+//!   "Steckerverbindungen: AB CD", "Grundstellung: ABC", "UKW: B").
+//!
+//! Posições e anéis podem vir como letras ou como números de 1 a 26.
+
+use crate::enigma::{EnigmaConfig, RotorConfig};
+
+/// Simulador de origem de um texto de configuração.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SettingsFormat {
+    Cryptii,
+    UniversalEnigma,
+}
+
+impl SettingsFormat {
+    /// Reconhece o formato de `text` pelos nomes dos ajustes.
+    pub fn detect(text: &str) -> Self {
+        if text.to_ascii_lowercase().contains("rotor1") {
+            Self::Cryptii
+        } else {
+            Self::UniversalEnigma
+        }
+    }
+}
+
+/// Letra de uma posição ou anel: "C", "c" ou "3".
+fn letter(value: &str) -> Result<char, String> {
+    let value = value.trim();
+    if let Ok(n) = value.parse::<u8>() {
+        return match n {
+            1..=26 => Ok((b'A' + n - 1) as char),
+            _ => Err(format!("Posição fora de 1 a 26: {}", n)),
+        };
+    }
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphabetic() => Ok(c.to_ascii_uppercase()),
+        _ => Err(format!("Posição inválida: {}", value)),
+    }
+}
+
+/// Três letras da esquerda para a direita: "ABC", "A B C" ou "01 02 03".
+fn letters(value: &str) -> Result<[char; 3], String> {
+    let parts: Vec<&str> = value.split([' ', ',', '-', '.']).filter(|p| !p.is_empty()).collect();
+    let parts: Vec<String> = match parts[..] {
+        [word] if word.len() == 3 && word.chars().all(|c| c.is_ascii_alphabetic()) => {
+            word.chars().map(String::from).collect()
+        }
+        _ => parts.iter().map(|p| p.to_string()).collect(),
+    };
+    match &parts[..] {
+        [l, m, r] => Ok([letter(l)?, letter(m)?, letter(r)?]),
+        _ => Err(format!("Esperava três posições: {}", value)),
+    }
+}
+
+/// Tira prefixos como "UKW " ou "UKW-" do nome do refletor.
+fn reflector(value: &str) -> String {
+    let value = value.trim().to_ascii_uppercase();
+    value.strip_prefix("UKW").unwrap_or(&value).trim_matches(|c: char| c == '-' || c == ' ').to_string()
+}
+
+/// Monta a configuração com rotores, posições e anéis da esquerda para a direita.
+fn config(
+    rotors: [String; 3],
+    positions: [char; 3],
+    rings: [char; 3],
+    reflector: String,
+    plugboard: &str,
+) -> Result<EnigmaConfig, String> {
+    let [l, m, r] = rotors;
+    let rotor = |name: String, i: usize| RotorConfig { name, position: positions[i], ring: rings[i] };
+    let pairs: String = plugboard.split_whitespace().collect::<Vec<_>>().join(" ");
+    EnigmaConfig { rotors: (rotor(r, 2), rotor(m, 1), rotor(l, 0)), reflector, plugboard_pairs: pairs }.normalized()
+}
+
+/// Pares `chave: valor` ou `chave=valor` separados por vírgulas, `&` ou linhas,
+/// com chaves e aspas do JSON descartadas e `+` (espaço numa URL) trocado por espaço.
+fn key_values(text: &str) -> Vec<(String, String)> {
+    text.split([',', '&', '\n', '{', '}'])
+        .filter_map(|pair| pair.split_once([':', '=']))
+        .map(|(key, value)| {
+            let clean = |s: &str| s.trim().trim_matches('"').trim().replace('+', " ");
+            (clean(key).to_ascii_lowercase(), clean(value))
+        })
+        .collect()
+}
+
+/// Lê os ajustes do bloco "Enigma" do Cryptii.
+pub fn parse_cryptii(text: &str) -> Result<EnigmaConfig, String> {
+    let values = key_values(text);
+    let get = |key: &str| {
+        values.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone()).ok_or(format!("Falta o ajuste {}.", key))
+    };
+    let rotors = [get("rotor1")?, get("rotor2")?, get("rotor3")?];
+    let positions = [letter(&get("position1")?)?, letter(&get("position2")?)?, letter(&get("position3")?)?];
+    // Anéis ausentes ficam em A, como no Cryptii
+    let ring = |i| get(&format!("ring{}", i)).map_or(Ok('A'), |v| letter(&v));
+    let rings = [ring(1)?, ring(2)?, ring(3)?];
+    config(rotors, positions, rings, reflector(&get("reflector")?), &get("plugboard").unwrap_or_default())
+}
+
+/// Lê um texto de ajustes do Universal Enigma (ou de uma folha de chaves).
+pub fn parse_universal(text: &str) -> Result<EnigmaConfig, String> {
+    let (mut rotors, mut positions, mut rings, mut umkehr, mut plugboard) = (None, None, None, None, String::new());
+    for line in text.lines() {
+        let Some((label, value)) = line.split_once([':', '=']) else { continue };
+        let label = label.trim().to_ascii_lowercase();
+        if label.starts_with("walzenlage") || label.starts_with("wheel") || label.starts_with("rotor") {
+            let names: Vec<String> = value.split_whitespace().map(String::from).collect();
+            rotors = Some(<[String; 3]>::try_from(names).map_err(|_| format!("Esperava três rotores: {}", value))?);
+        } else if label.starts_with("ring") {
+            rings = Some(letters(value)?);
+        } else if label.starts_with("grund") || label.starts_with("start") || label.starts_with("position") {
+            positions = Some(letters(value)?);
+        } else if label.starts_with("ukw") || label.starts_with("umkehr") || label.starts_with("reflector") {
+            umkehr = Some(reflector(value));
+        } else if label.starts_with("stecker") || label.starts_with("plug") {
+            plugboard = value.to_string();
+        }
+    }
+    config(
+        rotors.ok_or("Falta a Walzenlage (ordem dos rotores).")?,
+        positions.ok_or("Falta a Grundstellung (posições iniciais).")?,
+        rings.unwrap_or(['A'; 3]),
+        umkehr.ok_or("Falta o refletor (UKW).")?,
+        &plugboard,
+    )
+}
+
+/// Lê uma configuração de qualquer um dos formatos, reconhecido por `detect`.
+pub fn parse(text: &str) -> Result<EnigmaConfig, String> {
+    match SettingsFormat::detect(text) {
+        SettingsFormat::Cryptii => parse_cryptii(text),
+        SettingsFormat::UniversalEnigma => parse_universal(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expected() -> EnigmaConfig {
+        EnigmaConfig::from_share_code("ENIGMA:I.II.III:B:ABC:ALX:ABCD").unwrap()
+    }
+
+    #[test]
+    fn test_parse_cryptii() {
+        let json = r#"{"model": "M3", "reflector": "UKW-B", "rotor1": "I", "position1": 1, "ring1": 1,
+            "rotor2": "II", "position2": "b", "ring2": 12, "rotor3": "III", "position3": 3, "ring3": "x",
+            "plugboard": "ab cd"}"#;
+        assert_eq!(SettingsFormat::detect(json), SettingsFormat::Cryptii);
+        assert_eq!(parse(json).unwrap(), expected());
+        let pairs = "rotor1=I&position1=a&ring1=a&rotor2=II&position2=b&ring2=l&rotor3=III&position3=c&ring3=24\
+            &reflector=B&plugboard=ab+cd";
+        assert_eq!(parse(pairs).unwrap(), expected());
+        assert!(parse("rotor1=I").unwrap_err().contains("rotor2"));
+    }
+
+    #[test]
+    fn test_parse_universal_enigma() {
+        let text = "Enigma M3\nUKW: B\nWalzenlage: I II III\nRingstellung: 01 12 24\n\
+            Steckerverbindungen: AB CD\nGrundstellung: A B C\n";
+        assert_eq!(SettingsFormat::detect(text), SettingsFormat::UniversalEnigma);
+        assert_eq!(parse(text).unwrap(), expected());
+        let english = "Reflector = UKW B\nWheel order = I II III\nRing settings = A L X\nPlugs = AB CD\nStart = ABC";
+        assert_eq!(parse(english).unwrap(), expected());
+        assert!(parse("Walzenlage: I II\nGrundstellung: AAA\nUKW: B").is_err());
+        assert!(parse("Walzenlage: I II III\nGrundstellung: A A 27\nUKW: B").is_err());
+    }
+}
@@ -0,0 +1,121 @@
+//! Arquivos de traço: MessagePack, para reabrir no app, e CSV, para planilhas.
+//!
+//! O traço compacto (`enigma::Trace`) gravado em MessagePack ocupa uma fração do
+//! JSON completo dos `EncryptionStep`, e ao ser aberto volta a ele para a UI. O CSV
+//! tem uma linha por componente atravessado pelo sinal.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::enigma::{Component, EncryptionStep, EnigmaConfig, PathDirection, RotorSlot, Trace};
+
+/// Traço aberto de um arquivo, já no formato que a UI mostra.
+#[derive(Debug, Serialize, Clone)]
+pub struct ImportedTrace {
+    pub config: EnigmaConfig,
+    pub steps: Vec<EncryptionStep>,
+}
+
+/// Grava `trace` em `path` e retorna o tamanho do arquivo em bytes.
+pub fn save(trace: &Trace, path: &Path) -> Result<usize, String> {
+    let bytes = rmp_serde::to_vec(trace).map_err(|e| format!("Erro ao gerar o traço: {}", e))?;
+    std::fs::write(path, &bytes).map_err(|e| format!("Erro ao salvar o traço: {}", e))?;
+    Ok(bytes.len())
+}
+
+/// Abre um traço gravado por `save`.
+pub fn load(path: &Path) -> Result<ImportedTrace, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Erro ao abrir o traço: {}", e))?;
+    let trace: Trace = rmp_serde::from_slice(&bytes).map_err(|e| format!("Traço inválido: {}", e))?;
+    Ok(ImportedTrace { steps: trace.expand()?, config: trace.config })
+}
+
+/// Cabeçalho do CSV. As posições são as janelas (esquerda, meio, direita) antes e
+/// depois da tecla.
+const CSV_HEADER: &str =
+    "step,key,lamp,positions_before,positions_after,component,slot,name,input,output,direction";
+
+/// Uma linha por `PathEntry`, com o índice e os dados da tecla repetidos em cada uma.
+pub fn to_csv(steps: &[EncryptionStep]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+    for (index, step) in steps.iter().enumerate() {
+        let (bl, bm, br) = step.positions_before_step;
+        let (al, am, ar) = step.positions_after_step;
+        for entry in &step.path {
+            let (component, slot, name) = match &entry.component {
+                Component::Plugboard => ("Plugboard", "", ""),
+                Component::EntryWheel => ("EntryWheel", "", ""),
+                Component::Rotor { slot, name } => {
+                    let slot = match slot {
+                        RotorSlot::Left => "Left",
+                        RotorSlot::Middle => "Middle",
+                        RotorSlot::Right => "Right",
+                    };
+                    ("Rotor", slot, *name)
+                }
+                Component::Reflector { name } => ("Reflector", "", *name),
+            };
+            let direction = match entry.direction {
+                PathDirection::Forward => "Forward",
+                PathDirection::Reflect => "Reflect",
+                PathDirection::Backward => "Backward",
+            };
+            csv.push_str(&format!(
+                "{},{},{},{}{}{},{}{}{},{},{},{},{},{},{}\n",
+                index,
+                step.input_char,
+                step.output_char,
+                bl,
+                bm,
+                br,
+                al,
+                am,
+                ar,
+                component,
+                slot,
+                name,
+                entry.input_char,
+                entry.output_char,
+                direction
+            ));
+        }
+    }
+    csv
+}
+
+/// Grava o CSV de `steps` em `path` e retorna o tamanho do arquivo em bytes.
+pub fn save_csv(steps: &[EncryptionStep], path: &Path) -> Result<usize, String> {
+    let csv = to_csv(steps);
+    std::fs::write(path, &csv).map_err(|e| format!("Erro ao salvar o CSV: {}", e))?;
+    Ok(csv.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enigma::{EnigmaMachine, RotorConfig};
+
+    #[test]
+    fn test_csv_has_a_row_per_component() {
+        let rotor = |name: &str| RotorConfig { name: name.to_string(), position: 'A', ring: 'A' };
+        let config = EnigmaConfig {
+            rotors: (rotor("III"), rotor("II"), rotor("I")),
+            reflector: "B".into(),
+            plugboard_pairs: String::new(),
+        };
+        let steps = EnigmaMachine::new(config).process_string_detailed("AB");
+        let csv = to_csv(&steps);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines.len(), 1 + steps.iter().map(|s| s.path.len()).sum::<usize>());
+
+        let first = &steps[0];
+        let row = format!("0,A,{},AAA,AAB,Plugboard,,,A,A,Forward", first.output_char);
+        assert_eq!(lines[1], row);
+        assert!(lines[2].starts_with(&format!("0,A,{},AAA,AAB,Rotor,Right,III,", first.output_char)));
+        assert!(lines.iter().any(|l| l.contains(",Reflector,,B,")));
+        assert!(lines.last().unwrap().starts_with("1,B,"));
+    }
+}
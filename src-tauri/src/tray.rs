@@ -0,0 +1,64 @@
+//! Cifragem rápida pelo ícone da bandeja: o texto da área de transferência passa
+//! pela máquina na configuração salva escolhida no menu e o resultado volta para a
+//! área de transferência, sem abrir a janela.
+
+use crate::enigma::{EnigmaConfig, EnigmaMachine, OutputFormat};
+
+/// Ação escolhida no menu da bandeja.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuickAction {
+    /// Cifra e devolve no formato de transmissão (grupos de cinco).
+    Encrypt,
+    /// Decifra e devolve as letras seguidas, sem grupos.
+    Decrypt,
+}
+
+/// Letras de `text` que vão para a máquina. Ao decifrar, uma primeira linha com
+/// algarismos é o preâmbulo do formato de transmissão e fica de fora.
+fn letters(text: &str, action: QuickAction) -> String {
+    let mut lines = text.lines().peekable();
+    if action == QuickAction::Decrypt && text.lines().nth(1).is_some() {
+        lines.next_if(|line| line.chars().any(|c| c.is_ascii_digit()));
+    }
+    lines.flat_map(str::chars).filter(|c| c.is_ascii_alphabetic()).collect()
+}
+
+/// Passa o texto da área de transferência pela máquina em `config`.
+pub fn apply(config: EnigmaConfig, action: QuickAction, text: &str) -> Result<String, String> {
+    let config = config.normalized()?;
+    let letters = letters(text, action);
+    if letters.is_empty() {
+        return Err("A área de transferência não tem letras para cifrar.".to_string());
+    }
+    let output = EnigmaMachine::new(config).process_string(&letters);
+    Ok(match action {
+        QuickAction::Encrypt => OutputFormat::default().apply(&output),
+        QuickAction::Decrypt => output,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> EnigmaConfig {
+        EnigmaConfig::from_share_code("ENIGMA:I.II.III:B:AAA:AAA:").unwrap()
+    }
+
+    #[test]
+    fn test_encrypt_then_decrypt_round_trip() {
+        let ciphertext = apply(config(), QuickAction::Encrypt, "Ataque ao amanhecer!").unwrap();
+        assert_eq!(ciphertext.len(), 20);
+        assert_eq!(ciphertext.as_bytes()[5], b' ');
+        assert_eq!(apply(config(), QuickAction::Decrypt, &ciphertext).unwrap(), "ATAQUEAOAMANHECER");
+        assert!(apply(config(), QuickAction::Encrypt, "1234 !?").is_err());
+    }
+
+    #[test]
+    fn test_decrypt_skips_the_preamble() {
+        let preamble = Some("1230 = 2tl = 1tl = {letters} =".to_string());
+        let format = OutputFormat { preamble, ..OutputFormat::default() };
+        let ciphertext = format.apply(&apply(config(), QuickAction::Encrypt, "HELLO").unwrap());
+        assert_eq!(apply(config(), QuickAction::Decrypt, &ciphertext).unwrap(), "HELLO");
+    }
+}
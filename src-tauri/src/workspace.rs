@@ -0,0 +1,85 @@
+//! Arquivo de área de trabalho: a aula em andamento (configuração, textos, notas e
+//! marcadores) salva num único JSON, para continuar depois de fechar o app.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::enigma::EnigmaConfig;
+use crate::schema::{self, Kind};
+
+/// Marcador que a UI põe num ponto do texto (ex.: "início do crib").
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Bookmark {
+    pub label: String,
+    /// Posição da letra marcada no texto.
+    pub index: usize,
+}
+
+/// Tudo o que o aluno tinha aberto.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Workspace {
+    pub config: EnigmaConfig,
+    #[serde(default)]
+    pub plaintext: String,
+    #[serde(default)]
+    pub ciphertext: String,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub bookmarks: Vec<Bookmark>,
+}
+
+impl Workspace {
+    /// Grava em `path`, sempre na versão atual do formato.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let workspace = schema::to_value(self, Kind::Workspace)?;
+        let file = File::create(path).map_err(|e| format!("Erro ao criar a área de trabalho: {}", e))?;
+        serde_json::to_writer_pretty(BufWriter::new(file), &workspace)
+            .map_err(|e| format!("Erro ao salvar a área de trabalho: {}", e))
+    }
+
+    /// Abre uma área de trabalho gravada por `save`, em qualquer versão do formato.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("Erro ao abrir a área de trabalho: {}", e))?;
+        serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| e.to_string())
+            .and_then(|value| schema::from_value(value, Kind::Workspace))
+            .map_err(|e| format!("Área de trabalho inválida: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enigma::RotorConfig;
+
+    #[test]
+    fn test_old_files_open_and_newer_are_rejected() {
+        let rotor = |name: &str| RotorConfig { name: name.to_string(), position: 'A', ring: 'A' };
+        let workspace = Workspace {
+            config: EnigmaConfig {
+                rotors: (rotor("III"), rotor("II"), rotor("I")),
+                reflector: "B".into(),
+                plugboard_pairs: String::new(),
+            },
+            plaintext: String::new(),
+            ciphertext: String::new(),
+            notes: String::new(),
+            bookmarks: vec![Bookmark { label: "crib".into(), index: 4 }],
+        };
+        let path = std::env::temp_dir().join(format!("enigma-workspace-{}.json", std::process::id()));
+        workspace.save(&path).unwrap();
+        assert_eq!(Workspace::open(&path).unwrap(), workspace);
+
+        let config = serde_json::to_string(&workspace.config).unwrap();
+        std::fs::write(&path, format!(r#"{{"version": 1, "config": {}, "notes": "v1"}}"#, config)).unwrap();
+        assert_eq!(Workspace::open(&path).unwrap().notes, "v1");
+        std::fs::write(&path, format!(r#"{{"schema_version": 3, "config": {}}}"#, config)).unwrap();
+        assert!(Workspace::open(&path).unwrap_err().contains("mais nova"));
+        std::fs::remove_file(&path).unwrap();
+        assert!(Workspace::open(Path::new("/caminho/que/nao/existe.json")).is_err());
+    }
+}